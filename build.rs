@@ -3,7 +3,9 @@ use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::io::Write;
+use std::panic;
 use std::path::Path;
+use std::thread;
 
 const RESIDUES: &[(&str, &str, usize, usize)] = &[
     ("ARG", "Arg", 4, 75),
@@ -30,9 +32,91 @@ const RESIDUES: &[(&str, &str, usize, usize)] = &[
     ("VAL", "Val", 1, 3),
 ];
 
+/// One-letter code and alternate three-letter names of each residue type,
+/// keyed by its CSV name. Aliases are disjoint across types so a name always
+/// resolves to at most one type.
+const IDENTITY: &[(&str, char, &[&str])] = &[
+    ("ARG", 'R', &["ARN"]),
+    ("ASN", 'N', &[]),
+    ("ASP", 'D', &["ASH"]),
+    ("CPR", 'P', &[]),
+    ("CYD", 'C', &["CYX"]),
+    ("CYH", 'C', &[]),
+    ("CYS", 'C', &["CYM"]),
+    ("GLN", 'Q', &[]),
+    ("GLU", 'E', &["GLH"]),
+    ("HIS", 'H', &["HID", "HIE", "HIP", "HSD", "HSE", "HSP"]),
+    ("ILE", 'I', &[]),
+    ("LEU", 'L', &[]),
+    ("LYS", 'K', &["LYN"]),
+    ("MET", 'M', &["MSE"]),
+    ("PHE", 'F', &[]),
+    ("PRO", 'P', &[]),
+    ("SER", 'S', &[]),
+    ("THR", 'T', &[]),
+    ("TPR", 'P', &[]),
+    ("TRP", 'W', &[]),
+    ("TYR", 'Y', &[]),
+    ("VAL", 'V', &[]),
+];
+
+/// Physicochemical classes of each residue type, keyed by its CSV name, as
+/// marker trait names in `crate::class`. Variants take their parent's
+/// classes.
+const CLASSES: &[(&str, &[&str])] = &[
+    ("ARG", &["Charged", "Polar"]),
+    ("ASN", &["Polar", "Small"]),
+    ("ASP", &["Charged", "Polar", "Small"]),
+    ("CPR", &["Small"]),
+    ("CYD", &["Hydrophobic", "Small"]),
+    ("CYH", &["Hydrophobic", "Small"]),
+    ("CYS", &["Hydrophobic", "Small"]),
+    ("GLN", &["Polar"]),
+    ("GLU", &["Charged", "Polar"]),
+    ("HIS", &["Charged", "Polar"]),
+    ("ILE", &["Hydrophobic"]),
+    ("LEU", &["Hydrophobic"]),
+    ("LYS", &["Charged", "Polar"]),
+    ("MET", &["Hydrophobic"]),
+    ("PHE", &["Aromatic", "Hydrophobic"]),
+    ("PRO", &["Small"]),
+    ("SER", &["Polar", "Small"]),
+    ("THR", &["Polar", "Small"]),
+    ("TPR", &["Small"]),
+    ("TRP", &["Aromatic", "Hydrophobic"]),
+    ("TYR", &["Aromatic", "Polar"]),
+    ("VAL", &["Hydrophobic"]),
+];
+
+/// Bin partition of each semi-rotameric χ, keyed by CSV name: the 0-based
+/// χ index, the start of bin 1 in degrees, and the χ period (180° for a
+/// terminal χ with symmetric end atoms). Bins are equal-width ranges laid
+/// out from the start over one period. Every other χ is rotameric (three
+/// 120° bins from 0°) or a proline ring χ (bins of 360° / count from 0°).
+const SEMI_ROTAMERIC: &[(&str, usize, f32, f32)] = &[
+    ("ASN", 1, -45.0, 360.0),
+    ("ASP", 1, -30.0, 180.0),
+    ("GLN", 2, -45.0, 360.0),
+    ("GLU", 2, -15.0, 180.0),
+    ("HIS", 1, -90.0, 360.0),
+    ("PHE", 1, 75.0, 180.0),
+    ("TRP", 1, 75.0, 360.0),
+    ("TYR", 1, 75.0, 180.0),
+];
+
+/// Column names of the CSV header, in order.
+const COLUMNS: [&str; 16] = [
+    "res", "phi", "psi", "r1", "r2", "r3", "r4", "prob", "chi1-val", "chi2-val", "chi3-val",
+    "chi4-val", "chi1-sig", "chi2-sig", "chi3-sig", "chi4-sig",
+];
+
 /// Number of grid points per axis.
 const GRID_COUNT: usize = 37;
 
+/// Number of stored grid rows and columns per axis: the +180° points repeat
+/// the −180° ones and are left out of the tables.
+const GRID_PERIOD: usize = GRID_COUNT - 1;
+
 /// Grid minimum angle in degrees.
 const GRID_MIN: f32 = -180.0;
 
@@ -42,6 +126,13 @@ const GRID_STEP: f32 = 10.0;
 /// Degrees-to-radians factor.
 const DEG_TO_RAD: f64 = std::f64::consts::PI / 180.0;
 
+/// Source of this build script, hashed into the output stamp so that any
+/// change to the generator invalidates previously generated tables.
+const BUILD_SCRIPT: &[u8] = include_bytes!("build.rs");
+
+/// Magic header of the cached intermediate representation.
+const IR_MAGIC: &[u8; 4] = b"DBIR";
+
 /// A parsed CSV row (rotamer bin indices stored as 4 u8s, padded with 0).
 #[derive(Debug, Clone)]
 struct RawRow {
@@ -51,7 +142,145 @@ struct RawRow {
     chi_sig: [f32; 4],
 }
 
+/// Per-build settings shared by every residue's code generation.
+struct BuildConfig<'a> {
+    /// Directory receiving `tables.rs` and the table blobs.
+    out_dir: &'a Path,
+    /// Whether the target stores floats big-endian.
+    big_endian: bool,
+    /// Whether the `chi1` feature requests reduced χ1-only tables.
+    chi1: bool,
+    /// Whether the `gradients` feature requests probability gradient maps.
+    gradients: bool,
+    /// Whether the `fast-chi` feature requests χ means in centidegrees.
+    fast_chi: bool,
+    /// Whether the `delta-tables` feature embeds the tables delta-encoded.
+    delta: bool,
+}
+
+/// Rows of one residue, bucketed by `[phi_idx][psi_idx]`.
+type Grid = Vec<Vec<Vec<RawRow>>>;
+
 fn main() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let out_dir = Path::new(&out_dir);
+    let big_endian = env::var("CARGO_CFG_TARGET_ENDIAN").as_deref() == Ok("big");
+    let chi1 = env::var_os("CARGO_FEATURE_CHI1").is_some();
+    let gradients = env::var_os("CARGO_FEATURE_GRADIENTS").is_some();
+    let fast_chi = env::var_os("CARGO_FEATURE_FAST_CHI").is_some();
+    let delta = env::var_os("CARGO_FEATURE_DELTA_TABLES").is_some();
+
+    let csv = read_csv(out_dir);
+    let csv_hash = fnv1a(FNV_OFFSET, &csv);
+    let alt_csv = read_alt_csv();
+    let alt_hash = alt_csv
+        .as_deref()
+        .map_or(0, |alt_csv| fnv1a(FNV_OFFSET, alt_csv));
+    let stamp = format!(
+        "{:016x}",
+        fnv1a(
+            fnv1a(fnv1a(csv_hash, BUILD_SCRIPT), &alt_hash.to_le_bytes()),
+            &[
+                big_endian as u8,
+                chi1 as u8,
+                gradients as u8,
+                fast_chi as u8,
+                alt_csv.is_some() as u8,
+                delta as u8
+            ]
+        )
+    );
+
+    // Cargo reruns the script whenever the CSV's mtime changes (e.g. after a
+    // checkout); skip all work if the content and the generator are unchanged.
+    let stamp_path = out_dir.join("tables.stamp");
+    let dest_path = out_dir.join("tables.rs");
+    if dest_path.exists() && fs::read_to_string(&stamp_path).is_ok_and(|s| s == stamp) {
+        return;
+    }
+
+    let ir_path = out_dir.join("tables.ir");
+    let tables = load_ir(&ir_path, csv_hash).unwrap_or_else(|| {
+        let tables = parse_csv(&csv);
+        store_ir(&ir_path, csv_hash, &tables);
+        tables
+    });
+
+    check_residue_names(&tables, "CSV");
+
+    let config = &BuildConfig {
+        out_dir,
+        big_endian,
+        chi1,
+        gradients,
+        fast_chi,
+        delta,
+    };
+    if delta {
+        write_delta_sincos(config);
+    }
+    let sources: Vec<Vec<u8>> = thread::scope(|s| {
+        let handles: Vec<_> = RESIDUES
+            .iter()
+            .map(|&(csv_name, rust_name, n_chi, n_rotamers)| {
+                let grid = tables
+                    .get(csv_name)
+                    .unwrap_or_else(|| panic!("build.rs: residue '{csv_name}' not found in CSV"));
+                s.spawn(move || {
+                    generate_residue(config, csv_name, rust_name, n_chi, n_rotamers, grid, false)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .collect()
+    });
+    let alt_sources: Vec<Vec<u8>> = alt_csv.map_or_else(Vec::new, |alt_csv| {
+        let alt_tables = parse_csv(&alt_csv);
+        check_residue_names(&alt_tables, "DUNBRACK_ALT_DATA");
+        thread::scope(|s| {
+            let handles: Vec<_> = RESIDUES
+                .iter()
+                .map(|&(csv_name, rust_name, n_chi, _)| {
+                    let grid = alt_tables.get(csv_name).unwrap_or_else(|| {
+                        panic!("build.rs: residue '{csv_name}' not found in DUNBRACK_ALT_DATA")
+                    });
+                    // The alternative library may enumerate a different
+                    // rotamer set, e.g. for semi-rotameric residues.
+                    let n_rotamers = grid[0][0].len();
+                    s.spawn(move || {
+                        generate_residue(config, csv_name, rust_name, n_chi, n_rotamers, grid, true)
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|h| h.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+                .collect()
+        })
+    });
+
+    let mut out = Vec::new();
+    writeln!(out, "// Auto-generated by build.rs — do not edit.").unwrap();
+    writeln!(out).unwrap();
+    for source in sources.iter().chain(&alt_sources) {
+        out.extend_from_slice(source);
+    }
+    emit_footprint(&mut out, chi1, !alt_sources.is_empty());
+    emit_registry(&mut out);
+    if !alt_sources.is_empty() {
+        emit_alt_namespace(&mut out);
+    }
+    emit_for_all_residues_macro(&mut out);
+
+    fs::write(&dest_path, out).unwrap();
+    fs::write(&stamp_path, stamp).unwrap();
+}
+
+/// Reads the in-tree library CSV.
+#[cfg(not(feature = "fetch-data"))]
+fn read_csv(_out_dir: &Path) -> Vec<u8> {
     println!("cargo::rerun-if-changed=data/dunbrack-2010.lib.csv");
 
     let csv_path = Path::new("data/dunbrack-2010.lib.csv");
@@ -59,43 +288,253 @@ fn main() {
         csv_path.exists(),
         "build.rs: CSV file not found at {csv_path:?}"
     );
+    fs::read(csv_path).unwrap_or_else(|e| panic!("build.rs: cannot read CSV: {e}"))
+}
+
+/// Default download location of the library CSV for the `fetch-data`
+/// feature.
+#[cfg(feature = "fetch-data")]
+const DATA_URL: &str =
+    "https://raw.githubusercontent.com/TKanX/dunbrack/main/data/dunbrack-2010.lib.csv";
+
+/// SHA-256 of the CSV at [`DATA_URL`], as lowercase hex.
+#[cfg(feature = "fetch-data")]
+const DATA_SHA256: &str = "8c55da338e09464ece8854b0354d763952708f99aa68c34ba8a4a6fe46cca15b";
+
+/// Downloads the library CSV and verifies its SHA-256.
+///
+/// `DUNBRACK_DATA_URL` overrides the source, e.g. to build against another
+/// smoothing level of the library; it then requires `DUNBRACK_DATA_SHA256`,
+/// since an unverified download is never used. The verified file is kept in
+/// `OUT_DIR` and reused while the expected hash is unchanged, so rebuilds
+/// stay offline.
+#[cfg(feature = "fetch-data")]
+fn read_csv(out_dir: &Path) -> Vec<u8> {
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+
+    println!("cargo::rerun-if-env-changed=DUNBRACK_DATA_URL");
+    println!("cargo::rerun-if-env-changed=DUNBRACK_DATA_SHA256");
+
+    let url = env::var("DUNBRACK_DATA_URL").ok();
+    let sha256 = env::var("DUNBRACK_DATA_SHA256").ok();
+    let (url, expected) = match (url, sha256) {
+        (None, None) => (DATA_URL.to_string(), DATA_SHA256.to_string()),
+        (Some(url), Some(sha256)) => (url, sha256.trim().to_ascii_lowercase()),
+        (Some(_), None) => {
+            panic!("build.rs: DUNBRACK_DATA_URL is set but DUNBRACK_DATA_SHA256 is not")
+        }
+        (None, Some(_)) => {
+            panic!("build.rs: DUNBRACK_DATA_SHA256 is set but DUNBRACK_DATA_URL is not")
+        }
+    };
+    let digest = |bytes: &[u8]| -> String {
+        Sha256::digest(bytes)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    };
+
+    let cache_path = out_dir.join("dunbrack.lib.csv");
+    if let Ok(csv) = fs::read(&cache_path)
+        && digest(&csv) == expected
+    {
+        return csv;
+    }
+
+    let mut csv = Vec::new();
+    ureq::get(&url)
+        .call()
+        .unwrap_or_else(|e| panic!("build.rs: cannot download CSV from {url}: {e}"))
+        .into_reader()
+        .read_to_end(&mut csv)
+        .unwrap_or_else(|e| panic!("build.rs: cannot download CSV from {url}: {e}"));
+    let actual = digest(&csv);
+    assert!(
+        actual == expected,
+        "build.rs: CSV from {url} has SHA-256 {actual}, expected {expected}"
+    );
+
+    fs::write(&cache_path, &csv).unwrap();
+    csv
+}
+
+/// Asserts that every residue of the CSV named `source` is one of
+/// [`RESIDUES`].
+fn check_residue_names(tables: &HashMap<String, Grid>, source: &str) {
+    let mut unknown: Vec<&str> = tables
+        .keys()
+        .map(String::as_str)
+        .filter(|res| !RESIDUES.iter().any(|&(csv_name, ..)| csv_name == *res))
+        .collect();
+    unknown.sort_unstable();
+    assert!(
+        unknown.is_empty(),
+        "build.rs: {source} lists residue(s) {unknown:?} missing from RESIDUES"
+    );
+}
+
+/// Reads the second library CSV named by `DUNBRACK_ALT_DATA` when the
+/// `alt-data` feature is enabled.
+fn read_alt_csv() -> Option<Vec<u8>> {
+    env::var_os("CARGO_FEATURE_ALT_DATA")?;
+    println!("cargo::rerun-if-env-changed=DUNBRACK_ALT_DATA");
+
+    let path = env::var_os("DUNBRACK_ALT_DATA").unwrap_or_else(|| {
+        panic!("build.rs: the `alt-data` feature requires DUNBRACK_ALT_DATA (path to a CSV)")
+    });
+    let path = Path::new(&path);
+    println!("cargo::rerun-if-changed={}", path.display());
+    Some(
+        fs::read(path)
+            .unwrap_or_else(|e| panic!("build.rs: cannot read DUNBRACK_ALT_DATA {path:?}: {e}")),
+    )
+}
+
+/// FNV-1a offset basis.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Folds `bytes` into a 64-bit FNV-1a hash.
+fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for &b in bytes {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
+
+/// Parses the CSV into per-residue grids, splitting the rows into one
+/// contiguous chunk per available core.
+fn parse_csv(csv: &[u8]) -> HashMap<String, Grid> {
+    let header_end = csv
+        .iter()
+        .position(|&b| b == b'\n')
+        .map_or(csv.len(), |i| i + 1);
+    let header = String::from_utf8_lossy(&csv[..header_end]);
+    let expected = COLUMNS.join(",");
+    assert!(
+        header.trim_end() == expected,
+        "build.rs: CSV header is {:?}, expected {expected:?}",
+        header.trim_end()
+    );
+    let body = &csv[header_end..];
 
-    let mut tables: HashMap<String, Vec<Vec<Vec<RawRow>>>> = HashMap::new();
+    let n_chunks = thread::available_parallelism().map_or(1, |n| n.get());
+    let mut chunks = Vec::with_capacity(n_chunks);
+    let mut start = 0;
+    for k in 1..=n_chunks {
+        let mut end = (body.len() * k / n_chunks).max(start);
+        while end < body.len() && body[end - 1] != b'\n' {
+            end += 1;
+        }
+        chunks.push(&body[start..end]);
+        start = end;
+    }
+
+    // File line number of the first row of each chunk, for diagnostics.
+    let mut first_line = 2;
+    let lines: Vec<u64> = chunks
+        .iter()
+        .map(|chunk| {
+            let line = first_line;
+            first_line += chunk.iter().filter(|&&b| b == b'\n').count() as u64;
+            line
+        })
+        .collect();
+
+    let parsed: Vec<HashMap<String, Grid>> = thread::scope(|s| {
+        let handles: Vec<_> = chunks
+            .into_iter()
+            .zip(lines)
+            .map(|(chunk, line)| s.spawn(move || parse_chunk(chunk, line)))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .collect()
+    });
+
+    // Merge in chunk order so rows keep their file order within each cell.
+    let mut tables: HashMap<String, Grid> = HashMap::new();
+    for part in parsed {
+        for (res, grid) in part {
+            match tables.get_mut(&res) {
+                Some(dst) => {
+                    for (dst_row, src_row) in dst.iter_mut().zip(grid) {
+                        for (dst_cell, src_cell) in dst_row.iter_mut().zip(src_row) {
+                            dst_cell.extend(src_cell);
+                        }
+                    }
+                }
+                None => {
+                    tables.insert(res, grid);
+                }
+            }
+        }
+    }
+    tables
+}
 
-    let mut reader = ReaderBuilder::new()
-        .has_headers(true)
-        .from_path(csv_path)
-        .unwrap_or_else(|e| panic!("build.rs: cannot open CSV: {e}"));
+/// Parses a headerless run of complete CSV lines, the first of which is
+/// line `first_line` of the file.
+///
+/// Every field is checked as it is read: angles must be finite, bin
+/// indices and probabilities must parse, φ and ψ must lie on the grid,
+/// probabilities in \[0, 1\], χ means in \[−180°, 180°\], and σ must be
+/// finite and non-negative. Failures name the file line and column.
+fn parse_chunk(chunk: &[u8], first_line: u64) -> HashMap<String, Grid> {
+    let mut tables: HashMap<String, Grid> = HashMap::new();
+
+    let mut reader = ReaderBuilder::new().has_headers(false).from_reader(chunk);
 
     for result in reader.records() {
         let record = result.unwrap_or_else(|e| panic!("build.rs: malformed CSV row: {e}"));
+        let line = first_line + record.position().map_or(1, |p| p.line()) - 1;
+        let field = |col: usize| -> &str { &record[col] };
+        let number = |col: usize| -> f32 {
+            field(col)
+                .parse()
+                .ok()
+                .filter(|v: &f32| v.is_finite())
+                .unwrap_or_else(|| {
+                    panic!(
+                        "build.rs: CSV line {line}: column `{}` is not a finite number: {:?}",
+                        COLUMNS[col],
+                        field(col)
+                    )
+                })
+        };
+        let bin = |col: usize| -> u8 {
+            field(col).parse().unwrap_or_else(|_| {
+                panic!(
+                    "build.rs: CSV line {line}: column `{}` is not a bin index: {:?}",
+                    COLUMNS[col],
+                    field(col)
+                )
+            })
+        };
+        let check = |ok: bool, col: usize, expected: &str| {
+            assert!(
+                ok,
+                "build.rs: CSV line {line}: column `{}` = {} is outside {expected}",
+                COLUMNS[col],
+                field(col)
+            );
+        };
+
+        let res: &str = field(0);
+        let phi_idx = angle_to_index(number(1), line, COLUMNS[1]);
+        let psi_idx = angle_to_index(number(2), line, COLUMNS[2]);
 
-        let res: &str = &record[0];
-        let phi: f32 = record[1].parse().unwrap();
-        let psi: f32 = record[2].parse().unwrap();
-
-        let r: [u8; 4] = [
-            record[3].parse().unwrap(),
-            record[4].parse().unwrap(),
-            record[5].parse().unwrap(),
-            record[6].parse().unwrap(),
-        ];
-        let prob: f32 = record[7].parse().unwrap();
-        let chi_val: [f32; 4] = [
-            record[8].parse().unwrap(),
-            record[9].parse().unwrap(),
-            record[10].parse().unwrap(),
-            record[11].parse().unwrap(),
-        ];
-        let chi_sig: [f32; 4] = [
-            record[12].parse().unwrap(),
-            record[13].parse().unwrap(),
-            record[14].parse().unwrap(),
-            record[15].parse().unwrap(),
-        ];
-
-        let phi_idx = angle_to_index(phi);
-        let psi_idx = angle_to_index(psi);
+        let r: [u8; 4] = [bin(3), bin(4), bin(5), bin(6)];
+        let prob = number(7);
+        check((0.0..=1.0).contains(&prob), 7, "[0, 1]");
+        let chi_val: [f32; 4] = core::array::from_fn(|i| number(8 + i));
+        let chi_sig: [f32; 4] = core::array::from_fn(|i| number(12 + i));
+        for i in 0..4 {
+            check((-180.0..=180.0).contains(&chi_val[i]), 8 + i, "[-180, 180]");
+            check(chi_sig[i] >= 0.0, 12 + i, "[0, ∞)");
+        }
 
         let grid = tables
             .entry(res.to_string())
@@ -109,158 +548,390 @@ fn main() {
         });
     }
 
-    let out_dir = env::var("OUT_DIR").unwrap();
-    let dest_path = Path::new(&out_dir).join("tables.rs");
-    let mut out = fs::File::create(&dest_path).unwrap();
+    tables
+}
 
-    writeln!(out, "// Auto-generated by build.rs — do not edit.").unwrap();
-    writeln!(out).unwrap();
+/// Writes the parsed grids to `path`, tagged with the hash of the CSV they
+/// were parsed from.
+///
+/// Layout (little-endian): magic, CSV hash, residue count, then per residue
+/// its name and, for each of the 37×37 cells, a row count followed by the
+/// rows as `r: [u8; 4]`, `prob`, `chi_val: [f32; 4]`, `chi_sig: [f32; 4]`.
+fn store_ir(path: &Path, csv_hash: u64, tables: &HashMap<String, Grid>) {
+    let mut ir = Vec::new();
+    ir.extend_from_slice(IR_MAGIC);
+    ir.extend_from_slice(&csv_hash.to_le_bytes());
+    ir.extend_from_slice(&(tables.len() as u32).to_le_bytes());
+    for (res, grid) in tables {
+        ir.push(res.len() as u8);
+        ir.extend_from_slice(res.as_bytes());
+        for cell in grid.iter().flatten() {
+            ir.extend_from_slice(&(cell.len() as u32).to_le_bytes());
+            for row in cell {
+                ir.extend_from_slice(&row.r);
+                ir.extend_from_slice(&row.prob.to_le_bytes());
+                for v in row.chi_val.iter().chain(&row.chi_sig) {
+                    ir.extend_from_slice(&v.to_le_bytes());
+                }
+            }
+        }
+    }
+    fs::write(path, ir).unwrap();
+}
 
-    for &(csv_name, rust_name, n_chi, n_rotamers) in RESIDUES {
-        let grid = tables
-            .get(csv_name)
-            .unwrap_or_else(|| panic!("build.rs: residue '{csv_name}' not found in CSV"));
+/// Reads grids previously written by [`store_ir`], or `None` if the file is
+/// missing, malformed, or was produced from a different CSV.
+fn load_ir(path: &Path, csv_hash: u64) -> Option<HashMap<String, Grid>> {
+    let ir = fs::read(path).ok()?;
+    let mut pos = 0;
+    let mut take = |n: usize| -> Option<&[u8]> {
+        let bytes = ir.get(pos..pos + n)?;
+        pos += n;
+        Some(bytes)
+    };
+    let u32_at = |b: &[u8]| u32::from_le_bytes(b.try_into().unwrap());
+    let f32_at = |b: &[u8]| f32::from_le_bytes(b.try_into().unwrap());
+
+    if take(4)? != IR_MAGIC || take(8)? != csv_hash.to_le_bytes() {
+        return None;
+    }
+
+    let n_residues = u32_at(take(4)?);
+    let mut tables = HashMap::new();
+    for _ in 0..n_residues {
+        let name_len = take(1)?[0] as usize;
+        let res = String::from_utf8(take(name_len)?.to_vec()).ok()?;
+        let mut grid: Grid = vec![vec![Vec::new(); GRID_COUNT]; GRID_COUNT];
+        for cell in grid.iter_mut().flatten() {
+            let n_rows = u32_at(take(4)?) as usize;
+            for _ in 0..n_rows {
+                let b = take(40)?;
+                let f = |i: usize| f32_at(&b[4 + 4 * i..8 + 4 * i]);
+                cell.push(RawRow {
+                    r: b[..4].try_into().unwrap(),
+                    prob: f(0),
+                    chi_val: [f(1), f(2), f(3), f(4)],
+                    chi_sig: [f(5), f(6), f(7), f(8)],
+                });
+            }
+        }
+        tables.insert(res, grid);
+    }
+    Some(tables)
+}
+
+/// Validates one residue's grid, writes its table blob, and returns the
+/// generated Rust source for its keys, table, and trait implementation.
+///
+/// With `alt`, the grid comes from the `alt-data` CSV and is emitted as the
+/// tables of `Alt<{rust_name}>` instead.
+fn generate_residue(
+    config: &BuildConfig,
+    csv_name: &str,
+    rust_name: &str,
+    n_chi: usize,
+    n_rotamers: usize,
+    grid: &Grid,
+    alt: bool,
+) -> Vec<u8> {
+    let mut out = Vec::new();
+
+    let (prefix, rust_type, label) = if alt {
+        (
+            format!("{csv_name}_ALT"),
+            format!("crate::residue::Alt<crate::residue::{rust_name}>"),
+            format!("{csv_name} (DUNBRACK_ALT_DATA)"),
+        )
+    } else {
+        (
+            csv_name.to_string(),
+            format!("crate::residue::{rust_name}"),
+            csv_name.to_string(),
+        )
+    };
+    let table_name = format!("{prefix}_TABLE");
+    let keys_name = format!("{prefix}_KEYS");
 
-        let table_name = format!("{csv_name}_TABLE");
-        let keys_name = format!("{csv_name}_KEYS");
+    for (phi_idx, phi_row) in grid.iter().enumerate() {
+        for (psi_idx, cell) in phi_row.iter().enumerate() {
+            let at = cell_label(phi_idx, psi_idx);
+            assert!(
+                cell.len() == n_rotamers,
+                "build.rs: {label} cell {at} has {} rows, expected one per rotamer \
+                 ({n_rotamers})",
+                cell.len()
+            );
 
-        for (phi_idx, phi_row) in grid.iter().enumerate() {
-            for (psi_idx, cell) in phi_row.iter().enumerate() {
+            for row in cell {
                 assert!(
-                    cell.len() == n_rotamers,
-                    "build.rs: {csv_name} cell ({phi_idx},{psi_idx}) has {} rotamers, \
-                     expected {n_rotamers}",
-                    cell.len()
+                    row.prob >= 0.0,
+                    "build.rs: {label} cell {at} has negative prob={v}",
+                    v = row.prob
                 );
-
-                for row in cell {
+                for i in 0..n_chi {
                     assert!(
-                        row.prob >= 0.0,
-                        "build.rs: {csv_name} cell ({phi_idx},{psi_idx}) has negative prob={v}",
-                        v = row.prob
+                        row.r[i] > 0,
+                        "build.rs: {label} cell {at} has r{j}=0",
+                        j = i + 1
+                    );
+                    assert!(
+                        row.chi_sig[i] > 0.0,
+                        "build.rs: {label} cell {at} has chi_sigma[{j}]={v}",
+                        j = i + 1,
+                        v = row.chi_sig[i]
                     );
-                    for i in 0..n_chi {
-                        assert!(
-                            row.r[i] > 0,
-                            "build.rs: {csv_name} cell ({phi_idx},{psi_idx}) has r{j}=0",
-                            j = i + 1
-                        );
-                        assert!(
-                            row.chi_sig[i] > 0.0,
-                            "build.rs: {csv_name} cell ({phi_idx},{psi_idx}) has chi_sigma[{j}]={v}",
-                            j = i + 1,
-                            v = row.chi_sig[i]
-                        );
-                    }
                 }
+            }
 
-                let prob_sum: f32 = cell.iter().map(|row| row.prob).sum();
-                assert!(
-                    (0.99..=1.01).contains(&prob_sum),
-                    "build.rs: {csv_name} cell ({phi_idx},{psi_idx}) prob sum = {prob_sum:.6}"
+            let mut keys: Vec<[u8; 4]> = cell.iter().map(|row| row.r).collect();
+            keys.sort_unstable();
+            if let Some(pair) = keys.windows(2).find(|pair| pair[0] == pair[1]) {
+                panic!(
+                    "build.rs: {label} cell {at} lists bin key {:?} more than once",
+                    &pair[0][..n_chi]
                 );
             }
-        }
 
-        for (cell_0, cell_36) in grid[0].iter().zip(grid[36].iter()) {
-            assert_eq!(
-                cell_0.len(),
-                cell_36.len(),
-                "build.rs: {csv_name} φ=-180/φ=180 rotamer count mismatch"
+            let prob_sum: f32 = cell.iter().map(|row| row.prob).sum();
+            assert!(
+                (0.99..=1.01).contains(&prob_sum),
+                "build.rs: {label} cell {at} prob sum = {prob_sum:.6}"
             );
+        }
+    }
 
-            let mut s0: Vec<_> = cell_0.iter().collect();
-            let mut s36: Vec<_> = cell_36.iter().collect();
-            s0.sort_by_key(|r| r.r);
-            s36.sort_by_key(|r| r.r);
+    for (cell_0, cell_36) in grid[0].iter().zip(grid[36].iter()) {
+        assert_eq!(
+            cell_0.len(),
+            cell_36.len(),
+            "build.rs: {label} φ=-180/φ=180 rotamer count mismatch"
+        );
 
-            for (a, b) in s0.iter().zip(s36.iter()) {
-                assert!(
-                    a.r == b.r
-                        && a.prob == b.prob
-                        && a.chi_val == b.chi_val
-                        && a.chi_sig == b.chi_sig,
-                    "build.rs: {csv_name} φ=-180/φ=180 data mismatch"
-                );
-            }
-        }
+        let mut s0: Vec<_> = cell_0.iter().collect();
+        let mut s36: Vec<_> = cell_36.iter().collect();
+        s0.sort_by_key(|r| r.r);
+        s36.sort_by_key(|r| r.r);
 
-        for (phi_idx, phi_row) in grid.iter().enumerate() {
-            let cell_0 = &phi_row[0];
-            let cell_36 = &phi_row[36];
-            assert_eq!(
-                cell_0.len(),
-                cell_36.len(),
-                "build.rs: {csv_name} ψ=-180/ψ=180 rotamer count mismatch at φ idx {phi_idx}"
+        for (a, b) in s0.iter().zip(s36.iter()) {
+            assert!(
+                a.r == b.r && a.prob == b.prob && a.chi_val == b.chi_val && a.chi_sig == b.chi_sig,
+                "build.rs: {label} φ=-180/φ=180 data mismatch"
             );
+        }
+    }
 
-            let mut s0: Vec<_> = cell_0.iter().collect();
-            let mut s36: Vec<_> = cell_36.iter().collect();
-            s0.sort_by_key(|r| r.r);
-            s36.sort_by_key(|r| r.r);
+    for (phi_idx, phi_row) in grid.iter().enumerate() {
+        let cell_0 = &phi_row[0];
+        let cell_36 = &phi_row[36];
+        assert_eq!(
+            cell_0.len(),
+            cell_36.len(),
+            "build.rs: {label} ψ=-180/ψ=180 rotamer count mismatch at φ idx {phi_idx}"
+        );
 
-            for (a, b) in s0.iter().zip(s36.iter()) {
-                assert!(
-                    a.r == b.r
-                        && a.prob == b.prob
-                        && a.chi_val == b.chi_val
-                        && a.chi_sig == b.chi_sig,
-                    "build.rs: {csv_name} ψ=-180/ψ=180 data mismatch at φ idx {phi_idx}"
-                );
-            }
+        let mut s0: Vec<_> = cell_0.iter().collect();
+        let mut s36: Vec<_> = cell_36.iter().collect();
+        s0.sort_by_key(|r| r.r);
+        s36.sort_by_key(|r| r.r);
+
+        for (a, b) in s0.iter().zip(s36.iter()) {
+            assert!(
+                a.r == b.r && a.prob == b.prob && a.chi_val == b.chi_val && a.chi_sig == b.chi_sig,
+                "build.rs: {label} ψ=-180/ψ=180 data mismatch at φ idx {phi_idx}"
+            );
         }
+    }
 
-        let mut sorted_grid: Vec<Vec<Vec<&RawRow>>> =
-            vec![vec![Vec::new(); GRID_COUNT]; GRID_COUNT];
+    let mut sorted_grid: Vec<Vec<Vec<&RawRow>>> = vec![vec![Vec::new(); GRID_COUNT]; GRID_COUNT];
 
-        for (phi_idx, phi_row) in grid.iter().enumerate() {
-            for (psi_idx, cell) in phi_row.iter().enumerate() {
-                let mut sorted: Vec<&RawRow> = cell.iter().collect();
-                sorted.sort_by_key(|row| row.r);
-                sorted_grid[phi_idx][psi_idx] = sorted;
-            }
+    for (phi_idx, phi_row) in grid.iter().enumerate() {
+        for (psi_idx, cell) in phi_row.iter().enumerate() {
+            let mut sorted: Vec<&RawRow> = cell.iter().collect();
+            sorted.sort_by_key(|row| row.r);
+            sorted_grid[phi_idx][psi_idx] = sorted;
         }
+    }
 
-        let canonical_keys: Vec<[u8; 4]> = sorted_grid[0][0].iter().map(|row| row.r).collect();
-        for (phi_idx, phi_row) in sorted_grid.iter().enumerate() {
-            for (psi_idx, cell) in phi_row.iter().enumerate() {
-                let cell_keys: Vec<[u8; 4]> = cell.iter().map(|row| row.r).collect();
-                assert!(
-                    cell_keys == canonical_keys,
-                    "build.rs: {csv_name} cell ({phi_idx},{psi_idx}) has different \
-                     bin index key set than cell (0,0)"
-                );
-            }
+    let canonical_keys: Vec<[u8; 4]> = sorted_grid[0][0].iter().map(|row| row.r).collect();
+    for (phi_idx, phi_row) in sorted_grid.iter().enumerate() {
+        for (psi_idx, cell) in phi_row.iter().enumerate() {
+            let at = cell_label(phi_idx, psi_idx);
+            let cell_keys: Vec<[u8; 4]> = cell.iter().map(|row| row.r).collect();
+            assert!(
+                cell_keys == canonical_keys,
+                "build.rs: {label} cell {at} has different bin index key set than \
+                 cell {}",
+                cell_label(0, 0)
+            );
         }
+    }
+
+    check_bin_ranges(&label, csv_name, n_chi, &canonical_keys, grid);
+    emit_keys(&mut out, &keys_name, n_chi, &canonical_keys);
+    emit_bin_ranges(&mut out, csv_name, &keys_name, n_chi, &canonical_keys);
+    emit_reference_energies(&mut out, &table_name, &sorted_grid);
+    emit_default_rotamer(&mut out, &table_name, n_chi, &sorted_grid);
+    emit_table(
+        &mut out,
+        config,
+        &table_name,
+        n_chi,
+        n_rotamers,
+        &sorted_grid,
+    );
+    if config.gradients {
+        emit_gradients(&mut out, config, &table_name, &sorted_grid);
+    }
+    let library = if alt { "Alt" } else { "Primary" };
+    emit_sealed(
+        &mut out,
+        &rust_type,
+        library,
+        &table_name,
+        &keys_name,
+        n_chi,
+        n_rotamers,
+    );
+    emit_impl(
+        &mut out,
+        &rust_type,
+        csv_name,
+        &table_name,
+        &keys_name,
+        n_chi,
+        n_rotamers,
+    );
 
-        emit_keys(&mut out, &keys_name, n_chi, &canonical_keys);
-        emit_table(&mut out, &table_name, n_chi, n_rotamers, &sorted_grid);
+    if config.chi1 && !alt {
+        let reduced = reduce_to_chi1(&sorted_grid);
+        let reduced_grid: Vec<Vec<Vec<&RawRow>>> = reduced
+            .iter()
+            .map(|phi_row| phi_row.iter().map(|cell| cell.iter().collect()).collect())
+            .collect();
+        let reduced_keys: Vec<[u8; 4]> = reduced[0][0].iter().map(|row| row.r).collect();
+        let n_reduced = reduced_keys.len();
+        let table_name = format!("{csv_name}_CHI1_TABLE");
+        let keys_name = format!("{csv_name}_CHI1_KEYS");
+
+        emit_keys(&mut out, &keys_name, 1, &reduced_keys);
+        emit_bin_ranges(&mut out, csv_name, &keys_name, 1, &reduced_keys);
+        emit_reference_energies(&mut out, &table_name, &reduced_grid);
+        emit_default_rotamer(&mut out, &table_name, 1, &reduced_grid);
+        emit_table(&mut out, config, &table_name, 1, n_reduced, &reduced_grid);
+        if config.gradients {
+            emit_gradients(&mut out, config, &table_name, &reduced_grid);
+        }
+        let rust_type = format!("crate::residue::Chi1<crate::residue::{rust_name}>");
+        emit_sealed(
+            &mut out,
+            &rust_type,
+            "Chi1",
+            &table_name,
+            &keys_name,
+            1,
+            n_reduced,
+        );
         emit_impl(
             &mut out,
-            rust_name,
+            &rust_type,
             csv_name,
             &table_name,
             &keys_name,
-            n_chi,
-            n_rotamers,
+            1,
+            n_reduced,
         );
     }
 
-    emit_for_all_residues_macro(&mut out);
+    out
+}
+
+/// Collapses every cell to one row per χ1 bin.
+///
+/// Probabilities are summed over the rotamers sharing a χ1 bin. The χ1 mean
+/// is their probability-weighted circular mean and σ the standard deviation
+/// of the resulting mixture (within-rotamer variance plus the spread of the
+/// means). A bin with zero total probability in a cell weights its rotamers
+/// uniformly. Rows stay sorted by bin index.
+fn reduce_to_chi1(grid: &[Vec<Vec<&RawRow>>]) -> Grid {
+    grid.iter()
+        .map(|phi_row| {
+            phi_row
+                .iter()
+                .map(|cell| {
+                    let mut bins: Vec<u8> = cell.iter().map(|row| row.r[0]).collect();
+                    bins.dedup();
+                    bins.iter()
+                        .map(|&bin| {
+                            let members: Vec<&RawRow> =
+                                cell.iter().copied().filter(|row| row.r[0] == bin).collect();
+                            let prob: f64 = members.iter().map(|row| row.prob as f64).sum();
+                            let weight = |row: &RawRow| {
+                                if prob > 0.0 {
+                                    row.prob as f64 / prob
+                                } else {
+                                    1.0 / members.len() as f64
+                                }
+                            };
+
+                            let (mut s, mut c) = (0.0_f64, 0.0_f64);
+                            for row in &members {
+                                let rad = row.chi_val[0] as f64 * DEG_TO_RAD;
+                                s += weight(row) * rad.sin();
+                                c += weight(row) * rad.cos();
+                            }
+                            let mean = s.atan2(c) / DEG_TO_RAD;
+
+                            let mut var = 0.0_f64;
+                            for row in &members {
+                                let d = (row.chi_val[0] as f64 - mean + 540.0).rem_euclid(360.0)
+                                    - 180.0;
+                                let sig = row.chi_sig[0] as f64;
+                                var += weight(row) * (sig * sig + d * d);
+                            }
+
+                            RawRow {
+                                r: [bin, 0, 0, 0],
+                                prob: prob as f32,
+                                chi_val: [mean as f32, 0.0, 0.0, 0.0],
+                                chi_sig: [var.sqrt() as f32, 0.0, 0.0, 0.0],
+                            }
+                        })
+                        .collect()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Human-readable position of grid cell (`phi_idx`, `psi_idx`) for
+/// diagnostics.
+fn cell_label(phi_idx: usize, psi_idx: usize) -> String {
+    format!(
+        "(φ={}°, ψ={}°)",
+        GRID_MIN + phi_idx as f32 * GRID_STEP,
+        GRID_MIN + psi_idx as f32 * GRID_STEP
+    )
 }
 
-/// Converts a grid angle (−180..180, step 10) to a table index (0..36).
-fn angle_to_index(deg: f32) -> usize {
-    let idx = ((deg - GRID_MIN) / GRID_STEP).round() as usize;
-    assert!(idx < GRID_COUNT, "build.rs: angle {deg}° out of grid range");
-    idx
+/// Converts a grid angle (−180..180, step 10) to a table index (0..36),
+/// failing with CSV line `line` and `column` if `deg` is not a grid point.
+fn angle_to_index(deg: f32, line: u64, column: &str) -> usize {
+    let steps = (deg - GRID_MIN) / GRID_STEP;
+    let idx = steps.round();
+    assert!(
+        (0.0..GRID_COUNT as f32).contains(&idx) && (steps - idx).abs() < 1e-3,
+        "build.rs: CSV line {line}: column `{column}` = {deg} is not a grid point \
+         (−180° to 180° in steps of 10°)"
+    );
+    idx as usize
 }
 
 /// Emits a `static KEYS: [[u8; N]; R]` array containing the deduplicated
 /// rotamer bin indices. Since the bin indices are identical across all 37×37
 /// cells for any given residue (asserted above), storing them once saves
-/// R×37×37×N bytes.
-fn emit_keys(out: &mut fs::File, keys_name: &str, n_chi: usize, canonical_keys: &[[u8; 4]]) {
+/// R×37×37×N bytes. Also emits `const KEYS_BINS: [u8; N]`, the number of
+/// bins of each χ: bins are numbered from 1, so the largest index in use.
+fn emit_keys(out: &mut Vec<u8>, keys_name: &str, n_chi: usize, canonical_keys: &[[u8; 4]]) {
     let n_rotamers = canonical_keys.len();
     writeln!(out, "static {keys_name}: [[u8; {n_chi}]; {n_rotamers}] = [").unwrap();
     for key in canonical_keys {
@@ -268,138 +939,1176 @@ fn emit_keys(out: &mut fs::File, keys_name: &str, n_chi: usize, canonical_keys:
         writeln!(out, "[{}],", elems.join(", ")).unwrap();
     }
     writeln!(out, "];").unwrap();
+    let bins: Vec<String> = bin_counts(n_chi, canonical_keys)
+        .iter()
+        .map(|b| b.to_string())
+        .collect();
+    writeln!(
+        out,
+        "const {keys_name}_BINS: [u8; {n_chi}] = [{}];",
+        bins.join(", ")
+    )
+    .unwrap();
     writeln!(out).unwrap();
 }
 
-/// Emits a `static TABLE: [[[GridEntry<N>; R]; 37]; 37]` declaration.
-///
-/// Each `GridEntry` stores the rotamer probability, precomputed `(sin χᵢ, cos χᵢ)`
-/// pairs for all χ angles, and per-χ standard deviations.
-fn emit_table(
-    out: &mut fs::File,
-    table_name: &str,
+/// Number of bins of each χ: bins are numbered from 1, so the largest
+/// index in use.
+fn bin_counts(n_chi: usize, canonical_keys: &[[u8; 4]]) -> Vec<u8> {
+    (0..n_chi)
+        .map(|i| canonical_keys.iter().map(|key| key[i]).max().unwrap_or(0))
+        .collect()
+}
+
+/// Returns `(start, end, period)` in degrees of every bin of χ `chi` of
+/// `csv_name`, which has `bins` bins. See [`SEMI_ROTAMERIC`].
+fn bin_ranges(csv_name: &str, chi: usize, bins: u8) -> Vec<(f32, f32, f32)> {
+    let (start, period) = SEMI_ROTAMERIC
+        .iter()
+        .find(|&&(name, i, _, _)| name == csv_name && i == chi)
+        .map_or((0.0, 360.0), |&(_, _, start, period)| (start, period));
+    let width = period / bins as f32;
+    (0..bins)
+        .map(|k| {
+            let lo = start + width * k as f32;
+            (lo, lo + width, period)
+        })
+        .collect()
+}
+
+/// Whether `deg` lies in the half-open range `(start, end, period)`.
+fn range_contains((start, end, period): (f32, f32, f32), deg: f32) -> bool {
+    (deg - start).rem_euclid(period) < end - start
+}
+
+/// Asserts that every tabulated mean of a rotameric or proline χ lies in
+/// the range of its bin. Semi-rotameric bins shift with χ1 and the
+/// backbone, so their nominal ranges are not checked.
+fn check_bin_ranges(
+    label: &str,
+    csv_name: &str,
     n_chi: usize,
-    n_rotamers: usize,
-    grid: &[Vec<Vec<&RawRow>>],
+    canonical_keys: &[[u8; 4]],
+    grid: &Grid,
+) {
+    let bins = bin_counts(n_chi, canonical_keys);
+    for chi in 0..n_chi {
+        if SEMI_ROTAMERIC
+            .iter()
+            .any(|&(name, i, _, _)| name == csv_name && i == chi)
+        {
+            continue;
+        }
+        let ranges = bin_ranges(csv_name, chi, bins[chi]);
+        for row in grid.iter().flatten().flatten() {
+            let range = ranges[usize::from(row.r[chi]) - 1];
+            assert!(
+                range_contains(range, row.chi_val[chi]),
+                "build.rs: {label} χ{j} mean {v}° lies outside bin {b} {range:?}",
+                j = chi + 1,
+                v = row.chi_val[chi],
+                b = row.r[chi]
+            );
+        }
+    }
+}
+
+/// Emits `const {KEYS}_RANGES: [&[BinRange]; N]`, the angular range of
+/// every bin of each χ from [`bin_ranges`].
+fn emit_bin_ranges(
+    out: &mut Vec<u8>,
+    csv_name: &str,
+    keys_name: &str,
+    n_chi: usize,
+    canonical_keys: &[[u8; 4]],
 ) {
+    let bins = bin_counts(n_chi, canonical_keys);
     writeln!(
         out,
-        "static {table_name}: [[[crate::interp::GridEntry<{n_chi}>; {n_rotamers}]; \
-         {GRID_COUNT}]; {GRID_COUNT}] = ["
+        "const {keys_name}_RANGES: [&[crate::bins::BinRange]; {n_chi}] = ["
     )
     .unwrap();
-
-    for phi_row in grid.iter() {
-        writeln!(out, "[").unwrap();
-        for cell in phi_row.iter() {
-            writeln!(out, "[").unwrap();
-            for row in cell.iter() {
-                emit_grid_entry(out, row, n_chi);
-            }
-            writeln!(out, "],").unwrap();
-        }
-        writeln!(out, "],").unwrap();
+    for (chi, &count) in bins.iter().enumerate() {
+        let ranges: Vec<String> = bin_ranges(csv_name, chi, count)
+            .iter()
+            .map(|(start, end, period)| {
+                format!("crate::bins::BinRange::new({start:?}, {end:?}, {period:?})")
+            })
+            .collect();
+        writeln!(out, "&[{}],", ranges.join(", ")).unwrap();
     }
-
     writeln!(out, "];").unwrap();
     writeln!(out).unwrap();
 }
 
-/// Emits a single `GridEntry { prob, chi_sin, chi_cos, chi_sigma }` literal.
-///
-/// `chi_sin[i]` = sin(chi_val[i] × π/180), `chi_cos[i]` = cos(chi_val[i] × π/180).
-/// Using f64 for the intermediate computation preserves full f32 precision.
-fn emit_grid_entry(out: &mut fs::File, row: &RawRow, n_chi: usize) {
-    write!(
+/// Emits `const {TABLE}_REF_ENERGIES: [f32; R]`, the reference energy
+/// `−ln ⟨pₖ⟩` of every rotamer, where `⟨pₖ⟩` is its probability averaged
+/// uniformly over the 36×36 unique grid cells (the duplicated +180° row and
+/// column are skipped). Rotamers that never occur get `+∞`.
+fn emit_reference_energies(out: &mut Vec<u8>, table_name: &str, grid: &[Vec<Vec<&RawRow>>]) {
+    let n_rotamers = grid[0][0].len();
+    let mut mean = vec![0.0_f64; n_rotamers];
+    for phi_row in &grid[..GRID_PERIOD] {
+        for cell in &phi_row[..GRID_PERIOD] {
+            for (m, row) in mean.iter_mut().zip(cell) {
+                *m += row.prob as f64;
+            }
+        }
+    }
+    let n_cells = (GRID_PERIOD * GRID_PERIOD) as f64;
+
+    let elems: Vec<String> = mean
+        .iter()
+        .map(|&sum| {
+            let p = sum / n_cells;
+            if p > 0.0 {
+                format!("{:?}_f32", (-p.ln()) as f32)
+            } else {
+                "f32::INFINITY".to_string()
+            }
+        })
+        .collect();
+    writeln!(
         out,
-        "crate::interp::GridEntry {{ prob: {}_f32, ",
-        format_f32(row.prob)
+        "const {table_name}_REF_ENERGIES: [f32; {n_rotamers}] = [{}];",
+        elems.join(", ")
     )
     .unwrap();
+    writeln!(out).unwrap();
+}
 
-    write!(out, "chi_sin: [").unwrap();
-    for i in 0..n_chi {
-        if i > 0 {
-            write!(out, ", ").unwrap();
-        }
-        let sin_val = ((row.chi_val[i] as f64) * DEG_TO_RAD).sin() as f32;
-        write!(out, "{}_f32", format_f32(sin_val)).unwrap();
-    }
-    write!(out, "], ").unwrap();
-
-    write!(out, "chi_cos: [").unwrap();
-    for i in 0..n_chi {
-        if i > 0 {
-            write!(out, ", ").unwrap();
+/// Emits `const {TABLE}_DEFAULT: Rotamer<N>`, the rotamer with the highest
+/// probability averaged over the 36×36 unique grid cells. Its `prob` is that
+/// average, its χ means the probability-weighted circular means over the
+/// cells, and its σ the probability-weighted mean σ.
+fn emit_default_rotamer(
+    out: &mut Vec<u8>,
+    table_name: &str,
+    n_chi: usize,
+    grid: &[Vec<Vec<&RawRow>>],
+) {
+    let n_rotamers = grid[0][0].len();
+    let mut mass = vec![0.0_f64; n_rotamers];
+    for phi_row in &grid[..GRID_PERIOD] {
+        for cell in &phi_row[..GRID_PERIOD] {
+            for (m, row) in mass.iter_mut().zip(cell) {
+                *m += row.prob as f64;
+            }
         }
-        let cos_val = ((row.chi_val[i] as f64) * DEG_TO_RAD).cos() as f32;
-        write!(out, "{}_f32", format_f32(cos_val)).unwrap();
     }
-    write!(out, "], ").unwrap();
+    let best = (0..n_rotamers).fold(0, |best, k| if mass[k] > mass[best] { k } else { best });
 
-    write!(out, "chi_sigma: [").unwrap();
-    for i in 0..n_chi {
-        if i > 0 {
-            write!(out, ", ").unwrap();
+    let (mut sin, mut cos, mut sigma) = ([0.0_f64; 4], [0.0_f64; 4], [0.0_f64; 4]);
+    for phi_row in &grid[..GRID_PERIOD] {
+        for cell in &phi_row[..GRID_PERIOD] {
+            let row = cell[best];
+            let p = row.prob as f64;
+            for i in 0..n_chi {
+                let chi = (row.chi_val[i] as f64).to_radians();
+                sin[i] += p * chi.sin();
+                cos[i] += p * chi.cos();
+                sigma[i] += p * row.chi_sig[i] as f64;
+            }
         }
-        write!(out, "{}_f32", format_f32(row.chi_sig[i])).unwrap();
     }
-    writeln!(out, "] }},").unwrap();
-}
-
-/// Formats an f32 with the shortest representation that round-trips exactly.
-fn format_f32(v: f32) -> String {
-    format!("{v:?}")
+    let n_cells = (GRID_PERIOD * GRID_PERIOD) as f64;
+    let row = grid[0][0][best];
+    let list = |values: &mut dyn Iterator<Item = f64>| {
+        values
+            .map(|v| format!("{:?}_f32", v as f32))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let r: Vec<String> = row.r[..n_chi].iter().map(u8::to_string).collect();
+    writeln!(
+        out,
+        "const {table_name}_DEFAULT: crate::rotamer::Rotamer<{n_chi}> = crate::rotamer::Rotamer {{ \
+         r: [{}], prob: {:?}_f32, chi_mean: [{}], chi_sigma: [{}] }};",
+        r.join(", "),
+        (mass[best] / n_cells) as f32,
+        list(&mut (0..n_chi).map(|i| sin[i].atan2(cos[i]).to_degrees())),
+        list(&mut (0..n_chi).map(|i| sigma[i] / mass[best])),
+    )
+    .unwrap();
+    writeln!(out).unwrap();
 }
 
-/// Emits `impl sealed::Sealed for X {}` and `impl Residue for X { … }`.
-fn emit_impl(
-    out: &mut fs::File,
-    rust_name: &str,
-    csv_name: &str,
+/// Writes the table as raw binary blobs and emits a `static TABLE` that
+/// embeds them via `include_bytes!`.
+///
+/// Only the 36 unique φ and ψ grid points are stored; the +180° row and
+/// column, which the source file repeats from −180°, are checked to match
+/// and dropped. Cells whose entries are bit-for-bit identical are stored
+/// once when the saving outweighs a `[[u16; 36]; 36]` index of cell
+/// numbers, emitted as `TABLE_INDEX`; other tables store all 36 × 36 cells
+/// in φ-major order and share the dense index.
+///
+/// The cell blob is laid out exactly like `[[GridEntry<N>; R]; C]`: every
+/// `GridEntry` is `#[repr(C)]` and consists solely of `f32` fields, so the
+/// cells are a flat run of `C × R × (1 + 3N)` floats in the target's byte
+/// order. The generated `static`s reinterpret the bytes with a `transmute`,
+/// which the compiler rejects unless the blob size matches the array size
+/// exactly; every bit pattern is a valid `f32`, so no other invariant needs
+/// to hold. Embedding bytes instead of ~150 MB of float literals keeps the
+/// generated source tiny and fast to parse. `TABLE_BYTES` totals the cells
+/// and the residue's own index.
+///
+/// With the `delta-tables` feature, a table whose values are all exactly
+/// representable in the delta encoding is embedded encoded instead, see
+/// [`emit_delta_table`].
+fn emit_table(
+    out: &mut Vec<u8>,
+    config: &BuildConfig,
     table_name: &str,
-    keys_name: &str,
     n_chi: usize,
     n_rotamers: usize,
+    grid: &[Vec<Vec<&RawRow>>],
 ) {
-    writeln!(
-        out,
-        "impl crate::sealed::Sealed for crate::residue::{rust_name} {{}}"
-    )
-    .unwrap();
+    let same = |a: &[&RawRow], b: &[&RawRow]| {
+        a.iter().zip(b).all(|(x, y)| {
+            x.r == y.r && x.prob == y.prob && x.chi_val == y.chi_val && x.chi_sig == y.chi_sig
+        })
+    };
+    for (idx, phi_row) in grid.iter().enumerate() {
+        assert!(
+            same(&grid[GRID_PERIOD][idx], &grid[0][idx])
+                && same(&phi_row[GRID_PERIOD], &phi_row[0]),
+            "build.rs: {table_name} is not periodic at grid index {idx}"
+        );
+    }
+    if config.delta
+        && let Some(blob) = encode_delta(grid, n_chi)
+    {
+        emit_delta_table(out, config, table_name, n_chi, n_rotamers, grid, &blob);
+        return;
+    }
+
+    let floats_per_entry = 1 + 3 * n_chi;
+    let mut cells: Vec<Vec<u8>> = Vec::with_capacity(GRID_PERIOD * GRID_PERIOD);
+    for phi_row in &grid[..GRID_PERIOD] {
+        for cell in &phi_row[..GRID_PERIOD] {
+            let mut bytes = Vec::with_capacity(n_rotamers * floats_per_entry * 4);
+            let mut push = |v: f32| {
+                if config.big_endian {
+                    bytes.extend_from_slice(&v.to_be_bytes());
+                } else {
+                    bytes.extend_from_slice(&v.to_le_bytes());
+                }
+            };
+            for row in cell.iter() {
+                push_grid_entry(&mut push, row, n_chi);
+            }
+            cells.push(bytes);
+        }
+    }
+
+    // Number the distinct cells in order of first appearance.
+    let mut numbers: HashMap<&[u8], u16> = HashMap::new();
+    let index: Vec<u16> = cells
+        .iter()
+        .map(|cell| {
+            let next = numbers.len() as u16;
+            *numbers.entry(cell).or_insert(next)
+        })
+        .collect();
+    let cell_len = n_rotamers * floats_per_entry * 4;
+    let dedup = numbers.len() * cell_len + index.len() * 2 < cells.len() * cell_len;
+
+    let mut blob = Vec::with_capacity(cells.len() * cell_len);
+    let mut n_cells = 0;
+    for (cell, &number) in cells.iter().zip(&index) {
+        // Numbers follow first appearance, so a new number is a new cell.
+        if !dedup || number as usize == n_cells {
+            blob.extend_from_slice(cell);
+            n_cells += 1;
+        }
+    }
+    let blob_name = format!("{table_name}.bin");
+    fs::write(config.out_dir.join(&blob_name), &blob).unwrap();
 
+    let with_chi = if config.fast_chi {
+        emit_chi_centidegrees(out, config, table_name, n_chi, grid);
+        format!(".with_chi(&{table_name}_CHI)")
+    } else {
+        String::new()
+    };
     writeln!(
         out,
-        "impl crate::residue::Residue for crate::residue::{rust_name} {{"
+        "const _: () = assert!(core::mem::size_of::<crate::interp::GridEntry<{n_chi}>>() \
+         == {floats_per_entry} * core::mem::size_of::<f32>());"
     )
     .unwrap();
-    writeln!(out, "    const N_CHI: usize = {n_chi};").unwrap();
-    writeln!(out, "    const N_ROTAMERS: usize = {n_rotamers};").unwrap();
-    writeln!(out, "    const NAME: &'static str = \"{csv_name}\";").unwrap();
-    writeln!(out, "    type Rot = crate::rotamer::Rotamer<{n_chi}>;").unwrap();
     writeln!(
         out,
-        "    type Iter = crate::interp::RotamerIter<{n_chi}, {n_rotamers}>;"
+        "static {table_name}_CELLS: [[crate::interp::GridEntry<{n_chi}>; {n_rotamers}]; {n_cells}] = \
+         unsafe {{ core::mem::transmute(*include_bytes!(concat!(env!(\"OUT_DIR\"), \"/{blob_name}\"))) }};"
     )
     .unwrap();
-    writeln!(out, "    #[inline]").unwrap();
-    writeln!(
+    if dedup {
+        let index_name = format!("{table_name}_INDEX.bin");
+        let bytes: Vec<u8> = index
+            .iter()
+            .flat_map(|&n| {
+                if config.big_endian {
+                    n.to_be_bytes()
+                } else {
+                    n.to_le_bytes()
+                }
+            })
+            .collect();
+        fs::write(config.out_dir.join(&index_name), bytes).unwrap();
+        writeln!(
+            out,
+            "static {table_name}_INDEX: [[u16; {GRID_PERIOD}]; {GRID_PERIOD}] = \
+             unsafe {{ core::mem::transmute(*include_bytes!(concat!(env!(\"OUT_DIR\"), \"/{index_name}\"))) }};"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "static {table_name}: crate::interp::Table<'static, {n_chi}, {n_rotamers}> = \
+             crate::interp::Table::new(&{table_name}_INDEX, &{table_name}_CELLS){with_chi};"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "const {table_name}_BYTES: usize = core::mem::size_of_val(&{table_name}_CELLS) \
+             + core::mem::size_of_val(&{table_name}_INDEX);"
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            out,
+            "static {table_name}: crate::interp::Table<'static, {n_chi}, {n_rotamers}> = \
+             crate::interp::Table::dense(&{table_name}_CELLS){with_chi};"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "const {table_name}_BYTES: usize = core::mem::size_of_val(&{table_name}_CELLS);"
+        )
+        .unwrap();
+    }
+    writeln!(out).unwrap();
+}
+
+/// Probabilities of the delta encoding are stored in units of 10⁻⁶, the
+/// source precision.
+const DELTA_PROB_SCALE: f32 = 1e6;
+
+/// χ means and σ of the delta encoding are stored in units of 0.1°, the
+/// source precision.
+const DELTA_ANGLE_SCALE: f32 = 10.0;
+
+/// Largest |χ| of the delta encoding, in 0.1° units.
+const DELTA_HALF_TURN: i32 = 1800;
+
+/// Code of a χ mean of −0.0°, which the source writes for a few rotamers
+/// and whose sine keeps the sign; just below the encodable range.
+const DELTA_NEG_ZERO: i32 = -DELTA_HALF_TURN - 1;
+
+/// Writes the `(sin χ, cos χ)` pairs of every χ mean the delta encoding can
+/// hold, −0.0° first, then −180.0° to 180.0° in 0.1° steps, computed as
+/// the `f32` tables compute them so decoded tables match those bit for bit.
+fn write_delta_sincos(config: &BuildConfig) {
+    let mut blob = Vec::with_capacity((2 * DELTA_HALF_TURN as usize + 2) * 8);
+    for q in DELTA_NEG_ZERO..=DELTA_HALF_TURN {
+        let chi = if q == DELTA_NEG_ZERO {
+            -0.0
+        } else {
+            q as f32 / DELTA_ANGLE_SCALE
+        };
+        let row = RawRow {
+            r: [0; 4],
+            prob: 0.0,
+            chi_val: [chi, 0.0, 0.0, 0.0],
+            chi_sig: [0.0; 4],
+        };
+        let mut values = Vec::with_capacity(4);
+        push_grid_entry(&mut |v| values.push(v), &row, 1);
+        for v in &values[1..3] {
+            if config.big_endian {
+                blob.extend_from_slice(&v.to_be_bytes());
+            } else {
+                blob.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+    }
+    fs::write(config.out_dir.join("DELTA_SINCOS.bin"), blob).unwrap();
+}
+
+/// `value` as a whole number of `1 / scale` units, or `None` if dividing
+/// that number by `scale` in `f32`, as the decoder does, would not give
+/// back the exact bits of `value`.
+fn quantize(value: f32, scale: f32) -> Option<i32> {
+    let q = (value as f64 * scale as f64).round();
+    (q.abs() < f64::from(1 << 24) && ((q as i32) as f32 / scale).to_bits() == value.to_bits())
+        .then_some(q as i32)
+}
+
+/// A χ mean as its code in the delta encoding: [`DELTA_NEG_ZERO`] for
+/// −0.0°, otherwise as [`quantize`] gives it, within ±180°.
+fn quantize_chi(chi: f32) -> Option<i32> {
+    if chi.to_bits() == (-0.0_f32).to_bits() {
+        return Some(DELTA_NEG_ZERO);
+    }
+    quantize(chi, DELTA_ANGLE_SCALE).filter(|q| q.abs() <= DELTA_HALF_TURN)
+}
+
+/// Appends `value` as a zigzag LEB128 varint.
+fn write_varint(out: &mut Vec<u8>, value: i32) {
+    let mut bits = ((value << 1) ^ (value >> 31)) as u32;
+    while bits >= 0x80 {
+        out.push(bits as u8 | 0x80);
+        bits >>= 7;
+    }
+    out.push(bits as u8);
+}
+
+/// Delta-encodes the stored grid for the `delta-tables` feature, or
+/// returns `None` if a value would not decode to its exact `f32` bits, as
+/// for the derived χ1-only tables.
+///
+/// The layout is one table of the `write_delta_tables` file format: the
+/// little-endian `u32` offsets of the 36 φ rows, then every cell of each
+/// row as zigzag LEB128 varint differences from the previous cell along ψ
+/// (the first cell of a row from zero): each rotamer's probability, χ
+/// means, and σ. Unlike the file format, χ means are coded by
+/// [`quantize_chi`] and their differences do not wrap, so −180.0° and
+/// 180.0° stay distinct, as do −0.0° and 0.0°.
+fn encode_delta(grid: &[Vec<Vec<&RawRow>>], n_chi: usize) -> Option<Vec<u8>> {
+    let mut blob = vec![0; GRID_PERIOD * 4];
+    for (phi_idx, phi_row) in grid[..GRID_PERIOD].iter().enumerate() {
+        let start = blob.len() as u32;
+        blob[phi_idx * 4..][..4].copy_from_slice(&start.to_le_bytes());
+        let mut prev: Vec<i32> = Vec::new();
+        for cell in &phi_row[..GRID_PERIOD] {
+            let mut values = Vec::with_capacity(cell.len() * (1 + 2 * n_chi));
+            for row in cell {
+                values.push(quantize(row.prob, DELTA_PROB_SCALE)?);
+                for &chi in &row.chi_val[..n_chi] {
+                    values.push(quantize_chi(chi)?);
+                }
+                for &sigma in &row.chi_sig[..n_chi] {
+                    values.push(quantize(sigma, DELTA_ANGLE_SCALE)?);
+                }
+            }
+            for (slot, &value) in values.iter().enumerate() {
+                write_varint(&mut blob, value - prev.get(slot).copied().unwrap_or(0));
+            }
+            prev = values;
+        }
+    }
+    Some(blob)
+}
+
+/// Writes a table encoded by [`encode_delta`] as a blob and emits a
+/// `static TABLE` that decodes it on first use, dereferencing to the same
+/// `Table` the `f32` blob would give. `TABLE_BYTES` is the encoded size.
+fn emit_delta_table(
+    out: &mut Vec<u8>,
+    config: &BuildConfig,
+    table_name: &str,
+    n_chi: usize,
+    n_rotamers: usize,
+    grid: &[Vec<Vec<&RawRow>>],
+    blob: &[u8],
+) {
+    let blob_name = format!("{table_name}.delta");
+    fs::write(config.out_dir.join(&blob_name), blob).unwrap();
+    let with_chi = if config.fast_chi {
+        emit_chi_centidegrees(out, config, table_name, n_chi, grid);
+        format!(".with_chi(&{table_name}_CHI)")
+    } else {
+        String::new()
+    };
+    writeln!(
+        out,
+        "static {table_name}: crate::delta::EmbeddedTable<{n_chi}, {n_rotamers}> = \
+         crate::delta::EmbeddedTable::new(include_bytes!(concat!(env!(\"OUT_DIR\"), \"/{blob_name}\"))){with_chi};"
+    )
+    .unwrap();
+    writeln!(out, "const {table_name}_BYTES: usize = {};", blob.len()).unwrap();
+    writeln!(out).unwrap();
+}
+
+/// Writes the per-rotamer probability gradients as a binary blob and emits
+/// `static {TABLE}_GRAD: [[f32; 2]; 37 × 37 × R]`, flattened as
+/// `[phi_idx][psi_idx][rotamer]`.
+///
+/// Each entry is `(∂p/∂φ, ∂p/∂ψ)` in probability per degree, from central
+/// differences of the cell-normalized probabilities over the 36 unique grid
+/// nodes with periodic wrap; the duplicated +180° row and column repeat the
+/// −180° values.
+fn emit_gradients(
+    out: &mut Vec<u8>,
+    config: &BuildConfig,
+    table_name: &str,
+    grid: &[Vec<Vec<&RawRow>>],
+) {
+    let n_rotamers = grid[0][0].len();
+    let prob = |phi_idx: usize, psi_idx: usize, k: usize| {
+        let cell = &grid[phi_idx % GRID_PERIOD][psi_idx % GRID_PERIOD];
+        let sum: f64 = cell.iter().map(|row| row.prob as f64).sum();
+        cell[k].prob as f64 / sum
+    };
+
+    let mut blob = Vec::with_capacity(GRID_COUNT * GRID_COUNT * n_rotamers * 8);
+    let mut push = |v: f32| {
+        if config.big_endian {
+            blob.extend_from_slice(&v.to_be_bytes());
+        } else {
+            blob.extend_from_slice(&v.to_le_bytes());
+        }
+    };
+    let step = 2.0 * GRID_STEP as f64;
+    for phi_idx in 0..GRID_COUNT {
+        let (phi_prev, phi_next) = (phi_idx + GRID_PERIOD - 1, phi_idx + 1);
+        for psi_idx in 0..GRID_COUNT {
+            let (psi_prev, psi_next) = (psi_idx + GRID_PERIOD - 1, psi_idx + 1);
+            for k in 0..n_rotamers {
+                push(((prob(phi_next, psi_idx, k) - prob(phi_prev, psi_idx, k)) / step) as f32);
+                push(((prob(phi_idx, psi_next, k) - prob(phi_idx, psi_prev, k)) / step) as f32);
+            }
+        }
+    }
+
+    let blob_name = format!("{table_name}_GRAD.bin");
+    fs::write(config.out_dir.join(&blob_name), &blob).unwrap();
+
+    writeln!(
+        out,
+        "static {table_name}_GRAD: [[f32; 2]; {}] = \
+         unsafe {{ core::mem::transmute(*include_bytes!(concat!(env!(\"OUT_DIR\"), \"/{blob_name}\"))) }};",
+        GRID_COUNT * GRID_COUNT * n_rotamers
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+}
+
+/// Writes the χ means of every cell as a binary blob of centidegrees and
+/// emits `static {TABLE}_CHI: [[[i16; N]; R]; 36 × 36]`, φ-major like a
+/// dense table.
+///
+/// Library means carry one decimal, so centidegrees hold them exactly;
+/// derived means (the χ1-only tables) round to the nearest 0.01°.
+fn emit_chi_centidegrees(
+    out: &mut Vec<u8>,
+    config: &BuildConfig,
+    table_name: &str,
+    n_chi: usize,
+    grid: &[Vec<Vec<&RawRow>>],
+) {
+    let n_rotamers = grid[0][0].len();
+    let mut blob = Vec::with_capacity(GRID_PERIOD * GRID_PERIOD * n_rotamers * n_chi * 2);
+    for phi_row in &grid[..GRID_PERIOD] {
+        for cell in &phi_row[..GRID_PERIOD] {
+            for row in cell.iter() {
+                for &chi in &row.chi_val[..n_chi] {
+                    let v = (chi as f64 * 100.0).round() as i16;
+                    if config.big_endian {
+                        blob.extend_from_slice(&v.to_be_bytes());
+                    } else {
+                        blob.extend_from_slice(&v.to_le_bytes());
+                    }
+                }
+            }
+        }
+    }
+
+    let blob_name = format!("{table_name}_CHI.bin");
+    fs::write(config.out_dir.join(&blob_name), &blob).unwrap();
+
+    writeln!(
+        out,
+        "static {table_name}_CHI: [[[i16; {n_chi}]; {n_rotamers}]; {}] = \
+         unsafe {{ core::mem::transmute(*include_bytes!(concat!(env!(\"OUT_DIR\"), \"/{blob_name}\"))) }};",
+        GRID_PERIOD * GRID_PERIOD
+    )
+    .unwrap();
+}
+
+/// Pushes the fields of a single `GridEntry { prob, chi_sin, chi_cos, chi_sigma }`
+/// in declaration order.
+///
+/// `chi_sin[i]` = sin(chi_val[i] × π/180), `chi_cos[i]` = cos(chi_val[i] × π/180).
+/// Using f64 for the intermediate computation preserves full f32 precision.
+fn push_grid_entry(push: &mut impl FnMut(f32), row: &RawRow, n_chi: usize) {
+    push(row.prob);
+    for i in 0..n_chi {
+        push(((row.chi_val[i] as f64) * DEG_TO_RAD).sin() as f32);
+    }
+    for i in 0..n_chi {
+        push(((row.chi_val[i] as f64) * DEG_TO_RAD).cos() as f32);
+    }
+    for i in 0..n_chi {
+        push(row.chi_sig[i]);
+    }
+}
+
+/// Emits `stmt` as a method-body statement compiled only with the `trace`
+/// feature.
+fn emit_trace(out: &mut Vec<u8>, stmt: &str) {
+    writeln!(out, "        #[cfg(feature = \"trace\")]").unwrap();
+    writeln!(out, "        {stmt}").unwrap();
+}
+
+/// Emits a `metrics`-gated count of one table lookup of the residue at
+/// `index` in [`RESIDUES`].
+fn emit_count(out: &mut Vec<u8>, index: usize) {
+    writeln!(out, "        #[cfg(feature = \"metrics\")]").unwrap();
+    writeln!(out, "        crate::metrics::lookup({index});").unwrap();
+}
+
+/// Emits the class marker impls and `impl Residue for X { … }`, where
+/// `rust_type` is the full path of `X`.
+fn emit_impl(
+    out: &mut Vec<u8>,
+    rust_type: &str,
+    csv_name: &str,
+    table_name: &str,
+    keys_name: &str,
+    n_chi: usize,
+    n_rotamers: usize,
+) {
+    let index = RESIDUES
+        .iter()
+        .position(|&(name, ..)| name == csv_name)
+        .unwrap_or_else(|| panic!("build.rs: no RESIDUES entry for '{csv_name}'"));
+    let &(_, classes) = CLASSES
+        .iter()
+        .find(|(name, _)| *name == csv_name)
+        .unwrap_or_else(|| panic!("build.rs: no class entry for '{csv_name}'"));
+    for class in classes {
+        writeln!(out, "impl crate::class::{class} for {rust_type} {{}}").unwrap();
+    }
+
+    writeln!(out, "impl crate::residue::Residue for {rust_type} {{").unwrap();
+    writeln!(out, "    const N_CHI: usize = {n_chi};").unwrap();
+    writeln!(out, "    const N_ROTAMERS: usize = {n_rotamers};").unwrap();
+    writeln!(out, "    const NAME: &'static str = \"{csv_name}\";").unwrap();
+    let &(_, one_letter, aliases) = IDENTITY
+        .iter()
+        .find(|(name, _, _)| *name == csv_name)
+        .unwrap_or_else(|| panic!("build.rs: no identity entry for '{csv_name}'"));
+    writeln!(out, "    const ONE_LETTER: char = {one_letter:?};").unwrap();
+    writeln!(
+        out,
+        "    const ALIASES: &'static [&'static str] = &{aliases:?};"
+    )
+    .unwrap();
+    let class = classes
+        .iter()
+        .map(|class| format!("crate::class::Class::{}", class.to_uppercase()))
+        .reduce(|a, b| format!("{a}.union({b})"))
+        .unwrap_or_else(|| "crate::class::Class::NONE".to_string());
+    writeln!(out, "    const CLASS: crate::class::Class = {class};").unwrap();
+    writeln!(
+        out,
+        "    const BINS_PER_CHI: &'static [u8] = &{keys_name}_BINS;"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    const BIN_RANGES: &'static [&'static [crate::bins::BinRange]] = &{keys_name}_RANGES;"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    const REFERENCE_ENERGIES: &'static [f32] = &{table_name}_REF_ENERGIES;"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    const DEFAULT_ROTAMER: Self::Rot = {table_name}_DEFAULT;"
+    )
+    .unwrap();
+    // Variant pools share the side-chain topology and geometry of their
+    // parent residue.
+    let topology = match csv_name {
+        "CPR" | "TPR" => "PRO",
+        "CYD" | "CYH" => "CYS",
+        name => name,
+    };
+    writeln!(
+        out,
+        "    const TOPOLOGY: crate::topology::Topology = crate::topology::{topology};"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    const IDEAL_GEOMETRY: &'static [crate::geometry::InternalCoord] = \
+         crate::geometry::{topology};"
+    )
+    .unwrap();
+    writeln!(out, "    #[cfg(feature = \"gradients\")]").unwrap();
+    writeln!(
+        out,
+        "    const PROB_GRADIENTS: crate::gradient::GradientMap = \
+         crate::gradient::GradientMap::new({n_rotamers}, &{table_name}_GRAD);"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    const TABLE_BYTES: usize = {table_name}_BYTES + core::mem::size_of_val(&{keys_name});"
+    )
+    .unwrap();
+    writeln!(out, "    type Rot = crate::rotamer::Rotamer<{n_chi}>;").unwrap();
+    writeln!(
+        out,
+        "    type Iter = crate::interp::RotamerIter<{n_chi}, {n_rotamers}>;"
+    )
+    .unwrap();
+    writeln!(out, "    type Chis = [f32; {n_chi}];").unwrap();
+    writeln!(out, "    #[inline]").unwrap();
+    writeln!(
         out,
-        "    fn rotamers(phi: f32, psi: f32) -> crate::interp::RotamerIter<{n_chi}, {n_rotamers}> {{"
+        "    fn rotamers_with<I: crate::interp::Interpolator>(phi: f32, psi: f32) \
+         -> crate::interp::RotamerIter<{n_chi}, {n_rotamers}> {{"
     )
     .unwrap();
+    emit_trace(
+        out,
+        "crate::trace::lookup::<I>(<Self as crate::residue::Residue>::NAME, phi, psi);",
+    );
+    emit_count(out, index);
     writeln!(
         out,
-        "        crate::interp::build_iter(&{table_name}, &{keys_name}, phi, psi)"
+        "        crate::interp::build_iter::<I, {n_chi}, {n_rotamers}>(&{table_name}, &{keys_name}, phi, psi)"
     )
     .unwrap();
     writeln!(out, "    }}").unwrap();
+    writeln!(
+        out,
+        "    fn visit_rotamers(phi: f32, psi: f32, f: impl FnMut(&crate::rotamer::Rotamer<{n_chi}>)) {{"
+    )
+    .unwrap();
+    emit_trace(
+        out,
+        "crate::trace::lookup::<crate::interp::Bilinear>(<Self as crate::residue::Residue>::NAME, phi, psi);",
+    );
+    emit_count(out, index);
+    writeln!(
+        out,
+        "        crate::interp::visit_bilinear(&{table_name}, &{keys_name}, phi, psi, f)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(
+        out,
+        "    fn rotamers_detailed(phi: f32, psi: f32) \
+         -> crate::interp::BilinearDetail<crate::interp::RotamerIter<{n_chi}, {n_rotamers}>> {{"
+    )
+    .unwrap();
+    emit_count(out, index);
+    writeln!(
+        out,
+        "        crate::interp::bilinear_detail(&{table_name}, &{keys_name}, phi, psi)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(
+        out,
+        "    fn interpolation_error(phi: f32, psi: f32) -> f32 {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        crate::interp::interpolation_error(&{table_name}, &{keys_name}, phi, psi)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "    #[inline]").unwrap();
+    writeln!(
+        out,
+        "    fn rotamers_at(lo_phi: usize, lo_psi: usize, frac_phi: f32, frac_psi: f32) \
+         -> crate::interp::RotamerIter<{n_chi}, {n_rotamers}> {{"
+    )
+    .unwrap();
+    emit_count(out, index);
+    writeln!(
+        out,
+        "        crate::interp::build_iter_at(&{table_name}, &{keys_name}, lo_phi, lo_psi, frac_phi, frac_psi)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(
+        out,
+        "    fn write_gpu_table(out: &mut [u8]) \
+         -> Result<crate::gpu::GpuTableLayout, crate::error::Error> {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        crate::gpu::write(&{table_name}, &{keys_name}, out)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "    #[inline]").unwrap();
+    writeln!(
+        out,
+        "    fn try_rotamers_with<I: crate::interp::Interpolator>(phi: f32, psi: f32) \
+         -> Result<crate::interp::RotamerIter<{n_chi}, {n_rotamers}>, crate::error::Error> {{"
+    )
+    .unwrap();
+    emit_trace(
+        out,
+        "crate::trace::lookup::<I>(<Self as crate::residue::Residue>::NAME, phi, psi);",
+    );
+    emit_count(out, index);
+    writeln!(
+        out,
+        "        let result = crate::interp::try_build_iter::<I, {n_chi}, {n_rotamers}>(&{table_name}, &{keys_name}, phi, psi);"
+    )
+    .unwrap();
+    emit_trace(
+        out,
+        "crate::trace::outcome(<Self as crate::residue::Residue>::NAME, &result);",
+    );
+    writeln!(out, "        result").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "    #[inline]").unwrap();
+    writeln!(
+        out,
+        "    fn try_query(query: &crate::query::Query) \
+         -> Result<crate::interp::RotamerIter<{n_chi}, {n_rotamers}>, crate::error::Error> {{"
+    )
+    .unwrap();
+    emit_trace(
+        out,
+        "crate::trace::query(<Self as crate::residue::Residue>::NAME, query);",
+    );
+    emit_count(out, index);
+    writeln!(
+        out,
+        "        let result = crate::query::try_run(&{table_name}, &{keys_name}, query);"
+    )
+    .unwrap();
+    emit_trace(
+        out,
+        "crate::trace::outcome(<Self as crate::residue::Residue>::NAME, &result);",
+    );
+    writeln!(out, "        result").unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "    #[inline]").unwrap();
+    writeln!(
+        out,
+        "    fn query(query: &crate::query::Query) \
+         -> crate::interp::RotamerIter<{n_chi}, {n_rotamers}> {{"
+    )
+    .unwrap();
+    emit_trace(
+        out,
+        "crate::trace::query(<Self as crate::residue::Residue>::NAME, query);",
+    );
+    emit_count(out, index);
+    writeln!(
+        out,
+        "        crate::query::run(&{table_name}, &{keys_name}, query)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(
+        out,
+        "    fn ensemble_rotamers(samples: &[(f32, f32, f32)]) \
+         -> crate::interp::RotamerIter<{n_chi}, {n_rotamers}> {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        crate::ensemble::average(samples.iter().map(|&(phi, psi, weight)| \
+         (weight, <Self as crate::residue::Residue>::rotamers(phi, psi))))"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(
+        out,
+        "    fn rotamers_uncertain(phi: f32, psi: f32, sigma_phi: f32, sigma_psi: f32) \
+         -> crate::interp::RotamerIter<{n_chi}, {n_rotamers}> {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        crate::ensemble::average(\
+         crate::ensemble::gaussian_nodes(phi, psi, sigma_phi, sigma_psi).map(|(phi, psi, weight)| \
+         (weight, <Self as crate::residue::Residue>::rotamers(phi, psi))))"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    for method in ["mean_chis", "chi_circular_variance", "chi_order_parameters"] {
+        writeln!(out, "    #[inline]").unwrap();
+        writeln!(
+            out,
+            "    fn {method}(phi: f32, psi: f32) -> [f32; {n_chi}] {{"
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "        <Self as crate::residue::Residue>::rotamers(phi, psi).{method}()"
+        )
+        .unwrap();
+        writeln!(out, "    }}").unwrap();
+    }
+    writeln!(out, "    #[inline]").unwrap();
+    writeln!(
+        out,
+        "    fn chi_density<const P: usize>(phi: f32, psi: f32, chi_index: usize) -> [f32; P] {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        <Self as crate::residue::Residue>::rotamers(phi, psi).chi_density(chi_index)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "    #[inline]").unwrap();
+    writeln!(
+        out,
+        "    fn chi_quantile(phi: f32, psi: f32, chi_index: usize, q: f32) -> f32 {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        <Self as crate::residue::Residue>::rotamers(phi, psi).chi_quantile(chi_index, q)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "    #[inline]").unwrap();
+    writeln!(
+        out,
+        "    fn try_chi_quantile(phi: f32, psi: f32, chi_index: usize, q: f32) \
+         -> Result<f32, crate::error::Error> {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        <Self as crate::residue::Residue>::try_rotamers(phi, psi)?.try_chi_quantile(chi_index, q)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+    writeln!(out).unwrap();
+}
+
+/// Emits `impl sealed::Sealed for X { … }`, where `rust_type` is the full
+/// path of `X` and `library` its `Library` variant: the library tag and the
+/// table plumbing kept off the public `Residue` trait.
+fn emit_sealed(
+    out: &mut Vec<u8>,
+    rust_type: &str,
+    library: &str,
+    table_name: &str,
+    keys_name: &str,
+    n_chi: usize,
+    n_rotamers: usize,
+) {
+    writeln!(out, "impl crate::sealed::Sealed for {rust_type} {{").unwrap();
+    writeln!(
+        out,
+        "    const LIBRARY: crate::residue::Library = crate::residue::Library::{library};"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    fn stored_rotamers(cells: [&[f32]; 4], phi: f32, psi: f32) \
+         -> crate::interp::RotamerIter<{n_chi}, {n_rotamers}> {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        crate::storage::rotamers(cells, &{keys_name}, phi, psi)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "    #[cfg(feature = \"mmap\")]").unwrap();
+    writeln!(
+        out,
+        "    fn mapped_query(table: &[u8], query: &crate::query::Query) \
+         -> crate::interp::RotamerIter<{n_chi}, {n_rotamers}> {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        crate::query::run(&crate::mapped::table(table), &{keys_name}, query)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "    #[inline]").unwrap();
+    writeln!(
+        out,
+        "    fn profile(phi: f32, psi: f32) -> crate::compare::Profile {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        crate::compare::profile(<Self as crate::residue::Residue>::rotamers(phi, psi))"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "    #[inline]").unwrap();
+    writeln!(
+        out,
+        "    fn recovery_case(phi: f32, psi: f32, native: &[f32; {n_chi}], \
+         policy: crate::recovery::Policy) -> crate::recovery::Case {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        crate::recovery::case(<Self as crate::residue::Residue>::rotamers(phi, psi), \
+         <Self as crate::residue::Residue>::NAME, native, policy)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(
+        out,
+        "    fn grid_record(residue: &'static crate::identity::ResidueInfo, \
+         phi_idx: usize, psi_idx: usize, k: usize) -> crate::records::Record {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        crate::records::record(residue, &{table_name}, &{keys_name}, phi_idx, psi_idx, k)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "    #[cfg(feature = \"std\")]").unwrap();
+    writeln!(
+        out,
+        "    fn visit_rows(f: &mut dyn FnMut(crate::export::Row)) {{"
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "        crate::export::visit(&{table_name}, &{keys_name}, f)"
+    )
+    .unwrap();
+    writeln!(out, "    }}").unwrap();
+    writeln!(out, "}}").unwrap();
+}
+
+/// Emits the `ALL_RESIDUES` registry of residue type metadata.
+fn emit_registry(out: &mut Vec<u8>) {
+    writeln!(
+        out,
+        "/// Metadata of every residue type, in the order of [`for_all_residues!`]."
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "pub const ALL_RESIDUES: &[crate::identity::ResidueInfo] = &["
+    )
+    .unwrap();
+    for &(_, rust_name, _, _) in RESIDUES {
+        writeln!(
+            out,
+            "    crate::identity::ResidueInfo::of::<crate::residue::{rust_name}>(),"
+        )
+        .unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    writeln!(out).unwrap();
+}
+
+/// Emits the `TABLE_FOOTPRINT` report and the `TOTAL_TABLE_BYTES` constant,
+/// plus `CHI1_TABLE_FOOTPRINT` and `ALT_TABLE_FOOTPRINT` when the reduced or
+/// alternative tables are generated.
+fn emit_footprint(out: &mut Vec<u8>, chi1: bool, alt: bool) {
+    emit_footprint_array(
+        out,
+        "/// Static table footprint of every residue type, in the order of \
+         [`for_all_residues!`].",
+        "TABLE_FOOTPRINT",
+        "",
+    );
+
+    let mut total = String::from("crate::footprint::total(&TABLE_FOOTPRINT)");
+    if chi1 {
+        emit_footprint_array(
+            out,
+            "/// Static table footprint of every reduced [`Chi1`](crate::Chi1) \
+             residue type, in the order of [`for_all_residues!`].",
+            "CHI1_TABLE_FOOTPRINT",
+            "_CHI1",
+        );
+        total.push_str(" + crate::footprint::total(&CHI1_TABLE_FOOTPRINT)");
+    }
+    if alt {
+        emit_footprint_array(
+            out,
+            "/// Static table footprint of every alternative [`Alt`](crate::Alt) \
+             residue type, in the order of [`for_all_residues!`].",
+            "ALT_TABLE_FOOTPRINT",
+            "_ALT",
+        );
+        total.push_str(" + crate::footprint::total(&ALT_TABLE_FOOTPRINT)");
+    }
+
+    writeln!(
+        out,
+        "/// Total bytes of static rotamer data generated for the enabled features."
+    )
+    .unwrap();
+    writeln!(out, "pub const TOTAL_TABLE_BYTES: usize = {total};").unwrap();
+    writeln!(out).unwrap();
+}
+
+/// Emits one `TableFootprint` array named `name` over the tables whose
+/// statics carry `suffix` after the residue name.
+fn emit_footprint_array(out: &mut Vec<u8>, doc: &str, name: &str, suffix: &str) {
+    writeln!(out, "{doc}").unwrap();
+    writeln!(
+        out,
+        "pub const {name}: [crate::footprint::TableFootprint; {}] = [",
+        RESIDUES.len()
+    )
+    .unwrap();
+    for &(csv_name, _, _, _) in RESIDUES {
+        writeln!(
+            out,
+            "    crate::footprint::TableFootprint {{ name: \"{csv_name}\", \
+             table_bytes: {csv_name}{suffix}_TABLE_BYTES, \
+             keys_bytes: core::mem::size_of_val(&{csv_name}{suffix}_KEYS) }},"
+        )
+        .unwrap();
+    }
+    writeln!(out, "];").unwrap();
+    writeln!(out).unwrap();
+}
+
+/// Emits the `alt` module aliasing every [`RESIDUES`] type to its
+/// alternative-library `Alt` wrapper.
+fn emit_alt_namespace(out: &mut Vec<u8>) {
+    writeln!(
+        out,
+        "/// Every residue type of the alternative library (`alt-data` feature), \
+         under the names of the primary types."
+    )
+    .unwrap();
+    writeln!(out, "///").unwrap();
+    writeln!(
+        out,
+        "/// Swapping `use dunbrack::{{Arg, Residue}}` for \
+         `use dunbrack::{{alt::Arg, Residue}}` retargets code at the other library."
+    )
+    .unwrap();
+    writeln!(out, "pub mod alt {{").unwrap();
+    for &(_, rust_name, _, _) in RESIDUES {
+        writeln!(
+            out,
+            "    /// [`{rust_name}`](crate::{rust_name}) from the alternative library."
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "    pub type {rust_name} = crate::residue::Alt<crate::residue::{rust_name}>;"
+        )
+        .unwrap();
+    }
     writeln!(out, "}}").unwrap();
     writeln!(out).unwrap();
 }
 
 /// Emits the `for_all_residues!` macro exported from the crate root.
-fn emit_for_all_residues_macro(out: &mut fs::File) {
+fn emit_for_all_residues_macro(out: &mut Vec<u8>) {
     writeln!(
         out,
         "/// Invokes `$callback!(Type, N_CHI, N_ROTAMERS)` for all 22 residue types."
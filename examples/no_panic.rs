@@ -0,0 +1,28 @@
+//! Links every panic-free `try_` query so that release builds with the
+//! `no-panic` feature prove none of them can panic.
+//!
+//! ```bash
+//! cargo build --release --features no-panic --example no_panic
+//! ```
+
+use std::hint::black_box;
+
+use dunbrack::{Bicubic, Bilinear, Leu, Nearest, Query, Residue, Val};
+
+fn main() {
+    let (phi, psi) = (black_box(-65.0), black_box(-40.0));
+    let mut ok = 0_usize;
+    ok += Val::try_rotamers(phi, psi).is_ok() as usize;
+    ok += Leu::try_rotamers_with::<Bilinear>(phi, psi).is_ok() as usize;
+    ok += Leu::try_rotamers_with::<Nearest>(phi, psi).is_ok() as usize;
+    ok += Leu::try_rotamers_with::<Bicubic>(phi, psi).is_ok() as usize;
+    ok += Leu::try_chi_quantile(phi, psi, black_box(1), black_box(0.5)).is_ok() as usize;
+    ok += Query::new(phi, psi).try_run::<Leu>().is_ok() as usize;
+    if let Ok(rotamers) = Leu::try_rotamers(phi, psi) {
+        ok += rotamers
+            .try_chi_quantile(black_box(0), black_box(0.9))
+            .is_ok() as usize;
+    }
+    ok += Val::rotamers(phi, psi).len();
+    println!("{ok}");
+}
@@ -0,0 +1,259 @@
+//! Arithmetic kernels shared by the interpolators.
+//!
+//! Two evaluation orders are available, selected at compile time:
+//!
+//! - **Strict** (default, or forced by the `strict-math` feature): every sum
+//!   is evaluated left to right with one rounding per operation and no fused
+//!   multiply-add. Results are bit-identical on every IEEE 754 target.
+//! - **Fast** (`fast-math` feature): sums are split into independent
+//!   partial accumulators and combined pairwise, and multiply-add uses a
+//!   hardware FMA when the target enables one. Results may differ from the
+//!   strict path in the last ulp.
+//!
+//! With the `wasm-simd` feature on a `simd128` target, the per-χ kernels
+//! evaluate all χ lanes in one vector, in the same order as the scalar code.
+//!
+//! `strict-math` takes precedence when both features are enabled, so a crate
+//! that needs reproducibility is never overridden by another dependent.
+
+/// `true` when the relaxed evaluation order is compiled in.
+pub(crate) const FAST: bool = cfg!(all(feature = "fast-math", not(feature = "strict-math")));
+
+/// Computes `a * b + c`.
+///
+/// Fused into a single rounding under the fast path on targets with FMA.
+#[inline(always)]
+pub(crate) fn mul_add(a: f32, b: f32, c: f32) -> f32 {
+    #[cfg(all(
+        feature = "fast-math",
+        not(feature = "strict-math"),
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "fma"
+    ))]
+    {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::{_mm_cvtss_f32, _mm_fmadd_ss, _mm_set_ss};
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::{_mm_cvtss_f32, _mm_fmadd_ss, _mm_set_ss};
+        // SAFETY: the `fma` target feature is statically enabled.
+        unsafe { _mm_cvtss_f32(_mm_fmadd_ss(_mm_set_ss(a), _mm_set_ss(b), _mm_set_ss(c))) }
+    }
+    #[cfg(not(all(
+        feature = "fast-math",
+        not(feature = "strict-math"),
+        any(target_arch = "x86", target_arch = "x86_64"),
+        target_feature = "fma"
+    )))]
+    {
+        a * b + c
+    }
+}
+
+/// Computes `Σ w[i] · v[i]` over four terms.
+#[inline(always)]
+pub(crate) fn dot4(w: [f32; 4], v: [f32; 4]) -> f32 {
+    if FAST {
+        mul_add(w[1], v[1], w[0] * v[0]) + mul_add(w[3], v[3], w[2] * v[2])
+    } else {
+        ((w[0] * v[0] + w[1] * v[1]) + w[2] * v[2]) + w[3] * v[3]
+    }
+}
+
+/// Computes `Σ w[c] · rows[c][i]` for every lane `i`, in the same order
+/// as [`dot4`].
+///
+/// With the `wasm-simd` feature on a `simd128` target, the χ lanes (at most
+/// four) are evaluated together in one 128-bit vector. Without FMA the
+/// vector and scalar paths round identically.
+#[inline(always)]
+pub(crate) fn dot4_lanes<const N: usize>(w: [f32; 4], rows: [&[f32; N]; 4]) -> [f32; N] {
+    #[cfg(all(
+        feature = "wasm-simd",
+        target_arch = "wasm32",
+        target_feature = "simd128"
+    ))]
+    if N <= 4 {
+        use core::arch::wasm32::{f32x4_add, f32x4_mul, f32x4_splat};
+        let m: [_; 4] = core::array::from_fn(|c| f32x4_mul(f32x4_splat(w[c]), simd::load(rows[c])));
+        let acc = if FAST {
+            f32x4_add(f32x4_add(m[0], m[1]), f32x4_add(m[2], m[3]))
+        } else {
+            f32x4_add(f32x4_add(f32x4_add(m[0], m[1]), m[2]), m[3])
+        };
+        return simd::store(acc);
+    }
+    core::array::from_fn(|i| dot4(w, [rows[0][i], rows[1][i], rows[2][i], rows[3][i]]))
+}
+
+/// Computes `acc[i] + w · x[i]` for every lane `i`, as [`mul_add`] does
+/// per lane.
+#[inline(always)]
+pub(crate) fn mul_add_lanes<const N: usize>(w: f32, x: &[f32; N], acc: [f32; N]) -> [f32; N] {
+    #[cfg(all(
+        feature = "wasm-simd",
+        target_arch = "wasm32",
+        target_feature = "simd128"
+    ))]
+    if N <= 4 {
+        use core::arch::wasm32::{f32x4_add, f32x4_mul, f32x4_splat};
+        let v = f32x4_add(f32x4_mul(f32x4_splat(w), simd::load(x)), simd::load(&acc));
+        return simd::store(v);
+    }
+    core::array::from_fn(|i| mul_add(w, x[i], acc[i]))
+}
+
+/// Lane packing for the `simd128` kernels.
+#[cfg(all(
+    feature = "wasm-simd",
+    target_arch = "wasm32",
+    target_feature = "simd128"
+))]
+mod simd {
+    use core::arch::wasm32::{f32x4, f32x4_extract_lane, v128};
+
+    /// Loads up to four values into a vector, zero-filling unused lanes.
+    #[inline(always)]
+    pub(super) fn load<const N: usize>(x: &[f32; N]) -> v128 {
+        let l: [f32; 4] = core::array::from_fn(|i| if i < N { x[i] } else { 0.0 });
+        f32x4(l[0], l[1], l[2], l[3])
+    }
+
+    /// Stores the first `N ≤ 4` lanes of a vector.
+    #[inline(always)]
+    pub(super) fn store<const N: usize>(v: v128) -> [f32; N] {
+        let l = [
+            f32x4_extract_lane::<0>(v),
+            f32x4_extract_lane::<1>(v),
+            f32x4_extract_lane::<2>(v),
+            f32x4_extract_lane::<3>(v),
+        ];
+        core::array::from_fn(|i| if i < 4 { l[i] } else { 0.0 })
+    }
+}
+
+/// Sums `values`.
+///
+/// The fast path keeps four independent partial sums, which shortens the
+/// dependency chain and lets the compiler vectorize the loop.
+#[inline]
+pub(crate) fn sum(values: &[f32]) -> f32 {
+    sum_by(values.len(), |i| values[i])
+}
+
+/// Sums `value(0) .. value(len - 1)` in the same order, and so to the same
+/// bits, as [`sum`] over the materialized values.
+#[inline]
+pub(crate) fn sum_by(len: usize, value: impl Fn(usize) -> f32) -> f32 {
+    if FAST {
+        let chunked = len - len % 4;
+        let mut acc = [0.0_f32; 4];
+        for base in (0..chunked).step_by(4) {
+            for (lane, acc) in acc.iter_mut().enumerate() {
+                *acc += value(base + lane);
+            }
+        }
+        let mut tail = 0.0_f32;
+        for i in chunked..len {
+            tail += value(i);
+        }
+        ((acc[0] + acc[1]) + (acc[2] + acc[3])) + tail
+    } else {
+        let mut total = 0.0_f32;
+        for i in 0..len {
+            total += value(i);
+        }
+        total
+    }
+}
+
+/// Sums `values` with Neumaier's compensated summation, carrying the
+/// rounding error of every addition in a second accumulator.
+///
+/// The result is within one rounding of the exact sum whatever the order
+/// and magnitudes of the inputs, and identical under both evaluation
+/// orders.
+#[inline]
+pub(crate) fn compensated_sum(values: &[f32]) -> f32 {
+    let mut total = 0.0_f32;
+    let mut compensation = 0.0_f32;
+    for &v in values {
+        let t = total + v;
+        compensation += if total.abs() >= v.abs() {
+            (total - t) + v
+        } else {
+            (v - t) + total
+        };
+        total = t;
+    }
+    total + compensation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compensated_sum_recovers_lost_bits() {
+        // Naive left-to-right summation absorbs every 1.0 into 1e8.
+        let mut values = [1.0_f32; 9];
+        values[0] = 1.0e8;
+        values[8] = -1.0e8;
+        assert_eq!(values.iter().sum::<f32>(), 0.0);
+        assert_eq!(compensated_sum(&values), 7.0);
+        assert_eq!(compensated_sum(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_dot4_exact_on_representable_inputs() {
+        // Every partial sum is exactly representable, so both paths agree.
+        assert_eq!(dot4([0.25, 0.25, 0.25, 0.25], [1.0, 2.0, 3.0, 4.0]), 2.5);
+        assert_eq!(dot4([1.0, 0.0, 0.0, 0.0], [7.0, 1e30, -1e30, 3.0]), 7.0);
+    }
+
+    #[test]
+    fn test_lanes_match_scalar() {
+        let w = [0.1, 0.2, 0.3, 0.4];
+        let rows = [
+            [1.0, -2.0, 3.5],
+            [0.5, 7.0, -1.0],
+            [2.0, 2.0, 2.0],
+            [-3.0, 0.25, 9.0],
+        ];
+        let lanes = dot4_lanes(w, [&rows[0], &rows[1], &rows[2], &rows[3]]);
+        for i in 0..3 {
+            assert_eq!(
+                lanes[i],
+                dot4(w, [rows[0][i], rows[1][i], rows[2][i], rows[3][i]])
+            );
+        }
+        let acc = mul_add_lanes(0.5, &rows[0], rows[1]);
+        for i in 0..3 {
+            assert_eq!(acc[i], mul_add(0.5, rows[0][i], rows[1][i]));
+        }
+    }
+
+    #[test]
+    fn test_sum_handles_remainder() {
+        let values = [1.0_f32, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+        assert_eq!(sum(&values), 28.0);
+        assert_eq!(sum(&values[..3]), 6.0);
+        assert_eq!(sum(&[]), 0.0);
+    }
+
+    #[test]
+    fn test_strict_order_is_left_to_right() {
+        if FAST {
+            return;
+        }
+        // (1e8 + 1) rounds back to 1e8 in f32; a reordered sum would keep the 1.
+        let values = [1e8_f32, 1.0, -1e8, 0.0];
+        assert_eq!(sum(&values), 0.0);
+        assert_eq!(dot4([1.0; 4], values), 0.0);
+    }
+
+    #[test]
+    fn test_mul_add_close_to_separate_ops() {
+        let (a, b, c) = (1.1_f32, 2.3_f32, -0.7_f32);
+        assert!((mul_add(a, b, c) - (a * b + c)).abs() <= f32::EPSILON * 4.0);
+    }
+}
@@ -0,0 +1,438 @@
+//! Minimal HTTP query service over the embedded rotamer library.
+//!
+//! ```text
+//! cargo run --release --features server --bin dunbrack-server -- 127.0.0.1:8080
+//! curl 'http://127.0.0.1:8080/rotamers?res=ARG&phi=-65&psi=-43'
+//! ```
+//!
+//! `GET /rotamers` takes the residue name or alias (`res`) and the backbone
+//! angles in degrees (`phi`, `psi`), and answers with a JSON object listing
+//! every rotamer with its bin indices, probability, and per-χ mean and σ.
+//! Invalid queries get a `400` with an `{"error": ...}` body. Query
+//! parameters are percent-decoded.
+//!
+//! Connections are served by a fixed pool of worker threads, each closed
+//! after one response; when every worker is busy and the backlog is full,
+//! new connections get a `503`. A connection is closed unless its request
+//! line and headers arrive within [`TIMEOUT`] of a worker taking it up,
+//! however slowly the bytes trickle in, and writes time out after
+//! [`TIMEOUT`] as well; a request line or header longer than [`MAX_LINE`]
+//! bytes, or more than [`MAX_HEADERS`] headers, gets a `431`.
+
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use dunbrack::*;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+/// Worker threads serving connections.
+const WORKERS: usize = 8;
+
+/// Accepted connections waiting for a worker before new ones are refused.
+const BACKLOG: usize = 64;
+
+/// Time a connection has to send its request, and write timeout of every
+/// connection.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Longest request line or header accepted, in bytes.
+const MAX_LINE: usize = 8 * 1024;
+
+/// Most headers accepted per request.
+const MAX_HEADERS: usize = 64;
+
+fn main() -> io::Result<()> {
+    let addr = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| DEFAULT_ADDR.into());
+    let listener = TcpListener::bind(&addr)?;
+    eprintln!("dunbrack-server listening on http://{addr}");
+
+    let (queue, backlog) = mpsc::sync_channel::<TcpStream>(BACKLOG);
+    let backlog = Arc::new(Mutex::new(backlog));
+    for _ in 0..WORKERS {
+        let backlog = Arc::clone(&backlog);
+        thread::spawn(move || {
+            while let Some(stream) = next_connection(&backlog) {
+                if let Err(err) = serve_stream(&stream) {
+                    eprintln!("connection error: {err}");
+                }
+            }
+        });
+    }
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(TrySendError::Full(stream)) = queue.try_send(stream) {
+                    let _ = refuse(&stream);
+                }
+            }
+            Err(err) => eprintln!("accept error: {err}"),
+        }
+    }
+    Ok(())
+}
+
+/// Waits for the next queued connection; the lock is released as soon as
+/// one is taken.
+fn next_connection(backlog: &Mutex<Receiver<TcpStream>>) -> Option<TcpStream> {
+    backlog.lock().ok()?.recv().ok()
+}
+
+/// Serves one request on `stream` under the connection timeouts.
+fn serve_stream(stream: &TcpStream) -> io::Result<()> {
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    serve(BufReader::new(Deadline::new(stream, TIMEOUT)), stream)
+}
+
+/// Reads from a connection until a deadline, failing with
+/// [`io::ErrorKind::TimedOut`] once it has passed.
+///
+/// A plain read timeout restarts with every read, so a client sending one
+/// byte at a time could hold a worker indefinitely.
+struct Deadline<'a> {
+    stream: &'a TcpStream,
+    deadline: Instant,
+}
+
+impl<'a> Deadline<'a> {
+    /// Reader over `stream` that stops `timeout` from now.
+    fn new(stream: &'a TcpStream, timeout: Duration) -> Self {
+        Self {
+            stream,
+            deadline: Instant::now() + timeout,
+        }
+    }
+}
+
+impl Read for Deadline<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let left = self.deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return Err(io::ErrorKind::TimedOut.into());
+        }
+        self.stream.set_read_timeout(Some(left))?;
+        self.stream.read(buf)
+    }
+}
+
+/// Answers a connection no worker is free for.
+fn refuse(stream: &TcpStream) -> io::Result<()> {
+    stream.set_write_timeout(Some(TIMEOUT))?;
+    reply(
+        stream,
+        "503 Service Unavailable",
+        &error_body("server is busy"),
+    )
+}
+
+/// Reads one request from `reader` and writes its response to `out`.
+fn serve(mut reader: impl BufRead, out: impl Write) -> io::Result<()> {
+    let (status, body) = match read_head(&mut reader) {
+        Ok(Some(request_line)) => respond(&request_line),
+        Ok(None) => (
+            "431 Request Header Fields Too Large",
+            error_body("request line or headers too large"),
+        ),
+        Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+            ("400 Bad Request", error_body("request is not UTF-8"))
+        }
+        Err(err) => return Err(err),
+    };
+    reply(out, status, &body)
+}
+
+/// Writes a complete JSON response.
+fn reply(mut out: impl Write, status: &str, body: &str) -> io::Result<()> {
+    write!(
+        out,
+        "HTTP/1.1 {status}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    out.flush()
+}
+
+/// Reads the request line and drains the headers (no request carries a
+/// body), returning `None` if a line or the header count is over its limit.
+fn read_head(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut request_line = String::new();
+    if !read_line(reader, &mut request_line)? {
+        return Ok(None);
+    }
+    let mut header = String::new();
+    for _ in 0..=MAX_HEADERS {
+        header.clear();
+        if !read_line(reader, &mut header)? {
+            return Ok(None);
+        }
+        if header.len() <= 2 {
+            return Ok(Some(request_line));
+        }
+    }
+    Ok(None)
+}
+
+/// Appends one line of at most [`MAX_LINE`] bytes to `line`, returning
+/// `false` if the line is longer.
+fn read_line(reader: &mut impl BufRead, line: &mut String) -> io::Result<bool> {
+    let read = reader.by_ref().take(MAX_LINE as u64).read_line(line)?;
+    Ok(read < MAX_LINE || line.ends_with('\n'))
+}
+
+/// Status line and JSON body answering an HTTP request line.
+fn respond(request_line: &str) -> (&'static str, String) {
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return ("400 Bad Request", error_body("malformed request line"));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    if path != "/rotamers" {
+        return ("404 Not Found", error_body("unknown path"));
+    }
+    if method != "GET" {
+        return (
+            "405 Method Not Allowed",
+            error_body("only GET is supported"),
+        );
+    }
+    match rotamers_json(query) {
+        Ok(body) => ("200 OK", body),
+        Err(message) => ("400 Bad Request", error_body(&message)),
+    }
+}
+
+/// JSON body of `GET /rotamers` for the query string `query`.
+fn rotamers_json(query: &str) -> Result<String, String> {
+    let (mut res, mut phi, mut psi) = (None, None, None);
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let (key, value) = (percent_decode(key)?, percent_decode(value)?);
+        let value = value.trim();
+        match key.as_str() {
+            "res" => res = Some(value.to_string()),
+            "phi" => phi = Some(angle("phi", value)?),
+            "psi" => psi = Some(angle("psi", value)?),
+            _ => return Err(format!("unknown parameter `{key}`")),
+        }
+    }
+    let res = res.ok_or("missing parameter `res`")?;
+    let res = res.as_str();
+    let phi = phi.ok_or("missing parameter `phi`")?;
+    let psi = psi.ok_or("missing parameter `psi`")?;
+    let name = canonical_name(res).map_err(|err| format!("{err}: {res}"))?;
+
+    let mut body = String::new();
+    macro_rules! dispatch {
+        ($Res:ident, $n:literal, $r:literal) => {
+            if name == <$Res as Residue>::NAME {
+                let rotamers =
+                    <$Res as Residue>::try_rotamers(phi, psi).map_err(|err| err.to_string())?;
+                write!(
+                    body,
+                    r#"{{"residue":"{name}","phi":{phi},"psi":{psi},"rotamers":["#
+                )
+                .unwrap();
+                for (k, rot) in rotamers.enumerate() {
+                    if k > 0 {
+                        body.push(',');
+                    }
+                    write!(
+                        body,
+                        r#"{{"r":{:?},"prob":{},"chi_mean":{:?},"chi_sigma":{:?}}}"#,
+                        rot.r, rot.prob, rot.chi_mean, rot.chi_sigma
+                    )
+                    .unwrap();
+                }
+                body.push_str("]}");
+            }
+        };
+    }
+    for_all_residues!(dispatch);
+    Ok(body)
+}
+
+/// Decodes the `%XX` escapes and `+` (a space) of a query string
+/// component.
+fn percent_decode(text: &str) -> Result<String, String> {
+    let invalid = || format!("invalid percent-encoding: {text}");
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.bytes();
+    while let Some(byte) = rest.next() {
+        bytes.push(match byte {
+            b'%' => {
+                let mut digit = || {
+                    rest.next()
+                        .and_then(|d| char::from(d).to_digit(16))
+                        .ok_or_else(invalid)
+                };
+                (digit()? * 16 + digit()?) as u8
+            }
+            b'+' => b' ',
+            byte => byte,
+        });
+    }
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
+
+/// Parses a backbone angle parameter.
+fn angle(key: &str, value: &str) -> Result<f32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("parameter `{key}` is not a number: {value}"))
+}
+
+/// `{"error": message}`, with `message` escaped as a JSON string.
+fn error_body(message: &str) -> String {
+    let mut body = String::from(r#"{"error":""#);
+    for c in message.chars() {
+        match c {
+            '"' => body.push_str("\\\""),
+            '\\' => body.push_str("\\\\"),
+            c if c.is_control() => write!(body, "\\u{:04x}", c as u32).unwrap(),
+            c => body.push(c),
+        }
+    }
+    body.push_str("\"}");
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotamers_query() {
+        let (status, body) = respond("GET /rotamers?res=ARG&phi=-65&psi=-43 HTTP/1.1\r\n");
+        assert_eq!(status, "200 OK");
+        assert!(body.starts_with(r#"{"residue":"ARG","phi":-65,"psi":-43,"rotamers":[{"r":["#));
+        assert_eq!(body.matches(r#""prob":"#).count(), Arg::N_ROTAMERS);
+        let first = Arg::rotamers(-65.0, -43.0).next().unwrap();
+        assert!(body.contains(&format!(r#""prob":{},"#, first.prob)));
+
+        // Aliases resolve to the canonical name.
+        let (_, body) = respond("GET /rotamers?res=hie&phi=60&psi=40 HTTP/1.1");
+        assert!(body.starts_with(r#"{"residue":"HIS","#));
+    }
+
+    #[test]
+    fn test_invalid_requests() {
+        let cases = [
+            (
+                "GET /rotamers?res=GLY&phi=0&psi=0 HTTP/1.1",
+                "400 Bad Request",
+            ),
+            (
+                "GET /rotamers?res=ARG&phi=NaN&psi=0 HTTP/1.1",
+                "400 Bad Request",
+            ),
+            (
+                "GET /rotamers?res=ARG&phi=x&psi=0 HTTP/1.1",
+                "400 Bad Request",
+            ),
+            ("GET /rotamers?res=ARG&phi=0 HTTP/1.1", "400 Bad Request"),
+            (
+                "GET /rotamers?res=ARG&phi=0&psi=0&chi=1 HTTP/1.1",
+                "400 Bad Request",
+            ),
+            (
+                "POST /rotamers?res=ARG&phi=0&psi=0 HTTP/1.1",
+                "405 Method Not Allowed",
+            ),
+            ("GET / HTTP/1.1", "404 Not Found"),
+            ("", "400 Bad Request"),
+        ];
+        for (request, expected) in cases {
+            let (status, body) = respond(request);
+            assert_eq!(status, expected, "{request}");
+            assert!(body.starts_with(r#"{"error":""#), "{body}");
+        }
+        assert_eq!(error_body("a \"b\"\n"), r#"{"error":"a \"b\"\u000a"}"#);
+    }
+
+    #[test]
+    fn test_percent_decoding() {
+        let plain = respond("GET /rotamers?res=ARG&phi=-65&psi=-43 HTTP/1.1");
+        let encoded = respond("GET /rotamers?res=ARG%20&phi=%2D65&p%73i=-43 HTTP/1.1");
+        assert_eq!(encoded, plain);
+        assert_eq!(
+            respond("GET /rotamers?res=A+RG&phi=0&psi=0 HTTP/1.1").0,
+            "400 Bad Request"
+        );
+        for bad in ["%", "%2", "%G1", "%+1", "%FF"] {
+            assert!(percent_decode(bad).is_err(), "{bad}");
+        }
+    }
+
+    /// Status line of the response `serve` writes for the raw `request`.
+    fn served(request: &[u8]) -> String {
+        let mut out = Vec::new();
+        serve(request, &mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+        out.lines().next().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_malformed_and_oversized_requests() {
+        assert_eq!(
+            served(b"GET /rotamers?res=VAL&phi=-65&psi=-43 HTTP/1.1\r\nHost: x\r\n\r\n"),
+            "HTTP/1.1 200 OK"
+        );
+        assert_eq!(
+            served(b"GET /\xff HTTP/1.1\r\n\r\n"),
+            "HTTP/1.1 400 Bad Request"
+        );
+        assert_eq!(served(b"\r\n"), "HTTP/1.1 400 Bad Request");
+
+        let mut long_line = b"GET /rotamers?res=".to_vec();
+        long_line.resize(MAX_LINE + 10, b'A');
+        assert_eq!(
+            served(&long_line),
+            "HTTP/1.1 431 Request Header Fields Too Large"
+        );
+        let mut long_header = b"GET / HTTP/1.1\r\nX: ".to_vec();
+        long_header.resize(MAX_LINE * 2, b'A');
+        assert_eq!(
+            served(&long_header),
+            "HTTP/1.1 431 Request Header Fields Too Large"
+        );
+        let many_headers = format!(
+            "GET / HTTP/1.1\r\n{}\r\n",
+            "X: 1\r\n".repeat(MAX_HEADERS + 1)
+        );
+        assert_eq!(
+            served(many_headers.as_bytes()),
+            "HTTP/1.1 431 Request Header Fields Too Large"
+        );
+    }
+
+    #[test]
+    fn test_slow_client_hits_deadline() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let client = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            // Trickle a request line that never ends, each byte well
+            // inside any per-read timeout, until the server hangs up.
+            while stream.write_all(b"G").is_ok() {
+                thread::sleep(Duration::from_millis(20));
+            }
+        });
+        let (stream, _) = listener.accept().unwrap();
+        let start = Instant::now();
+        let mut reader = BufReader::new(Deadline::new(&stream, Duration::from_millis(200)));
+        let err = read_head(&mut reader).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert!(start.elapsed() < Duration::from_secs(2));
+        drop(reader);
+        drop(stream);
+        client.join().unwrap();
+    }
+}
@@ -0,0 +1,362 @@
+//! Angular ranges of the discrete χ bins.
+
+use crate::error::Error;
+use crate::math::wrap_deg;
+
+/// Angular range of one χ bin: the half-open interval
+/// \[`start`, `end`) in degrees, taken modulo `period`.
+///
+/// Every χ of every residue is partitioned into [`Residue::BINS_PER_CHI`]
+/// consecutive ranges of equal width, listed in
+/// [`Residue::BIN_RANGES`]; bin `k` of a χ is its `k − 1`-th range. The
+/// same type describes the χ ranges of a [`FlexRotamer`](crate::FlexRotamer).
+///
+/// [`Residue::BINS_PER_CHI`]: crate::Residue::BINS_PER_CHI
+/// [`Residue::BIN_RANGES`]: crate::Residue::BIN_RANGES
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::BinRange;
+///
+/// assert!(BinRange::TRANS.contains(-170.0));
+/// assert!(BinRange::G_MINUS.contains(-60.0));
+/// assert!(!BinRange::G_PLUS.contains(120.0));
+/// assert_eq!(BinRange::G_PLUS.center(), 60.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinRange {
+    /// Lower bound in degrees, inclusive.
+    pub start: f32,
+    /// Upper bound in degrees, exclusive. Exceeds `start` by the bin width
+    /// and may lie past 180°, as for [`TRANS`](Self::TRANS).
+    pub end: f32,
+    /// Period of the χ in degrees: 360°, or 180° for a terminal χ whose two
+    /// end atoms are chemically equivalent (Asp χ2, Glu χ3, Phe χ2, Tyr χ2).
+    pub period: f32,
+}
+
+impl BinRange {
+    /// Rotameric bin 1, gauche+: \[0°, 120°).
+    pub const G_PLUS: Self = Self::new(0.0, 120.0, 360.0);
+    /// Rotameric bin 2, trans: \[120°, 240°), i.e. χ ≥ 120° or χ < −120°.
+    pub const TRANS: Self = Self::new(120.0, 240.0, 360.0);
+    /// Rotameric bin 3, gauche−: \[240°, 360°), i.e. \[−120°, 0°).
+    pub const G_MINUS: Self = Self::new(240.0, 360.0, 360.0);
+
+    /// The range \[`start`, `end`) modulo `period`.
+    #[inline]
+    pub const fn new(start: f32, end: f32, period: f32) -> Self {
+        Self { start, end, period }
+    }
+
+    /// Width of the range in degrees.
+    #[inline]
+    pub fn width(self) -> f32 {
+        self.end - self.start
+    }
+
+    /// Midpoint of the range, wrapped into \[−180°, 180°).
+    #[inline]
+    pub fn center(self) -> f32 {
+        wrap_deg(self.start + 0.5 * self.width())
+    }
+
+    /// Whether the angle `chi`, in degrees, lies in the range. Any real
+    /// angle is accepted and reduced modulo [`period`](Self::period); NaN
+    /// and infinities lie in no range.
+    #[inline]
+    pub fn contains(self, chi: f32) -> bool {
+        let mut offset = (chi - self.start) % self.period;
+        if offset < 0.0 {
+            offset += self.period;
+        }
+        offset < self.width()
+    }
+
+    /// Angular distance in degrees from `chi` to the nearest point of the
+    /// range, modulo [`period`](Self::period): `0.0` inside the range,
+    /// otherwise how far `chi` lies past the nearer boundary. NaN for NaN
+    /// or infinite `chi`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::BinRange;
+    ///
+    /// assert_eq!(BinRange::G_PLUS.distance(60.0), 0.0);
+    /// assert_eq!(BinRange::G_PLUS.distance(130.0), 10.0);
+    /// assert_eq!(BinRange::G_PLUS.distance(-15.0), 15.0);
+    /// ```
+    #[inline]
+    pub fn distance(self, chi: f32) -> f32 {
+        let mut offset = (chi - self.start) % self.period;
+        if offset < 0.0 {
+            offset += self.period;
+        }
+        if offset < self.width() {
+            0.0
+        } else {
+            (offset - self.width()).min(self.period - offset)
+        }
+    }
+}
+
+/// Per-χ disagreement between a bin key and χ values, produced by
+/// [`Residue::bin_discrepancy`](crate::Residue::bin_discrepancy).
+///
+/// Each entry is the [`BinRange::distance`] of a χ value from the range of
+/// its assigned bin, in degrees: `0.0` where the assignment agrees with
+/// [`Residue::BIN_RANGES`](crate::Residue::BIN_RANGES).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinDiscrepancy {
+    degrees: [f32; 4],
+    n_chi: usize,
+}
+
+impl BinDiscrepancy {
+    /// Discrepancy of each χ in degrees, one entry per χ.
+    #[inline]
+    pub fn as_slice(&self) -> &[f32] {
+        &self.degrees[..self.n_chi]
+    }
+
+    /// Largest discrepancy over all χ, in degrees.
+    #[inline]
+    pub fn max(&self) -> f32 {
+        self.as_slice().iter().fold(0.0, |acc, &d| acc.max(d))
+    }
+
+    /// Whether every χ lies within `tolerance` degrees of its assigned bin.
+    ///
+    /// A tolerance of `0.0` demands strict agreement. Semi-rotameric χ use
+    /// nominal boundaries (see [`Residue::BIN_RANGES`]), so assignments
+    /// from other software usually need about one 30° range of slack there.
+    ///
+    /// [`Residue::BIN_RANGES`]: crate::Residue::BIN_RANGES
+    #[inline]
+    pub fn is_within(&self, tolerance: f32) -> bool {
+        self.as_slice().iter().all(|&d| d <= tolerance)
+    }
+}
+
+/// Named bin of a rotameric χ: the value of a bin index from 1 to 3 in
+/// [`Rotamer::r`](crate::Rotamer::r) when the χ has the three
+/// [`BinRange`]s g+, t, g−.
+///
+/// Converting with [`TryFrom<u8>`] rejects anything but 1, 2, or 3, so
+/// raw bin indices from third-party files are checked once on the way in;
+/// [`Residue::chi_bin`](crate::Residue::chi_bin) additionally rejects χ
+/// angles that are not rotameric. Converting back to `u8` yields the
+/// library's index.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{BinRange, ChiBin, Error};
+///
+/// assert_eq!(ChiBin::try_from(3), Ok(ChiBin::GMinus));
+/// assert_eq!(ChiBin::try_from(0), Err(Error::InvalidBinKey));
+/// assert_eq!(u8::from(ChiBin::Trans), 2);
+/// assert_eq!(ChiBin::GPlus.range(), BinRange::G_PLUS);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum ChiBin {
+    /// Gauche+, bin 1: \[0°, 120°).
+    GPlus = 1,
+    /// Trans, bin 2: \[120°, 240°).
+    Trans = 2,
+    /// Gauche−, bin 3: \[240°, 360°), i.e. \[−120°, 0°).
+    GMinus = 3,
+}
+
+impl ChiBin {
+    /// All three bins in index order.
+    pub const ALL: [Self; 3] = [Self::GPlus, Self::Trans, Self::GMinus];
+
+    /// Angular range of the bin.
+    #[inline]
+    pub const fn range(self) -> BinRange {
+        match self {
+            Self::GPlus => BinRange::G_PLUS,
+            Self::Trans => BinRange::TRANS,
+            Self::GMinus => BinRange::G_MINUS,
+        }
+    }
+}
+
+impl TryFrom<u8> for ChiBin {
+    type Error = Error;
+
+    /// Bin with library index `bin`.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidBinKey`] unless `bin` is 1, 2, or 3.
+    #[inline]
+    fn try_from(bin: u8) -> Result<Self, Error> {
+        match bin {
+            1 => Ok(Self::GPlus),
+            2 => Ok(Self::Trans),
+            3 => Ok(Self::GMinus),
+            _ => Err(Error::InvalidBinKey),
+        }
+    }
+}
+
+impl From<ChiBin> for u8 {
+    #[inline]
+    fn from(bin: ChiBin) -> Self {
+        bin as u8
+    }
+}
+
+/// Whether `ranges` are the three rotameric bins g+, t, g−.
+pub(crate) fn is_rotameric(ranges: &[BinRange]) -> bool {
+    ranges == ChiBin::ALL.map(ChiBin::range)
+}
+
+/// Measures how far each χ of `chis` lies from the range of its bin in
+/// `r` under `ranges`, a residue's [`Residue::BIN_RANGES`].
+///
+/// [`Residue::BIN_RANGES`]: crate::Residue::BIN_RANGES
+pub(crate) fn discrepancy(
+    ranges: &[&[BinRange]],
+    r: &[u8],
+    chis: &[f32],
+) -> Result<BinDiscrepancy, Error> {
+    if r.len() != ranges.len() || chis.len() != ranges.len() {
+        return Err(Error::InvalidBinKey);
+    }
+    let mut degrees = [0.0; 4];
+    for (((out, chi_ranges), &bin), &chi) in degrees.iter_mut().zip(ranges).zip(r).zip(chis) {
+        if !chi.is_finite() {
+            return Err(Error::NonFiniteAngle);
+        }
+        let range = usize::from(bin)
+            .checked_sub(1)
+            .and_then(|k| chi_ranges.get(k))
+            .ok_or(Error::InvalidBinKey)?;
+        *out = range.distance(chi);
+    }
+    Ok(BinDiscrepancy {
+        degrees,
+        n_chi: ranges.len(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Asp, Leu, Pro, Residue, Trp};
+
+    #[test]
+    fn test_rotameric_ranges_partition_circle() {
+        for chi in (-360..=360).map(|deg| deg as f32 * 0.5 + 0.25) {
+            let hits = [BinRange::G_PLUS, BinRange::TRANS, BinRange::G_MINUS]
+                .iter()
+                .filter(|range| range.contains(chi))
+                .count();
+            assert_eq!(hits, 1, "χ = {chi}");
+        }
+        assert!(BinRange::G_PLUS.contains(0.0) && !BinRange::G_MINUS.contains(0.0));
+        assert!(BinRange::TRANS.contains(-180.0) && BinRange::TRANS.contains(180.0));
+        assert!(!BinRange::TRANS.contains(f32::NAN));
+        assert_eq!(BinRange::TRANS.center(), -180.0);
+    }
+
+    #[test]
+    fn test_half_period_range_wraps() {
+        let range = Asp::BIN_RANGES[1][0];
+        assert_eq!(range.period, 180.0);
+        assert!(range.contains(range.start) && range.contains(range.start + 180.0));
+        assert!(!range.contains(range.end));
+    }
+
+    #[test]
+    fn test_classify_chi() {
+        assert_eq!(Leu::classify_chi(0, 65.0), Some(1));
+        assert_eq!(Leu::classify_chi(1, 175.0), Some(2));
+        assert_eq!(Leu::classify_chi(1, -65.0), Some(3));
+        assert_eq!(Leu::classify_chi(2, 65.0), None);
+        assert_eq!(Pro::classify_chi(0, 30.0), Some(1));
+        assert_eq!(Pro::classify_chi(0, -25.0), Some(2));
+        assert_eq!(Pro::classify_chi(2, 123.0), Some(1));
+        assert_eq!(Trp::classify_chi(1, f32::INFINITY), None);
+    }
+
+    #[test]
+    fn test_distance_wraps_both_ways() {
+        assert_eq!(BinRange::TRANS.distance(-100.0), 20.0);
+        assert_eq!(BinRange::TRANS.distance(100.0), 20.0);
+        assert_eq!(BinRange::G_MINUS.distance(5.0), 5.0);
+        let range = Asp::BIN_RANGES[1][0];
+        assert_eq!(range.distance(range.start + 180.0), 0.0);
+        assert!(BinRange::G_PLUS.distance(f32::NAN).is_nan());
+    }
+
+    #[test]
+    fn test_bin_discrepancy() {
+        let ok = Leu::bin_discrepancy(&[3, 2], &[-65.0, 175.0]).unwrap();
+        assert_eq!(ok.as_slice(), [0.0, 0.0]);
+        assert!(ok.is_within(0.0));
+
+        // χ1 labelled g+ but measured at −10°; χ2 labelled t at 100°.
+        let off = Leu::bin_discrepancy(&[1, 2], &[-10.0, 100.0]).unwrap();
+        assert_eq!(off.as_slice(), [10.0, 20.0]);
+        assert_eq!(off.max(), 20.0);
+        assert!(off.is_within(20.0) && !off.is_within(15.0));
+
+        assert_eq!(
+            Leu::bin_discrepancy(&[1], &[60.0, 60.0]),
+            Err(Error::InvalidBinKey)
+        );
+        assert_eq!(
+            Leu::bin_discrepancy(&[0, 1], &[60.0, 60.0]),
+            Err(Error::InvalidBinKey)
+        );
+        assert_eq!(
+            Leu::bin_discrepancy(&[4, 1], &[60.0, 60.0]),
+            Err(Error::InvalidBinKey)
+        );
+        assert_eq!(
+            Leu::bin_discrepancy(&[1, 1], &[60.0, f32::NAN]),
+            Err(Error::NonFiniteAngle)
+        );
+    }
+
+    #[test]
+    fn test_chi_bin_round_trip() {
+        for bin in ChiBin::ALL {
+            assert_eq!(ChiBin::try_from(u8::from(bin)), Ok(bin));
+            assert!(bin.range().contains(bin.range().center()));
+        }
+        for raw in [0, 4, 12, u8::MAX] {
+            assert_eq!(ChiBin::try_from(raw), Err(Error::InvalidBinKey));
+        }
+    }
+
+    #[test]
+    fn test_residue_chi_bin() {
+        assert_eq!(Leu::chi_bin(1, 3), Ok(ChiBin::GMinus));
+        assert_eq!(Leu::chi_bin(0, 4), Err(Error::InvalidBinKey));
+        assert_eq!(
+            Leu::chi_bin(2, 1),
+            Err(Error::ChiIndexOutOfRange { index: 2, n_chi: 2 })
+        );
+        // Semi-rotameric Trp χ2 and proline χ1 have no g+/t/g− bins.
+        assert_eq!(Trp::chi_bin(0, 2), Ok(ChiBin::Trans));
+        assert_eq!(Trp::chi_bin(1, 2), Err(Error::InvalidBinKey));
+        assert_eq!(Pro::chi_bin(0, 1), Err(Error::InvalidBinKey));
+    }
+
+    #[test]
+    fn test_library_rotamers_consistent() {
+        // Interpolated rotameric means stay inside their bins.
+        for rot in Leu::rotamers(-65.0, -40.0) {
+            let d = Leu::bin_discrepancy(&rot.r, &rot.chi_mean).unwrap();
+            assert!(d.is_within(0.0), "{:?}: {d:?}", rot.r);
+        }
+    }
+}
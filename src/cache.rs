@@ -0,0 +1,173 @@
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use crate::grid::{GRID_COUNT, GRID_MIN, GRID_STEP, angle_to_grid};
+use crate::residue::Residue;
+
+/// Coarse grid cells per axis.
+const CELLS: usize = GRID_COUNT - 1;
+
+/// Adaptive cache that refines frequently queried regions of the
+/// Ramachandran plot onto a finer sub-grid, turning repeated interpolation
+/// in packing hot spots into table lookups.
+///
+/// Each 10° × 10° grid cell counts its queries. Below the hit threshold a
+/// query is evaluated exactly with [`Residue::rotamers`]; once a cell
+/// reaches the threshold, the rotamers at every node of a
+/// `subdivisions × subdivisions` sub-grid of the cell are precomputed, and
+/// later queries in that cell return the set at the nearest sub-grid node.
+/// Refined results are therefore exactly
+/// [`Residue::rotamers`] at the snapped angles, off by at most half a
+/// sub-grid step (`5° / subdivisions`) in φ and ψ.
+///
+/// A refined cell holds `(subdivisions + 1)² × N_ROTAMERS` rotamers, so
+/// memory grows with the number of hot cells, not with the query count.
+///
+/// Requires the `alloc` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{FineGridCache, Leu, Residue};
+///
+/// // 1° sub-grid, refined after 2 queries in the same cell.
+/// let mut cache = FineGridCache::<Leu>::new(10, 2);
+/// let cold = cache.rotamers(-64.6, -41.3).to_vec();
+/// assert!(cold.iter().eq(Leu::rotamers(-64.6, -41.3).collect::<Vec<_>>().iter()));
+///
+/// cache.rotamers(-66.0, -48.0);
+/// assert!(cache.is_refined(-64.6, -41.3));
+/// let hot = cache.rotamers(-64.6, -41.3);
+/// assert!(hot.iter().eq(Leu::rotamers(-65.0, -41.0).collect::<Vec<_>>().iter()));
+/// ```
+pub struct FineGridCache<R: Residue> {
+    subdivisions: usize,
+    threshold: u32,
+    hits: Vec<u32>,
+    refined: Vec<Option<Vec<R::Rot>>>,
+    scratch: Vec<R::Rot>,
+    _residue: PhantomData<R>,
+}
+
+impl<R: Residue> FineGridCache<R> {
+    /// Create an empty cache that refines a cell onto a sub-grid with
+    /// `subdivisions` steps per axis after `threshold` queries in it.
+    ///
+    /// A `threshold` of `0` or `1` refines each cell on its first query.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `subdivisions` is zero.
+    pub fn new(subdivisions: usize, threshold: u32) -> Self {
+        assert!(subdivisions > 0, "subdivisions must be positive");
+        Self {
+            subdivisions,
+            threshold,
+            hits: alloc::vec![0; CELLS * CELLS],
+            refined: (0..CELLS * CELLS).map(|_| None).collect(),
+            scratch: Vec::with_capacity(R::N_ROTAMERS),
+            _residue: PhantomData,
+        }
+    }
+
+    /// Rotamers at the given backbone dihedral angles, in table order.
+    ///
+    /// Exact in cold cells; snapped to the nearest sub-grid node in refined
+    /// cells. Angles are clamped to \[−180.0, 180.0\] as in
+    /// [`Residue::rotamers`].
+    pub fn rotamers(&mut self, phi: f32, psi: f32) -> &[R::Rot] {
+        let (phi_idx, phi_frac) = angle_to_grid(phi);
+        let (psi_idx, psi_frac) = angle_to_grid(psi);
+        let cell = phi_idx * CELLS + psi_idx;
+        self.hits[cell] = self.hits[cell].saturating_add(1);
+        if self.refined[cell].is_none() && self.hits[cell] >= self.threshold {
+            self.refined[cell] = Some(self.refine(phi_idx, psi_idx));
+        }
+        #[cfg(feature = "metrics")]
+        crate::metrics::cache(self.refined[cell].is_some());
+
+        match &self.refined[cell] {
+            Some(nodes) => {
+                let snap = |frac: f32| (frac * self.subdivisions as f32 + 0.5) as usize;
+                let node = snap(phi_frac) * (self.subdivisions + 1) + snap(psi_frac);
+                &nodes[node * R::N_ROTAMERS..(node + 1) * R::N_ROTAMERS]
+            }
+            None => {
+                self.scratch.clear();
+                self.scratch.extend(R::rotamers(phi, psi));
+                &self.scratch
+            }
+        }
+    }
+
+    /// Whether the cell containing (`phi`, `psi`) has been refined.
+    pub fn is_refined(&self, phi: f32, psi: f32) -> bool {
+        let (phi_idx, _) = angle_to_grid(phi);
+        let (psi_idx, _) = angle_to_grid(psi);
+        self.refined[phi_idx * CELLS + psi_idx].is_some()
+    }
+
+    /// Number of refined cells.
+    pub fn refined_cells(&self) -> usize {
+        self.refined.iter().filter(|cell| cell.is_some()).count()
+    }
+
+    /// Drop every refined cell and reset the hit counts.
+    pub fn clear(&mut self) {
+        self.hits.fill(0);
+        self.refined.fill_with(|| None);
+    }
+
+    /// Evaluates every sub-grid node of one cell, ψ varying fastest.
+    fn refine(&self, phi_idx: usize, psi_idx: usize) -> Vec<R::Rot> {
+        let n = self.subdivisions + 1;
+        let step = GRID_STEP / self.subdivisions as f32;
+        let angle = |idx: usize, j: usize| GRID_MIN + GRID_STEP * idx as f32 + step * j as f32;
+        let mut nodes = Vec::with_capacity(n * n * R::N_ROTAMERS);
+        for i in 0..n {
+            for j in 0..n {
+                nodes.extend(R::rotamers(angle(phi_idx, i), angle(psi_idx, j)));
+            }
+        }
+        nodes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Arg, Val};
+
+    fn exact<R: Residue>(phi: f32, psi: f32) -> Vec<R::Rot> {
+        R::rotamers(phi, psi).collect()
+    }
+
+    #[test]
+    fn test_cold_queries_are_exact() {
+        let mut cache = FineGridCache::<Arg>::new(4, 3);
+        for (phi, psi) in [(-63.3, -41.7), (-61.0, -44.0)] {
+            assert_eq!(cache.rotamers(phi, psi), exact::<Arg>(phi, psi));
+        }
+        assert_eq!(cache.refined_cells(), 0);
+    }
+
+    #[test]
+    fn test_refined_queries_snap_to_sub_grid() {
+        let mut cache = FineGridCache::<Val>::new(2, 1);
+        // Sub-grid nodes every 5°: −63.3 snaps to −65, −41.7 to −40.
+        assert_eq!(cache.rotamers(-63.3, -41.7), exact::<Val>(-65.0, -40.0));
+        assert_eq!(cache.rotamers(-61.0, -44.0), exact::<Val>(-60.0, -45.0));
+        assert_eq!(cache.refined_cells(), 1);
+        assert!(!cache.is_refined(-55.0, -41.7));
+    }
+
+    #[test]
+    fn test_clamped_edges_and_clear() {
+        let mut cache = FineGridCache::<Val>::new(5, 0);
+        assert_eq!(cache.rotamers(180.0, 180.0), exact::<Val>(180.0, 180.0));
+        assert_eq!(cache.rotamers(-200.0, 0.0), exact::<Val>(-180.0, 0.0));
+        assert_eq!(cache.refined_cells(), 2);
+        cache.clear();
+        assert_eq!(cache.refined_cells(), 0);
+    }
+}
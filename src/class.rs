@@ -0,0 +1,133 @@
+//! Physicochemical residue classes.
+//!
+//! Classes follow the IMGT physicochemical classification (Pommié et al.,
+//! *J. Mol. Recognit.* 17, 17–32, 2004): hydropathy, volume, charge,
+//! polarity, and chemical side-chain type. The cysteine and proline
+//! variants belong to the classes of their parent residue. A residue type
+//! can belong to several classes or none beyond its own.
+
+use crate::residue::Residue;
+
+/// Set of physicochemical classes, the value of [`Residue::CLASS`].
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Class, Phe, Residue, Tyr};
+///
+/// assert!(Phe::CLASS.contains(Class::AROMATIC | Class::HYDROPHOBIC));
+/// assert!(!Tyr::CLASS.contains(Class::HYDROPHOBIC));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Class(u8);
+
+impl Class {
+    /// No class.
+    pub const NONE: Self = Self(0);
+    /// Aromatic side chain (Phe, Trp, Tyr). See [`Aromatic`].
+    pub const AROMATIC: Self = Self(1 << 0);
+    /// Charged at neutral pH (Arg, His, Lys, Asp, Glu). See [`Charged`].
+    pub const CHARGED: Self = Self(1 << 1);
+    /// Polar side chain. See [`Polar`].
+    pub const POLAR: Self = Self(1 << 2);
+    /// Hydrophobic by hydropathy. See [`Hydrophobic`].
+    pub const HYDROPHOBIC: Self = Self(1 << 3);
+    /// Small or very small side-chain volume. See [`Small`].
+    pub const SMALL: Self = Self(1 << 4);
+
+    /// Classes in either set.
+    #[inline]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Whether every class of `other` is in `self`.
+    #[inline]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Whether `self` and `other` share a class.
+    #[inline]
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+}
+
+impl core::ops::BitOr for Class {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+/// Residue types with an aromatic side chain: [`Phe`](crate::Phe),
+/// [`Trp`](crate::Trp), and [`Tyr`](crate::Tyr).
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Aromatic, Phe, Residue, Trp};
+///
+/// fn ring_flip_candidates<R: Aromatic>() -> usize {
+///     R::N_ROTAMERS
+/// }
+///
+/// assert_eq!(ring_flip_candidates::<Phe>(), 18);
+/// assert_eq!(ring_flip_candidates::<Trp>(), 36);
+/// ```
+pub trait Aromatic: Residue {}
+
+/// Residue types charged at neutral pH: [`Arg`](crate::Arg),
+/// [`His`](crate::His), [`Lys`](crate::Lys), [`Asp`](crate::Asp), and
+/// [`Glu`](crate::Glu).
+pub trait Charged: Residue {}
+
+/// Residue types with a polar side chain: Arg, Asn, Asp, Gln, Glu, His,
+/// Lys, Ser, Thr, and Tyr.
+pub trait Polar: Residue {}
+
+/// Hydrophobic residue types: Cys (all variants), Ile, Leu, Met, Phe, Trp,
+/// and Val.
+pub trait Hydrophobic: Residue {}
+
+/// Residue types with a small or very small side chain: Asn, Asp, Cys and
+/// Pro (all variants), Ser, and Thr.
+pub trait Small: Residue {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::*;
+
+    fn aromatic<R: Aromatic>() -> &'static str {
+        R::NAME
+    }
+
+    #[test]
+    fn test_marker_traits_match_constants() {
+        assert_eq!(aromatic::<Tyr>(), "TYR");
+        macro_rules! check {
+            ($Res:ident, $n:literal, $r:literal) => {
+                let class = <$Res as Residue>::CLASS;
+                assert_ne!(class, Class::NONE, "{}", <$Res as Residue>::NAME);
+            };
+        }
+        for_all_residues!(check);
+        assert!(His::CLASS.contains(Class::CHARGED | Class::POLAR));
+        assert!(!His::CLASS.intersects(Class::AROMATIC));
+        assert_eq!(Cyd::CLASS, Cys::CLASS);
+        assert_eq!(Tpr::CLASS, Class::SMALL);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let both = Class::AROMATIC | Class::POLAR;
+        assert!(both.contains(Class::POLAR));
+        assert!(!Class::POLAR.contains(both));
+        assert!(both.intersects(Class::POLAR | Class::SMALL));
+        assert!(Class::NONE.contains(Class::NONE));
+    }
+}
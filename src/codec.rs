@@ -0,0 +1,208 @@
+//! Compact binary encoding of rotamer sets for inter-process transfer.
+
+use crate::error::Error;
+use crate::interp::RotamerIter;
+use crate::rotamer::Rotamer;
+
+/// Version byte written by [`RotamerIter::encode`] and required by
+/// [`RotamerIter::decode`].
+///
+/// All integers and floats are little-endian. A message is an 8-byte header
+/// followed by one fixed-size record per rotamer:
+///
+/// | Offset | Size | Field                                 |
+/// |--------|------|---------------------------------------|
+/// | 0      | 4    | magic `b"DBRS"`                       |
+/// | 4      | 1    | format version (`ENCODING_VERSION`)   |
+/// | 5      | 1    | χ count `N`                           |
+/// | 6      | 2    | rotamer count `n` (`u16`)             |
+/// | 8      | …    | `n` records of `9N + 4` bytes each    |
+///
+/// Each record holds `r` (`N` × `u8`), `prob` (`f32`), `chi_mean`
+/// (`N` × `f32`), then `chi_sigma` (`N` × `f32`), so a consumer can map
+/// the records directly, e.g. in NumPy as
+/// `np.dtype([("r", "u1", N), ("prob", "<f4"), ("chi_mean", "<f4", N),
+/// ("chi_sigma", "<f4", N)])`.
+pub const ENCODING_VERSION: u8 = 1;
+
+const MAGIC: &[u8; 4] = b"DBRS";
+const HEADER_LEN: usize = 8;
+
+impl<const N: usize, const R: usize> RotamerIter<N, R> {
+    /// Size in bytes of the encoding of the remaining rotamers.
+    #[inline]
+    pub fn encoded_len(&self) -> usize {
+        HEADER_LEN + self.len() * record_len::<N>()
+    }
+
+    /// Encode the remaining rotamers into `buf`, returning the number of
+    /// bytes written. See the format description on
+    /// [`ENCODING_VERSION`].
+    ///
+    /// # Errors
+    ///
+    /// [`Error::BufferTooSmall`] if `buf` is shorter than
+    /// [`encoded_len`](Self::encoded_len).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Query, Residue, RotamerIter, Val};
+    ///
+    /// let set = Query::new(-65.0, -40.0).prune(0.05).run::<Val>();
+    /// let mut buf = [0_u8; 64];
+    /// let n = set.encode(&mut buf).unwrap();
+    /// let back = RotamerIter::<1, 3>::decode(&buf[..n]).unwrap();
+    /// assert!(back.eq(Query::new(-65.0, -40.0).prune(0.05).run::<Val>()));
+    /// ```
+    pub fn encode(&self, buf: &mut [u8]) -> Result<usize, Error> {
+        let needed = self.encoded_len();
+        if buf.len() < needed {
+            return Err(Error::BufferTooSmall { needed });
+        }
+        buf[..4].copy_from_slice(MAGIC);
+        buf[4] = ENCODING_VERSION;
+        buf[5] = N as u8;
+        buf[6..8].copy_from_slice(&(self.len() as u16).to_le_bytes());
+        let records = buf[HEADER_LEN..needed].chunks_exact_mut(record_len::<N>());
+        for (rot, record) in self.as_slice().iter().zip(records) {
+            let (r, mut rest) = record.split_at_mut(N);
+            r.copy_from_slice(&rot.r);
+            for value in core::iter::once(rot.prob)
+                .chain(rot.chi_mean)
+                .chain(rot.chi_sigma)
+            {
+                let (head, tail) = rest.split_at_mut(4);
+                head.copy_from_slice(&value.to_le_bytes());
+                rest = tail;
+            }
+        }
+        Ok(needed)
+    }
+
+    /// Encode the remaining rotamers into a new `Vec`.
+    ///
+    /// Requires the `alloc` feature.
+    #[cfg(feature = "alloc")]
+    pub fn to_bytes(&self) -> alloc::vec::Vec<u8> {
+        let mut buf = alloc::vec![0; self.encoded_len()];
+        let written = self.encode(&mut buf);
+        debug_assert_eq!(written, Ok(buf.len()));
+        buf
+    }
+
+    /// Decode a rotamer set produced by [`encode`](Self::encode).
+    ///
+    /// Trailing bytes after the last record are ignored, so messages can be
+    /// read from a larger buffer.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::MalformedEncoding`] if the magic is wrong, the χ count is not
+    /// `N`, more than `R` rotamers are declared, or the buffer is truncated;
+    /// [`Error::UnsupportedVersion`] if the version byte is not
+    /// [`ENCODING_VERSION`].
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+            return Err(Error::MalformedEncoding);
+        }
+        if bytes[4] != ENCODING_VERSION {
+            return Err(Error::UnsupportedVersion(bytes[4]));
+        }
+        let count = u16::from_le_bytes([bytes[6], bytes[7]]) as usize;
+        let needed = HEADER_LEN + count * record_len::<N>();
+        if usize::from(bytes[5]) != N || count > R || bytes.len() < needed {
+            return Err(Error::MalformedEncoding);
+        }
+
+        let mut items = [Rotamer {
+            r: [0; N],
+            prob: 0.0,
+            chi_mean: [0.0; N],
+            chi_sigma: [0.0; N],
+        }; R];
+        let records = bytes[HEADER_LEN..needed].chunks_exact(record_len::<N>());
+        for (item, record) in items.iter_mut().zip(records) {
+            let (r, floats) = record.split_at(N);
+            let float = |k: usize| {
+                let at = 4 * k;
+                f32::from_le_bytes([floats[at], floats[at + 1], floats[at + 2], floats[at + 3]])
+            };
+            item.r.copy_from_slice(r);
+            item.prob = float(0);
+            item.chi_mean = core::array::from_fn(|i| float(1 + i));
+            item.chi_sigma = core::array::from_fn(|i| float(1 + N + i));
+        }
+        Ok(Self::from_parts(items, count))
+    }
+}
+
+/// Bytes per encoded rotamer.
+const fn record_len<const N: usize>() -> usize {
+    N + 4 * (1 + 2 * N)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::Query;
+    use crate::residue::{Arg, Residue};
+
+    #[test]
+    fn test_round_trip_full_and_pruned() {
+        let full = Arg::rotamers(-65.0, -43.0);
+        let mut buf = [0_u8; 8 + 75 * 40];
+        let n = full.encode(&mut buf).unwrap();
+        assert_eq!(n, buf.len());
+        let back = RotamerIter::<4, 75>::decode(&buf).unwrap();
+        assert!(back.eq(Arg::rotamers(-65.0, -43.0)));
+
+        let pruned = Query::new(-65.0, -43.0).prune(0.02).run::<Arg>();
+        let m = pruned.encode(&mut buf).unwrap();
+        assert!(m < n);
+        let back = RotamerIter::<4, 75>::decode(&buf[..m]).unwrap();
+        assert_eq!(back.len(), pruned.len());
+    }
+
+    #[test]
+    fn test_partially_consumed_set_encodes_remaining() {
+        let mut set = Arg::rotamers(-65.0, -43.0);
+        set.next();
+        let mut buf = [0_u8; 8 + 75 * 40];
+        let n = set.encode(&mut buf).unwrap();
+        assert_eq!(u16::from_le_bytes([buf[6], buf[7]]), 74);
+        assert_eq!(RotamerIter::<4, 75>::decode(&buf[..n]).unwrap().len(), 74);
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let set = Arg::rotamers(-65.0, -43.0);
+        let mut small = [0_u8; 16];
+        assert_eq!(
+            set.encode(&mut small),
+            Err(Error::BufferTooSmall {
+                needed: 8 + 75 * 40
+            })
+        );
+
+        let mut buf = [0_u8; 8 + 75 * 40];
+        set.encode(&mut buf).unwrap();
+        assert_eq!(
+            RotamerIter::<4, 75>::decode(&buf[..100]).err(),
+            Some(Error::MalformedEncoding)
+        );
+        assert_eq!(
+            RotamerIter::<4, 10>::decode(&buf).err(),
+            Some(Error::MalformedEncoding)
+        );
+        assert_eq!(
+            RotamerIter::<2, 75>::decode(&buf).err(),
+            Some(Error::MalformedEncoding)
+        );
+        buf[4] = 9;
+        assert_eq!(
+            RotamerIter::<4, 75>::decode(&buf).err(),
+            Some(Error::UnsupportedVersion(9))
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use alloc::vec::Vec;
+
+use crate::query::Query;
+use crate::residue::*;
+use crate::rotamer::Rotamer;
+
+/// Rotamers of one residue type with per-χ arrays padded to four slots.
+///
+/// Slots beyond [`n_chi`](Self::n_chi) hold `0` bin indices and `0.0`
+/// angles, matching the layout of the source CSV. Produced by
+/// [`collect_all_residues`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResidueRotamers {
+    /// Three-letter residue name, as in [`Residue::NAME`].
+    pub name: &'static str,
+    /// Number of meaningful χ slots, as in [`Residue::N_CHI`].
+    pub n_chi: usize,
+    /// Interpolated rotamers in table order.
+    pub rotamers: Vec<Rotamer<4>>,
+}
+
+/// Query every residue type at one backbone conformation and collect the
+/// results, in the order of [`for_all_residues!`](crate::for_all_residues).
+///
+/// Requires the `alloc` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::collect_all_residues;
+///
+/// let all = collect_all_residues(-65.0, -43.0);
+/// assert_eq!(all.len(), 22);
+/// let arg = all.iter().find(|r| r.name == "ARG").unwrap();
+/// assert_eq!((arg.n_chi, arg.rotamers.len()), (4, 75));
+/// ```
+pub fn collect_all_residues(phi: f32, psi: f32) -> Vec<ResidueRotamers> {
+    let mut out = Vec::with_capacity(22);
+    macro_rules! push {
+        ($Res:ident, $n:literal, $r:literal) => {
+            out.push(ResidueRotamers {
+                name: <$Res as Residue>::NAME,
+                n_chi: $n,
+                rotamers: <$Res as Residue>::rotamers(phi, psi)
+                    .map(Rotamer::padded)
+                    .collect(),
+            });
+        };
+    }
+    for_all_residues!(push);
+    out
+}
+
+impl Query {
+    /// Execute the query for residue type `R` and collect the result.
+    ///
+    /// Requires the `alloc` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Arg, Query};
+    ///
+    /// let top = Query::new(-65.0, -43.0).prune(0.05).sorted(true).run_vec::<Arg>();
+    /// assert!(top.windows(2).all(|w| w[0].prob >= w[1].prob));
+    /// ```
+    #[inline]
+    pub fn run_vec<R: Residue>(self) -> Vec<R::Rot> {
+        self.run::<R>().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotamers_vec_matches_iterator() {
+        let v = Leu::rotamers_vec(-65.0, -40.0);
+        assert_eq!(v.len(), 9);
+        assert!(v.iter().copied().eq(Leu::rotamers(-65.0, -40.0)));
+    }
+
+    #[test]
+    fn test_sorted_rotamers_vec() {
+        let v = Arg::sorted_rotamers_vec(-65.0, -43.0, 0.01);
+        assert!(!v.is_empty() && v.len() < 75);
+        assert!(v.windows(2).all(|w| w[0].prob >= w[1].prob));
+        assert!(v.iter().all(|r| r.prob >= 0.01));
+    }
+
+    #[test]
+    fn test_collect_all_residues_padding() {
+        let all = collect_all_residues(-60.0, -40.0);
+        let val = all.iter().find(|r| r.name == "VAL").unwrap();
+        assert_eq!(val.n_chi, 1);
+        for rot in &val.rotamers {
+            assert_eq!(&rot.r[1..], &[0, 0, 0]);
+            assert_eq!(&rot.chi_mean[1..], &[0.0; 3]);
+        }
+        let expected: Vec<_> = Val::rotamers(-60.0, -40.0).map(|r| r.prob).collect();
+        let got: Vec<_> = val.rotamers.iter().map(|r| r.prob).collect();
+        assert_eq!(got, expected);
+    }
+}
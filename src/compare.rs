@@ -0,0 +1,314 @@
+use crate::error::Error;
+use crate::identity::ResidueInfo;
+use crate::interp::RotamerIter;
+use crate::math::{self, lnf, sqrtf, wrap_deg};
+use crate::residue::*;
+use crate::rotamer::Rotamer;
+
+/// Change in the rotamer distribution when the residue at one backbone
+/// position is mutated from type `A` to type `B`.
+///
+/// Produced by [`compare`]. Residue types with different rotamer sets are
+/// compared on what they share: χ1 bins (1 = g+, 2 = t, 3 = g−) and the
+/// entropy of their full distributions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    /// Jensen–Shannon divergence between the χ1-bin marginals of `A` and
+    /// `B`, in nats, in \[0, ln 2\].
+    pub chi1_divergence: f32,
+    /// Shannon entropy of `A`'s rotamer distribution, in nats.
+    pub entropy_from: f32,
+    /// Shannon entropy of `B`'s rotamer distribution, in nats.
+    pub entropy_to: f32,
+    /// `entropy_to − entropy_from`: positive when the mutation frees the
+    /// side chain, negative when it restricts it.
+    pub delta_entropy: f32,
+    /// Most probable rotamer of `A`, padded to four χ slots as in
+    /// `ResidueRotamers`.
+    pub best_from: Rotamer<4>,
+    /// Most probable rotamer of `B`, padded to four χ slots.
+    pub best_to: Rotamer<4>,
+    /// Whether the most probable rotamers fall in different χ1 bins.
+    pub best_chi1_bin_changed: bool,
+    /// Wrapped difference of the best rotamers' χ1 means, `B − A`, in
+    /// degrees, in \[−180°, 180°).
+    pub best_chi1_shift: f32,
+}
+
+/// Compare the rotamer distributions of residue types `A` and `B` at the
+/// same backbone conformation, as a design tool evaluating the point
+/// mutation `A → B` would.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Leu, Phe, Val, compare};
+///
+/// let same = compare::<Leu, Leu>(-65.0, -40.0);
+/// assert_eq!(same.chi1_divergence, 0.0);
+/// assert_eq!(same.delta_entropy, 0.0);
+///
+/// // Val → Phe in a helix: more rotamers, a broader distribution.
+/// let cmp = compare::<Val, Phe>(-65.0, -40.0);
+/// assert!(cmp.delta_entropy > 0.0);
+/// assert!(cmp.chi1_divergence > 0.0);
+/// ```
+pub fn compare<A: Residue, B: Residue>(phi: f32, psi: f32) -> Comparison {
+    let from = A::profile(phi, psi);
+    let to = B::profile(phi, psi);
+    let (bin_from, bin_to) = (from.best.r[0], to.best.r[0]);
+    Comparison {
+        chi1_divergence: jensen_shannon(&from.chi1, &to.chi1),
+        entropy_from: from.entropy,
+        entropy_to: to.entropy,
+        delta_entropy: to.entropy - from.entropy,
+        best_from: from.best,
+        best_to: to.best,
+        best_chi1_bin_changed: bin_from != bin_to,
+        best_chi1_shift: wrap_deg(to.best.chi_mean[0] - from.best.chi_mean[0]),
+    }
+}
+
+/// χ1-bin distributions of several residue types at the same backbone
+/// conformation.
+///
+/// Produced by [`compare_chi1`]. Bins are numbered as in the library
+/// (1 = g+, 2 = t, 3 = g−); residue types with fewer χ1 bins leave the
+/// missing ones at zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Chi1Comparison<const K: usize> {
+    /// Residue types compared, in the order given.
+    pub residues: [&'static ResidueInfo; K],
+    /// χ1-bin marginal of each residue type, normalized to sum to 1.
+    pub chi1: [[f32; 3]; K],
+    /// Most probable χ1 bin of each residue type.
+    pub preferred: [u8; K],
+    /// Pearson correlation between the χ1 marginals of each pair of residue
+    /// types, in \[−1, 1\]. The diagonal is 1; pairs involving a uniform
+    /// marginal, which has no preference to correlate with, are 0.
+    pub correlation: [[f32; K]; K],
+    /// The χ1 bin every residue type prefers, or `None` if they disagree.
+    pub shared_bin: Option<u8>,
+}
+
+/// Compare the χ1-bin distributions of several residue types, named as in
+/// [`ResidueInfo::lookup`], at the same backbone conformation, for analyses
+/// of whether a set of mutations preserves the local packing geometry.
+///
+/// # Errors
+///
+/// [`Error::UnknownResidue`] if a name matches no residue type;
+/// [`Error::NonFiniteAngle`] if `phi` or `psi` is NaN or infinite.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::compare_chi1;
+///
+/// let cmp = compare_chi1(["PHE", "TYR", "TRP"], -65.0, -40.0).unwrap();
+/// assert_eq!(cmp.residues[1].name, "TYR");
+/// // Aromatic side chains agree on a t χ1 in helices...
+/// assert_eq!(cmp.shared_bin, Some(2));
+/// assert!(cmp.correlation[0][1] > 0.9);
+///
+/// // ...where β-branched Ile prefers g−.
+/// let cmp = compare_chi1(["PHE", "ILE"], -65.0, -40.0).unwrap();
+/// assert_eq!(cmp.preferred, [2, 3]);
+/// assert_eq!(cmp.shared_bin, None);
+/// ```
+pub fn compare_chi1<const K: usize>(
+    residues: [&str; K],
+    phi: f32,
+    psi: f32,
+) -> Result<Chi1Comparison<K>, Error> {
+    let mut infos = [&crate::ALL_RESIDUES[0]; K];
+    let mut chi1 = [[0.0_f32; 3]; K];
+    for ((name, info), marginal) in residues.iter().zip(&mut infos).zip(&mut chi1) {
+        *info = ResidueInfo::lookup(name)?;
+        *marginal = chi1_marginal(info, phi, psi)?;
+    }
+    let preferred = chi1.map(|marginal| {
+        let mut best = 0;
+        for (i, &p) in marginal.iter().enumerate() {
+            if p > marginal[best] {
+                best = i;
+            }
+        }
+        best as u8 + 1
+    });
+    let correlation =
+        core::array::from_fn(|i| core::array::from_fn(|j| pearson(&chi1[i], &chi1[j], i == j)));
+    let shared_bin = preferred
+        .first()
+        .copied()
+        .filter(|&first| preferred.iter().all(|&bin| bin == first));
+    Ok(Chi1Comparison {
+        residues: infos,
+        chi1,
+        preferred,
+        correlation,
+        shared_bin,
+    })
+}
+
+/// Normalized χ1-bin marginal of one residue type.
+fn chi1_marginal(info: &ResidueInfo, phi: f32, psi: f32) -> Result<[f32; 3], Error> {
+    macro_rules! dispatch {
+        ($Res:ident, $n:literal, $r:literal) => {
+            if info.name == <$Res as Residue>::NAME {
+                let chi1 = profile(<$Res as Residue>::try_rotamers(phi, psi)?).chi1;
+                let sum: f32 = chi1.iter().sum();
+                return Ok(if sum > 0.0 {
+                    chi1.map(|p| p / sum)
+                } else {
+                    chi1
+                });
+            }
+        };
+    }
+    for_all_residues!(dispatch);
+    Err(Error::UnknownResidue)
+}
+
+/// Pearson correlation of two χ1 marginals over their three bins; 0 when
+/// either is uniform, unless `same`.
+fn pearson(p: &[f32; 3], q: &[f32; 3], same: bool) -> f32 {
+    if same {
+        return 1.0;
+    }
+    let (mp, mq) = (p.iter().sum::<f32>() / 3.0, q.iter().sum::<f32>() / 3.0);
+    let (mut cov, mut vp, mut vq) = (0.0_f32, 0.0_f32, 0.0_f32);
+    for (&a, &b) in p.iter().zip(q) {
+        let (da, db) = (a - mp, b - mq);
+        cov += da * db;
+        vp += da * da;
+        vq += db * db;
+    }
+    if vp <= 0.0 || vq <= 0.0 {
+        return 0.0;
+    }
+    (cov / sqrtf(vp * vq)).clamp(-1.0, 1.0)
+}
+
+/// Summary of one residue's distribution used by [`compare`].
+#[derive(Debug, Clone, Copy)]
+pub struct Profile {
+    entropy: f32,
+    chi1: [f32; 3],
+    best: Rotamer<4>,
+}
+
+/// Summarizes a full rotamer set for [`compare`].
+pub(crate) fn profile<const N: usize, const R: usize>(iter: RotamerIter<N, R>) -> Profile {
+    let rotamers = iter.as_slice();
+    let mut chi1 = [0.0_f32; 3];
+    let mut best = &rotamers[0];
+    for rot in rotamers {
+        if let Some(bin) = chi1.get_mut(usize::from(rot.r[0]).wrapping_sub(1)) {
+            *bin += rot.prob;
+        }
+        if rot.prob > best.prob {
+            best = rot;
+        }
+    }
+    Profile {
+        entropy: math::entropy(rotamers.iter().map(|rot| rot.prob)),
+        chi1,
+        best: best.padded(),
+    }
+}
+
+/// Jensen–Shannon divergence of two (re-normalized) discrete distributions,
+/// in nats.
+fn jensen_shannon(p: &[f32; 3], q: &[f32; 3]) -> f32 {
+    let (sp, sq): (f32, f32) = (p.iter().sum(), q.iter().sum());
+    let mut js = 0.0_f32;
+    for i in 0..3 {
+        let (a, b) = (p[i] / sp, q[i] / sq);
+        let m = 0.5 * (a + b);
+        if a > 0.0 {
+            js += 0.5 * a * lnf(a / m);
+        }
+        if b > 0.0 {
+            js += 0.5 * b * lnf(b / m);
+        }
+    }
+    js.max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Leu, Phe, Tyr, Val};
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_jensen_shannon_bounds() {
+        assert_eq!(jensen_shannon(&[0.2, 0.3, 0.5], &[0.2, 0.3, 0.5]), 0.0);
+        let disjoint = jensen_shannon(&[1.0, 0.0, 0.0], &[0.0, 0.0, 1.0]);
+        assert_relative_eq!(disjoint, core::f32::consts::LN_2, epsilon = 1e-5);
+        // Inputs are re-normalized.
+        assert_eq!(jensen_shannon(&[2.0, 2.0, 0.0], &[0.5, 0.5, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn test_profile_matches_iterator() {
+        let p = profile(Leu::rotamers(-65.0, -40.0));
+        let best = Leu::rotamers(-65.0, -40.0)
+            .max_by(|a, b| a.prob.total_cmp(&b.prob))
+            .unwrap();
+        assert_eq!(p.best.r[..2], best.r);
+        assert_eq!(p.best.r[2..], [0, 0]);
+        let marginal: f32 = p.chi1.iter().sum();
+        assert_relative_eq!(marginal, 1.0, epsilon = 1e-5);
+        assert!(p.entropy > 0.0 && p.entropy < lnf(9.0));
+    }
+
+    #[test]
+    fn test_pearson() {
+        let p = [0.7, 0.2, 0.1];
+        assert_relative_eq!(pearson(&p, &[0.6, 0.3, 0.1], false), 0.968, epsilon = 1e-3);
+        assert_relative_eq!(pearson(&p, &[0.1, 0.2, 0.7], false), -0.742, epsilon = 1e-3);
+        let flat = [1.0 / 3.0; 3];
+        assert_eq!(pearson(&p, &flat, false), 0.0);
+        assert_eq!(pearson(&flat, &flat, true), 1.0);
+    }
+
+    #[test]
+    fn test_chi1_comparison() {
+        let cmp = compare_chi1(["LEU", "ile", "VAL"], -120.0, 130.0).unwrap();
+        let from_compare = profile(Leu::rotamers(-120.0, 130.0)).chi1;
+        assert_relative_eq!(cmp.chi1[0][..], from_compare[..], epsilon = 1e-6);
+        for (i, row) in cmp.correlation.iter().enumerate() {
+            assert_eq!(row[i], 1.0);
+            for (j, &r) in row.iter().enumerate() {
+                assert_eq!(r, cmp.correlation[j][i]);
+            }
+        }
+        assert_eq!(
+            cmp.shared_bin,
+            Some(cmp.preferred[0]).filter(|&bin| cmp.preferred.iter().all(|&b| b == bin))
+        );
+        assert_eq!(
+            compare_chi1(["LEU", "GLY"], 0.0, 0.0),
+            Err(Error::UnknownResidue)
+        );
+        assert_eq!(
+            compare_chi1(["LEU"], f32::NAN, 0.0),
+            Err(Error::NonFiniteAngle)
+        );
+        assert_eq!(compare_chi1([], 0.0, 0.0).unwrap().shared_bin, None);
+    }
+
+    #[test]
+    fn test_antisymmetric_entropy_and_shift() {
+        let ab = compare::<Phe, Tyr>(-120.0, 130.0);
+        let ba = compare::<Tyr, Phe>(-120.0, 130.0);
+        assert_relative_eq!(ab.chi1_divergence, ba.chi1_divergence, epsilon = 1e-7);
+        assert_eq!(ab.delta_entropy, -ba.delta_entropy);
+        assert_eq!(ab.best_from, ba.best_to);
+        let v = compare::<Val, Val>(60.0, 40.0);
+        assert!(!v.best_chi1_bin_changed);
+        assert_eq!(v.best_chi1_shift, 0.0);
+    }
+}
@@ -0,0 +1,214 @@
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+use crate::error::Error;
+use crate::grid::{GRID_COUNT, grid_angle};
+use crate::identity::ResidueInfo;
+use crate::residue::Residue;
+
+/// Number of grid cells per axis.
+const CELLS: usize = GRID_COUNT - 1;
+
+/// One iso-probability line over the Ramachandran plane.
+///
+/// Produced by [`isocontours`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contour {
+    /// Vertices as `(φ, ψ)` in degrees, in drawing order.
+    pub points: Vec<(f32, f32)>,
+    /// Whether the line closes on itself; open lines start and end on the
+    /// border of the plane. The last point of a closed line repeats the
+    /// first.
+    pub closed: bool,
+}
+
+/// Extract the contours where the probability of one rotamer of `R` equals
+/// `level`, for plotting which backbone regions support that side-chain
+/// state.
+///
+/// The rotamer is named by its bin indices `r`, one per χ. Marching squares
+/// runs over the 37 × 37 grid with linear interpolation along cell edges,
+/// and resolves saddle cells by the mean of their corners, so the lines
+/// enclose the regions where the probability exceeds `level` and match
+/// [`Bilinear`](crate::Bilinear) interpolation at every edge crossing.
+/// Requires the `alloc` feature.
+///
+/// # Errors
+///
+/// [`Error::InvalidBinKey`] if `R` has no rotamer with bin indices `r`.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Leu, isocontours};
+///
+/// // Where the mt rotamer of Leu holds at least half the probability.
+/// let contours = isocontours::<Leu>(&[3, 2], 0.5).unwrap();
+/// assert!(!contours.is_empty());
+/// for contour in &contours {
+///     assert!(contour.points.len() >= 2);
+///     assert!(contour.points.iter().all(|&(phi, psi)| phi.abs() <= 180.0 && psi.abs() <= 180.0));
+/// }
+/// ```
+pub fn isocontours<R: Residue>(r: &[u8], level: f32) -> Result<Vec<Contour>, Error> {
+    let info = const { &ResidueInfo::of::<R>() };
+    let k = (0..R::N_ROTAMERS)
+        .find(|&k| R::grid_record(info, 0, 0, k).bins() == r)
+        .ok_or(Error::InvalidBinKey)?;
+    let mut values = [[0.0_f32; GRID_COUNT]; GRID_COUNT];
+    for (phi_idx, row) in values.iter_mut().enumerate() {
+        for (psi_idx, value) in row.iter_mut().enumerate() {
+            *value = R::grid_record(info, phi_idx, psi_idx, k).prob;
+        }
+    }
+    Ok(trace(&values, level))
+}
+
+/// Edge crossing, identified by its grid edge: `(phi_idx, psi_idx, 0)` runs
+/// along φ from that node, `(.., 1)` along ψ.
+type Edge = (usize, usize, u8);
+
+/// Marching squares over `values[phi_idx][psi_idx]` at `level`.
+fn trace(values: &[[f32; GRID_COUNT]; GRID_COUNT], level: f32) -> Vec<Contour> {
+    let above = |i: usize, j: usize| values[i][j] > level;
+    let point = |(i, j, axis): Edge| {
+        let (a, b) = match axis {
+            0 => (values[i][j], values[i + 1][j]),
+            _ => (values[i][j], values[i][j + 1]),
+        };
+        let t = if a == b { 0.5 } else { (level - a) / (b - a) };
+        let (phi, psi) = (grid_angle(i), grid_angle(j));
+        match axis {
+            0 => (phi + t * (grid_angle(i + 1) - phi), psi),
+            _ => (phi, psi + t * (grid_angle(j + 1) - psi)),
+        }
+    };
+
+    // Segments per cell; edges named bottom/right/top/left walking
+    // counter-clockwise from node (i, j).
+    let mut links: BTreeMap<Edge, Vec<Edge>> = BTreeMap::new();
+    for i in 0..CELLS {
+        for j in 0..CELLS {
+            let case = usize::from(above(i, j))
+                | usize::from(above(i + 1, j)) << 1
+                | usize::from(above(i + 1, j + 1)) << 2
+                | usize::from(above(i, j + 1)) << 3;
+            let (b, r, t, l) = ((i, j, 0), (i + 1, j, 1), (i, j + 1, 0), (i, j, 1));
+            let center_above = || {
+                let sum = values[i][j] + values[i + 1][j] + values[i + 1][j + 1] + values[i][j + 1];
+                sum / 4.0 > level
+            };
+            let segments: &[(Edge, Edge)] = match case {
+                0 | 15 => &[],
+                1 | 14 => &[(l, b)],
+                2 | 13 => &[(b, r)],
+                3 | 12 => &[(l, r)],
+                4 | 11 => &[(r, t)],
+                6 | 9 => &[(b, t)],
+                7 | 8 => &[(l, t)],
+                5 if center_above() => &[(l, t), (b, r)],
+                5 => &[(l, b), (r, t)],
+                10 if center_above() => &[(l, b), (r, t)],
+                _ => &[(l, t), (b, r)],
+            };
+            for &(p, q) in segments {
+                links.entry(p).or_default().push(q);
+                links.entry(q).or_default().push(p);
+            }
+        }
+    }
+
+    // Chain segments, starting open lines at their border ends.
+    let mut contours = Vec::new();
+    let starts: Vec<Edge> = links
+        .iter()
+        .filter(|(_, next)| next.len() == 1)
+        .map(|(&edge, _)| edge)
+        .collect();
+    for start in starts
+        .into_iter()
+        .chain(links.keys().copied().collect::<Vec<_>>())
+    {
+        if links.get(&start).is_none_or(Vec::is_empty) {
+            continue;
+        }
+        let mut points = Vec::from([point(start)]);
+        let mut at = start;
+        while let Some(next) = links.get_mut(&at).and_then(Vec::pop) {
+            if let Some(back) = links.get_mut(&next)
+                && let Some(pos) = back.iter().position(|&edge| edge == at)
+            {
+                back.swap_remove(pos);
+            }
+            points.push(point(next));
+            at = next;
+            if at == start {
+                break;
+            }
+        }
+        contours.push(Contour {
+            closed: at == start && points.len() > 2,
+            points,
+        });
+    }
+    contours
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Leu, Val};
+
+    fn bump() -> [[f32; GRID_COUNT]; GRID_COUNT] {
+        let mut values = [[0.0; GRID_COUNT]; GRID_COUNT];
+        for (i, row) in values.iter_mut().enumerate() {
+            for (j, v) in row.iter_mut().enumerate() {
+                let (di, dj) = (i as f32 - 18.0, j as f32 - 18.0);
+                *v = 1.0 - (di * di + dj * dj) / 100.0;
+            }
+        }
+        values
+    }
+
+    #[test]
+    fn test_closed_circle() {
+        let contours = trace(&bump(), 0.5);
+        assert_eq!(contours.len(), 1);
+        let circle = &contours[0];
+        assert!(circle.closed);
+        assert_eq!(circle.points.first(), circle.points.last());
+        // Radius √50 cells ≈ 70.7°.
+        for &(phi, psi) in &circle.points {
+            let radius = (phi * phi + psi * psi).sqrt();
+            assert!((radius - 70.7).abs() < 3.0, "{radius}");
+        }
+    }
+
+    #[test]
+    fn test_open_lines_end_on_border() {
+        let mut values = [[0.0; GRID_COUNT]; GRID_COUNT];
+        for (i, row) in values.iter_mut().enumerate() {
+            row.fill(i as f32);
+        }
+        let contours = trace(&values, 10.5);
+        assert_eq!(contours.len(), 1);
+        let line = &contours[0];
+        assert!(!line.closed);
+        assert_eq!(line.points.len(), GRID_COUNT);
+        assert!(
+            line.points
+                .iter()
+                .all(|&(phi, _)| (phi + 75.0).abs() < 1e-4)
+        );
+        let ends = [line.points[0].1, line.points[GRID_COUNT - 1].1];
+        assert!(ends.contains(&-180.0) && ends.contains(&180.0));
+    }
+
+    #[test]
+    fn test_levels_outside_range_are_empty() {
+        assert!(isocontours::<Val>(&[2], 1.5).unwrap().is_empty());
+        assert!(isocontours::<Val>(&[2], -0.5).unwrap().is_empty());
+        assert_eq!(isocontours::<Leu>(&[4, 4], 0.5), Err(Error::InvalidBinKey));
+        assert_eq!(isocontours::<Leu>(&[3], 0.5), Err(Error::InvalidBinKey));
+    }
+}
@@ -0,0 +1,653 @@
+//! Delta-encoded rotamer tables.
+//!
+//! The encoding serves two purposes. As a file format, it is written by
+//! [`write_delta_tables`] and read through [`TableStorage`] by
+//! [`DeltaTables`]. As a storage mode, the `delta-tables` feature has
+//! `build.rs` embed each table in the same encoding instead of as `f32`
+//! values, and decode it into the heap the first time it is used.
+
+#[cfg(feature = "delta-tables")]
+use alloc::boxed::Box;
+#[cfg(all(feature = "delta-tables", not(feature = "std")))]
+use alloc::vec::Vec;
+#[cfg(feature = "delta-tables")]
+use core::ops::Deref;
+#[cfg(feature = "delta-tables")]
+use core::sync::atomic::{AtomicBool, AtomicPtr, Ordering};
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+use crate::error::Error;
+use crate::grid::GRID_PERIOD;
+use crate::identity::ResidueInfo;
+#[cfg(feature = "delta-tables")]
+use crate::interp::{GridEntry, Table};
+use crate::math::sincos_deg;
+use crate::residue::Library;
+#[cfg(feature = "std")]
+use crate::residue::*;
+use crate::storage::{TableKey, TableStorage};
+
+const MAGIC: &[u8; 4] = b"DBDT";
+
+/// Version byte written by `write_delta_tables` and required by
+/// `DeltaTables::new`.
+const VERSION: u8 = 1;
+
+const HEADER_LEN: usize = 8;
+const ENTRY_LEN: usize = 12;
+
+/// Probabilities are stored in units of 10⁻⁶, the source precision.
+const PROB_SCALE: f32 = 1e6;
+/// χ means and σ are stored in units of 0.1°, the source precision.
+const ANGLE_SCALE: f32 = 10.0;
+/// A full turn in angle units; χ deltas wrap into `[-HALF_TURN, HALF_TURN)`.
+const TURN: i32 = 3600;
+const HALF_TURN: i32 = TURN / 2;
+
+/// Write every embedded rotamer table to `out` in the delta-encoded format
+/// read by [`DeltaTables`].
+///
+/// Values are quantized to the precision of the source library (10⁻⁶ for
+/// probabilities, 0.1° for χ means and σ). Within each φ row of the grid,
+/// every cell stores the difference of each value from the same value of
+/// the previous cell along ψ, the first cell storing its values outright;
+/// χ differences take the short way around the circle. The library is
+/// smooth, so most differences fit a single byte of a zigzag LEB128
+/// varint, and the file is far smaller than the embedded `f32` tables.
+/// Rows start afresh, so any row decodes without the others.
+///
+/// All integers are little-endian. A 8-byte header (magic `b"DBDT"`,
+/// format version `1`, residue type count, two reserved zero bytes) is
+/// followed by a 12-byte directory entry per residue type: the residue
+/// name (4 bytes, NUL-padded), the χ count (`u8`), a reserved byte, the
+/// rotamer count (`u16`), and the byte offset of its table (`u32`). A
+/// table starts with the byte offsets of its 36 φ rows (`u32` each).
+/// Within a cell, the values of each rotamer in table order are its
+/// probability, then its χ means, then its σ.
+///
+/// Requires the `std` feature.
+///
+/// # Errors
+///
+/// Returns any error reported by `out`.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{DeltaTables, Residue, StoredTables, Val, write_delta_tables};
+///
+/// let mut bytes = Vec::new();
+/// write_delta_tables(&mut bytes)?;
+///
+/// let mut tables = StoredTables::<_>::new(DeltaTables::new(&bytes)?);
+/// let decoded = tables.rotamers::<Val>(-65.0, -40.0)?;
+/// for (a, b) in decoded.zip(Val::rotamers(-65.0, -40.0)) {
+///     assert!((a.prob - b.prob).abs() < 1e-5);
+///     assert!((a.chi_mean[0] - b.chi_mean[0]).abs() < 0.01);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "std")]
+pub fn write_delta_tables<W: Write>(mut out: W) -> io::Result<()> {
+    let mut entries = Vec::new();
+    let mut tables = Vec::new();
+    macro_rules! encode {
+        ($Res:ident, $n:literal, $r:literal) => {
+            entries.push((<$Res as Residue>::NAME, $n, $r));
+            tables.push(encode_table::<$Res>($n, $r));
+        };
+    }
+    for_all_residues!(encode);
+
+    let mut header = [0_u8; HEADER_LEN];
+    header[..4].copy_from_slice(MAGIC);
+    header[4] = VERSION;
+    header[5] = entries.len() as u8;
+    out.write_all(&header)?;
+
+    let mut offset = HEADER_LEN + entries.len() * ENTRY_LEN;
+    for (&(name, n_chi, n_rotamers), table) in entries.iter().zip(&tables) {
+        let mut entry = [0_u8; ENTRY_LEN];
+        entry[..name.len()].copy_from_slice(name.as_bytes());
+        entry[4] = n_chi as u8;
+        entry[6..8].copy_from_slice(&(n_rotamers as u16).to_le_bytes());
+        entry[8..12].copy_from_slice(&(offset as u32).to_le_bytes());
+        out.write_all(&entry)?;
+        offset += table.len();
+    }
+
+    // Row offsets were written relative to their table; rebase them.
+    let mut offset = HEADER_LEN + entries.len() * ENTRY_LEN;
+    for mut table in tables {
+        for row in table[..GRID_PERIOD * 4].chunks_exact_mut(4) {
+            let start = u32::from_le_bytes(row.try_into().unwrap()) as usize + offset;
+            row.copy_from_slice(&(start as u32).to_le_bytes());
+        }
+        offset += table.len();
+        out.write_all(&table)?;
+    }
+    out.flush()
+}
+
+/// Encodes the stored grid of residue type `Res`, with row offsets
+/// relative to the start of the table.
+#[cfg(feature = "std")]
+fn encode_table<Res: Residue>(n_chi: usize, n_rotamers: usize) -> Vec<u8> {
+    let values = n_rotamers * (1 + 2 * n_chi);
+    let mut cells = Vec::with_capacity(GRID_PERIOD * GRID_PERIOD * values);
+    Res::visit_rows(&mut |row| {
+        if row.phi_idx < GRID_PERIOD && row.psi_idx < GRID_PERIOD {
+            cells.push((row.prob * PROB_SCALE).round() as i32);
+            for i in 0..n_chi {
+                let chi = (row.chi_sin[i] as f64).atan2(row.chi_cos[i] as f64);
+                cells.push((chi.to_degrees() * ANGLE_SCALE as f64).round() as i32);
+            }
+            for i in 0..n_chi {
+                cells.push((row.chi_sigma[i] * ANGLE_SCALE).round() as i32);
+            }
+        }
+    });
+
+    let mut table = std::vec![0; GRID_PERIOD * 4];
+    for (phi_idx, row) in cells.chunks_exact(GRID_PERIOD * values).enumerate() {
+        let start = table.len() as u32;
+        table[phi_idx * 4..][..4].copy_from_slice(&start.to_le_bytes());
+        let mut prev: &[i32] = &[];
+        for cell in row.chunks_exact(values) {
+            for (slot, &value) in cell.iter().enumerate() {
+                let base = prev.get(slot).copied().unwrap_or(0);
+                let mut delta = value - base;
+                if is_chi(slot, n_chi) {
+                    delta = wrap_angle(delta);
+                }
+                write_varint(&mut table, delta);
+            }
+            prev = cell;
+        }
+    }
+    table
+}
+
+/// Rotamer tables decoded on demand from bytes written by
+/// `write_delta_tables` (`std` feature), for use as the [`TableStorage`]
+/// of a [`StoredTables`](crate::StoredTables).
+///
+/// Each cell read decodes its φ row from the start up to the requested
+/// cell, at most 36 cells of varints, so the tables can stay compressed in
+/// flash or on disk and only the cells cached by the
+/// [`StoredTables`](crate::StoredTables) are expanded into RAM. Queries
+/// match [`Residue::rotamers`](crate::Residue::rotamers) on the embedded tables to the precision of
+/// the source library.
+#[derive(Debug, Clone, Copy)]
+pub struct DeltaTables<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> DeltaTables<'a> {
+    /// Validate the header and directory of `bytes` against the compiled-in
+    /// residue types.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::MalformedEncoding`] if the magic is wrong or the bytes are
+    /// truncated, [`Error::UnsupportedVersion`] for another format version,
+    /// and [`Error::DataMismatch`] if any residue's table shape differs
+    /// from this build.
+    pub fn new(bytes: &'a [u8]) -> Result<Self, Error> {
+        if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+            return Err(Error::MalformedEncoding);
+        }
+        if bytes[4] != VERSION {
+            return Err(Error::UnsupportedVersion(bytes[4]));
+        }
+        if usize::from(bytes[5]) != crate::ALL_RESIDUES.len() {
+            return Err(Error::DataMismatch);
+        }
+        let tables = Self { bytes };
+        for (i, info) in crate::ALL_RESIDUES.iter().enumerate() {
+            tables.check_entry(i, info)?;
+        }
+        Ok(tables)
+    }
+
+    /// The encoded bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Checks directory entry `i` against the residue type `info`.
+    fn check_entry(&self, i: usize, info: &ResidueInfo) -> Result<(), Error> {
+        let name = info.name;
+        let entry = self
+            .bytes
+            .get(HEADER_LEN + i * ENTRY_LEN..)
+            .and_then(|rest| rest.get(..ENTRY_LEN))
+            .ok_or(Error::MalformedEncoding)?;
+        if &entry[..name.len()] != name.as_bytes()
+            || entry[name.len()..4].iter().any(|&b| b != 0)
+            || usize::from(entry[4]) != info.n_chi
+            || usize::from(u16::from_le_bytes([entry[6], entry[7]])) != info.n_rotamers
+        {
+            return Err(Error::DataMismatch);
+        }
+        let offset = u32_at(entry, 8) as usize;
+        let rows = self
+            .bytes
+            .get(offset..)
+            .and_then(|rest| rest.get(..GRID_PERIOD * 4))
+            .ok_or(Error::MalformedEncoding)?;
+        if (0..GRID_PERIOD).any(|phi_idx| u32_at(rows, phi_idx * 4) as usize >= self.bytes.len()) {
+            return Err(Error::MalformedEncoding);
+        }
+        Ok(())
+    }
+
+    /// Directory entry of the residue named `residue`.
+    fn entry(&self, residue: &str) -> Option<&'a [u8]> {
+        let count = usize::from(self.bytes[5]);
+        self.bytes[HEADER_LEN..HEADER_LEN + count * ENTRY_LEN]
+            .chunks_exact(ENTRY_LEN)
+            .find(|entry| {
+                let name = &entry[..4];
+                let len = name.iter().position(|&b| b == 0).unwrap_or(4);
+                &name[..len] == residue.as_bytes()
+            })
+    }
+}
+
+impl TableStorage for DeltaTables<'_> {
+    type Error = Error;
+
+    /// Decode grid cell (`phi_idx`, `psi_idx`) of `table`, wrapping
+    /// indices past the stored period.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::UnknownResidue`] if the bytes hold no primary table named
+    /// `table.residue` (the `Chi1<R>` and `Alt<R>` variants are not
+    /// encoded), [`Error::DataMismatch`] if the stored shape differs from
+    /// `table` or `cell` is not its cell size, and
+    /// [`Error::MalformedEncoding`] if the row is truncated.
+    fn read_cell(
+        &mut self,
+        table: TableKey,
+        phi_idx: usize,
+        psi_idx: usize,
+        cell: &mut [f32],
+    ) -> Result<(), Error> {
+        let entry = self
+            .entry(table.residue)
+            .filter(|_| table.library == Library::Primary)
+            .ok_or(Error::UnknownResidue)?;
+        let n_chi = usize::from(entry[4]);
+        let n_rotamers = usize::from(u16::from_le_bytes([entry[6], entry[7]]));
+        let floats = 1 + 3 * n_chi;
+        if (n_chi, n_rotamers) != (table.n_chi, table.n_rotamers)
+            || cell.len() != n_rotamers * floats
+        {
+            return Err(Error::DataMismatch);
+        }
+        let rows = u32_at(entry, 8) as usize;
+        let start = u32_at(&self.bytes[rows..], (phi_idx % GRID_PERIOD) * 4) as usize;
+        let mut row = &self.bytes[start..];
+
+        cell.fill(0.0);
+        for _ in 0..=psi_idx % GRID_PERIOD {
+            accumulate(&mut row, n_chi, cell, true)?;
+        }
+        finish_cell(n_chi, cell);
+        Ok(())
+    }
+}
+
+/// Adds the deltas of the next encoded cell at the front of `row` to the
+/// running totals in `totals`, advancing `row`. χ totals are wrapped into
+/// `[-180°, 180°)` if `wrap_chi`, as the file format requires; embedded
+/// tables keep them as encoded.
+///
+/// Running totals are small integers, exact in `f32`, so they accumulate
+/// in a cell of the [`GridEntry`](crate::GridEntry) layout itself: the
+/// probability in its slot, each χ mean in the sin χ slot, each σ in the σ
+/// slot.
+///
+/// # Errors
+///
+/// [`Error::MalformedEncoding`] if the row is truncated or a total
+/// overflows `i32`, which no encoder output does.
+fn accumulate(
+    row: &mut &[u8],
+    n_chi: usize,
+    totals: &mut [f32],
+    wrap_chi: bool,
+) -> Result<(), Error> {
+    for rot in totals.chunks_exact_mut(1 + 3 * n_chi) {
+        for slot in (0..=n_chi).chain(1 + 2 * n_chi..rot.len()) {
+            let delta = read_varint(row).ok_or(Error::MalformedEncoding)?;
+            let total = (rot[slot] as i32)
+                .checked_add(delta)
+                .ok_or(Error::MalformedEncoding)?;
+            rot[slot] = if wrap_chi && (1..=n_chi).contains(&slot) {
+                wrap_angle(total)
+            } else {
+                total
+            } as f32;
+        }
+    }
+    Ok(())
+}
+
+/// Turns the running totals of [`accumulate`] into cell values in place.
+fn finish_cell(n_chi: usize, cell: &mut [f32]) {
+    for rot in cell.chunks_exact_mut(1 + 3 * n_chi) {
+        rot[0] /= PROB_SCALE;
+        for i in 0..n_chi {
+            let (sin, cos) = sincos_deg(rot[1 + i] / ANGLE_SCALE);
+            rot[1 + i] = sin;
+            rot[1 + n_chi + i] = cos;
+            rot[1 + 2 * n_chi + i] /= ANGLE_SCALE;
+        }
+    }
+}
+
+/// `(sin χ, cos χ)` of every χ mean in the embedded encoding, as `build.rs`
+/// computes them for the `f32` tables: −0.0°, coded as `-HALF_TURN - 1`,
+/// then −180.0° to 180.0° in 0.1° steps.
+#[cfg(feature = "delta-tables")]
+static SINCOS: [[f32; 2]; 2 * HALF_TURN as usize + 2] = unsafe {
+    core::mem::transmute(*include_bytes!(concat!(
+        env!("OUT_DIR"),
+        "/DELTA_SINCOS.bin"
+    )))
+};
+
+/// Embedded rotamer table stored delta-encoded by the `delta-tables`
+/// feature, decoded on first use.
+///
+/// Dereferences to the decoded [`Table`], which matches the table the
+/// `f32` build embeds bit for bit. It is decoded once into the heap and
+/// kept for the rest of the program; threads that find another thread
+/// decoding wait for it.
+#[cfg(feature = "delta-tables")]
+pub(crate) struct EmbeddedTable<const N: usize, const R: usize> {
+    encoded: &'static [u8],
+    #[cfg(feature = "fast-chi")]
+    chi: &'static [[[i16; N]; R]],
+    decoding: AtomicBool,
+    table: AtomicPtr<Table<'static, N, R>>,
+}
+
+#[cfg(feature = "delta-tables")]
+impl<const N: usize, const R: usize> EmbeddedTable<N, R> {
+    /// Table decoded from `encoded`, laid out as `build.rs` writes it.
+    pub(crate) const fn new(encoded: &'static [u8]) -> Self {
+        Self {
+            encoded,
+            #[cfg(feature = "fast-chi")]
+            chi: &[],
+            decoding: AtomicBool::new(false),
+            table: AtomicPtr::new(core::ptr::null_mut()),
+        }
+    }
+
+    /// The same table, carrying χ means in centidegrees as
+    /// [`Table::with_chi`] does.
+    #[cfg(feature = "fast-chi")]
+    pub(crate) const fn with_chi(self, chi: &'static [[[i16; N]; R]]) -> Self {
+        Self { chi, ..self }
+    }
+
+    /// Decodes every cell, φ-major.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the encoding is malformed, which `build.rs` rules out.
+    fn decode(&self) -> Table<'static, N, R> {
+        let mut cells = Vec::with_capacity(GRID_PERIOD * GRID_PERIOD);
+        let mut totals = alloc::vec![0.0; R * (1 + 3 * N)];
+        for phi_idx in 0..GRID_PERIOD {
+            let mut row = &self.encoded[u32_at(self.encoded, phi_idx * 4) as usize..];
+            totals.fill(0.0);
+            for _ in 0..GRID_PERIOD {
+                accumulate(&mut row, N, &mut totals, false)
+                    .expect("build.rs encodes every embedded table");
+                let cell: [GridEntry<N>; R] =
+                    core::array::from_fn(|k| embedded_entry(&totals[k * (1 + 3 * N)..]));
+                cells.push(cell);
+            }
+        }
+        let table = Table::dense(Box::leak(cells.into_boxed_slice()));
+        #[cfg(feature = "fast-chi")]
+        let table = if self.chi.is_empty() {
+            table
+        } else {
+            table.with_chi(self.chi)
+        };
+        table
+    }
+}
+
+#[cfg(feature = "delta-tables")]
+impl<const N: usize, const R: usize> Deref for EmbeddedTable<N, R> {
+    type Target = Table<'static, N, R>;
+
+    fn deref(&self) -> &Self::Target {
+        let mut table = self.table.load(Ordering::Acquire);
+        if table.is_null() {
+            if self.decoding.swap(true, Ordering::Acquire) {
+                while table.is_null() {
+                    core::hint::spin_loop();
+                    table = self.table.load(Ordering::Acquire);
+                }
+            } else {
+                table = Box::into_raw(Box::new(self.decode()));
+                self.table.store(table, Ordering::Release);
+            }
+        }
+        // SAFETY: a non-null pointer was stored from `Box::into_raw` and is
+        // never freed or written through.
+        unsafe { &*table }
+    }
+}
+
+/// The [`GridEntry`] of one rotamer's running totals of an embedded table,
+/// laid out as [`accumulate`] leaves them.
+#[cfg(feature = "delta-tables")]
+fn embedded_entry<const N: usize>(totals: &[f32]) -> GridEntry<N> {
+    let sincos = |i: usize| SINCOS[(totals[1 + i] as i32 + HALF_TURN + 1) as usize];
+    GridEntry {
+        prob: totals[0] / PROB_SCALE,
+        chi_sin: core::array::from_fn(|i| sincos(i)[0]),
+        chi_cos: core::array::from_fn(|i| sincos(i)[1]),
+        chi_sigma: core::array::from_fn(|i| totals[1 + 2 * N + i] / ANGLE_SCALE),
+    }
+}
+
+/// Whether value `slot` of an encoded rotamer (probability, χ means, σ)
+/// is a χ mean.
+#[cfg(feature = "std")]
+fn is_chi(slot: usize, n_chi: usize) -> bool {
+    let slot = slot % (1 + 2 * n_chi);
+    (1..=n_chi).contains(&slot)
+}
+
+/// Wraps an angle in 0.1° units into `[-180°, 180°)`.
+#[inline]
+fn wrap_angle(value: i32) -> i32 {
+    let turns = value.rem_euclid(TURN);
+    if turns >= HALF_TURN {
+        turns - TURN
+    } else {
+        turns
+    }
+}
+
+#[inline]
+fn u32_at(bytes: &[u8], at: usize) -> u32 {
+    u32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]])
+}
+
+/// Appends `value` as a zigzag LEB128 varint.
+#[cfg(feature = "std")]
+fn write_varint(out: &mut Vec<u8>, value: i32) {
+    let mut bits = ((value << 1) ^ (value >> 31)) as u32;
+    while bits >= 0x80 {
+        out.push(bits as u8 | 0x80);
+        bits >>= 7;
+    }
+    out.push(bits as u8);
+}
+
+/// Reads a zigzag LEB128 varint from the front of `bytes`, advancing it.
+fn read_varint(bytes: &mut &[u8]) -> Option<i32> {
+    let mut bits = 0_u32;
+    for shift in (0..35).step_by(7) {
+        let (&byte, rest) = bytes.split_first()?;
+        *bytes = rest;
+        bits |= u32::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some((bits >> 1) as i32 ^ -((bits & 1) as i32));
+        }
+    }
+    None
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::storage::StoredTables;
+
+    fn encoded() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_delta_tables(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_decoded_queries_match_embedded() {
+        let bytes = encoded();
+        let mut tables = StoredTables::<_>::new(DeltaTables::new(&bytes).unwrap());
+        macro_rules! check {
+            ($Res:ident, $n:literal, $r:literal) => {
+                for (phi, psi) in [
+                    (-63.7, -41.2),
+                    (180.0, 180.0),
+                    (-180.0, 55.5),
+                    (61.0, -175.0),
+                ] {
+                    let decoded = tables.rotamers::<$Res>(phi, psi).unwrap();
+                    for (a, b) in decoded.zip(<$Res as Residue>::rotamers(phi, psi)) {
+                        assert_eq!(a.r, b.r);
+                        assert!((a.prob - b.prob).abs() < 1e-5, "{}", stringify!($Res));
+                        for i in 0..$n {
+                            let d = crate::math::wrap_deg(a.chi_mean[i] - b.chi_mean[i]);
+                            assert!(d.abs() < 0.01, "{} χ{}: {d}", stringify!($Res), i + 1);
+                            assert!((a.chi_sigma[i] - b.chi_sigma[i]).abs() < 1e-4);
+                        }
+                    }
+                }
+            };
+        }
+        for_all_residues!(check);
+    }
+
+    #[test]
+    fn test_smaller_than_f32_tables() {
+        let bytes = encoded();
+        let mut dense = 0;
+        macro_rules! add {
+            ($Res:ident, $n:literal, $r:literal) => {
+                dense += 36 * 36 * $r * (1 + 3 * $n) * 4;
+            };
+        }
+        for_all_residues!(add);
+        // Measured at 5,473,700 bytes against about 27 MB dense (4.9×);
+        // a ratio of 4 leaves headroom for other smoothing levels.
+        assert!(bytes.len() * 4 < dense, "{} vs {dense}", bytes.len());
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let bytes = encoded();
+        let reason = |bytes: &[u8]| DeltaTables::new(bytes).err();
+        assert_eq!(reason(&bytes[..4]), Some(Error::MalformedEncoding));
+        assert_eq!(reason(&bytes[..100]), Some(Error::MalformedEncoding));
+
+        let mut bad = bytes.clone();
+        bad[4] = 2;
+        assert_eq!(reason(&bad), Some(Error::UnsupportedVersion(2)));
+        let mut bad = bytes.clone();
+        bad[5] -= 1;
+        assert_eq!(reason(&bad), Some(Error::DataMismatch));
+        let mut bad = bytes.clone();
+        bad[HEADER_LEN + 4] += 1;
+        assert_eq!(reason(&bad), Some(Error::DataMismatch));
+
+        let mut tables = DeltaTables::new(&bytes).unwrap();
+        let mut cell = [0.0; 12];
+        let val = TableKey::of::<Val>();
+        let gly = TableKey {
+            residue: "GLY",
+            ..val
+        };
+        assert_eq!(
+            tables.read_cell(gly, 0, 0, &mut cell),
+            Err(Error::UnknownResidue)
+        );
+        let chi1 = TableKey {
+            library: Library::Chi1,
+            ..val
+        };
+        assert_eq!(
+            tables.read_cell(chi1, 0, 0, &mut cell),
+            Err(Error::UnknownResidue)
+        );
+        let reshaped = TableKey {
+            n_rotamers: 4,
+            ..val
+        };
+        assert_eq!(
+            tables.read_cell(reshaped, 0, 0, &mut [0.0; 16]),
+            Err(Error::DataMismatch)
+        );
+        assert_eq!(
+            tables.read_cell(val, 0, 0, &mut cell[..11]),
+            Err(Error::DataMismatch)
+        );
+        // Grid index 36 is read as index 0.
+        let mut wrapped = [0.0; 12];
+        tables.read_cell(val, 3, 0, &mut cell).unwrap();
+        tables.read_cell(val, 3, 36, &mut wrapped).unwrap();
+        assert_eq!(cell, wrapped);
+    }
+
+    #[test]
+    fn test_overflowing_varints_are_rejected() {
+        // One rotamer with one χ: probability, χ mean, σ per cell.
+        let mut row = Vec::new();
+        for value in [i32::MAX, i32::MAX, 0, 1, i32::MAX, 0] {
+            write_varint(&mut row, value);
+        }
+        let mut totals = [0.0; 4];
+        let mut rest = row.as_slice();
+        accumulate(&mut rest, 1, &mut totals, true).unwrap();
+        assert!((-1800.0..1800.0).contains(&totals[1]));
+        assert_eq!(
+            accumulate(&mut rest, 1, &mut totals, true),
+            Err(Error::MalformedEncoding)
+        );
+
+        // A varint running past 32 bits.
+        let mut rest: &[u8] = &[0xff; 6];
+        assert_eq!(
+            accumulate(&mut rest, 1, &mut totals, true),
+            Err(Error::MalformedEncoding)
+        );
+        for value in [i32::MIN, -1801, 1800, i32::MAX] {
+            assert!((-HALF_TURN..HALF_TURN).contains(&wrap_angle(value)));
+        }
+    }
+}
@@ -0,0 +1,432 @@
+use crate::interp::RotamerIter;
+
+/// Probability of one rotamer bin key before and after a change, an entry
+/// of a [`RotamerDiff`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinDelta<const N: usize> {
+    /// Rotamer bin indices (1-based).
+    pub r: [u8; N],
+    /// Probability in the first set, or `None` if the key is absent there.
+    pub before: Option<f32>,
+    /// Probability in the second set, or `None` if the key is absent there.
+    pub after: Option<f32>,
+}
+
+impl<const N: usize> BinDelta<N> {
+    /// Change in probability, `after − before`, counting an absent key as
+    /// probability zero.
+    #[inline]
+    pub fn delta(&self) -> f32 {
+        self.after.unwrap_or(0.0) - self.before.unwrap_or(0.0)
+    }
+}
+
+/// Per-bin comparison of two rotamer sets of the same residue type, e.g.
+/// before and after a backbone move.
+///
+/// Produced by [`RotamerIter::diff`]. Holds one [`BinDelta`] per bin key
+/// present in either set: the keys of the first set in its order, then
+/// the keys only the second set has, in its order. Incremental packers can
+/// use [`changed`](Self::changed) to decide which rotamers need their
+/// energies recomputed.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Leu, Query};
+///
+/// let before = Query::new(-65.0, -40.0).prune(0.01).run::<Leu>();
+/// let after = Query::new(-120.0, 130.0).prune(0.01).run::<Leu>();
+/// let diff = before.diff(&after);
+///
+/// assert!(diff.total_variation() > 0.0);
+/// assert_eq!(
+///     diff.common().count() + diff.appeared().count() + diff.disappeared().count(),
+///     diff.as_slice().len(),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RotamerDiff<const N: usize, const R: usize> {
+    entries: [BinDelta<N>; R],
+    len: usize,
+}
+
+impl<const N: usize, const R: usize> RotamerDiff<N, R> {
+    /// Every bin key present in either set.
+    #[inline]
+    pub fn as_slice(&self) -> &[BinDelta<N>] {
+        &self.entries[..self.len]
+    }
+
+    /// Keys present only in the second set.
+    pub fn appeared(&self) -> impl Iterator<Item = &BinDelta<N>> + '_ {
+        self.as_slice()
+            .iter()
+            .filter(|e| e.before.is_none() && e.after.is_some())
+    }
+
+    /// Keys present only in the first set.
+    pub fn disappeared(&self) -> impl Iterator<Item = &BinDelta<N>> + '_ {
+        self.as_slice()
+            .iter()
+            .filter(|e| e.before.is_some() && e.after.is_none())
+    }
+
+    /// Keys present in both sets, their intersection.
+    pub fn common(&self) -> impl Iterator<Item = &BinDelta<N>> + '_ {
+        self.as_slice()
+            .iter()
+            .filter(|e| e.before.is_some() && e.after.is_some())
+    }
+
+    /// Keys whose probability changed by more than `tolerance`, absent keys
+    /// counting as probability zero.
+    pub fn changed(&self, tolerance: f32) -> impl Iterator<Item = &BinDelta<N>> + '_ {
+        self.as_slice()
+            .iter()
+            .filter(move |e| e.delta().abs() > tolerance)
+    }
+
+    /// Total variation distance `½ Σ |after − before|` between the two
+    /// distributions, in \[0, 1\] for normalized sets. Probabilities are
+    /// used as stored, so pruned sets are compared on their retained mass.
+    pub fn total_variation(&self) -> f32 {
+        0.5 * self.as_slice().iter().map(|e| e.delta().abs()).sum::<f32>()
+    }
+}
+
+impl<const N: usize, const R: usize> RotamerIter<N, R> {
+    /// Compare the remaining rotamers of this set with those of `after`, a
+    /// set of the same residue type, bin key by bin key.
+    ///
+    /// Sets of one residue type draw on the same `R` keys. Should the two
+    /// sets hold more than `R` distinct keys between them, as only sets of
+    /// different residue types of the same shape can, the keys only
+    /// `after` has that do not fit are left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// let a = Val::rotamers(-65.0, -40.0);
+    /// let diff = a.diff(&a.clone());
+    /// assert_eq!(diff.total_variation(), 0.0);
+    /// assert_eq!(diff.changed(0.0).count(), 0);
+    /// ```
+    pub fn diff(&self, after: &Self) -> RotamerDiff<N, R> {
+        let mut entries = [BinDelta {
+            r: [0; N],
+            before: None,
+            after: None,
+        }; R];
+        let mut len = 0;
+        for rot in self.as_slice() {
+            entries[len] = BinDelta {
+                r: rot.r,
+                before: Some(rot.prob),
+                after: after
+                    .as_slice()
+                    .iter()
+                    .find(|other| other.r == rot.r)
+                    .map(|other| other.prob),
+            };
+            len += 1;
+        }
+        for rot in after.as_slice() {
+            if len < R && !entries[..len].iter().any(|e| e.r == rot.r) {
+                entries[len] = BinDelta {
+                    r: rot.r,
+                    before: None,
+                    after: Some(rot.prob),
+                };
+                len += 1;
+            }
+        }
+        RotamerDiff { entries, len }
+    }
+}
+
+/// One flow of probability mass between two rotamer bin keys, an entry of
+/// a [`BinTransition`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinFlow<const N: usize> {
+    /// Bin indices of the rotamer losing mass.
+    pub from: [u8; N],
+    /// Bin indices of the rotamer gaining mass.
+    pub to: [u8; N],
+    /// Probability mass moved, as a fraction of the whole distribution.
+    pub mass: f32,
+}
+
+/// Bin-level transition matrix between the rotamer distributions of one
+/// residue type at two backbone points, e.g. consecutive frames of a
+/// backbone morph or trajectory.
+///
+/// Produced by [`RotamerIter::transition`]. Both sets are normalized to
+/// unit mass and aligned by bin key as in [`RotamerIter::diff`]. Each key
+/// keeps `min(before, after)` in place, and the mass the losing keys shed is
+/// spread over the gaining keys in proportion to their gains: the coupling
+/// that moves the least mass, equal to the total variation distance, so
+/// [`flows`](Self::flows) names the repacking a side chain needs and
+/// nothing more.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Leu, Residue};
+///
+/// let helix = Leu::rotamers(-65.0, -40.0);
+/// let strand = Leu::rotamers(-120.0, 130.0);
+/// let t = helix.transition(&strand);
+///
+/// // The largest flow, and how much of its source it drains.
+/// let big = t.flows().max_by(|a, b| a.mass.total_cmp(&b.mass)).unwrap();
+/// assert!(big.mass <= t.moved());
+/// assert!(t.probability(big.from, big.to) > 0.0);
+///
+/// // Every row of the matrix is a distribution.
+/// let to_anywhere: f32 = t.keys().iter().map(|&to| t.probability(big.from, to)).sum();
+/// assert!((to_anywhere - 1.0).abs() < 1e-5);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinTransition<const N: usize, const R: usize> {
+    keys: [[u8; N]; R],
+    before: [f32; R],
+    after: [f32; R],
+    len: usize,
+    moved: f32,
+}
+
+impl<const N: usize, const R: usize> BinTransition<N, R> {
+    /// Every bin key present in either set, in the order of
+    /// [`RotamerDiff::as_slice`].
+    #[inline]
+    pub fn keys(&self) -> &[[u8; N]] {
+        &self.keys[..self.len]
+    }
+
+    /// Total mass that changes bin, the total variation distance between
+    /// the two normalized distributions, in \[0, 1\].
+    #[inline]
+    pub fn moved(&self) -> f32 {
+        self.moved
+    }
+
+    /// Mass flowing from bin key `from` to bin key `to`; the mass `from`
+    /// keeps when they are equal, and 0 for keys in neither set.
+    pub fn flow(&self, from: [u8; N], to: [u8; N]) -> f32 {
+        match (self.position(from), self.position(to)) {
+            (Some(i), Some(j)) => self.flow_at(i, j),
+            _ => 0.0,
+        }
+    }
+
+    /// Probability that mass in bin key `from` before moves to `to` after,
+    /// the row-normalized transition matrix; 0 if `from` held no mass.
+    pub fn probability(&self, from: [u8; N], to: [u8; N]) -> f32 {
+        match self.position(from) {
+            Some(i) if self.before[i] > 0.0 => self.flow(from, to) / self.before[i],
+            _ => 0.0,
+        }
+    }
+
+    /// Keys losing mass with the amount each loses, in key order.
+    pub fn losing(&self) -> impl Iterator<Item = ([u8; N], f32)> + '_ {
+        (0..self.len)
+            .map(|i| (self.keys[i], self.before[i] - self.after[i]))
+            .filter(|&(_, loss)| loss > 0.0)
+    }
+
+    /// Keys gaining mass with the amount each gains, in key order.
+    pub fn gaining(&self) -> impl Iterator<Item = ([u8; N], f32)> + '_ {
+        (0..self.len)
+            .map(|i| (self.keys[i], self.after[i] - self.before[i]))
+            .filter(|&(_, gain)| gain > 0.0)
+    }
+
+    /// Every non-zero flow between different bin keys, grouped by source
+    /// in key order.
+    pub fn flows(&self) -> impl Iterator<Item = BinFlow<N>> + '_ {
+        self.losing().flat_map(move |(from, loss)| {
+            self.gaining().map(move |(to, gain)| BinFlow {
+                from,
+                to,
+                mass: loss * gain / self.moved,
+            })
+        })
+    }
+
+    fn position(&self, key: [u8; N]) -> Option<usize> {
+        self.keys().iter().position(|&k| k == key)
+    }
+
+    fn flow_at(&self, i: usize, j: usize) -> f32 {
+        let (b, a) = (self.before, self.after);
+        if i == j {
+            b[i].min(a[i])
+        } else if self.moved > 0.0 {
+            (b[i] - a[i]).max(0.0) * (a[j] - b[j]).max(0.0) / self.moved
+        } else {
+            0.0
+        }
+    }
+}
+
+impl<const N: usize, const R: usize> RotamerIter<N, R> {
+    /// Bin-level transition matrix from the remaining rotamers of this set
+    /// to those of `after`, a set of the same residue type at another
+    /// backbone point: which bins lose mass, which gain it, and how much
+    /// flows between each pair.
+    ///
+    /// See [`BinTransition`]. An empty side contributes no mass.
+    pub fn transition(&self, after: &Self) -> BinTransition<N, R> {
+        let diff = self.diff(after);
+        let mut transition = BinTransition {
+            keys: [[0; N]; R],
+            before: [0.0; R],
+            after: [0.0; R],
+            len: diff.len,
+            moved: 0.0,
+        };
+        let (sum_before, sum_after) = (self.total_prob(), after.total_prob());
+        let scale = |p: Option<f32>, sum: f32| {
+            if sum > 0.0 {
+                p.unwrap_or(0.0) / sum
+            } else {
+                0.0
+            }
+        };
+        for (i, entry) in diff.as_slice().iter().enumerate() {
+            transition.keys[i] = entry.r;
+            transition.before[i] = scale(entry.before, sum_before);
+            transition.after[i] = scale(entry.after, sum_after);
+        }
+        transition.moved = transition.losing().map(|(_, loss)| loss).sum();
+        transition
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interp::RotamerIter;
+    use crate::query::Query;
+    use crate::residue::{Arg, Leu, Residue, Val};
+    use crate::rotamer::Rotamer;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_pruned_sets_split_into_three_groups() {
+        let before = Query::new(-65.0, -40.0).prune(0.02).run::<Arg>();
+        let after = Query::new(-120.0, 130.0)
+            .prune(0.02)
+            .sorted(true)
+            .run::<Arg>();
+        let diff = before.diff(&after);
+
+        for e in diff.disappeared() {
+            assert!(!after.as_slice().iter().any(|rot| rot.r == e.r));
+            assert_eq!(e.delta(), -e.before.unwrap());
+        }
+        for e in diff.appeared() {
+            assert!(!before.as_slice().iter().any(|rot| rot.r == e.r));
+        }
+        assert!(diff.appeared().count() > 0 && diff.disappeared().count() > 0);
+        let common = diff.common().count();
+        assert_eq!(before.len() + after.len(), diff.as_slice().len() + common);
+
+        // Comparing the other way round mirrors every entry.
+        let back = after.diff(&before);
+        assert_eq!(back.appeared().count(), diff.disappeared().count());
+        assert_relative_eq!(
+            back.total_variation(),
+            diff.total_variation(),
+            epsilon = 1e-6
+        );
+    }
+
+    #[test]
+    fn test_total_variation_of_full_sets() {
+        let a = Leu::rotamers(-65.0, -40.0);
+        let b = Leu::rotamers(60.0, 40.0);
+        let diff = a.diff(&b);
+        assert_eq!(diff.as_slice().len(), 9);
+        assert_eq!(diff.common().count(), 9);
+        let expected: f32 = 0.5
+            * a.as_slice()
+                .iter()
+                .zip(b.as_slice())
+                .map(|(x, y)| (x.prob - y.prob).abs())
+                .sum::<f32>();
+        assert_relative_eq!(diff.total_variation(), expected, epsilon = 1e-6);
+        assert!((0.0..=1.0).contains(&diff.total_variation()));
+        assert!(diff.changed(0.01).count() > 0);
+    }
+
+    #[test]
+    fn test_only_remaining_rotamers_compared() {
+        let mut a = Leu::rotamers(-65.0, -40.0);
+        let b = a.clone();
+        a.next();
+        let diff = a.diff(&b);
+        assert_eq!(diff.appeared().count(), 1);
+        assert_eq!(diff.appeared().next().unwrap().r, b.as_slice()[0].r);
+    }
+
+    #[test]
+    fn test_transition_conserves_mass() {
+        let a = Query::new(-65.0, -40.0).prune(0.02).run::<Arg>();
+        let b = Query::new(-120.0, 130.0).run::<Arg>();
+        let t = a.transition(&b);
+        assert!(t.moved() > 0.0 && t.moved() <= 1.0);
+        let lost: f32 = t.losing().map(|(_, m)| m).sum();
+        let gained: f32 = t.gaining().map(|(_, m)| m).sum();
+        assert_relative_eq!(lost, gained, epsilon = 1e-5);
+        let flowed: f32 = t.flows().map(|f| f.mass).sum();
+        assert_relative_eq!(flowed, t.moved(), epsilon = 1e-5);
+        for &from in t.keys() {
+            let out: f32 = t.keys().iter().map(|&to| t.flow(from, to)).sum();
+            let into: f32 = t.keys().iter().map(|&to| t.flow(to, from)).sum();
+            let (before, after) = (
+                a.as_slice()
+                    .iter()
+                    .find(|r| r.r == from)
+                    .map_or(0.0, |r| r.prob)
+                    / a.total_prob(),
+                b.as_slice()
+                    .iter()
+                    .find(|r| r.r == from)
+                    .map_or(0.0, |r| r.prob),
+            );
+            assert_relative_eq!(out, before, epsilon = 1e-5);
+            assert_relative_eq!(into, after, epsilon = 1e-5);
+        }
+        assert_eq!(t.flow([9, 9, 9, 9], t.keys()[0]), 0.0);
+    }
+
+    #[test]
+    fn test_identical_sets_move_nothing() {
+        let a = Leu::rotamers(-65.0, -40.0);
+        let t = a.transition(&a.clone());
+        assert_eq!(t.moved(), 0.0);
+        assert_eq!(t.flows().count(), 0);
+        let key = a.as_slice()[0].r;
+        assert_eq!(t.probability(key, key), 1.0);
+    }
+
+    #[test]
+    fn test_excess_keys_are_left_out() {
+        // Same shape as Val, but none of its keys.
+        let before = Val::rotamers(-65.0, -40.0);
+        let after = RotamerIter::from_items(core::array::from_fn(|k| {
+            let rot = before.as_slice()[k];
+            Rotamer {
+                r: [rot.r[0] + 3],
+                ..rot
+            }
+        }));
+        let diff = before.diff(&after);
+        assert_eq!(diff.as_slice().len(), 3);
+        assert_eq!(diff.disappeared().count(), 3);
+    }
+}
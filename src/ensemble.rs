@@ -0,0 +1,455 @@
+use crate::interp::RotamerIter;
+use crate::math::{atan2f, expf, lnf, normal_cdf, sincos_deg, sqrtf, wrap_deg};
+use crate::residue::{Cpr, Cyd, Cyh, Residue, Tpr};
+use crate::rotamer::Rotamer;
+
+/// Radians-to-degrees conversion factor.
+const RAD_TO_DEG: f32 = 180.0 / core::f32::consts::PI;
+
+/// Averages full (unpruned, table-order) rotamer sets, each with a
+/// non-negative backbone weight, into one distribution.
+///
+/// For rotamer `k`, with `aₛ = wₛ · pₛₖ` the weight of sample `s`:
+///
+/// - `prob` is `Σ wₛ pₛₖ / Σ wₛ`;
+/// - `chi_mean` is the circular mean of the sample means, weighted by `aₛ`;
+/// - `chi_sigma` is `√(⟨σ²⟩ + s²)`, where `⟨σ²⟩` is the `aₛ`-weighted mean
+///   variance and `s = √(−2 ln R̄)` is the circular standard deviation of
+///   the sample means — the within-sample width plus the spread the
+///   backbone ensemble adds.
+///
+/// A rotamer with zero probability in every sample falls back to plain
+/// `wₛ` weights for its χ statistics.
+///
+/// # Panics
+///
+/// Panics if the weights do not have a finite, positive sum.
+pub(crate) fn average<const N: usize, const R: usize>(
+    sets: impl IntoIterator<Item = (f32, RotamerIter<N, R>)>,
+) -> RotamerIter<N, R> {
+    let mut keys = [[0_u8; N]; R];
+    let mut total_weight = 0.0_f32;
+    // Per rotamer: Σ wₛ pₛₖ and the χ moments weighted by wₛ pₛₖ, plus the
+    // moments with plain wₛ weights for the zero-probability fallback.
+    let mut prob = [0.0_f32; R];
+    let mut acc = [Moments::<N>::ZERO; R];
+    let mut flat = [Moments::<N>::ZERO; R];
+
+    for (weight, set) in sets {
+        assert!(
+            weight.is_finite() && weight >= 0.0,
+            "ensemble weight must be finite and non-negative, got {weight}"
+        );
+        total_weight += weight;
+        for (k, rot) in set.as_slice().iter().enumerate() {
+            keys[k] = rot.r;
+            prob[k] += weight * rot.prob;
+            acc[k].add(weight * rot.prob, rot);
+            flat[k].add(weight, rot);
+        }
+    }
+    assert!(
+        total_weight.is_finite() && total_weight > 0.0,
+        "ensemble weights must have a finite, positive sum, got {total_weight}"
+    );
+
+    let inv = 1.0 / total_weight;
+    RotamerIter::from_items(core::array::from_fn(|k| {
+        let moments = if acc[k].weight > 0.0 {
+            &acc[k]
+        } else {
+            &flat[k]
+        };
+        let (chi_mean, chi_sigma) = moments.finish();
+        Rotamer {
+            r: keys[k],
+            prob: prob[k] * inv,
+            chi_mean,
+            chi_sigma,
+        }
+    }))
+}
+
+/// Return the cysteine rotamer distribution at the given backbone dihedral
+/// angles as a mixture of the disulfide-bonded ([`Cyd`]) and free ([`Cyh`])
+/// libraries, with `p_disulfide` the probability that the residue is in a
+/// disulfide, e.g. from a bond predictor.
+///
+/// Rotamer `k` gets probability `p · p_Cyd,k + (1 − p) · p_Cyh,k`; χ means
+/// and σ combine as in [`Residue::ensemble_rotamers`], so σ widens where the
+/// two libraries disagree. `p_disulfide` of 1.0 or 0.0 reproduces [`Cyd`]
+/// or [`Cyh`].
+///
+/// # Panics
+///
+/// Panics if `p_disulfide` is not in \[0, 1\].
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Cyd, Cyh, Residue, blend_cysteine};
+///
+/// let mixed: Vec<_> = blend_cysteine(-65.0, -40.0, 0.3).collect();
+/// let bonded: Vec<_> = Cyd::rotamers(-65.0, -40.0).collect();
+/// let free: Vec<_> = Cyh::rotamers(-65.0, -40.0).collect();
+/// let expected = 0.3 * bonded[0].prob + 0.7 * free[0].prob;
+/// assert!((mixed[0].prob - expected).abs() < 1e-6);
+/// ```
+pub fn blend_cysteine(phi: f32, psi: f32, p_disulfide: f32) -> RotamerIter<1, 3> {
+    assert!(
+        (0.0..=1.0).contains(&p_disulfide),
+        "disulfide probability must be in [0, 1], got {p_disulfide}"
+    );
+    average([
+        (p_disulfide, Cyd::rotamers(phi, psi)),
+        (1.0 - p_disulfide, Cyh::rotamers(phi, psi)),
+    ])
+}
+
+/// Return the proline rotamer distribution at the given backbone dihedral
+/// angles as a mixture of the cis ([`Cpr`]) and trans ([`Tpr`]) libraries,
+/// with `p_cis` the cis population of the preceding peptide bond.
+///
+/// Mixes exactly like [`blend_cysteine`]. When only the ω angle is known,
+/// with some uncertainty, take `p_cis` from [`cis_probability`].
+///
+/// # Panics
+///
+/// Panics if `p_cis` is not in \[0, 1\].
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Cpr, Residue, blend_proline, cis_probability};
+///
+/// // An ensemble in which 20% of frames have a cis Xaa–Pro bond.
+/// let mixed = blend_proline(-65.0, 140.0, 0.2);
+/// assert_eq!(mixed.len(), 2);
+///
+/// // ω = −10° ± 30° from a low-resolution model is almost surely cis.
+/// let p = cis_probability(-10.0, 30.0);
+/// assert!(p > 0.99);
+/// let cis = Cpr::rotamers(-65.0, 140.0);
+/// for (a, b) in blend_proline(-65.0, 140.0, p).zip(cis) {
+///     assert!((a.prob - b.prob).abs() < 0.01);
+/// }
+/// ```
+pub fn blend_proline(phi: f32, psi: f32, p_cis: f32) -> RotamerIter<3, 2> {
+    assert!(
+        (0.0..=1.0).contains(&p_cis),
+        "cis probability must be in [0, 1], got {p_cis}"
+    );
+    average([
+        (p_cis, Cpr::rotamers(phi, psi)),
+        (1.0 - p_cis, Tpr::rotamers(phi, psi)),
+    ])
+}
+
+/// Probability that a peptide bond is cis, |ω| < 90°, given ω
+/// ~ N(`omega`, `sigma_omega`²) in degrees, wrapped around the circle.
+///
+/// With `sigma_omega` of 0.0 this is 1.0 for a cis ω and 0.0 for a trans
+/// one.
+///
+/// # Panics
+///
+/// Panics if `omega` is not finite or `sigma_omega` is negative or not
+/// finite.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::cis_probability;
+///
+/// assert_eq!(cis_probability(178.0, 0.0), 0.0);
+/// assert!((cis_probability(90.0, 15.0) - 0.5).abs() < 1e-5);
+/// assert!(cis_probability(170.0, 20.0) < 1e-4);
+/// ```
+pub fn cis_probability(omega: f32, sigma_omega: f32) -> f32 {
+    assert!(
+        omega.is_finite() && sigma_omega.is_finite() && sigma_omega >= 0.0,
+        "ω must be finite and its σ finite and non-negative, got {omega} ± {sigma_omega}"
+    );
+    let omega = wrap_deg(omega);
+    if sigma_omega == 0.0 {
+        return if omega.abs() < 90.0 { 1.0 } else { 0.0 };
+    }
+    let mut mass = 0.0_f32;
+    if sigma_omega <= 90.0 {
+        // Five wraps reach at least 10 σ on either side.
+        for wrap in [-720.0, -360.0, 0.0, 360.0, 720.0] {
+            mass += normal_cdf((90.0 - omega + wrap) / sigma_omega)
+                - normal_cdf((-90.0 - omega + wrap) / sigma_omega);
+        }
+    } else {
+        // Fourier series of the wrapped normal, fast for wide σ:
+        // ½ + (2/π) Σ_{n odd} (−1)^((n−1)/2) exp(−n²σ²/2) cos(nω) / n.
+        let half_var = 0.5 * (sigma_omega / RAD_TO_DEG) * (sigma_omega / RAD_TO_DEG);
+        let mut sign = 1.0_f32;
+        mass = 0.5;
+        for n in [1.0_f32, 3.0, 5.0, 7.0] {
+            let (_, cos) = sincos_deg(n * omega);
+            mass += core::f32::consts::FRAC_2_PI * sign * expf(-n * n * half_var) * cos / n;
+            sign = -sign;
+        }
+    }
+    mass.clamp(0.0, 1.0)
+}
+
+/// Five-point Gauss–Hermite rule for a standard normal: `(node, weight)`
+/// pairs with weights summing to 1. Exact for polynomials up to degree 9.
+const GAUSS_HERMITE: [(f32, f32); 5] = [
+    (-2.856_97, 0.011_257_41),
+    (-1.355_626, 0.222_075_9),
+    (0.0, 0.533_333_3),
+    (1.355_626, 0.222_075_9),
+    (2.856_97, 0.011_257_41),
+];
+
+/// Weighted `(phi, psi, weight)` quadrature nodes for independent Gaussian
+/// uncertainty `sigma_phi`, `sigma_psi` (degrees) around (`phi`, `psi`),
+/// wrapped into \[−180°, 180°).
+///
+/// # Panics
+///
+/// Panics if a σ is negative or not finite.
+pub(crate) fn gaussian_nodes(
+    phi: f32,
+    psi: f32,
+    sigma_phi: f32,
+    sigma_psi: f32,
+) -> impl Iterator<Item = (f32, f32, f32)> {
+    assert!(
+        sigma_phi.is_finite() && sigma_phi >= 0.0 && sigma_psi.is_finite() && sigma_psi >= 0.0,
+        "backbone σ must be finite and non-negative, got ({sigma_phi}, {sigma_psi})"
+    );
+    GAUSS_HERMITE.into_iter().flat_map(move |(x, wx)| {
+        GAUSS_HERMITE.into_iter().map(move |(y, wy)| {
+            (
+                wrap_deg(phi + sigma_phi * x),
+                wrap_deg(psi + sigma_psi * y),
+                wx * wy,
+            )
+        })
+    })
+}
+
+/// Weighted circular first moment and mean variance of each χ angle.
+#[derive(Clone, Copy)]
+struct Moments<const N: usize> {
+    weight: f32,
+    sin: [f32; N],
+    cos: [f32; N],
+    var: [f32; N],
+}
+
+impl<const N: usize> Moments<N> {
+    const ZERO: Self = Self {
+        weight: 0.0,
+        sin: [0.0; N],
+        cos: [0.0; N],
+        var: [0.0; N],
+    };
+
+    fn add(&mut self, weight: f32, rot: &Rotamer<N>) {
+        self.weight += weight;
+        for i in 0..N {
+            let (s, c) = sincos_deg(rot.chi_mean[i]);
+            self.sin[i] += weight * s;
+            self.cos[i] += weight * c;
+            self.var[i] += weight * rot.chi_sigma[i] * rot.chi_sigma[i];
+        }
+    }
+
+    /// Mean direction and combined σ, both in degrees.
+    fn finish(&self) -> ([f32; N], [f32; N]) {
+        let mut mean = [0.0_f32; N];
+        let mut sigma = [0.0_f32; N];
+        if self.weight <= 0.0 {
+            return (mean, sigma);
+        }
+        let inv = 1.0 / self.weight;
+        for i in 0..N {
+            let (s, c) = (self.sin[i] * inv, self.cos[i] * inv);
+            let resultant = sqrtf(s * s + c * c).clamp(f32::MIN_POSITIVE, 1.0);
+            let spread = sqrtf(-2.0 * lnf(resultant)) * RAD_TO_DEG;
+            mean[i] = if s == 0.0 && c == 0.0 {
+                0.0
+            } else {
+                atan2f(s, c) * RAD_TO_DEG
+            };
+            sigma[i] = sqrtf(self.var[i] * inv + spread * spread);
+        }
+        (mean, sigma)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Leu, Val};
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_single_sample_matches_rotamers() {
+        let avg = average([(2.5, Leu::rotamers(-65.0, -40.0))]);
+        for (a, b) in avg.zip(Leu::rotamers(-65.0, -40.0)) {
+            assert_eq!(a.r, b.r);
+            assert_relative_eq!(a.prob, b.prob, epsilon = 1e-6);
+            for i in 0..2 {
+                assert_relative_eq!(a.chi_mean[i], b.chi_mean[i], epsilon = 1e-2);
+                assert_relative_eq!(a.chi_sigma[i], b.chi_sigma[i], epsilon = 1e-2);
+            }
+        }
+    }
+
+    #[test]
+    fn test_probabilities_are_weighted_means() {
+        let (helix, sheet) = ((-65.0, -40.0), (-120.0, 130.0));
+        let avg: [Rotamer<1>; 3] = {
+            let mut it = average([
+                (1.0, Val::rotamers(helix.0, helix.1)),
+                (3.0, Val::rotamers(sheet.0, sheet.1)),
+            ]);
+            core::array::from_fn(|_| it.next().unwrap())
+        };
+        let total: f32 = avg.iter().map(|r| r.prob).sum();
+        assert_relative_eq!(total, 1.0, epsilon = 1e-5);
+        for ((a, h), s) in avg
+            .iter()
+            .zip(Val::rotamers(helix.0, helix.1))
+            .zip(Val::rotamers(sheet.0, sheet.1))
+        {
+            assert_relative_eq!(a.prob, 0.25 * h.prob + 0.75 * s.prob, epsilon = 1e-6);
+            // Spread between the two backbones can only widen σ.
+            assert!(a.chi_sigma[0] >= h.chi_sigma[0].min(s.chi_sigma[0]) - 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_chi_means_average_across_wrap() {
+        let rot = |mean: f32| Rotamer {
+            r: [1],
+            prob: 1.0,
+            chi_mean: [mean],
+            chi_sigma: [10.0],
+        };
+        let set = |mean| RotamerIter::from_items([rot(mean)]);
+        let [avg] = average([(1.0, set(170.0)), (1.0, set(-170.0))])
+            .next()
+            .unwrap()
+            .chi_mean;
+        assert_relative_eq!(avg.abs(), 180.0, epsilon = 1e-2);
+    }
+
+    #[test]
+    fn test_gaussian_nodes_moments() {
+        let nodes = || gaussian_nodes(10.0, -20.0, 4.0, 2.0);
+        assert_eq!(nodes().count(), 25);
+        let total: f32 = nodes().map(|(_, _, w)| w).sum();
+        assert_relative_eq!(total, 1.0, epsilon = 1e-6);
+        let var_phi: f32 = nodes().map(|(p, _, w)| w * (p - 10.0) * (p - 10.0)).sum();
+        let var_psi: f32 = nodes().map(|(_, q, w)| w * (q + 20.0) * (q + 20.0)).sum();
+        assert_relative_eq!(var_phi, 16.0, epsilon = 1e-3);
+        assert_relative_eq!(var_psi, 4.0, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_zero_uncertainty_matches_point_query() {
+        for (a, b) in
+            Val::rotamers_uncertain(-65.0, -40.0, 0.0, 0.0).zip(Val::rotamers(-65.0, -40.0))
+        {
+            assert_relative_eq!(a.prob, b.prob, epsilon = 1e-6);
+            assert_relative_eq!(a.chi_mean[0], b.chi_mean[0], epsilon = 1e-2);
+            assert_relative_eq!(a.chi_sigma[0], b.chi_sigma[0], epsilon = 1e-2);
+        }
+    }
+
+    #[test]
+    fn test_blend_cysteine_endpoints_and_mixture() {
+        let (phi, psi) = (-120.0, 130.0);
+        for (p, pure) in [
+            (1.0, Cyd::rotamers(phi, psi)),
+            (0.0, Cyh::rotamers(phi, psi)),
+        ] {
+            for (a, b) in blend_cysteine(phi, psi, p).zip(pure) {
+                assert_eq!(a.r, b.r);
+                assert_relative_eq!(a.prob, b.prob, epsilon = 1e-6);
+                assert_relative_eq!(a.chi_mean[0], b.chi_mean[0], epsilon = 1e-2);
+                assert_relative_eq!(a.chi_sigma[0], b.chi_sigma[0], epsilon = 1e-2);
+            }
+        }
+        let half = blend_cysteine(phi, psi, 0.5);
+        let total: f32 = half.iter().map(|r| r.prob).sum();
+        assert_relative_eq!(total, 1.0, epsilon = 1e-5);
+        for ((m, d), h) in half
+            .zip(Cyd::rotamers(phi, psi))
+            .zip(Cyh::rotamers(phi, psi))
+        {
+            assert_relative_eq!(m.prob, 0.5 * (d.prob + h.prob), epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "disulfide probability")]
+    fn test_blend_cysteine_rejects_invalid_probability() {
+        blend_cysteine(-65.0, -40.0, 1.5);
+    }
+
+    #[test]
+    fn test_blend_proline_endpoints() {
+        let (phi, psi) = (-65.0, 140.0);
+        for (p, pure) in [
+            (1.0, Cpr::rotamers(phi, psi)),
+            (0.0, Tpr::rotamers(phi, psi)),
+        ] {
+            for (a, b) in blend_proline(phi, psi, p).zip(pure) {
+                assert_eq!(a.r, b.r);
+                assert_relative_eq!(a.prob, b.prob, epsilon = 1e-6);
+                for i in 0..3 {
+                    assert_relative_eq!(a.chi_mean[i], b.chi_mean[i], epsilon = 1e-2);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cis_probability() {
+        assert_eq!(cis_probability(5.0, 0.0), 1.0);
+        assert_eq!(cis_probability(-540.0, 0.0), 0.0);
+        assert_relative_eq!(cis_probability(0.0, 30.0), 0.997_3, epsilon = 1e-4);
+        // Symmetric about the cis and trans axes, and in [0, 1] for any σ.
+        assert_relative_eq!(
+            cis_probability(40.0, 50.0),
+            cis_probability(-40.0, 50.0),
+            epsilon = 1e-6
+        );
+        assert_relative_eq!(
+            cis_probability(150.0, 50.0),
+            1.0 - cis_probability(-30.0, 50.0),
+            epsilon = 1e-5
+        );
+        // The two evaluation schemes meet at σ = 90°.
+        for omega in [0.0, 45.0, 120.0, 180.0] {
+            assert_relative_eq!(
+                cis_probability(omega, 90.0),
+                cis_probability(omega, 90.001),
+                epsilon = 1e-4
+            );
+        }
+        assert_relative_eq!(cis_probability(0.0, 1.0e4), 0.5, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_gaussian_nodes_wrap() {
+        assert!(
+            gaussian_nodes(175.0, 0.0, 10.0, 0.0).all(|(p, _, _)| (-180.0..180.0).contains(&p))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "positive sum")]
+    fn test_zero_total_weight_panics() {
+        average([(0.0, Val::rotamers(-65.0, -40.0))]);
+    }
+}
@@ -0,0 +1,197 @@
+use core::fmt;
+
+/// Crate-wide error type, returned by every fallible API: the `try_` query
+/// variants, residue name lookup, the binary rotamer set codec, and
+/// integrity checks.
+///
+/// Implements [`core::error::Error`], so it composes with `?` into
+/// `Box<dyn Error>` and other error types in both `no_std` and `std` code.
+/// The infallible methods handle the same conditions silently (angles are
+/// clamped, `q` is clamped) or panic (χ index out of range); the `try_`
+/// variants surface each one as a value instead, and never panic.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Error, Residue, Val};
+///
+/// assert_eq!(Val::try_rotamers(f32::NAN, -40.0).err(), Some(Error::NonFiniteAngle));
+/// assert_eq!(
+///     Val::try_chi_quantile(-60.0, -40.0, 1, 0.5),
+///     Err(Error::ChiIndexOutOfRange { index: 1, n_chi: 1 }),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// φ or ψ is NaN or infinite.
+    NonFiniteAngle,
+    /// φ or ψ is outside \[−180°, 180°\] under
+    /// [`OutOfRange::Error`](crate::OutOfRange::Error).
+    AngleOutOfRange,
+    /// A χ index is not below the residue's number of χ angles.
+    ChiIndexOutOfRange {
+        /// The requested χ index.
+        index: usize,
+        /// Number of χ angles of the residue type.
+        n_chi: usize,
+    },
+    /// A quantile level is NaN or outside \[0, 1\].
+    InvalidQuantile,
+    /// The probabilities do not have a finite, positive sum, so they cannot
+    /// be re-normalized: a custom [`Interpolator`](crate::Interpolator)
+    /// produced them, or a mask passed to
+    /// [`RotamerIter::renormalize_masked`](crate::RotamerIter::renormalize_masked)
+    /// allows no probability mass.
+    DegenerateDistribution,
+    /// An output buffer is too small for the encoded rotamer set.
+    BufferTooSmall {
+        /// Bytes required.
+        needed: usize,
+    },
+    /// An encoded rotamer set has a format version this build cannot read.
+    UnsupportedVersion(u8),
+    /// An encoded rotamer set is truncated, has the wrong magic, or does not
+    /// fit the requested `RotamerIter<N, R>` shape.
+    MalformedEncoding,
+    /// A residue name matches no residue type or alias.
+    UnknownResidue,
+    /// Library output or data does not match an expected reference.
+    DataMismatch,
+    /// A rotamer bin key or its χ values do not have one entry per χ, a
+    /// bin index is outside `1..=BINS_PER_CHI`, or a raw bin index does not
+    /// name a rotameric [`ChiBin`](crate::ChiBin).
+    InvalidBinKey,
+    /// A rotamer mask does not have one entry per rotamer of the set.
+    MaskLengthMismatch {
+        /// Rotamers in the set.
+        expected: usize,
+        /// Entries in the mask.
+        found: usize,
+    },
+    /// Every conformation drawn for a loop position failed the clash test
+    /// of `sample_loop_avoiding` (`alloc` feature).
+    NoAcceptableSample {
+        /// Index of the position in the loop.
+        position: usize,
+    },
+}
+
+/// Alias of [`Error`] for downstream code whose own `Error` type would
+/// collide with a glob import.
+pub type DunbrackError = Error;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NonFiniteAngle => f.write_str("backbone dihedral angle is not finite"),
+            Self::AngleOutOfRange => {
+                f.write_str("backbone dihedral angle is outside [-180°, 180°]")
+            }
+            Self::ChiIndexOutOfRange { index, n_chi } => {
+                write!(f, "chi_index {index} out of range for {n_chi} χ angles")
+            }
+            Self::InvalidQuantile => f.write_str("quantile level is not in [0, 1]"),
+            Self::DegenerateDistribution => {
+                f.write_str("interpolated probabilities cannot be normalized")
+            }
+            Self::BufferTooSmall { needed } => {
+                write!(f, "buffer too small: {needed} bytes needed")
+            }
+            Self::UnsupportedVersion(version) => {
+                write!(f, "unsupported rotamer set encoding version {version}")
+            }
+            Self::MalformedEncoding => f.write_str("malformed rotamer set encoding"),
+            Self::UnknownResidue => f.write_str("unknown residue name"),
+            Self::DataMismatch => f.write_str("library data does not match the expected reference"),
+            Self::InvalidBinKey => f.write_str("rotamer bin key does not fit the residue's χ bins"),
+            Self::MaskLengthMismatch { expected, found } => {
+                write!(f, "rotamer mask has {found} entries, expected {expected}")
+            }
+            Self::NoAcceptableSample { position } => {
+                write!(
+                    f,
+                    "no clash-free conformation found for loop position {position}"
+                )
+            }
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// Checks that both backbone dihedral angles are finite.
+#[inline]
+pub(crate) fn check_angles(phi: f32, psi: f32) -> Result<(), Error> {
+    if phi.is_finite() && psi.is_finite() {
+        Ok(())
+    } else {
+        Err(Error::NonFiniteAngle)
+    }
+}
+
+/// Aborts the build at link time if the guarded code can unwind.
+///
+/// Constructed before the guarded body and forgotten after it. If the
+/// optimizer cannot prove that the body never panics, the unwind path keeps
+/// the `Drop` call alive, which references an undefined symbol whose name
+/// is the error message. Only meaningful with `panic = "unwind"` and
+/// whole-function visibility (`codegen-units = 1` or LTO); otherwise calls
+/// into other codegen units are assumed to unwind.
+#[cfg(all(feature = "no-panic", not(debug_assertions)))]
+pub(crate) struct NoPanic;
+
+#[cfg(all(feature = "no-panic", not(debug_assertions)))]
+impl Drop for NoPanic {
+    #[inline(always)]
+    fn drop(&mut self) {
+        unsafe extern "C" {
+            #[link_name = "\n\ndunbrack: the `no-panic` feature could not prove that the rotamer query path never panics\n\n"]
+            fn panic_path_detected() -> !;
+        }
+        // SAFETY: never executed; the call only exists on unwind paths, and
+        // its presence fails the link.
+        unsafe { panic_path_detected() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_angles() {
+        assert_eq!(check_angles(-60.0, 400.0), Ok(()));
+        assert_eq!(check_angles(f32::NAN, 0.0), Err(Error::NonFiniteAngle));
+        assert_eq!(
+            check_angles(0.0, f32::NEG_INFINITY),
+            Err(Error::NonFiniteAngle)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        extern crate std;
+        use std::string::ToString;
+        let err = Error::ChiIndexOutOfRange { index: 4, n_chi: 2 };
+        assert_eq!(err.to_string(), "chi_index 4 out of range for 2 χ angles");
+    }
+
+    #[test]
+    fn test_composes_with_question_mark() {
+        extern crate std;
+        use std::boxed::Box;
+        fn run() -> Result<f32, Box<dyn core::error::Error>> {
+            let name = crate::canonical_name("VAL")?;
+            assert_eq!(name, "VAL");
+            Ok(<crate::Val as crate::Residue>::try_chi_quantile(
+                -60.0, -40.0, 3, 0.5,
+            )?)
+        }
+        let err = run().unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<Error>(),
+            Some(&Error::ChiIndexOutOfRange { index: 3, n_chi: 1 })
+        );
+    }
+}
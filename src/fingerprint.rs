@@ -0,0 +1,135 @@
+use crate::error::Error;
+use crate::grid::{GRID_MIN, GRID_STEP};
+use crate::residue::*;
+
+/// Number of φ (and ψ) values in the canonical query set.
+const POINTS: usize = 12;
+
+/// The `i`-th canonical backbone angle, in degrees: an irregular stride
+/// through every part of the Ramachandran plot, never on a grid node, so
+/// every bilinear weight is exercised.
+fn canonical_angle(i: usize) -> f32 {
+    GRID_MIN + 4.1 + (3.0 * GRID_STEP - 0.7) * i as f32
+}
+
+/// 64-bit FNV-1a.
+struct Fnv(u64);
+
+impl Fnv {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_f32s(&mut self, values: &[f32]) {
+        for value in values {
+            self.write(&value.to_bits().to_le_bytes());
+        }
+    }
+}
+
+/// Hash the library's interpolated output over a fixed, canonical set of
+/// query points into one 64-bit fingerprint.
+///
+/// Every residue type, in [`for_all_residues!`](crate::for_all_residues)
+/// order, is queried with [`Residue::rotamers`] on a 12 × 12 grid of
+/// off-node (φ, ψ) points, and the exact bits of every yielded rotamer
+/// (bin indices, probability, χ means, χ σ) are hashed with 64-bit FNV-1a.
+/// Any change to the embedded data, the interpolation, or the math kernels
+/// changes the fingerprint.
+///
+/// The value is stable across platforms for a given crate version and
+/// feature set. `fast-math` builds may legitimately differ from the
+/// default, so record the expected value from a build with the same
+/// features as the deployment. Takes a few milliseconds in optimized
+/// builds.
+///
+/// # Examples
+///
+/// ```
+/// let expected = dunbrack::fingerprint();
+/// assert_eq!(dunbrack::fingerprint(), expected);
+/// ```
+pub fn fingerprint() -> u64 {
+    let mut hash = Fnv(Fnv::OFFSET);
+    macro_rules! hash_residue {
+        ($Res:ident, $n:literal, $r:literal) => {
+            hash.write(<$Res as Residue>::NAME.as_bytes());
+            for i in 0..POINTS {
+                for j in 0..POINTS {
+                    let (phi, psi) = (canonical_angle(i), canonical_angle(j));
+                    for rot in <$Res as Residue>::rotamers(phi, psi) {
+                        hash.write(&rot.r);
+                        hash.write_f32s(&[rot.prob]);
+                        hash.write_f32s(&rot.chi_mean);
+                        hash.write_f32s(&rot.chi_sigma);
+                    }
+                }
+            }
+        };
+    }
+    for_all_residues!(hash_residue);
+    hash.0
+}
+
+/// Check that this build reproduces a [`fingerprint`] recorded from a
+/// trusted build, e.g. at service startup.
+///
+/// # Errors
+///
+/// [`Error::DataMismatch`] if the fingerprints differ.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Error, fingerprint, verify_fingerprint};
+///
+/// let recorded = fingerprint();
+/// assert_eq!(verify_fingerprint(recorded), Ok(()));
+/// assert_eq!(verify_fingerprint(!recorded), Err(Error::DataMismatch));
+/// ```
+pub fn verify_fingerprint(expected: u64) -> Result<(), Error> {
+    if fingerprint() == expected {
+        Ok(())
+    } else {
+        Err(Error::DataMismatch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canonical_angles_off_grid() {
+        for i in 0..POINTS {
+            let angle = canonical_angle(i);
+            assert!((-180.0..180.0).contains(&angle), "{angle}");
+            assert_ne!((angle - GRID_MIN) % GRID_STEP, 0.0);
+        }
+    }
+
+    #[test]
+    fn test_fnv_reference_vectors() {
+        let hash = |bytes: &[u8]| {
+            let mut h = Fnv(Fnv::OFFSET);
+            h.write(bytes);
+            h.0
+        };
+        assert_eq!(hash(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(hash(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(hash(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[cfg(not(feature = "fast-math"))]
+    #[test]
+    fn test_fingerprint_is_pinned() {
+        // Update deliberately whenever the data or the default arithmetic
+        // changes.
+        assert_eq!(fingerprint(), 0x9066_9994_82e3_250b);
+    }
+}
@@ -0,0 +1,243 @@
+//! Flexible rotamers for soft packing.
+
+use core::iter::FusedIterator;
+
+use crate::bins::BinRange;
+use crate::interp::RotamerIter;
+use crate::math::wrap_deg;
+use crate::rotamer::Rotamer;
+
+/// A rotamer widened into a region of χ space: a central conformation plus
+/// one bounded range per χ, `mean ± k_sigma · σ`, in the style of SCWRL
+/// flexible rotamers.
+///
+/// Soft-packing algorithms treat every conformation inside the ranges as
+/// the same rotamer, so small χ adjustments can relieve clashes without
+/// changing the discrete assignment. Ranges are [`BinRange`]s of period
+/// 360°; one whose half-width `k_sigma · σ` reaches 180° covers the full
+/// circle.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Leu, Residue};
+///
+/// let flex: Vec<_> = Leu::rotamers(-65.0, -40.0).flex(1.0).collect();
+/// let best = flex
+///     .iter()
+///     .max_by(|a, b| a.prob.total_cmp(&b.prob))
+///     .unwrap();
+/// assert!(best.contains(&best.chi));
+///
+/// // Pull a strained conformation back to the nearest allowed one.
+/// let strained = [best.chi[0] + 90.0, best.chi[1]];
+/// assert!(!best.contains(&strained));
+/// let relaxed = best.clamp(&strained);
+/// assert!(best.distance(&relaxed).iter().all(|&d| d < 1e-3));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FlexRotamer<const N: usize> {
+    /// Rotamer bin indices (1-based), as in [`Rotamer::r`].
+    pub r: [u8; N],
+    /// Prior probability P(r | φ, ψ), as in [`Rotamer::prob`].
+    pub prob: f32,
+    /// Central conformation, the rotamer's χ means in degrees.
+    pub chi: [f32; N],
+    /// Allowed range of each χ angle.
+    pub ranges: [BinRange; N],
+}
+
+impl<const N: usize> FlexRotamer<N> {
+    /// Widen `rot` to `mean ± k_sigma · σ` in every χ. The ranges agree with
+    /// [`Rotamer::chi_interval`]; the sign of `k_sigma` is ignored.
+    pub fn new(rot: &Rotamer<N>, k_sigma: f32) -> Self {
+        let ranges = core::array::from_fn(|i| {
+            let (mean, half) = (rot.chi_mean[i], (k_sigma * rot.chi_sigma[i]).abs());
+            if half >= 180.0 {
+                BinRange::new(mean - 180.0, mean + 180.0, 360.0)
+            } else {
+                BinRange::new(mean - half, mean + half, 360.0)
+            }
+        });
+        Self {
+            r: rot.r,
+            prob: rot.prob,
+            chi: rot.chi_mean,
+            ranges,
+        }
+    }
+
+    /// Whether every χ of `chis`, in degrees, lies in its range.
+    #[inline]
+    pub fn contains(&self, chis: &[f32; N]) -> bool {
+        self.ranges
+            .iter()
+            .zip(chis)
+            .all(|(range, &chi)| range.contains(chi))
+    }
+
+    /// How far each χ of `chis` lies outside its range, in degrees: all
+    /// zeros for a conformation inside the rotamer.
+    #[inline]
+    pub fn distance(&self, chis: &[f32; N]) -> [f32; N] {
+        core::array::from_fn(|i| self.ranges[i].distance(chis[i]))
+    }
+
+    /// The conformation inside the rotamer nearest to `chis`, moving each χ
+    /// independently to its closest allowed value. Angles are wrapped into
+    /// \[−180°, 180°).
+    #[inline]
+    pub fn clamp(&self, chis: &[f32; N]) -> [f32; N] {
+        core::array::from_fn(|i| self.ranges[i].clamp(chis[i]))
+    }
+
+    /// Whether the two rotamers share a conformation: every pair of χ
+    /// ranges overlaps.
+    #[inline]
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.ranges
+            .iter()
+            .zip(&other.ranges)
+            .all(|(a, b)| a.overlaps(*b))
+    }
+}
+
+impl<const N: usize> Rotamer<N> {
+    /// This rotamer widened to `mean ± k_sigma · σ`, see
+    /// [`FlexRotamer::new`].
+    #[inline]
+    pub fn flex(&self, k_sigma: f32) -> FlexRotamer<N> {
+        FlexRotamer::new(self, k_sigma)
+    }
+}
+
+impl<const N: usize, const R: usize> RotamerIter<N, R> {
+    /// Widen each remaining rotamer to `mean ± k_sigma · σ`, yielding a flex
+    /// set in iteration order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// let flex = Val::rotamers(-60.0, -40.0).flex(2.0);
+    /// assert_eq!(flex.len(), 3);
+    /// ```
+    pub fn flex(
+        self,
+        k_sigma: f32,
+    ) -> impl ExactSizeIterator<Item = FlexRotamer<N>> + FusedIterator {
+        self.map(move |rot| FlexRotamer::new(&rot, k_sigma))
+    }
+}
+
+impl BinRange {
+    /// The angle of the range nearest to `chi`, wrapped into
+    /// \[−180°, 180°): `chi` itself when inside, otherwise the closer
+    /// boundary. The result may be the exclusive `end`, so it lies at
+    /// [`distance`](Self::distance) zero up to rounding rather than
+    /// strictly [`contains`](Self::contains). NaN for NaN or infinite `chi`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::BinRange;
+    ///
+    /// assert_eq!(BinRange::G_PLUS.clamp(60.0), 60.0);
+    /// assert_eq!(BinRange::G_PLUS.clamp(-10.0), 0.0);
+    /// assert_eq!(BinRange::G_PLUS.clamp(130.0), 120.0);
+    /// ```
+    pub fn clamp(self, chi: f32) -> f32 {
+        let mut offset = (chi - self.start) % self.period;
+        if offset < 0.0 {
+            offset += self.period;
+        }
+        if offset.is_nan() {
+            return f32::NAN;
+        }
+        let inside = if offset < self.width() {
+            offset
+        } else if offset - self.width() <= self.period - offset {
+            self.width()
+        } else {
+            0.0
+        };
+        wrap_deg(self.start + inside)
+    }
+
+    /// Whether the two ranges share an angle. Both are taken modulo the
+    /// period of `self`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::BinRange;
+    ///
+    /// assert!(!BinRange::G_PLUS.overlaps(BinRange::TRANS));
+    /// assert!(BinRange::new(100.0, 130.0, 360.0).overlaps(BinRange::TRANS));
+    /// assert!(BinRange::new(-10.0, 10.0, 360.0).overlaps(BinRange::G_MINUS));
+    /// ```
+    #[inline]
+    pub fn overlaps(self, other: Self) -> bool {
+        self.contains(other.start) || other.contains(self.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Arg, Residue};
+    use approx::assert_relative_eq;
+
+    const ROT: Rotamer<2> = Rotamer {
+        r: [2, 3],
+        prob: 0.4,
+        chi_mean: [175.0, -65.0],
+        chi_sigma: [10.0, 100.0],
+    };
+
+    #[test]
+    fn test_ranges_match_chi_interval() {
+        let flex = ROT.flex(2.0);
+        let (lo, hi) = ROT.chi_interval(0, 2.0);
+        assert_relative_eq!(wrap_deg(flex.ranges[0].start), lo, epsilon = 1e-4);
+        assert_relative_eq!(wrap_deg(flex.ranges[0].end), hi, epsilon = 1e-4);
+        // 2 × 100° reaches past 180°: the full circle.
+        assert_eq!(flex.ranges[1].width(), 360.0);
+        assert!(flex.contains(&[-170.0, 115.0]));
+        assert!(!flex.contains(&[150.0, 0.0]));
+        assert_eq!(ROT.flex(-2.0), flex);
+    }
+
+    #[test]
+    fn test_distance_and_clamp() {
+        let flex = ROT.flex(1.0);
+        let chis = [150.0, 0.0];
+        assert_eq!(flex.distance(&chis), [15.0, 0.0]);
+        assert_eq!(flex.clamp(&chis), [165.0, 0.0]);
+        assert_eq!(flex.clamp(&[-170.0, 0.0]), [-175.0, 0.0]);
+        assert_eq!(flex.distance(&flex.clamp(&chis)), [0.0, 0.0]);
+        assert!(BinRange::TRANS.clamp(f32::NAN).is_nan());
+    }
+
+    #[test]
+    fn test_overlaps_across_wrap() {
+        let a = ROT.flex(1.0);
+        let mut b = ROT;
+        b.chi_mean[0] = -168.0;
+        assert!(a.overlaps(&b.flex(1.0)));
+        b.chi_mean[0] = -150.0;
+        assert!(!a.overlaps(&b.flex(1.0)));
+        assert!(a.overlaps(&b.flex(3.0)));
+    }
+
+    #[test]
+    fn test_flex_set_follows_iterator() {
+        let mut rotamers = Arg::rotamers(-65.0, -40.0);
+        rotamers.next();
+        let expected: [_; 4] = core::array::from_fn(|i| rotamers.as_slice()[i].flex(1.5));
+        let flex = rotamers.flex(1.5);
+        assert_eq!(flex.len(), 74);
+        assert!(flex.take(4).eq(expected));
+    }
+}
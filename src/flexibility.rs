@@ -0,0 +1,118 @@
+use alloc::vec::Vec;
+
+use crate::error::Error;
+use crate::identity::ResidueInfo;
+use crate::math::{self, expf};
+use crate::residue::*;
+
+/// Side-chain flexibility of one chain position, an entry of a
+/// [`flexibility_profile`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Flexibility {
+    /// Residue type, or `None` for a name with no rotamers in the library
+    /// (GLY, ALA, and anything unrecognized).
+    pub residue: Option<&'static ResidueInfo>,
+    /// Shannon entropy of the rotamer distribution at the position's
+    /// backbone, in nats; 0 without a residue type.
+    pub entropy: f32,
+    /// Effective number of rotamers, `exp(entropy)`: 1 for a side chain
+    /// locked in one rotamer, up to the residue's rotamer count.
+    pub effective_rotamers: f32,
+}
+
+/// Map a chain, given as `(residue name, φ, ψ)` per position with angles in
+/// degrees, to its per-position rotamer entropy and effective rotamer
+/// count, the flexibility track dashboards show alongside a structure.
+///
+/// Names are resolved as by [`ResidueInfo::lookup`]; positions whose name
+/// has no library entry, such as glycine and alanine, count as rigid
+/// rather than failing, so a whole chain maps in one call. Requires the
+/// `alloc` feature.
+///
+/// # Errors
+///
+/// [`Error::NonFiniteAngle`] if the φ or ψ of a library residue is NaN or
+/// infinite.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::flexibility_profile;
+///
+/// let chain = [("GLY", -75.0, 150.0), ("LEU", -65.0, -40.0), ("ARG", -65.0, -40.0)];
+/// let track = flexibility_profile(&chain).unwrap();
+/// assert_eq!(track.len(), 3);
+/// assert_eq!((track[0].residue, track[0].effective_rotamers), (None, 1.0));
+/// // Long Arg explores more rotamers than Leu on the same backbone.
+/// assert!(track[2].entropy > track[1].entropy);
+/// assert!(track[1].effective_rotamers > 1.0 && track[1].effective_rotamers < 9.0);
+/// ```
+pub fn flexibility_profile(chain: &[(&str, f32, f32)]) -> Result<Vec<Flexibility>, Error> {
+    chain
+        .iter()
+        .map(|&(name, phi, psi)| {
+            let Ok(residue) = ResidueInfo::lookup(name) else {
+                return Ok(Flexibility {
+                    residue: None,
+                    entropy: 0.0,
+                    effective_rotamers: 1.0,
+                });
+            };
+            let entropy = entropy(residue, phi, psi)?;
+            Ok(Flexibility {
+                residue: Some(residue),
+                entropy,
+                effective_rotamers: expf(entropy),
+            })
+        })
+        .collect()
+}
+
+/// Shannon entropy of one residue type's rotamer distribution, in nats.
+fn entropy(residue: &ResidueInfo, phi: f32, psi: f32) -> Result<f32, Error> {
+    macro_rules! dispatch {
+        ($Res:ident, $n:literal, $r:literal) => {
+            if residue.name == <$Res as Residue>::NAME {
+                let rotamers = <$Res as Residue>::try_rotamers(phi, psi)?;
+                return Ok(math::entropy(rotamers.map(|rot| rot.prob)).max(0.0));
+            }
+        };
+    }
+    for_all_residues!(dispatch);
+    Err(Error::UnknownResidue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compare::compare;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_matches_compare_entropy() {
+        let track = flexibility_profile(&[("PHE", -120.0, 130.0), ("tyr", -120.0, 130.0)]).unwrap();
+        let cmp = compare::<Phe, Tyr>(-120.0, 130.0);
+        assert_relative_eq!(track[0].entropy, cmp.entropy_from, epsilon = 1e-6);
+        assert_relative_eq!(track[1].entropy, cmp.entropy_to, epsilon = 1e-6);
+        assert_eq!(track[1].residue.map(|info| info.name), Some("TYR"));
+        for pos in &track {
+            let n = pos.residue.unwrap().n_rotamers as f32;
+            assert!(pos.effective_rotamers >= 1.0 && pos.effective_rotamers <= n);
+        }
+    }
+
+    #[test]
+    fn test_rigid_and_invalid_positions() {
+        let track = flexibility_profile(&[("ALA", f32::NAN, 0.0), ("HOH", 0.0, 0.0)]).unwrap();
+        assert!(
+            track
+                .iter()
+                .all(|pos| pos.residue.is_none() && pos.entropy == 0.0)
+        );
+        assert_eq!(
+            flexibility_profile(&[("SER", f32::NAN, 0.0)]),
+            Err(Error::NonFiniteAngle)
+        );
+        assert!(flexibility_profile(&[]).unwrap().is_empty());
+    }
+}
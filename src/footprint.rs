@@ -0,0 +1,105 @@
+/// Static memory used by one residue type's embedded tables.
+///
+/// One entry per residue type is listed in [`TABLE_FOOTPRINT`](crate::TABLE_FOOTPRINT);
+/// the same per-residue total is available generically as
+/// [`Residue::TABLE_BYTES`](crate::Residue::TABLE_BYTES).
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{TABLE_FOOTPRINT, TOTAL_TABLE_BYTES};
+///
+/// let gln = TABLE_FOOTPRINT.iter().find(|f| f.name == "GLN").unwrap();
+/// assert!(gln.bytes() < TOTAL_TABLE_BYTES);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TableFootprint {
+    /// Three-letter residue name, as in [`Residue::NAME`](crate::Residue::NAME).
+    pub name: &'static str,
+    /// Bytes of the 36×36 grid of [`GridEntry`](crate::GridEntry) values,
+    /// or of its encoded form when built with `delta-tables`.
+    pub table_bytes: usize,
+    /// Bytes of the deduplicated rotamer bin index keys.
+    pub keys_bytes: usize,
+}
+
+impl TableFootprint {
+    /// Total bytes of this residue's static data.
+    #[inline]
+    pub const fn bytes(&self) -> usize {
+        self.table_bytes + self.keys_bytes
+    }
+}
+
+/// Sums the footprint of every entry.
+pub(crate) const fn total(entries: &[TableFootprint]) -> usize {
+    let mut sum = 0;
+    let mut k = 0;
+    while k < entries.len() {
+        sum += entries[k].bytes();
+        k += 1;
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::residue::{Cpr, Gln, Residue, Val};
+    use crate::{TABLE_FOOTPRINT, TOTAL_TABLE_BYTES};
+
+    #[test]
+    fn test_footprint_matches_trait_constants() {
+        let gln = TABLE_FOOTPRINT
+            .iter()
+            .find(|f| f.name == Gln::NAME)
+            .unwrap();
+        assert_eq!(gln.bytes(), Gln::TABLE_BYTES);
+        let dense = 36 * 36 * 108 * 10 * 4;
+        #[cfg(not(feature = "delta-tables"))]
+        assert_eq!(gln.table_bytes, dense);
+        #[cfg(feature = "delta-tables")]
+        assert!(
+            gln.table_bytes < dense / 2,
+            "{} >= {dense}",
+            gln.table_bytes
+        );
+        assert_eq!(gln.keys_bytes, 108 * 3);
+    }
+
+    #[test]
+    fn test_repeated_cells_stored_once() {
+        let cpr = TABLE_FOOTPRINT
+            .iter()
+            .find(|f| f.name == Cpr::NAME)
+            .unwrap();
+        let dense = 36 * 36 * 2 * 10 * 4;
+        assert!(cpr.table_bytes < dense, "{} >= {dense}", cpr.table_bytes);
+        assert_eq!(cpr.bytes(), Cpr::TABLE_BYTES);
+    }
+
+    #[test]
+    fn test_total_is_sum() {
+        #[allow(unused_mut)]
+        let mut sum: usize = TABLE_FOOTPRINT.iter().map(|f| f.bytes()).sum();
+        #[cfg(feature = "chi1")]
+        {
+            sum += crate::CHI1_TABLE_FOOTPRINT
+                .iter()
+                .map(|f| f.bytes())
+                .sum::<usize>();
+        }
+        #[cfg(feature = "alt-data")]
+        {
+            sum += crate::ALT_TABLE_FOOTPRINT
+                .iter()
+                .map(|f| f.bytes())
+                .sum::<usize>();
+        }
+        assert_eq!(sum, TOTAL_TABLE_BYTES);
+        let val = TABLE_FOOTPRINT
+            .iter()
+            .find(|f| f.name == Val::NAME)
+            .unwrap();
+        assert_eq!(val.bytes(), Val::TABLE_BYTES);
+    }
+}
@@ -0,0 +1,397 @@
+use crate::math::{sincos_deg, sqrtf};
+
+/// Source of the dihedral angle that places a side-chain atom.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Torsion {
+    /// χ angle `index` (0-based) plus a constant `offset` in degrees, for
+    /// atoms that rotate with a χ: the χ-defining atom itself (offset 0) and
+    /// its branch siblings (e.g. `OD2` of Asp at χ2 + 180°).
+    Chi {
+        /// 0-based χ index.
+        index: usize,
+        /// Offset added to the χ angle, in degrees.
+        offset: f32,
+    },
+    /// A fixed dihedral in degrees, for atoms held by ring planarity or
+    /// conjugation.
+    Fixed(f32),
+}
+
+impl Torsion {
+    /// Dihedral angle in degrees for the side-chain angles `chis`, or `None`
+    /// if a χ index is not below `chis.len()`.
+    #[inline]
+    pub fn resolve(self, chis: &[f32]) -> Option<f32> {
+        match self {
+            Self::Chi { index, offset } => chis.get(index).map(|chi| chi + offset),
+            Self::Fixed(angle) => Some(angle),
+        }
+    }
+}
+
+/// Ideal internal coordinates of one side-chain heavy atom.
+///
+/// The atom `d` is placed from three already-built reference atoms
+/// `refs = [a, b, c]`: bond `c–d` of length [`bond_length`], angle
+/// `b–c–d` of [`bond_angle`], and dihedral `a–b–c–d` given by
+/// [`torsion`]. `c` is always the atom's topology parent.
+///
+/// Values follow Engh & Huber (1991) small-molecule averages.
+///
+/// [`bond_length`]: Self::bond_length
+/// [`bond_angle`]: Self::bond_angle
+/// [`torsion`]: Self::torsion
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Residue, Ser, Torsion};
+///
+/// let og = &Ser::IDEAL_GEOMETRY[1];
+/// assert_eq!(og.atom, "OG");
+/// assert_eq!(og.refs, ["N", "CA", "CB"]);
+/// assert_eq!(og.torsion, Torsion::Chi { index: 0, offset: 0.0 });
+/// assert_eq!(og.torsion.resolve(&[-65.0]), Some(-65.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InternalCoord {
+    /// PDB name of the placed atom.
+    pub atom: &'static str,
+    /// Reference atoms `[a, b, c]`; backbone atoms are referenced by name.
+    pub refs: [&'static str; 3],
+    /// Bond length `c–atom` in Å.
+    pub bond_length: f32,
+    /// Bond angle `b–c–atom` in degrees.
+    pub bond_angle: f32,
+    /// Dihedral `a–b–c–atom`.
+    pub torsion: Torsion,
+}
+
+impl InternalCoord {
+    /// Cartesian position of the atom given the positions of its reference
+    /// atoms `[a, b, c]` and the side-chain angles `chis` in degrees, or
+    /// `None` if the torsion needs a χ beyond `chis.len()`.
+    ///
+    /// Uses the natural extension reference frame (NeRF) construction.
+    pub fn place(&self, refs: [[f32; 3]; 3], chis: &[f32]) -> Option<[f32; 3]> {
+        let torsion = self.torsion.resolve(chis)?;
+        let [a, b, c] = refs;
+        let bc = normalize(sub(c, b));
+        let n = normalize(cross(sub(b, a), bc));
+        let m = cross(n, bc);
+        let (sin_angle, cos_angle) = sincos_deg(self.bond_angle);
+        let (sin_torsion, cos_torsion) = sincos_deg(torsion);
+        let d = [
+            -self.bond_length * cos_angle,
+            self.bond_length * sin_angle * cos_torsion,
+            self.bond_length * sin_angle * sin_torsion,
+        ];
+        Some(core::array::from_fn(|i| {
+            c[i] + d[0] * bc[i] + d[1] * m[i] + d[2] * n[i]
+        }))
+    }
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let len = sqrtf(v[0] * v[0] + v[1] * v[1] + v[2] * v[2]);
+    [v[0] / len, v[1] / len, v[2] / len]
+}
+
+const fn ic(
+    atom: &'static str,
+    refs: [&'static str; 3],
+    bond_length: f32,
+    bond_angle: f32,
+    torsion: Torsion,
+) -> InternalCoord {
+    InternalCoord {
+        atom,
+        refs,
+        bond_length,
+        bond_angle,
+        torsion,
+    }
+}
+
+const fn chi(index: usize, offset: f32) -> Torsion {
+    Torsion::Chi { index, offset }
+}
+
+use Torsion::Fixed;
+
+/// `N–C–CA–CB` improper dihedral of an L-amino acid.
+const CB_TORSION: Torsion = Fixed(122.6);
+const CB: InternalCoord = ic("CB", ["N", "C", "CA"], 1.530, 110.1, CB_TORSION);
+/// β-branched residues close the `C–CA–CB` angle slightly.
+const CB_BRANCHED: InternalCoord = ic("CB", ["N", "C", "CA"], 1.540, 109.1, CB_TORSION);
+
+const G1: [&str; 3] = ["N", "CA", "CB"];
+const G2: [&str; 3] = ["CA", "CB", "CG"];
+const G3: [&str; 3] = ["CB", "CG", "CD"];
+
+pub(crate) const ARG: &[InternalCoord] = &[
+    CB,
+    ic("CG", G1, 1.520, 114.1, chi(0, 0.0)),
+    ic("CD", G2, 1.520, 111.3, chi(1, 0.0)),
+    ic("NE", G3, 1.460, 112.0, chi(2, 0.0)),
+    ic("CZ", ["CG", "CD", "NE"], 1.329, 124.2, chi(3, 0.0)),
+    ic("NH1", ["CD", "NE", "CZ"], 1.326, 120.0, Fixed(0.0)),
+    ic("NH2", ["CD", "NE", "CZ"], 1.326, 120.0, Fixed(180.0)),
+];
+
+pub(crate) const ASN: &[InternalCoord] = &[
+    CB,
+    ic("CG", G1, 1.516, 112.6, chi(0, 0.0)),
+    ic("OD1", G2, 1.231, 120.8, chi(1, 0.0)),
+    ic("ND2", G2, 1.328, 116.4, chi(1, 180.0)),
+];
+
+pub(crate) const ASP: &[InternalCoord] = &[
+    CB,
+    ic("CG", G1, 1.516, 112.6, chi(0, 0.0)),
+    ic("OD1", G2, 1.249, 118.4, chi(1, 0.0)),
+    ic("OD2", G2, 1.249, 118.4, chi(1, 180.0)),
+];
+
+pub(crate) const CYS: &[InternalCoord] = &[CB, ic("SG", G1, 1.808, 114.4, chi(0, 0.0))];
+
+pub(crate) const GLN: &[InternalCoord] = &[
+    CB,
+    ic("CG", G1, 1.520, 114.1, chi(0, 0.0)),
+    ic("CD", G2, 1.516, 112.6, chi(1, 0.0)),
+    ic("OE1", G3, 1.231, 120.8, chi(2, 0.0)),
+    ic("NE2", G3, 1.328, 116.4, chi(2, 180.0)),
+];
+
+pub(crate) const GLU: &[InternalCoord] = &[
+    CB,
+    ic("CG", G1, 1.520, 114.1, chi(0, 0.0)),
+    ic("CD", G2, 1.516, 112.6, chi(1, 0.0)),
+    ic("OE1", G3, 1.249, 118.4, chi(2, 0.0)),
+    ic("OE2", G3, 1.249, 118.4, chi(2, 180.0)),
+];
+
+pub(crate) const HIS: &[InternalCoord] = &[
+    CB,
+    ic("CG", G1, 1.497, 113.8, chi(0, 0.0)),
+    ic("ND1", G2, 1.378, 122.7, chi(1, 0.0)),
+    ic("CD2", G2, 1.354, 131.0, chi(1, 180.0)),
+    ic("CE1", ["CB", "CG", "ND1"], 1.321, 109.3, Fixed(180.0)),
+    ic("NE2", ["CB", "CG", "CD2"], 1.374, 107.2, Fixed(180.0)),
+];
+
+pub(crate) const ILE: &[InternalCoord] = &[
+    CB_BRANCHED,
+    ic("CG1", G1, 1.530, 110.4, chi(0, 0.0)),
+    ic("CG2", G1, 1.521, 110.5, chi(0, -123.2)),
+    ic("CD1", ["CA", "CB", "CG1"], 1.513, 113.8, chi(1, 0.0)),
+];
+
+pub(crate) const LEU: &[InternalCoord] = &[
+    CB,
+    ic("CG", G1, 1.530, 116.3, chi(0, 0.0)),
+    ic("CD1", G2, 1.521, 110.7, chi(1, 0.0)),
+    ic("CD2", G2, 1.521, 110.7, chi(1, 122.8)),
+];
+
+pub(crate) const LYS: &[InternalCoord] = &[
+    CB,
+    ic("CG", G1, 1.520, 114.1, chi(0, 0.0)),
+    ic("CD", G2, 1.520, 111.3, chi(1, 0.0)),
+    ic("CE", G3, 1.520, 111.3, chi(2, 0.0)),
+    ic("NZ", ["CG", "CD", "CE"], 1.489, 111.9, chi(3, 0.0)),
+];
+
+pub(crate) const MET: &[InternalCoord] = &[
+    CB,
+    ic("CG", G1, 1.520, 114.1, chi(0, 0.0)),
+    ic("SD", G2, 1.803, 112.7, chi(1, 0.0)),
+    ic("CE", ["CB", "CG", "SD"], 1.791, 100.9, chi(2, 0.0)),
+];
+
+pub(crate) const PHE: &[InternalCoord] = &[
+    CB,
+    ic("CG", G1, 1.502, 113.8, chi(0, 0.0)),
+    ic("CD1", G2, 1.384, 120.7, chi(1, 0.0)),
+    ic("CD2", G2, 1.384, 120.7, chi(1, 180.0)),
+    ic("CE1", ["CB", "CG", "CD1"], 1.382, 120.7, Fixed(180.0)),
+    ic("CE2", ["CB", "CG", "CD2"], 1.382, 120.7, Fixed(180.0)),
+    ic("CZ", ["CG", "CD1", "CE1"], 1.382, 120.0, Fixed(0.0)),
+];
+
+pub(crate) const PRO: &[InternalCoord] = &[
+    CB,
+    ic("CG", G1, 1.495, 104.5, chi(0, 0.0)),
+    ic("CD", G2, 1.503, 105.5, chi(1, 0.0)),
+];
+
+pub(crate) const SER: &[InternalCoord] = &[CB, ic("OG", G1, 1.417, 111.1, chi(0, 0.0))];
+
+pub(crate) const THR: &[InternalCoord] = &[
+    CB_BRANCHED,
+    ic("OG1", G1, 1.433, 109.6, chi(0, 0.0)),
+    ic("CG2", G1, 1.521, 110.5, chi(0, -120.0)),
+];
+
+pub(crate) const TRP: &[InternalCoord] = &[
+    CB,
+    ic("CG", G1, 1.498, 113.6, chi(0, 0.0)),
+    ic("CD1", G2, 1.365, 127.0, chi(1, 0.0)),
+    ic("CD2", G2, 1.433, 126.6, chi(1, 180.0)),
+    ic("NE1", ["CB", "CG", "CD1"], 1.374, 110.2, Fixed(180.0)),
+    ic("CE2", ["CB", "CG", "CD2"], 1.409, 107.2, Fixed(180.0)),
+    ic("CE3", ["CB", "CG", "CD2"], 1.398, 133.9, Fixed(0.0)),
+    ic("CZ2", ["CG", "CD2", "CE2"], 1.394, 122.4, Fixed(180.0)),
+    ic("CZ3", ["CG", "CD2", "CE3"], 1.382, 118.6, Fixed(180.0)),
+    ic("CH2", ["CD2", "CE2", "CZ2"], 1.368, 117.5, Fixed(0.0)),
+];
+
+pub(crate) const TYR: &[InternalCoord] = &[
+    CB,
+    ic("CG", G1, 1.512, 113.9, chi(0, 0.0)),
+    ic("CD1", G2, 1.389, 120.8, chi(1, 0.0)),
+    ic("CD2", G2, 1.389, 120.8, chi(1, 180.0)),
+    ic("CE1", ["CB", "CG", "CD1"], 1.382, 121.2, Fixed(180.0)),
+    ic("CE2", ["CB", "CG", "CD2"], 1.382, 121.2, Fixed(180.0)),
+    ic("CZ", ["CG", "CD1", "CE1"], 1.378, 119.6, Fixed(0.0)),
+    ic("OH", ["CD1", "CE1", "CZ"], 1.376, 119.9, Fixed(180.0)),
+];
+
+pub(crate) const VAL: &[InternalCoord] = &[
+    CB_BRANCHED,
+    ic("CG1", G1, 1.521, 110.5, chi(0, 0.0)),
+    ic("CG2", G1, 1.521, 110.5, chi(0, 122.8)),
+];
+
+#[cfg(test)]
+mod tests {
+    use crate::residue::*;
+
+    /// Ideal backbone of an L-residue in the local frame used by
+    /// AlphaFold's rigid groups: CA at the origin, C on +x.
+    const BACKBONE: [(&str, [f32; 3]); 3] = [
+        ("N", [-0.525, 1.363, 0.0]),
+        ("CA", [0.0, 0.0, 0.0]),
+        ("C", [1.526, 0.0, 0.0]),
+    ];
+
+    /// Builds every side-chain atom of `R` into `out` and returns the count.
+    fn build<R: Residue>(chis: &[f32], out: &mut [(&'static str, [f32; 3]); 16]) -> usize {
+        out[..3].copy_from_slice(&BACKBONE);
+        let mut len = 3;
+        for coord in R::IDEAL_GEOMETRY {
+            let find = |name: &str| out[..len].iter().find(|(n, _)| *n == name).unwrap().1;
+            let refs = coord.refs.map(find);
+            out[len] = (coord.atom, coord.place(refs, chis).unwrap());
+            len += 1;
+        }
+        len
+    }
+
+    fn pos(atoms: &[(&str, [f32; 3])], name: &str) -> [f32; 3] {
+        atoms.iter().find(|(n, _)| *n == name).unwrap().1
+    }
+
+    fn dist(a: [f32; 3], b: [f32; 3]) -> f32 {
+        let d: [f32; 3] = core::array::from_fn(|i| a[i] - b[i]);
+        (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
+    }
+
+    fn dihedral(p: [[f32; 3]; 4]) -> f32 {
+        use super::{cross, normalize, sub};
+        let dot = |a: [f32; 3], b: [f32; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+        let (b0, b1, b2) = (sub(p[0], p[1]), normalize(sub(p[2], p[1])), sub(p[3], p[2]));
+        let v = sub(b0, b1.map(|x| x * dot(b0, b1)));
+        let w = sub(b2, b1.map(|x| x * dot(b2, b1)));
+        dot(cross(b1, v), w).atan2(dot(v, w)).to_degrees()
+    }
+
+    fn check<R: Residue>() {
+        let (geometry, topo) = (R::IDEAL_GEOMETRY, R::TOPOLOGY);
+        assert_eq!(geometry.len(), topo.atoms.len(), "{}", R::NAME);
+        for (coord, atom) in geometry.iter().zip(topo.atoms) {
+            assert_eq!(coord.atom, atom.name, "{}", R::NAME);
+            assert_eq!(coord.refs[2], atom.parent, "{}: {}", R::NAME, coord.atom);
+            if let super::Torsion::Chi { index, offset: 0.0 } = coord.torsion {
+                let [a, b, c] = coord.refs;
+                assert_eq!(topo.chi_atoms[index], [a, b, c, coord.atom], "{}", R::NAME);
+            }
+        }
+
+        // Built χ dihedrals reproduce the requested angles. Proline's χ3 is
+        // fixed by ring closure rather than driven by a torsion.
+        let chis = [-65.0, 175.0, 62.0, -170.0];
+        let mut atoms = [("", [0.0; 3]); 16];
+        let len = build::<R>(&chis[..R::N_CHI], &mut atoms);
+        for (k, names) in topo.chi_atoms.iter().enumerate() {
+            if names[3] == "N" {
+                continue;
+            }
+            let measured = dihedral(names.map(|n| pos(&atoms[..len], n)));
+            let diff = (measured - chis[k] + 540.0).rem_euclid(360.0) - 180.0;
+            assert!(diff.abs() < 0.01, "{} χ{}: {measured}", R::NAME, k + 1);
+        }
+    }
+
+    #[test]
+    fn test_geometry_matches_topology() {
+        macro_rules! run {
+            ($Res:ident, $n:literal, $r:literal) => {
+                check::<$Res>();
+            };
+        }
+        for_all_residues!(run);
+    }
+
+    #[test]
+    fn test_cb_is_l_chirality() {
+        let mut atoms = [("", [0.0; 3]); 16];
+        let len = build::<Ser>(&[60.0], &mut atoms);
+        // AlphaFold's ideal CB position for this backbone frame.
+        assert!(dist(pos(&atoms[..len], "CB"), [-0.529, -0.774, -1.205]) < 0.05);
+    }
+
+    #[test]
+    fn test_branch_siblings_match_reference_chirality() {
+        // N–CA–CB–X dihedral of the second γ atom relative to χ1, from
+        // AlphaFold's ideal rigid-group coordinates.
+        let sibling = |atoms: &[(&str, [f32; 3])], name: &str| {
+            dihedral(["N", "CA", "CB", name].map(|n| pos(atoms, n)))
+        };
+        let mut atoms = [("", [0.0; 3]); 16];
+        let len = build::<Val>(&[0.0], &mut atoms);
+        assert!((sibling(&atoms[..len], "CG2") - 122.8).abs() < 1.0);
+        let len = build::<Ile>(&[0.0, 0.0], &mut atoms);
+        assert!((sibling(&atoms[..len], "CG2") + 123.2).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_aromatic_rings_close() {
+        let mut atoms = [("", [0.0; 3]); 16];
+        let ring = |len: usize, atoms: &[(&str, [f32; 3])], a: &str, b: &str, ideal: f32| {
+            let d = dist(pos(&atoms[..len], a), pos(&atoms[..len], b));
+            assert!((d - ideal).abs() < 0.03, "{a}-{b}: {d}");
+        };
+        let len = build::<Phe>(&[-65.0, 90.0], &mut atoms);
+        ring(len, &atoms, "CE2", "CZ", 1.382);
+        let len = build::<Tyr>(&[-65.0, 90.0], &mut atoms);
+        ring(len, &atoms, "CE2", "CZ", 1.378);
+        let len = build::<His>(&[-65.0, 90.0], &mut atoms);
+        ring(len, &atoms, "CE1", "NE2", 1.321);
+        let len = build::<Trp>(&[-65.0, 90.0], &mut atoms);
+        ring(len, &atoms, "NE1", "CE2", 1.370);
+        ring(len, &atoms, "CZ3", "CH2", 1.400);
+    }
+}
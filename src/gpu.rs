@@ -0,0 +1,239 @@
+//! Flattened rotamer tables for GPU upload.
+
+use crate::error::Error;
+use crate::grid::{GRID_COUNT, GRID_MIN, GRID_STEP};
+use crate::interp::Table;
+use crate::residue::Residue;
+
+/// Every array of a GPU table starts at a multiple of this many bytes, the
+/// largest storage-buffer offset alignment required by WebGPU, Vulkan, and
+/// CUDA, so each array can be bound separately at its offset.
+pub const GPU_ARRAY_ALIGN: usize = 256;
+
+/// Rotamer slots per cell are padded to a multiple of this, so every cell
+/// row of an array is 16-byte aligned for `vec4` loads.
+const SLOT_MULTIPLE: usize = 4;
+
+/// Descriptor of the flattened table written by
+/// [`Residue::write_gpu_table`], telling a kernel where each array lives.
+///
+/// The table is a structure of arrays, all little-endian: one `f32` array
+/// for the probabilities, then one per χ for sin χ, for cos χ, and for σ
+/// (every sin array first, then every cos array, then every σ array), then
+/// one `u32` array of bin keys. Each `f32` array holds
+/// `grid_count² × rotamer_stride` values; the value of rotamer `k` in grid
+/// cell (`phi_idx`, `psi_idx`) is at index
+/// `(phi_idx × grid_count + psi_idx) × rotamer_stride + k`, where cell
+/// (`i`, `j`) lies at φ = `grid_min + i × grid_step`, ψ likewise. The
+/// values are exactly those the CPU path interpolates. Slots
+/// `n_rotamers..rotamer_stride` of every cell are zero, so padded rotamers
+/// carry no probability. Key `k` packs the 1-based bin of χ`i + 1` into
+/// byte `i`, zero past the last χ.
+///
+/// Arrays start at multiples of [`GPU_ARRAY_ALIGN`] bytes; the descriptor
+/// itself is `#[repr(C)]` with only 4-byte fields, so it can be uploaded
+/// as a uniform buffer alongside the table.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{GpuTableLayout, Residue, Val};
+///
+/// let layout = GpuTableLayout::of::<Val>();
+/// assert_eq!((layout.n_rotamers, layout.rotamer_stride), (3, 4));
+///
+/// let mut bytes = vec![0_u8; layout.len()];
+/// assert_eq!(Val::write_gpu_table(&mut bytes), Ok(layout));
+///
+/// // Probability of the first rotamer at φ = ψ = −180°.
+/// let at = layout.prob_offset as usize;
+/// let p = f32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+/// assert!(p > 0.0 && p < 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct GpuTableLayout {
+    /// Number of χ angles.
+    pub n_chi: u32,
+    /// Number of rotamers per grid cell.
+    pub n_rotamers: u32,
+    /// Rotamer slots per cell, `n_rotamers` rounded up to a multiple of 4.
+    pub rotamer_stride: u32,
+    /// Grid points along each of φ and ψ, [`GRID_COUNT`].
+    pub grid_count: u32,
+    /// Angle of grid index 0 in degrees, [`GRID_MIN`].
+    pub grid_min: f32,
+    /// Spacing of the grid in degrees, [`GRID_STEP`].
+    pub grid_step: f32,
+    /// Distance in bytes between the starts of consecutive arrays.
+    pub array_stride: u32,
+    /// Byte offset of the probability array.
+    pub prob_offset: u32,
+    /// Byte offset of the first sin χ array; χ`i + 1` follows at
+    /// `i × array_stride` bytes further.
+    pub chi_sin_offset: u32,
+    /// Byte offset of the first cos χ array, laid out like `chi_sin_offset`.
+    pub chi_cos_offset: u32,
+    /// Byte offset of the first σ array, laid out like `chi_sin_offset`.
+    pub chi_sigma_offset: u32,
+    /// Byte offset of the `rotamer_stride` packed bin keys.
+    pub keys_offset: u32,
+}
+
+impl GpuTableLayout {
+    /// Layout of the table of residue type `R`.
+    #[inline]
+    pub const fn of<R: Residue>() -> Self {
+        Self::new(R::N_CHI, R::N_ROTAMERS)
+    }
+
+    /// Layout of a table with `n_chi` χ angles and `n_rotamers` rotamers.
+    const fn new(n_chi: usize, n_rotamers: usize) -> Self {
+        let stride = n_rotamers.next_multiple_of(SLOT_MULTIPLE);
+        let array = (GRID_COUNT * GRID_COUNT * stride * 4).next_multiple_of(GPU_ARRAY_ALIGN);
+        let sin = array;
+        let cos = sin + n_chi * array;
+        let sigma = cos + n_chi * array;
+        Self {
+            n_chi: n_chi as u32,
+            n_rotamers: n_rotamers as u32,
+            rotamer_stride: stride as u32,
+            grid_count: GRID_COUNT as u32,
+            grid_min: GRID_MIN,
+            grid_step: GRID_STEP,
+            array_stride: array as u32,
+            prob_offset: 0,
+            chi_sin_offset: sin as u32,
+            chi_cos_offset: cos as u32,
+            chi_sigma_offset: sigma as u32,
+            keys_offset: (sigma + n_chi * array) as u32,
+        }
+    }
+
+    /// Total size of the table in bytes, keys included.
+    #[inline]
+    pub const fn len(&self) -> usize {
+        self.keys_offset as usize + self.rotamer_stride as usize * 4
+    }
+
+    /// Always `false`: every table holds at least its keys.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+/// Writes `table` and `keys` into `out` in the layout of
+/// [`GpuTableLayout`], backing [`Residue::write_gpu_table`].
+pub(crate) fn write<const N: usize, const R: usize>(
+    table: &Table<N, R>,
+    keys: &[[u8; N]; R],
+    out: &mut [u8],
+) -> Result<GpuTableLayout, Error> {
+    let layout = GpuTableLayout::new(N, R);
+    let needed = layout.len();
+    let out = out
+        .get_mut(..needed)
+        .ok_or(Error::BufferTooSmall { needed })?;
+    out.fill(0);
+
+    let stride = layout.rotamer_stride as usize;
+    let array = layout.array_stride as usize;
+    let mut put = |offset: usize, value: f32| {
+        out[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    };
+    let cells =
+        (0..GRID_COUNT).flat_map(|phi_idx| (0..GRID_COUNT).map(move |psi_idx| (phi_idx, psi_idx)));
+    for (cell_idx, (phi_idx, psi_idx)) in cells.enumerate() {
+        for (k, entry) in table.cell(phi_idx, psi_idx).iter().enumerate() {
+            let slot = (cell_idx * stride + k) * 4;
+            put(layout.prob_offset as usize + slot, entry.prob);
+            for i in 0..N {
+                let chi = i * array + slot;
+                put(layout.chi_sin_offset as usize + chi, entry.chi_sin[i]);
+                put(layout.chi_cos_offset as usize + chi, entry.chi_cos[i]);
+                put(layout.chi_sigma_offset as usize + chi, entry.chi_sigma[i]);
+            }
+        }
+    }
+    for (k, key) in keys.iter().enumerate() {
+        let at = layout.keys_offset as usize + k * 4;
+        out[at..at + N].copy_from_slice(key);
+    }
+    Ok(layout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::*;
+    use approx::assert_relative_eq;
+
+    fn read(bytes: &[u8], at: usize) -> f32 {
+        f32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn test_layouts_are_aligned_and_contiguous() {
+        macro_rules! check {
+            ($Res:ident, $n:literal, $r:literal) => {
+                let layout = GpuTableLayout::of::<$Res>();
+                let stride = layout.rotamer_stride as usize;
+                assert!(stride >= $r && stride % 4 == 0 && stride - $r < 4);
+                let offsets = [
+                    layout.prob_offset,
+                    layout.chi_sin_offset,
+                    layout.chi_cos_offset,
+                    layout.chi_sigma_offset,
+                    layout.keys_offset,
+                ];
+                assert!(offsets.iter().all(|&o| o as usize % GPU_ARRAY_ALIGN == 0));
+                let arrays = 1 + 3 * $n;
+                assert_eq!(layout.keys_offset, arrays as u32 * layout.array_stride);
+            };
+        }
+        for_all_residues!(check);
+    }
+
+    #[test]
+    fn test_values_match_raw_cells() {
+        extern crate std;
+
+        let layout = GpuTableLayout::of::<Leu>();
+        let mut bytes = std::vec![0xff_u8; layout.len() + 16];
+        assert_eq!(Leu::write_gpu_table(&mut bytes), Ok(layout));
+        let (stride, array) = (layout.rotamer_stride as usize, layout.array_stride as usize);
+
+        // Cell (11, 14) is the lower corner of φ = −65°, ψ = −40°.
+        let detail = Leu::rotamers_detailed(-65.0, -40.0);
+        let cell = 11 * GRID_COUNT + 14;
+        for (k, rot) in detail.corners[0].clone().enumerate() {
+            let slot = (cell * stride + k) * 4;
+            assert_eq!(read(&bytes, slot), rot.prob);
+            for i in 0..2 {
+                let at = i * array + slot;
+                let sin = read(&bytes, layout.chi_sin_offset as usize + at);
+                let cos = read(&bytes, layout.chi_cos_offset as usize + at);
+                assert_relative_eq!(sin.atan2(cos).to_degrees(), rot.chi_mean[i], epsilon = 1e-2);
+                let sigma = read(&bytes, layout.chi_sigma_offset as usize + at);
+                assert_eq!(sigma, rot.chi_sigma[i]);
+            }
+            let key = layout.keys_offset as usize + k * 4;
+            assert_eq!(bytes[key..key + 4], [rot.r[0], rot.r[1], 0, 0]);
+        }
+        // Padding slots are zeroed; bytes past the table are untouched.
+        let pad = (cell * stride + 9) * 4;
+        assert_eq!(read(&bytes, pad), 0.0);
+        assert_eq!(bytes[layout.len()..], [0xff; 16]);
+    }
+
+    #[test]
+    fn test_buffer_too_small() {
+        let needed = GpuTableLayout::of::<Val>().len();
+        let mut bytes = [0_u8; 64];
+        assert_eq!(
+            Val::write_gpu_table(&mut bytes),
+            Err(Error::BufferTooSmall { needed })
+        );
+    }
+}
@@ -0,0 +1,124 @@
+use crate::grid::{GRID_COUNT, angle_to_grid};
+
+/// Per-rotamer probability gradients `(∂p/∂φ, ∂p/∂ψ)` at every node of the
+/// 37×37 Ramachandran grid, in probability per degree.
+///
+/// Generated at build time by periodic central differences of the tabulated
+/// probabilities, so a large magnitude marks backbone regions where a
+/// rotamer's preference changes quickly. Exposed per residue type as
+/// [`Residue::PROB_GRADIENTS`](crate::Residue::PROB_GRADIENTS).
+///
+/// Requires the `gradients` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Residue, Val};
+///
+/// let grad = Val::PROB_GRADIENTS;
+/// // Gradient of rotamer 0 at (φ, ψ) = (−60°, −40°).
+/// let [dphi, dpsi] = grad.get(12, 14, 0).unwrap();
+/// assert!(dphi.is_finite() && dpsi.is_finite());
+/// assert!(grad.get(37, 0, 0).is_none());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GradientMap {
+    n_rotamers: usize,
+    data: &'static [[f32; 2]],
+}
+
+impl GradientMap {
+    /// Wraps a flat `[phi_idx][psi_idx][rotamer]` array of gradients.
+    pub(crate) const fn new(n_rotamers: usize, data: &'static [[f32; 2]]) -> Self {
+        assert!(data.len() == GRID_COUNT * GRID_COUNT * n_rotamers);
+        Self { n_rotamers, data }
+    }
+
+    /// Number of rotamers per grid node.
+    #[inline]
+    pub const fn n_rotamers(&self) -> usize {
+        self.n_rotamers
+    }
+
+    /// Gradient `[∂p/∂φ, ∂p/∂ψ]` of rotamer `rotamer` at grid node
+    /// (`phi_idx`, `psi_idx`), or `None` if any index is out of range.
+    ///
+    /// Grid indices map to angles via `GRID_MIN + idx × GRID_STEP`; rotamers
+    /// are in table order.
+    #[inline]
+    pub const fn get(&self, phi_idx: usize, psi_idx: usize, rotamer: usize) -> Option<[f32; 2]> {
+        if phi_idx >= GRID_COUNT || psi_idx >= GRID_COUNT || rotamer >= self.n_rotamers {
+            return None;
+        }
+        Some(self.data[(phi_idx * GRID_COUNT + psi_idx) * self.n_rotamers + rotamer])
+    }
+
+    /// Gradient of rotamer `rotamer` at an arbitrary (`phi`, `psi`) in
+    /// degrees, bilinearly interpolated between the surrounding grid nodes.
+    ///
+    /// Angles are clamped to \[−180°, 180°\]. Returns `None` if `rotamer`
+    /// is out of range.
+    pub fn at(&self, phi: f32, psi: f32, rotamer: usize) -> Option<[f32; 2]> {
+        if rotamer >= self.n_rotamers {
+            return None;
+        }
+        let (lo_phi, frac_phi) = angle_to_grid(phi);
+        let (lo_psi, frac_psi) = angle_to_grid(psi);
+        let node = |i: usize, j: usize| self.data[(i * GRID_COUNT + j) * self.n_rotamers + rotamer];
+        let (g00, g10) = (node(lo_phi, lo_psi), node(lo_phi + 1, lo_psi));
+        let (g01, g11) = (node(lo_phi, lo_psi + 1), node(lo_phi + 1, lo_psi + 1));
+        Some(core::array::from_fn(|d| {
+            let lo = g00[d] + frac_phi * (g10[d] - g00[d]);
+            let hi = g01[d] + frac_phi * (g11[d] - g01[d]);
+            lo + frac_psi * (hi - lo)
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::grid::GRID_STEP;
+    use crate::interp::Nearest;
+    use crate::residue::{Leu, Residue};
+    use approx::assert_relative_eq;
+
+    fn prob_at(phi_idx: usize, psi_idx: usize, k: usize) -> f32 {
+        let angle = |idx: usize| -180.0 + GRID_STEP * idx as f32;
+        Leu::rotamers_with::<Nearest>(angle(phi_idx), angle(psi_idx))
+            .nth(k)
+            .unwrap()
+            .prob
+    }
+
+    #[test]
+    fn test_matches_central_difference() {
+        let grad = Leu::PROB_GRADIENTS;
+        assert_eq!(grad.n_rotamers(), Leu::N_ROTAMERS);
+        for (i, j, k) in [(12, 14, 0), (20, 5, 3), (6, 30, 8)] {
+            let [dphi, dpsi] = grad.get(i, j, k).unwrap();
+            let expect_phi = (prob_at(i + 1, j, k) - prob_at(i - 1, j, k)) / (2.0 * GRID_STEP);
+            let expect_psi = (prob_at(i, j + 1, k) - prob_at(i, j - 1, k)) / (2.0 * GRID_STEP);
+            assert_relative_eq!(dphi, expect_phi, epsilon = 1e-4);
+            assert_relative_eq!(dpsi, expect_psi, epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_periodic_edges_agree() {
+        let grad = Leu::PROB_GRADIENTS;
+        for j in 0..37 {
+            assert_eq!(grad.get(0, j, 2), grad.get(36, j, 2));
+            assert_eq!(grad.get(j, 0, 2), grad.get(j, 36, 2));
+        }
+    }
+
+    #[test]
+    fn test_at_grid_node_matches_get() {
+        let grad = Leu::PROB_GRADIENTS;
+        let [a, b] = grad.at(-60.0, -40.0, 1).unwrap();
+        let [c, d] = grad.get(12, 14, 1).unwrap();
+        assert_relative_eq!(a, c, epsilon = 1e-7);
+        assert_relative_eq!(b, d, epsilon = 1e-7);
+        assert!(grad.at(0.0, 0.0, Leu::N_ROTAMERS).is_none());
+    }
+}
@@ -1,3 +1,9 @@
+use core::marker::PhantomData;
+
+use crate::interp::bilinear_weights;
+use crate::math::{sqrtf, wrap_deg};
+use crate::residue::Residue;
+
 /// Minimum angle on the φ/ψ grid, in degrees (−180°).
 ///
 /// The grid spans from `GRID_MIN` (−180°) to `GRID_MIN + (GRID_COUNT - 1) × GRID_STEP`
@@ -9,11 +15,18 @@ pub const GRID_STEP: f32 = 10.0;
 
 /// Number of grid points along each axis, φ and ψ (37).
 ///
-/// Indices 0 through 36 cover −180° to +180° inclusive. Both endpoints carry
-/// identical data (angular periodicity), so bilinear interpolation always
-/// reads two adjacent indices without wrapping.
+/// Indices 0 through 36 cover −180° to +180° inclusive. Both endpoints are
+/// the same backbone conformation (angular periodicity), so tables store
+/// only the first [`GRID_PERIOD`] indices and read index 36 as index 0.
 pub const GRID_COUNT: usize = 37;
 
+/// Number of distinct grid points along each axis (36), the period of the
+/// grid in indices.
+///
+/// Rotamer tables hold `GRID_PERIOD × GRID_PERIOD` cells; grid index `idx`
+/// is stored at `idx % GRID_PERIOD`.
+pub const GRID_PERIOD: usize = GRID_COUNT - 1;
+
 /// Map a backbone dihedral angle (in degrees) to a grid index and fractional offset.
 ///
 /// The input is clamped to \[−180.0, 180.0\] before conversion. Returns
@@ -29,6 +42,222 @@ pub fn angle_to_grid(deg: f32) -> (usize, f32) {
     (lo, frac)
 }
 
+/// Angle in degrees of grid index `idx`, the inverse of
+/// [`nearest_grid_index`] on grid points.
+///
+/// # Panics
+///
+/// Panics if `idx` is not below [`GRID_COUNT`].
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::grid_angle;
+///
+/// assert_eq!(grid_angle(0), -180.0);
+/// assert_eq!(grid_angle(12), -60.0);
+/// ```
+#[inline]
+pub const fn grid_angle(idx: usize) -> f32 {
+    assert!(idx < GRID_COUNT, "grid index out of range");
+    GRID_MIN + GRID_STEP * idx as f32
+}
+
+/// Index of the grid point nearest to `deg`, in \[0, 36\]; halfway
+/// angles round up, as in [`Nearest`](crate::Nearest) interpolation.
+///
+/// The input is clamped to \[−180.0, 180.0\] first.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::nearest_grid_index;
+///
+/// assert_eq!(nearest_grid_index(-63.0), 12);
+/// assert_eq!(nearest_grid_index(-65.0), 12);
+/// assert_eq!(nearest_grid_index(179.0), 36);
+/// ```
+#[inline]
+pub fn nearest_grid_index(deg: f32) -> usize {
+    let (lo, frac) = angle_to_grid(deg);
+    lo + (frac >= 0.5) as usize
+}
+
+/// Snap `deg` to the nearest grid angle, e.g. to query raw table values
+/// without interpolation.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::snap_to_grid;
+///
+/// assert_eq!(snap_to_grid(-63.0), -60.0);
+/// assert_eq!(snap_to_grid(-200.0), -180.0);
+/// ```
+#[inline]
+pub fn snap_to_grid(deg: f32) -> f32 {
+    grid_angle(nearest_grid_index(deg))
+}
+
+/// One grid point surrounding a (φ, ψ) query, with its bilinear weight.
+///
+/// Produced by [`grid_neighbors`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridPoint {
+    /// φ grid index, in \[0, 36\].
+    pub phi_idx: usize,
+    /// ψ grid index, in \[0, 36\].
+    pub psi_idx: usize,
+    /// Bilinear weight of this point in the interpolated result.
+    pub weight: f32,
+}
+
+impl GridPoint {
+    /// The `(phi, psi)` angles of this point, in degrees.
+    #[inline]
+    pub const fn angles(&self) -> (f32, f32) {
+        (grid_angle(self.phi_idx), grid_angle(self.psi_idx))
+    }
+}
+
+/// The four grid points surrounding (`phi`, `psi`) with their bilinear
+/// weights, which sum to 1.0.
+///
+/// Points come in the corner order of
+/// [`BilinearDetail`](crate::BilinearDetail): `(lo_φ, lo_ψ)`,
+/// `(lo_φ + 1, lo_ψ)`, `(lo_φ, lo_ψ + 1)`, `(lo_φ + 1, lo_ψ + 1)`. Both
+/// angles are clamped to \[−180.0, 180.0\] first.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::grid_neighbors;
+///
+/// let points = grid_neighbors(-65.0, -40.0);
+/// assert_eq!(points[0].angles(), (-70.0, -40.0));
+/// assert_eq!(points[3].angles(), (-60.0, -30.0));
+/// assert_eq!(points[0].weight + points[1].weight, 1.0);
+/// ```
+pub fn grid_neighbors(phi: f32, psi: f32) -> [GridPoint; 4] {
+    let ([lo_phi, lo_psi], weights) = grid_weights(phi, psi);
+    let offsets = [(0, 0), (1, 0), (0, 1), (1, 1)];
+    core::array::from_fn(|i| GridPoint {
+        phi_idx: lo_phi + offsets[i].0,
+        psi_idx: lo_psi + offsets[i].1,
+        weight: weights[i],
+    })
+}
+
+/// Lower grid indices `[lo_φ, lo_ψ]` of the cell containing (`phi`,
+/// `psi`) and the bilinear weights `[w00, w10, w01, w11]` of its corners,
+/// exactly as the crate's own interpolation computes them.
+///
+/// Corner `w10` is `(lo_φ + 1, lo_ψ)` and `w01` is `(lo_φ, lo_ψ + 1)`.
+/// Downstream code interpolating its own per-cell quantities with these
+/// stays numerically consistent with
+/// [`Residue::rotamers`](crate::Residue::rotamers). Both angles are clamped
+/// to \[−180.0, 180.0\] first.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::grid_weights;
+///
+/// // A per-cell quantity laid out like the rotamer tables.
+/// let field = |phi_idx: usize, psi_idx: usize| (phi_idx * 37 + psi_idx) as f32;
+///
+/// let ([i, j], w) = grid_weights(-65.0, -37.5);
+/// let value = w[0] * field(i, j)
+///     + w[1] * field(i + 1, j)
+///     + w[2] * field(i, j + 1)
+///     + w[3] * field(i + 1, j + 1);
+/// assert_eq!(value, 11.5 * 37.0 + 14.25);
+/// ```
+#[inline]
+pub fn grid_weights(phi: f32, psi: f32) -> ([usize; 2], [f32; 4]) {
+    let (lo_phi, frac_phi) = angle_to_grid(phi);
+    let (lo_psi, frac_psi) = angle_to_grid(psi);
+    ([lo_phi, lo_psi], bilinear_weights(frac_phi, frac_psi))
+}
+
+/// One grid point near a (φ, ψ) query, with the residue's rotamers there.
+///
+/// Produced by [`Residue::neighborhood`].
+#[derive(Debug, Clone)]
+pub struct GridCell<I> {
+    /// φ grid index, in \[0, 35\]; −180° and +180° are the same point.
+    pub phi_idx: usize,
+    /// ψ grid index, in \[0, 35\].
+    pub psi_idx: usize,
+    /// Angular distance from the query, in degrees, with each axis wrapped
+    /// to the shorter way around.
+    pub distance: f32,
+    /// Raw table rotamers of this grid point, in table order.
+    pub rotamers: I,
+}
+
+impl<I> GridCell<I> {
+    /// The `(phi, psi)` angles of this point, in degrees.
+    #[inline]
+    pub const fn angles(&self) -> (f32, f32) {
+        (grid_angle(self.phi_idx), grid_angle(self.psi_idx))
+    }
+}
+
+/// Iterator over the grid points within an angular radius of a (φ, ψ)
+/// query, in φ-major index order.
+///
+/// Produced by [`Residue::neighborhood`].
+#[derive(Debug, Clone)]
+pub struct Neighborhood<R: Residue> {
+    phi: f32,
+    psi: f32,
+    radius: f32,
+    next: usize,
+    _residue: PhantomData<R>,
+}
+
+impl<R: Residue> Neighborhood<R> {
+    pub(crate) fn new(phi: f32, psi: f32, radius_deg: f32) -> Self {
+        Self {
+            phi: phi.clamp(GRID_MIN, -GRID_MIN),
+            psi: psi.clamp(GRID_MIN, -GRID_MIN),
+            radius: radius_deg,
+            next: 0,
+            _residue: PhantomData,
+        }
+    }
+}
+
+impl<R: Residue> Iterator for Neighborhood<R> {
+    type Item = GridCell<R::Iter>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next < GRID_PERIOD * GRID_PERIOD {
+            let (phi_idx, psi_idx) = (self.next / GRID_PERIOD, self.next % GRID_PERIOD);
+            self.next += 1;
+            let d_phi = wrap_deg(grid_angle(phi_idx) - self.phi);
+            let d_psi = wrap_deg(grid_angle(psi_idx) - self.psi);
+            let distance = sqrtf(d_phi * d_phi + d_psi * d_psi);
+            if distance <= self.radius {
+                return Some(GridCell {
+                    phi_idx,
+                    psi_idx,
+                    distance,
+                    rotamers: R::rotamers_at(phi_idx, psi_idx, 0.0, 0.0),
+                });
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(GRID_PERIOD * GRID_PERIOD - self.next))
+    }
+}
+
+impl<R: Residue> core::iter::FusedIterator for Neighborhood<R> {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -82,4 +311,83 @@ mod tests {
         assert_eq!(lo, 12);
         assert_relative_eq!(frac, 0.0, epsilon = 1e-6);
     }
+
+    #[test]
+    fn test_snapping_round_trips_grid_points() {
+        for idx in 0..GRID_COUNT {
+            let angle = grid_angle(idx);
+            assert_eq!(nearest_grid_index(angle), idx);
+            assert_eq!(snap_to_grid(angle + 4.9), angle);
+            assert_eq!(snap_to_grid(angle - 4.9), angle);
+        }
+        assert_eq!(nearest_grid_index(-175.0), 1);
+        assert_eq!(nearest_grid_index(-175.1), 0);
+        assert_eq!(nearest_grid_index(200.0), 36);
+    }
+
+    #[test]
+    #[should_panic(expected = "grid index out of range")]
+    fn test_grid_angle_out_of_range() {
+        grid_angle(GRID_COUNT);
+    }
+
+    #[test]
+    fn test_grid_neighbors_match_detail() {
+        use crate::residue::{Leu, Residue};
+
+        let detail = Leu::rotamers_detailed(-63.0, 147.5);
+        let points = grid_neighbors(-63.0, 147.5);
+        for (point, (angles, weight)) in points
+            .iter()
+            .zip(detail.corner_angles().into_iter().zip(detail.weights))
+        {
+            assert_eq!(point.angles(), angles);
+            assert_eq!(point.weight, weight);
+        }
+        let total: f32 = points.iter().map(|p| p.weight).sum();
+        assert_relative_eq!(total, 1.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_grid_weights_match_detail() {
+        use crate::residue::{Residue, Val};
+
+        for (phi, psi) in [(-63.0, 147.5), (180.0, -180.0), (0.0, 0.0), (57.5, -121.25)] {
+            let detail = Val::rotamers_detailed(phi, psi);
+            let (lo, weights) = grid_weights(phi, psi);
+            assert_eq!(lo, [detail.phi_idx, detail.psi_idx]);
+            assert_eq!(weights, detail.weights);
+        }
+    }
+
+    #[test]
+    fn test_neighborhood_counts_and_wrap() {
+        use crate::residue::{Leu, Residue};
+
+        // A node, its four edge neighbours, then the diagonals.
+        assert_eq!(Leu::neighborhood(-60.0, -40.0, 0.0).count(), 1);
+        assert_eq!(Leu::neighborhood(-60.0, -40.0, 10.0).count(), 5);
+        assert_eq!(Leu::neighborhood(-60.0, -40.0, 15.0).count(), 9);
+        assert_eq!(Leu::neighborhood(0.0, 0.0, 255.0).count(), 1296);
+        assert_eq!(Leu::neighborhood(0.0, 0.0, -1.0).count(), 0);
+        assert_eq!(Leu::neighborhood(f32::NAN, 0.0, 400.0).count(), 0);
+
+        // +180° is −180°; neighbours wrap to the far side.
+        let mut cells =
+            Leu::neighborhood(180.0, 175.0, 6.0).map(|cell| (cell.phi_idx, cell.psi_idx));
+        assert_eq!(cells.next(), Some((0, 0)));
+        assert_eq!(cells.next(), Some((0, 35)));
+        assert_eq!(cells.next(), None);
+    }
+
+    #[test]
+    fn test_neighborhood_rotamers_are_grid_values() {
+        use crate::residue::{Leu, Residue};
+
+        for cell in Leu::neighborhood(-63.0, -41.0, 20.0) {
+            assert!(cell.distance <= 20.0);
+            let (phi, psi) = cell.angles();
+            assert!(cell.rotamers.eq(Leu::rotamers(phi, psi)));
+        }
+    }
 }
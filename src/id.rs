@@ -0,0 +1,205 @@
+//! Packed rotamer identifiers.
+
+use crate::error::Error;
+use crate::identity::ResidueInfo;
+use crate::residue::Residue;
+
+/// Compact identity of one rotamer of one residue type: the residue and its
+/// bin key packed into a `u32`.
+///
+/// Ids are cheap to hash, compare, and sort, so packers and caches can key
+/// flat maps and arrays by rotamer identity across a whole structure. The
+/// layout is stable within a crate version:
+///
+/// | Bits   | Field                                              |
+/// |--------|----------------------------------------------------|
+/// | 31..24 | reserved, zero                                     |
+/// | 23..16 | index of the residue type in [`ALL_RESIDUES`]      |
+/// | 15..12 | bin of χ1                                          |
+/// | 11..8  | bin of χ2, or 0 past the residue's last χ          |
+/// | 7..4   | bin of χ3, or 0                                    |
+/// | 3..0   | bin of χ4, or 0                                    |
+///
+/// Ids order by residue type, then lexicographically by bin key.
+///
+/// [`ALL_RESIDUES`]: crate::ALL_RESIDUES
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Leu, Residue, RotamerId};
+///
+/// let best = Leu::rotamers(-65.0, -40.0)
+///     .max_by(|a, b| a.prob.total_cmp(&b.prob))
+///     .unwrap();
+/// let id = RotamerId::new::<Leu>(&best.r).unwrap();
+/// assert_eq!(id.residue().name, "LEU");
+/// assert_eq!(&id.bins()[..2], best.r);
+///
+/// let raw: u32 = id.into();
+/// assert_eq!(RotamerId::from_u32(raw), Ok(id));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RotamerId(u32);
+
+impl RotamerId {
+    /// Identifier of the rotamer of `R` with bin key `r`.
+    ///
+    /// Ids always hold a full bin key, so a reduced `Chi1` type of a
+    /// residue with several χ angles has none; key its rotamers by the
+    /// parent's full bin keys instead.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidBinKey`] if `r` does not have one bin per χ of `R`,
+    /// a bin is outside `1..=BINS_PER_CHI`, or `R` is such a reduced
+    /// type.
+    pub fn new<R: Residue>(r: &[u8]) -> Result<Self, Error> {
+        let index = crate::ALL_RESIDUES
+            .iter()
+            .position(|info| info.name == R::NAME)
+            .ok_or(Error::UnknownResidue)?;
+        if R::N_CHI != crate::ALL_RESIDUES[index].n_chi {
+            return Err(Error::InvalidBinKey);
+        }
+        Self::pack(index, r)
+    }
+
+    /// Decode an id produced by [`to_u32`](Self::to_u32).
+    ///
+    /// # Errors
+    ///
+    /// [`Error::UnknownResidue`] if the residue index is not an entry of
+    /// [`ALL_RESIDUES`](crate::ALL_RESIDUES); [`Error::InvalidBinKey`] if
+    /// the bins do not form a valid key of that residue type.
+    pub fn from_u32(raw: u32) -> Result<Self, Error> {
+        let index = (raw >> 16) as usize;
+        let info = crate::ALL_RESIDUES
+            .get(index)
+            .ok_or(Error::UnknownResidue)?;
+        let bins = nibbles(raw);
+        if bins[info.n_chi..].iter().any(|&bin| bin != 0) {
+            return Err(Error::InvalidBinKey);
+        }
+        let id = Self::pack(index, &bins[..info.n_chi])?;
+        debug_assert_eq!(id.0, raw);
+        Ok(id)
+    }
+
+    /// The packed `u32` representation.
+    #[inline]
+    pub const fn to_u32(self) -> u32 {
+        self.0
+    }
+
+    /// Index of the residue type in [`ALL_RESIDUES`](crate::ALL_RESIDUES).
+    #[inline]
+    pub const fn residue_index(self) -> usize {
+        (self.0 >> 16) as usize
+    }
+
+    /// Metadata of the residue type.
+    #[inline]
+    pub fn residue(self) -> &'static ResidueInfo {
+        &crate::ALL_RESIDUES[self.residue_index()]
+    }
+
+    /// Bin key, padded with zeros to four χ slots.
+    #[inline]
+    pub const fn bins(self) -> [u8; 4] {
+        nibbles(self.0)
+    }
+
+    /// Packs a validated residue index and bin key.
+    fn pack(index: usize, r: &[u8]) -> Result<Self, Error> {
+        let info = &crate::ALL_RESIDUES[index];
+        if r.len() != info.n_chi {
+            return Err(Error::InvalidBinKey);
+        }
+        let mut raw = (index as u32) << 16;
+        for (i, (&bin, &count)) in r.iter().zip(info.bins_per_chi).enumerate() {
+            if !(1..=count).contains(&bin) {
+                return Err(Error::InvalidBinKey);
+            }
+            raw |= u32::from(bin) << (12 - 4 * i);
+        }
+        Ok(Self(raw))
+    }
+}
+
+impl From<RotamerId> for u32 {
+    #[inline]
+    fn from(id: RotamerId) -> Self {
+        id.0
+    }
+}
+
+impl TryFrom<u32> for RotamerId {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(raw: u32) -> Result<Self, Error> {
+        Self::from_u32(raw)
+    }
+}
+
+/// The four bin nibbles of a packed id, χ1 first.
+const fn nibbles(raw: u32) -> [u8; 4] {
+    [
+        ((raw >> 12) & 0xf) as u8,
+        ((raw >> 8) & 0xf) as u8,
+        ((raw >> 4) & 0xf) as u8,
+        (raw & 0xf) as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::*;
+
+    #[test]
+    fn test_every_rotamer_round_trips() {
+        // Tables list rotamers by ascending key and residues in registry
+        // order, so distinct rotamers must give strictly increasing ids.
+        let mut prev = None;
+        macro_rules! check {
+            ($Res:ident, $n:literal, $r:literal) => {
+                for rot in <$Res as Residue>::rotamers(-65.0, -40.0) {
+                    let id = RotamerId::new::<$Res>(&rot.r).unwrap();
+                    assert_eq!(RotamerId::from_u32(id.to_u32()), Ok(id));
+                    assert_eq!(id.residue().name, <$Res as Residue>::NAME);
+                    assert_eq!(&id.bins()[..$n], rot.r);
+                    assert!(prev < Some(id), "{prev:?} !< {id:?}");
+                    prev = Some(id);
+                }
+            };
+        }
+        for_all_residues!(check);
+    }
+
+    #[test]
+    fn test_invalid_keys_rejected() {
+        assert_eq!(RotamerId::new::<Val>(&[]), Err(Error::InvalidBinKey));
+        assert_eq!(RotamerId::new::<Val>(&[4]), Err(Error::InvalidBinKey));
+        assert_eq!(RotamerId::new::<Asn>(&[1, 13]), Err(Error::InvalidBinKey));
+        assert!(RotamerId::new::<Asn>(&[1, 12]).is_ok());
+
+        let arg = RotamerId::new::<Arg>(&[1, 2, 3, 1]).unwrap().to_u32();
+        assert_eq!(RotamerId::from_u32(arg & !0xf), Err(Error::InvalidBinKey));
+        assert_eq!(
+            RotamerId::try_from(arg | 1 << 24),
+            Err(Error::UnknownResidue)
+        );
+        let val = RotamerId::new::<Val>(&[2]).unwrap().to_u32();
+        assert_eq!(RotamerId::from_u32(val | 1), Err(Error::InvalidBinKey));
+    }
+
+    #[test]
+    fn test_ordering_follows_residue_then_bins() {
+        let a = RotamerId::new::<Arg>(&[1, 2, 3, 1]).unwrap();
+        let b = RotamerId::new::<Arg>(&[2, 1, 1, 1]).unwrap();
+        let c = RotamerId::new::<Val>(&[1]).unwrap();
+        assert!(a < b && b < c);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::error::Error;
+use crate::residue::*;
+
+/// Resolve a residue name or alias to the library name of its type, as in
+/// [`Residue::NAME`].
+///
+/// Matches [`Residue::NAME`] and every [`Residue::ALIASES`] entry, ignoring
+/// ASCII case and surrounding whitespace (PDB columns are space-padded).
+///
+/// # Errors
+///
+/// [`Error::UnknownResidue`] if no residue type has that name or alias.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Error, canonical_name};
+///
+/// assert_eq!(canonical_name("hie"), Ok("HIS"));
+/// assert_eq!(canonical_name(" CYX"), Ok("CYD"));
+/// assert_eq!(canonical_name("GLY"), Err(Error::UnknownResidue));
+/// ```
+pub fn canonical_name(name: &str) -> Result<&'static str, Error> {
+    let name = name.trim();
+    macro_rules! find {
+        ($Res:ident, $n:literal, $r:literal) => {
+            let known =
+                core::iter::once(&<$Res as Residue>::NAME).chain(<$Res as Residue>::ALIASES);
+            if known
+                .into_iter()
+                .any(|alias| alias.eq_ignore_ascii_case(name))
+            {
+                return Ok(<$Res as Residue>::NAME);
+            }
+        };
+    }
+    for_all_residues!(find);
+    Err(Error::UnknownResidue)
+}
+
+/// Runtime description of one residue type, an entry of
+/// [`ALL_RESIDUES`](crate::ALL_RESIDUES).
+///
+/// Mirrors the [`Residue`] constants of the same names, so CLIs, FFI
+/// layers, and UIs can enumerate the supported types and their shapes
+/// without a type parameter.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{ALL_RESIDUES, ResidueInfo};
+///
+/// assert_eq!(ALL_RESIDUES.len(), 22);
+/// let largest = ALL_RESIDUES.iter().max_by_key(|info| info.n_rotamers).unwrap();
+/// assert_eq!(largest.name, "GLN");
+///
+/// let his = ResidueInfo::lookup("hie").unwrap();
+/// assert_eq!((his.one_letter, his.n_chi, his.n_rotamers), ('H', 2, 36));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ResidueInfo {
+    /// Three-letter library name, as in [`Residue::NAME`].
+    pub name: &'static str,
+    /// One-letter code, as in [`Residue::ONE_LETTER`].
+    pub one_letter: char,
+    /// Number of χ angles, as in [`Residue::N_CHI`].
+    pub n_chi: usize,
+    /// Rotamers per grid cell, as in [`Residue::N_ROTAMERS`].
+    pub n_rotamers: usize,
+    /// Bins of each χ, as in [`Residue::BINS_PER_CHI`].
+    pub bins_per_chi: &'static [u8],
+}
+
+impl ResidueInfo {
+    /// Metadata of residue type `R`.
+    #[inline]
+    pub const fn of<R: Residue>() -> Self {
+        Self {
+            name: R::NAME,
+            one_letter: R::ONE_LETTER,
+            n_chi: R::N_CHI,
+            n_rotamers: R::N_ROTAMERS,
+            bins_per_chi: R::BINS_PER_CHI,
+        }
+    }
+
+    /// Find the entry of [`ALL_RESIDUES`](crate::ALL_RESIDUES) for a
+    /// residue name or alias, matched as by [`canonical_name`].
+    ///
+    /// # Errors
+    ///
+    /// [`Error::UnknownResidue`] if no residue type has that name or alias.
+    pub fn lookup(name: &str) -> Result<&'static Self, Error> {
+        let name = canonical_name(name)?;
+        crate::ALL_RESIDUES
+            .iter()
+            .find(|info| info.name == name)
+            .ok_or(Error::UnknownResidue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_name_and_alias_resolves() {
+        macro_rules! check {
+            ($Res:ident, $n:literal, $r:literal) => {
+                assert_eq!(
+                    canonical_name(<$Res as Residue>::NAME),
+                    Ok(<$Res as Residue>::NAME)
+                );
+                for alias in <$Res as Residue>::ALIASES {
+                    assert_eq!(canonical_name(alias), Ok(<$Res as Residue>::NAME));
+                }
+            };
+        }
+        for_all_residues!(check);
+    }
+
+    #[test]
+    fn test_registry_matches_residue_constants() {
+        let mut entries = crate::ALL_RESIDUES.iter();
+        macro_rules! check {
+            ($Res:ident, $n:literal, $r:literal) => {
+                let info = entries.next().unwrap();
+                assert_eq!(*info, ResidueInfo::of::<$Res>());
+                assert_eq!((info.n_chi, info.n_rotamers), ($n, $r));
+                assert_eq!(ResidueInfo::lookup(info.name), Ok(info));
+            };
+        }
+        for_all_residues!(check);
+        assert!(entries.next().is_none());
+        assert_eq!(ResidueInfo::lookup("GLY"), Err(Error::UnknownResidue));
+    }
+
+    #[test]
+    fn test_unknown_names() {
+        for name in ["", "ALA", "GLY", "HISX", "H"] {
+            assert_eq!(canonical_name(name), Err(Error::UnknownResidue));
+        }
+    }
+}
@@ -1,5 +1,8 @@
-use crate::grid::{GRID_COUNT, angle_to_grid};
-use crate::math::atan2f;
+use crate::arith;
+use crate::error::{self, Error};
+use crate::grid::{GRID_COUNT, GRID_PERIOD, angle_to_grid, grid_angle};
+use crate::math::{atan2f, sqrtf, wrap_deg};
+use crate::query::Normalization;
 use crate::rotamer::Rotamer;
 
 /// One rotamer entry in the (φ, ψ) static lookup table.
@@ -21,10 +24,11 @@ pub struct GridEntry<const N: usize> {
 
 /// Eagerly constructed iterator over bilinearly interpolated rotamers.
 ///
-/// All `R` interpolated values (including probability re-normalization)
-/// are computed eagerly in the constructor. Calling [`Iterator::next`]
-/// simply returns the next pre-computed entry — no floating-point work,
-/// no branches.
+/// All interpolated values (including probability re-normalization) are
+/// computed eagerly in the constructor. At most `R` rotamers are yielded;
+/// fewer when the set was pruned through a [`Query`](crate::Query). Calling
+/// [`Iterator::next`] simply returns the next pre-computed entry — no
+/// floating-point work, no branches.
 ///
 /// This type is produced by [`Residue::rotamers`](crate::Residue::rotamers)
 /// and should not be constructed directly.
@@ -39,9 +43,182 @@ pub struct GridEntry<const N: usize> {
 /// let first = iter.next().unwrap();
 /// assert!(first.prob > 0.0);
 /// ```
+///
+/// A computed set can be traversed again without recomputing the
+/// interpolation, either by cloning it or by rewinding it with
+/// [`reset`](Self::reset).
+#[derive(Clone)]
 pub struct RotamerIter<const N: usize, const R: usize> {
     items: [Rotamer<N>; R],
     idx: usize,
+    len: usize,
+}
+
+impl<const N: usize, const R: usize> RotamerIter<N, R> {
+    /// Rewind to the first rotamer of the set, so rotamers already yielded
+    /// are yielded again. Rotamers dropped by pruning stay dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// let mut rots = Val::rotamers(-60.0, -40.0);
+    /// let best = rots.by_ref().map(|r| r.prob).fold(0.0, f32::max);
+    /// rots.reset();
+    /// assert_eq!(rots.len(), 3);
+    /// assert!(rots.any(|r| r.prob == best));
+    /// ```
+    #[inline]
+    pub fn reset(&mut self) {
+        self.idx = 0;
+    }
+
+    /// Drops every remaining rotamer whose probability is below `min_prob`,
+    /// preserving the order of the rest.
+    pub(crate) fn prune(&mut self, min_prob: f32) {
+        let mut kept = self.idx;
+        for k in self.idx..self.len {
+            if self.items[k].prob >= min_prob {
+                self.items[kept] = self.items[k];
+                kept += 1;
+            }
+        }
+        self.len = kept;
+    }
+
+    /// Condition the set on an allowed subset of its rotamers: keep the
+    /// remaining rotamers whose `mask` entry is `true`, in order, and rescale
+    /// their probabilities to sum to 1.0.
+    ///
+    /// `mask` is aligned with [`as_slice`](Self::as_slice), so it can be
+    /// built from the set itself, e.g. to admit only rotamers compatible
+    /// with a disulfide or a bound ligand. On error the set is left
+    /// unchanged.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::MaskLengthMismatch`] if `mask` does not have one entry per
+    /// remaining rotamer; [`Error::DegenerateDistribution`] if the allowed
+    /// rotamers have no probability mass.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Leu, Residue};
+    ///
+    /// // Suppose a contact rules out every g+ χ1 rotamer.
+    /// let mut rots = Leu::rotamers(-65.0, -40.0);
+    /// let mask: Vec<bool> = rots.iter().map(|rot| rot.r[0] != 1).collect();
+    /// rots.renormalize_masked(&mask).unwrap();
+    /// assert!(rots.iter().all(|rot| rot.r[0] != 1));
+    /// let total: f32 = rots.map(|rot| rot.prob).sum();
+    /// assert!((total - 1.0).abs() < 1e-5);
+    /// ```
+    pub fn renormalize_masked(&mut self, mask: &[bool]) -> Result<(), Error> {
+        let remaining = &mut self.items[self.idx..self.len];
+        if mask.len() != remaining.len() {
+            return Err(Error::MaskLengthMismatch {
+                expected: remaining.len(),
+                found: mask.len(),
+            });
+        }
+        let allowed: f32 = remaining
+            .iter()
+            .zip(mask)
+            .filter(|&(_, &keep)| keep)
+            .map(|(rot, _)| rot.prob)
+            .sum();
+        if !(allowed.is_finite() && allowed > 0.0) {
+            return Err(Error::DegenerateDistribution);
+        }
+
+        let inv = 1.0 / allowed;
+        let mut kept = 0;
+        for k in 0..remaining.len() {
+            if mask[k] {
+                remaining[kept] = remaining[k];
+                remaining[kept].prob *= inv;
+                kept += 1;
+            }
+        }
+        self.len = self.idx + kept;
+        Ok(())
+    }
+
+    /// Wraps the first `len` entries of a rotamer array.
+    pub(crate) fn from_parts(items: [Rotamer<N>; R], len: usize) -> Self {
+        debug_assert!(len <= R);
+        Self { items, idx: 0, len }
+    }
+
+    /// Wraps an already computed rotamer array.
+    pub(crate) fn from_items(items: [Rotamer<N>; R]) -> Self {
+        Self {
+            items,
+            idx: 0,
+            len: R,
+        }
+    }
+
+    /// The rotamers not yet yielded, in iteration order, borrowed from the
+    /// internal buffer.
+    #[inline]
+    pub fn as_slice(&self) -> &[Rotamer<N>] {
+        &self.items[self.idx..self.len]
+    }
+
+    /// Sum of the probabilities of the rotamers not yet yielded.
+    ///
+    /// `1.0` up to rounding for a fresh, re-normalized set; after pruning,
+    /// the mass the retained rotamers cover; under
+    /// [`Normalization::Raw`], the raw interpolated total.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Arg, Query, Residue};
+    ///
+    /// assert!((Arg::rotamers(-65.0, -40.0).prob_sum() - 1.0).abs() < 1e-5);
+    /// assert!(Query::new(-65.0, -40.0).prune(0.05).run::<Arg>().prob_sum() < 1.0);
+    /// ```
+    #[inline]
+    pub fn prob_sum(&self) -> f32 {
+        self.as_slice().iter().map(|rot| rot.prob).sum()
+    }
+
+    /// Iterate over the rotamers not yet yielded by reference, without
+    /// consuming them or copying each value out.
+    ///
+    /// For tight loops over large sets (Arg, Gln, Lys) on targets where
+    /// moving every `Rotamer` through the stack is costly. `&iter` can be
+    /// used in a `for` loop directly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Arg, Residue};
+    ///
+    /// let rots = Arg::rotamers(-65.0, -40.0);
+    /// let best = rots.iter().max_by(|a, b| a.prob.total_cmp(&b.prob)).unwrap();
+    /// let mut mass = 0.0;
+    /// for rot in &rots {
+    ///     mass += rot.prob;
+    /// }
+    /// assert!(best.prob > 0.0 && (mass - 1.0_f32).abs() < 1e-5);
+    /// assert_eq!(rots.len(), 75);
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> core::slice::Iter<'_, Rotamer<N>> {
+        self.as_slice().iter()
+    }
+
+    /// Sorts the remaining rotamers by descending probability, breaking ties
+    /// by ascending bin indices so the order is deterministic.
+    pub(crate) fn sort_by_prob(&mut self) {
+        self.items[self.idx..self.len]
+            .sort_unstable_by(|a, b| b.prob.total_cmp(&a.prob).then_with(|| a.r.cmp(&b.r)));
+    }
 }
 
 impl<const N: usize, const R: usize> Iterator for RotamerIter<N, R> {
@@ -49,7 +226,7 @@ impl<const N: usize, const R: usize> Iterator for RotamerIter<N, R> {
 
     #[inline]
     fn next(&mut self) -> Option<Rotamer<N>> {
-        if self.idx < R {
+        if self.idx < self.len {
             let item = self.items[self.idx];
             self.idx += 1;
             Some(item)
@@ -60,7 +237,7 @@ impl<const N: usize, const R: usize> Iterator for RotamerIter<N, R> {
 
     #[inline]
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let remaining = R - self.idx;
+        let remaining = self.len - self.idx;
         (remaining, Some(remaining))
     }
 }
@@ -68,132 +245,940 @@ impl<const N: usize, const R: usize> Iterator for RotamerIter<N, R> {
 impl<const N: usize, const R: usize> ExactSizeIterator for RotamerIter<N, R> {
     #[inline]
     fn len(&self) -> usize {
-        R - self.idx
+        self.len - self.idx
     }
 }
 
 impl<const N: usize, const R: usize> core::iter::FusedIterator for RotamerIter<N, R> {}
 
+impl<'a, const N: usize, const R: usize> IntoIterator for &'a RotamerIter<N, R> {
+    type Item = &'a Rotamer<N>;
+    type IntoIter = core::slice::Iter<'a, Rotamer<N>>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
 /// Radians-to-degrees conversion factor.
 const RAD_TO_DEG: f32 = 180.0 / core::f32::consts::PI;
 
-/// Recovers a χ mean angle in degrees from a bilinearly weighted sum of
+/// Recovers the χ mean angles in degrees from bilinearly weighted sums of
 /// `(sin, cos)` pairs.
 ///
 /// Accumulates the weighted sin and cos components over the four surrounding
-/// grid cells, then applies `atan2f` once to obtain the circular mean.
-/// Returns a value in (−180°, 180°].
+/// grid cells, then applies `atan2f` once per χ to obtain the circular mean.
+/// Returns values in (−180°, 180°].
 #[inline]
-fn chi_mean_from_sc(weights: [f32; 4], sins: [f32; 4], coss: [f32; 4]) -> f32 {
-    let mut sin_sum = 0.0_f32;
-    let mut cos_sum = 0.0_f32;
-    for i in 0..4 {
-        sin_sum += weights[i] * sins[i];
-        cos_sum += weights[i] * coss[i];
-    }
-    atan2f(sin_sum, cos_sum) * RAD_TO_DEG
+fn chi_mean_from_sc<const N: usize>(
+    weights: [f32; 4],
+    sins: [&[f32; N]; 4],
+    coss: [&[f32; N]; 4],
+) -> [f32; N] {
+    let sin_sum = arith::dot4_lanes(weights, sins);
+    let cos_sum = arith::dot4_lanes(weights, coss);
+    core::array::from_fn(|i| atan2f(sin_sum[i], cos_sum[i]) * RAD_TO_DEG)
 }
 
 /// Computes a bilinear combination of four scalar values.
 #[inline]
 fn bilinear(weights: [f32; 4], values: [f32; 4]) -> f32 {
-    weights[0] * values[0]
-        + weights[1] * values[1]
-        + weights[2] * values[2]
-        + weights[3] * values[3]
+    arith::dot4(weights, values)
 }
 
-/// Build a [`RotamerIter`] by bilinearly interpolating four grid-point cells.
+/// Static (φ, ψ) lookup table for a residue with `N` χ angles and `R` rotamers.
 ///
-/// `keys` contains the rotamer bin indices shared across all grid cells for
-/// this residue.
-pub fn build_iter<const N: usize, const R: usize>(
-    table: &[[[GridEntry<N>; R]; GRID_COUNT]; GRID_COUNT],
+/// [`cell`](Self::cell) returns the `R` entries at a pair of grid indices,
+/// which map to angles via `GRID_MIN + idx × GRID_STEP`. Only the
+/// [`GRID_PERIOD`] distinct indices per axis are stored; grid index 36
+/// (+180°) is read from index 0. Rotamer `k` in every cell corresponds to the
+/// bin indices `keys[k]` passed alongside the table.
+///
+/// Cells are reached through a 36×36 index, so a table stores each distinct
+/// cell once: `build.rs` merges cells that are identical in every value
+/// whenever that shrinks the residue's data, and all other tables share one
+/// index of the dense grid.
+#[derive(Clone, Copy)]
+pub struct Table<'a, const N: usize, const R: usize> {
+    index: &'a [[u16; GRID_PERIOD]; GRID_PERIOD],
+    cells: &'a [[GridEntry<N>; R]],
+    /// χ means in centidegrees over the dense grid, φ-major; empty when the
+    /// table carries none.
+    #[cfg(feature = "fast-chi")]
+    chi: &'a [[[i16; N]; R]],
+}
+
+/// Index of a dense table, cell (`phi_idx`, `psi_idx`) stored at
+/// `phi_idx × GRID_PERIOD + psi_idx`.
+pub(crate) static DENSE_INDEX: [[u16; GRID_PERIOD]; GRID_PERIOD] = {
+    let mut index = [[0; GRID_PERIOD]; GRID_PERIOD];
+    let mut k = 0;
+    while k < GRID_PERIOD * GRID_PERIOD {
+        index[k / GRID_PERIOD][k % GRID_PERIOD] = k as u16;
+        k += 1;
+    }
+    index
+};
+
+impl<'a, const N: usize, const R: usize> Table<'a, N, R> {
+    /// Table whose cell (`phi_idx`, `psi_idx`) is `cells[index[phi_idx][psi_idx]]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index entry is out of bounds for `cells`; for the
+    /// embedded tables this is checked at compile time.
+    #[cfg_attr(feature = "delta-tables", allow(dead_code))]
+    pub(crate) const fn new(
+        index: &'a [[u16; GRID_PERIOD]; GRID_PERIOD],
+        cells: &'a [[GridEntry<N>; R]],
+    ) -> Self {
+        let mut k = 0;
+        while k < GRID_PERIOD * GRID_PERIOD {
+            assert!(
+                (index[k / GRID_PERIOD][k % GRID_PERIOD] as usize) < cells.len(),
+                "table index out of bounds"
+            );
+            k += 1;
+        }
+        Self {
+            index,
+            cells,
+            #[cfg(feature = "fast-chi")]
+            chi: &[],
+        }
+    }
+
+    /// Table over the `GRID_PERIOD²` cells of a dense grid, φ-major.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cells` does not hold exactly `GRID_PERIOD²` cells.
+    pub(crate) const fn dense(cells: &'a [[GridEntry<N>; R]]) -> Self {
+        assert!(
+            cells.len() == GRID_PERIOD * GRID_PERIOD,
+            "dense table needs one cell per grid point"
+        );
+        Self {
+            index: &DENSE_INDEX,
+            cells,
+            #[cfg(feature = "fast-chi")]
+            chi: &[],
+        }
+    }
+
+    /// The same table, carrying the χ means of every cell in centidegrees
+    /// for [`FastBilinear`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chi` does not hold exactly `GRID_PERIOD²` cells.
+    #[cfg(feature = "fast-chi")]
+    pub(crate) const fn with_chi(self, chi: &'a [[[i16; N]; R]]) -> Self {
+        assert!(
+            chi.len() == GRID_PERIOD * GRID_PERIOD,
+            "χ table needs one cell per grid point"
+        );
+        Self { chi, ..self }
+    }
+
+    /// χ means of grid cell (`phi_idx`, `psi_idx`) in centidegrees, if the
+    /// table carries them, wrapping indices like [`cell`](Self::cell).
+    #[cfg(feature = "fast-chi")]
+    #[inline(always)]
+    fn chi_centidegrees(&self, phi_idx: usize, psi_idx: usize) -> Option<&'a [[i16; N]; R]> {
+        self.chi
+            .get(phi_idx % GRID_PERIOD * GRID_PERIOD + psi_idx % GRID_PERIOD)
+    }
+
+    /// Entries of grid cell (`phi_idx`, `psi_idx`), wrapping indices past
+    /// the stored period, so index 36 reads index 0.
+    #[inline(always)]
+    pub fn cell(&self, phi_idx: usize, psi_idx: usize) -> &'a [GridEntry<N>; R] {
+        let k = self.index[phi_idx % GRID_PERIOD][psi_idx % GRID_PERIOD] as usize;
+        // SAFETY: `new` and `dense` check every index entry against
+        // `cells.len()`, and neither field changes afterwards.
+        unsafe { self.cells.get_unchecked(k) }
+    }
+}
+
+/// Interpolation scheme used to evaluate a rotamer [`Table`] at an arbitrary
+/// (φ, ψ) backbone conformation.
+///
+/// Implementations return all `R` rotamers in table order. Probabilities need
+/// not sum to 1.0 — [`Residue::rotamers_with`](crate::Residue::rotamers_with)
+/// re-normalizes them afterwards. The trait is open, so experimental schemes
+/// can be plugged in without forking the crate.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Bicubic, Nearest, Residue, Val};
+///
+/// let nearest: Vec<_> = Val::rotamers_with::<Nearest>(-61.0, -41.0).collect();
+/// let bicubic: Vec<_> = Val::rotamers_with::<Bicubic>(-61.0, -41.0).collect();
+/// assert_eq!(nearest.len(), bicubic.len());
+/// ```
+pub trait Interpolator {
+    /// Evaluate every rotamer of `table` at (`phi`, `psi`), in degrees.
+    ///
+    /// `keys[k]` holds the bin indices of rotamer `k`.
+    fn interpolate<const N: usize, const R: usize>(
+        table: &Table<N, R>,
+        keys: &[[u8; N]; R],
+        phi: f32,
+        psi: f32,
+    ) -> [Rotamer<N>; R];
+}
+
+/// Bilinear interpolation across the four surrounding grid cells.
+///
+/// χ means use a circular weighted mean of the precomputed `(sin χ, cos χ)`
+/// pairs. This is the scheme used by [`Residue::rotamers`](crate::Residue::rotamers).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Bilinear;
+
+/// Nearest-grid-point lookup without interpolation.
+///
+/// Returns the raw values of the closest grid cell; ties round towards the
+/// upper cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Nearest;
+
+/// Bicubic (Catmull–Rom) interpolation over the surrounding 4×4 grid cells.
+///
+/// Neighbors beyond ±180° are fetched with periodic wrap. Overshoot is
+/// contained by clamping probabilities at zero and standard deviations to
+/// the range spanned by the 16 contributing cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Bicubic;
+
+/// Bilinear interpolation with χ means interpolated linearly in degrees:
+/// an approximate, trig-free variant of [`Bilinear`] for hot loops that do
+/// not need its χ fidelity.
+///
+/// Probabilities and σ match [`Bilinear`] exactly. Each χ mean is the
+/// weighted mean of the four corner means after unwrapping them by ±360°
+/// onto the first corner, with plain branches instead of `sin`, `cos`, and
+/// `atan2`; the embedded tables carry the corner means in centidegrees, so
+/// none are recovered from their `(sin, cos)` pairs either.
+///
+/// The linear mean departs from the circular one by a term cubic in the
+/// spread of the corner means. Over the whole library the difference stays
+/// within 0.003° where the four corners agree within 5° (the accuracy of
+/// [`Bilinear`] itself), 0.01° within 10°, 0.5° within 45°, and 5° within
+/// 90°. Corners further apart, found mostly in rare rotamers and at
+/// transitions between χ wells, can land on opposite sides of the circle
+/// and differ by up to 180°; use [`Bilinear`] or
+/// [`BilinearDetail::uncertainty`] where those matter.
+///
+/// Tables without centidegree means (such as
+/// [`MappedTables`](crate::MappedTables)) recover each corner mean with
+/// `atan2` and give the same results more slowly. Requires the `fast-chi`
+/// feature, which embeds 2 bytes per χ mean (about 4 MB for the full
+/// library).
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Arg, Bilinear, FastBilinear, Residue};
+///
+/// let fast = Arg::rotamers_with::<FastBilinear>(-63.0, 147.5);
+/// let exact = Arg::rotamers_with::<Bilinear>(-63.0, 147.5);
+/// for (a, b) in fast.zip(exact) {
+///     assert_eq!(a.prob, b.prob);
+///     if b.prob > 0.05 {
+///         for (x, y) in a.chi_mean.iter().zip(&b.chi_mean) {
+///             assert!((x - y).abs() < 0.05, "{x} vs {y}");
+///         }
+///     }
+/// }
+/// ```
+#[cfg(feature = "fast-chi")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FastBilinear;
+
+impl Interpolator for Bilinear {
+    fn interpolate<const N: usize, const R: usize>(
+        table: &Table<N, R>,
+        keys: &[[u8; N]; R],
+        phi: f32,
+        psi: f32,
+    ) -> [Rotamer<N>; R] {
+        let (lo_phi, frac_phi) = angle_to_grid(phi);
+        let (lo_psi, frac_psi) = angle_to_grid(psi);
+
+        // Four corner cell references (contiguous entry slices).
+        let corners = [
+            table.cell(lo_phi, lo_psi),
+            table.cell(lo_phi + 1, lo_psi),
+            table.cell(lo_phi, lo_psi + 1),
+            table.cell(lo_phi + 1, lo_psi + 1),
+        ];
+        bilinear_corners(corners, keys, bilinear_weights(frac_phi, frac_psi))
+    }
+}
+
+/// Bilinear combination of every rotamer over four corner cells, ordered
+/// `w00, w10, w01, w11` like the weights `w`.
+#[inline]
+fn bilinear_corners<const N: usize, const R: usize>(
+    corners: [&[GridEntry<N>; R]; 4],
+    keys: &[[u8; N]; R],
+    w: [f32; 4],
+) -> [Rotamer<N>; R] {
+    core::array::from_fn(|k| bilinear_rotamer(corners, keys, w, k))
+}
+
+/// Bilinear combination of rotamer `k` over four corner cells.
+#[inline(always)]
+fn bilinear_rotamer<const N: usize, const R: usize>(
+    corners: [&[GridEntry<N>; R]; 4],
+    keys: &[[u8; N]; R],
+    w: [f32; 4],
+    k: usize,
+) -> Rotamer<N> {
+    let prob = bilinear(
+        w,
+        [
+            corners[0][k].prob,
+            corners[1][k].prob,
+            corners[2][k].prob,
+            corners[3][k].prob,
+        ],
+    );
+
+    let chi_mean = chi_mean_from_sc(
+        w,
+        [
+            &corners[0][k].chi_sin,
+            &corners[1][k].chi_sin,
+            &corners[2][k].chi_sin,
+            &corners[3][k].chi_sin,
+        ],
+        [
+            &corners[0][k].chi_cos,
+            &corners[1][k].chi_cos,
+            &corners[2][k].chi_cos,
+            &corners[3][k].chi_cos,
+        ],
+    );
+
+    let chi_sigma = arith::dot4_lanes(
+        w,
+        [
+            &corners[0][k].chi_sigma,
+            &corners[1][k].chi_sigma,
+            &corners[2][k].chi_sigma,
+            &corners[3][k].chi_sigma,
+        ],
+    );
+
+    Rotamer {
+        r: keys[k],
+        prob,
+        chi_mean,
+        chi_sigma,
+    }
+}
+
+/// Evaluates `table` bilinearly at (`phi`, `psi`) one rotamer at a time,
+/// backing [`Residue::visit_rotamers`](crate::Residue::visit_rotamers).
+///
+/// A first pass sums the probabilities in the order of [`build_iter`], so
+/// every visited rotamer is bit-identical to the one it would yield.
+pub(crate) fn visit_bilinear<const N: usize, const R: usize>(
+    table: &Table<N, R>,
     keys: &[[u8; N]; R],
     phi: f32,
     psi: f32,
-) -> RotamerIter<N, R> {
+    mut f: impl FnMut(&Rotamer<N>),
+) {
     let (lo_phi, frac_phi) = angle_to_grid(phi);
     let (lo_psi, frac_psi) = angle_to_grid(psi);
-
-    // Bilinear weights for the four surrounding grid cells.
-    let w = [
-        (1.0 - frac_phi) * (1.0 - frac_psi), // w00
-        frac_phi * (1.0 - frac_psi),         // w10
-        (1.0 - frac_phi) * frac_psi,         // w01
-        frac_phi * frac_psi,                 // w11
-    ];
-
-    // Four corner cell references (contiguous entry slices).
     let corners = [
-        &table[lo_phi][lo_psi],
-        &table[lo_phi + 1][lo_psi],
-        &table[lo_phi][lo_psi + 1],
-        &table[lo_phi + 1][lo_psi + 1],
+        table.cell(lo_phi, lo_psi),
+        table.cell(lo_phi + 1, lo_psi),
+        table.cell(lo_phi, lo_psi + 1),
+        table.cell(lo_phi + 1, lo_psi + 1),
     ];
+    let w = bilinear_weights(frac_phi, frac_psi);
+    let prob_sum = arith::sum_by(R, |k| bilinear(w, corners.map(|c| c[k].prob)));
+    debug_assert!(
+        prob_sum.is_finite() && prob_sum > 0.0,
+        "prob_sum must be finite and positive, got {prob_sum}"
+    );
+    let inv = 1.0 / prob_sum;
+    for k in 0..R {
+        let mut rot = bilinear_rotamer(corners, keys, w, k);
+        rot.prob *= inv;
+        f(&rot);
+    }
+}
 
-    // Eagerly compute all R interpolated rotamers.
-    let mut items: [Rotamer<N>; R] = core::array::from_fn(|k| {
-        let prob = bilinear(
-            w,
-            [
-                corners[0][k].prob,
-                corners[1][k].prob,
-                corners[2][k].prob,
-                corners[3][k].prob,
-            ],
-        );
+#[cfg(feature = "fast-chi")]
+impl Interpolator for FastBilinear {
+    fn interpolate<const N: usize, const R: usize>(
+        table: &Table<N, R>,
+        keys: &[[u8; N]; R],
+        phi: f32,
+        psi: f32,
+    ) -> [Rotamer<N>; R] {
+        let (lo_phi, frac_phi) = angle_to_grid(phi);
+        let (lo_psi, frac_psi) = angle_to_grid(psi);
+        let w = bilinear_weights(frac_phi, frac_psi);
+        let at = [
+            (lo_phi, lo_psi),
+            (lo_phi + 1, lo_psi),
+            (lo_phi, lo_psi + 1),
+            (lo_phi + 1, lo_psi + 1),
+        ];
+        let corners = at.map(|(i, j)| table.cell(i, j));
+        let rotamer = |k: usize, chi_mean: [f32; N]| Rotamer {
+            r: keys[k],
+            prob: bilinear(w, corners.map(|c| c[k].prob)),
+            chi_mean,
+            chi_sigma: arith::dot4_lanes(w, corners.map(|c| &c[k].chi_sigma)),
+        };
 
-        let chi_mean: [f32; N] = core::array::from_fn(|i| {
-            chi_mean_from_sc(
-                w,
-                [
-                    corners[0][k].chi_sin[i],
-                    corners[1][k].chi_sin[i],
-                    corners[2][k].chi_sin[i],
-                    corners[3][k].chi_sin[i],
-                ],
-                [
-                    corners[0][k].chi_cos[i],
-                    corners[1][k].chi_cos[i],
-                    corners[2][k].chi_cos[i],
-                    corners[3][k].chi_cos[i],
-                ],
-            )
-        });
-
-        let chi_sigma: [f32; N] = core::array::from_fn(|i| {
-            bilinear(
-                w,
-                [
-                    corners[0][k].chi_sigma[i],
-                    corners[1][k].chi_sigma[i],
-                    corners[2][k].chi_sigma[i],
-                    corners[3][k].chi_sigma[i],
-                ],
+        let [Some(c0), Some(c1), Some(c2), Some(c3)] =
+            at.map(|(i, j)| table.chi_centidegrees(i, j))
+        else {
+            // No centidegree means: recover each corner's from its pair.
+            // Library means carry one decimal, so rounding restores them.
+            let cells = corners.map(|c| raw_cell(c, keys));
+            return core::array::from_fn(|k| {
+                let chi = |c: usize, i: usize| {
+                    let x = cells[c][k].chi_mean[i] * 100.0;
+                    (x + 0.5_f32.copysign(x)) as i16
+                };
+                rotamer(
+                    k,
+                    core::array::from_fn(|i| {
+                        linear_chi_mean(w, [chi(0, i), chi(1, i), chi(2, i), chi(3, i)])
+                    }),
+                )
+            });
+        };
+        core::array::from_fn(|k| {
+            let (a, b, c, d) = (&c0[k], &c1[k], &c2[k], &c3[k]);
+            rotamer(
+                k,
+                core::array::from_fn(|i| linear_chi_mean(w, [a[i], b[i], c[i], d[i]])),
             )
-        });
+        })
+    }
+}
+
+/// Weighted mean of four angles in centidegrees, each unwrapped by ±360°
+/// onto the first before averaging. Returns degrees in (−180°, 180°].
+#[cfg(feature = "fast-chi")]
+#[inline(always)]
+fn linear_chi_mean(weights: [f32; 4], chis: [i16; 4]) -> f32 {
+    let base = i32::from(chis[0]);
+    let unwrap = |chi: i16| {
+        let d = i32::from(chi) - base;
+        (d - 36_000 * (i32::from(d > 18_000) - i32::from(d < -18_000))) as f32
+    };
+    let offset =
+        weights[1] * unwrap(chis[1]) + weights[2] * unwrap(chis[2]) + weights[3] * unwrap(chis[3]);
+    let mean = (base as f32 + offset) * 0.01;
+    mean - 360.0 * f32::from(i8::from(mean > 180.0) - i8::from(mean <= -180.0))
+}
+
+impl Interpolator for Nearest {
+    fn interpolate<const N: usize, const R: usize>(
+        table: &Table<N, R>,
+        keys: &[[u8; N]; R],
+        phi: f32,
+        psi: f32,
+    ) -> [Rotamer<N>; R] {
+        let (lo_phi, frac_phi) = angle_to_grid(phi);
+        let (lo_psi, frac_psi) = angle_to_grid(psi);
+        let cell = table.cell(
+            lo_phi + (frac_phi >= 0.5) as usize,
+            lo_psi + (frac_psi >= 0.5) as usize,
+        );
+        raw_cell(cell, keys)
+    }
+}
+
+/// Bilinear weights `[w00, w10, w01, w11]` for the four surrounding grid
+/// cells at fractional offsets (`frac_phi`, `frac_psi`).
+#[inline]
+pub(crate) fn bilinear_weights(frac_phi: f32, frac_psi: f32) -> [f32; 4] {
+    [
+        (1.0 - frac_phi) * (1.0 - frac_psi),
+        frac_phi * (1.0 - frac_psi),
+        (1.0 - frac_phi) * frac_psi,
+        frac_phi * frac_psi,
+    ]
+}
 
+/// The stored values of one grid cell, with χ means recovered from their
+/// `(sin, cos)` pairs.
+#[inline]
+fn raw_cell<const N: usize, const R: usize>(
+    cell: &[GridEntry<N>; R],
+    keys: &[[u8; N]; R],
+) -> [Rotamer<N>; R] {
+    core::array::from_fn(|k| {
+        let entry = &cell[k];
         Rotamer {
             r: keys[k],
-            prob,
-            chi_mean,
-            chi_sigma,
+            prob: entry.prob,
+            chi_mean: core::array::from_fn(|i| {
+                atan2f(entry.chi_sin[i], entry.chi_cos[i]) * RAD_TO_DEG
+            }),
+            chi_sigma: entry.chi_sigma,
         }
-    });
+    })
+}
 
-    // Re-normalize probabilities so that Σ prob = 1.0.
-    let prob_sum: f32 = items.iter().map(|rot| rot.prob).sum();
-    debug_assert!(
-        prob_sum.is_finite() && prob_sum > 0.0,
-        "prob_sum must be finite and positive, got {prob_sum}"
+/// Intermediate values of one bilinear lookup, returned by
+/// [`Residue::rotamers_detailed`](crate::Residue::rotamers_detailed) for
+/// validating results against a reference implementation.
+///
+/// The four corners are the grid cells at (φ, ψ) indices
+/// `(phi_idx, psi_idx)`, `(phi_idx + 1, psi_idx)`, `(phi_idx, psi_idx + 1)`,
+/// and `(phi_idx + 1, psi_idx + 1)`, in that order, matching `weights`.
+pub struct BilinearDetail<T> {
+    /// Lower φ grid index of the cell containing the query, in \[0, 35\].
+    pub phi_idx: usize,
+    /// Lower ψ grid index of the cell containing the query, in \[0, 35\].
+    pub psi_idx: usize,
+    /// Bilinear weight of each corner; the four sum to 1.0.
+    pub weights: [f32; 4],
+    /// Raw table values at each corner, in table order: probabilities as
+    /// stored (not re-normalized), χ means recovered from the stored
+    /// `(sin χ, cos χ)` pairs.
+    pub corners: [T; 4],
+    /// The interpolated, re-normalized rotamers, identical to
+    /// [`Residue::rotamers`](crate::Residue::rotamers).
+    pub result: T,
+}
+
+/// Spread of one rotamer's corner values around a bilinear result,
+/// treating the library as noisy rather than exact.
+///
+/// Produced by [`BilinearDetail::uncertainty`]. Both figures are standard
+/// deviations across the four grid corners, each corner weighted by its
+/// bilinear weight, so they vanish on a grid node and grow where the
+/// Ramachandran map is steep.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uncertainty<const N: usize> {
+    /// Bin indices of the rotamer.
+    pub r: [u8; N],
+    /// Weighted standard deviation of the raw corner probabilities about
+    /// their bilinear mean.
+    pub prob: f32,
+    /// Weighted root-mean-square deviation of each corner χ mean from the
+    /// interpolated χ mean, in degrees, with differences wrapped into
+    /// \[−180°, 180°).
+    pub chi: [f32; N],
+}
+
+impl<T> BilinearDetail<T> {
+    /// The `(phi, psi)` angles of the four corners, in degrees.
+    pub fn corner_angles(&self) -> [(f32, f32); 4] {
+        let (phi, psi) = (self.phi_idx, self.psi_idx);
+        [
+            (grid_angle(phi), grid_angle(psi)),
+            (grid_angle(phi + 1), grid_angle(psi)),
+            (grid_angle(phi), grid_angle(psi + 1)),
+            (grid_angle(phi + 1), grid_angle(psi + 1)),
+        ]
+    }
+}
+
+impl<const N: usize, const R: usize> BilinearDetail<RotamerIter<N, R>> {
+    /// Spread `max − min` of each rotamer's raw probability over the four
+    /// corners, in table order like `corners`.
+    ///
+    /// Large values mark steep regions of the Ramachandran map, where the
+    /// interpolated probability is sensitive to small backbone changes.
+    /// Corner sets are read in full, however far they have been iterated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Leu, Residue};
+    ///
+    /// let detail = Leu::rotamers_detailed(-65.0, -40.0);
+    /// let spread = detail.corner_spread();
+    /// let first = detail.corners.each_ref().map(|c| c.as_slice()[0].prob);
+    /// assert!(spread[0] >= (first[0] - first[1]).abs());
+    /// assert!(detail.max_corner_spread() >= spread[0]);
+    /// ```
+    pub fn corner_spread(&self) -> [f32; R] {
+        core::array::from_fn(|k| {
+            let probs = self.corners.each_ref().map(|corner| corner.items[k].prob);
+            let lo = probs.iter().copied().fold(f32::INFINITY, f32::min);
+            let hi = probs.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+            hi - lo
+        })
+    }
+
+    /// Weighted standard deviation of each rotamer's probability and χ
+    /// means over the four corners, in table order like `corners` and
+    /// `result`, for downstream models that treat library values as
+    /// uncertain.
+    ///
+    /// Where [`corner_spread`](Self::corner_spread) reports the full range,
+    /// this weights each corner by its contribution to the interpolated
+    /// value, so a query close to one node inherits little of the far
+    /// corners' spread. Probability deviations are on the raw corner
+    /// values, before the result is re-normalized. Corner sets and the
+    /// result are read in full, however far they have been iterated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Leu, Residue};
+    ///
+    /// let detail = Leu::rotamers_detailed(-65.0, -40.0);
+    /// let noise = detail.uncertainty();
+    /// for (rot, u) in detail.result.as_slice().iter().zip(&noise) {
+    ///     assert_eq!(rot.r, u.r);
+    ///     assert!(u.prob >= 0.0 && u.chi.iter().all(|&s| (0.0..=180.0).contains(&s)));
+    /// }
+    ///
+    /// // On a grid node only one corner carries weight.
+    /// let node = Leu::rotamers_detailed(-60.0, -40.0).uncertainty();
+    /// assert!(node.iter().all(|u| u.prob == 0.0));
+    /// ```
+    pub fn uncertainty(&self) -> [Uncertainty<N>; R] {
+        let w = self.weights;
+        core::array::from_fn(|k| {
+            let corners = self.corners.each_ref().map(|corner| &corner.items[k]);
+            let center = &self.result.items[k];
+            let mean = bilinear(w, corners.map(|c| c.prob));
+            let prob = bilinear(w, corners.map(|c| (c.prob - mean) * (c.prob - mean)));
+            Uncertainty {
+                r: center.r,
+                prob: sqrtf(prob.max(0.0)),
+                chi: core::array::from_fn(|i| {
+                    let var = bilinear(
+                        w,
+                        corners.map(|c| {
+                            let d = wrap_deg(c.chi_mean[i] - center.chi_mean[i]);
+                            d * d
+                        }),
+                    );
+                    sqrtf(var)
+                }),
+            }
+        })
+    }
+
+    /// Largest [`corner_spread`](Self::corner_spread) over all rotamers,
+    /// a single sensitivity figure for the query.
+    pub fn max_corner_spread(&self) -> f32 {
+        self.corner_spread().into_iter().fold(0.0, f32::max)
+    }
+}
+
+/// Evaluates `table` bilinearly at (`phi`, `psi`), keeping the grid
+/// indices, weights, and corner values.
+pub(crate) fn bilinear_detail<const N: usize, const R: usize>(
+    table: &Table<N, R>,
+    keys: &[[u8; N]; R],
+    phi: f32,
+    psi: f32,
+) -> BilinearDetail<RotamerIter<N, R>> {
+    let (phi_idx, frac_phi) = angle_to_grid(phi);
+    let (psi_idx, frac_psi) = angle_to_grid(psi);
+    let corner = |dphi: usize, dpsi: usize| {
+        RotamerIter::from_items(raw_cell(table.cell(phi_idx + dphi, psi_idx + dpsi), keys))
+    };
+    BilinearDetail {
+        phi_idx,
+        psi_idx,
+        weights: bilinear_weights(frac_phi, frac_psi),
+        corners: [corner(0, 0), corner(1, 0), corner(0, 1), corner(1, 1)],
+        result: build_iter::<Bilinear, N, R>(table, keys, phi, psi),
+    }
+}
+
+/// Largest probability difference between bilinear and bicubic evaluation
+/// of `table` at (`phi`, `psi`), backing
+/// [`Residue::interpolation_error`](crate::Residue::interpolation_error).
+pub(crate) fn interpolation_error<const N: usize, const R: usize>(
+    table: &Table<N, R>,
+    keys: &[[u8; N]; R],
+    phi: f32,
+    psi: f32,
+) -> f32 {
+    let linear = build_iter::<Bilinear, N, R>(table, keys, phi, psi);
+    let cubic = build_iter::<Bicubic, N, R>(table, keys, phi, psi);
+    linear
+        .items
+        .iter()
+        .zip(&cubic.items)
+        .map(|(a, b)| (a.prob - b.prob).abs())
+        .fold(0.0, f32::max)
+}
+
+/// Catmull–Rom weights for the four samples at offsets −1, 0, +1, +2 given a
+/// fractional position `t ∈ [0, 1]` between samples 0 and +1.
+#[inline]
+fn catmull_rom(t: f32) -> [f32; 4] {
+    let t2 = t * t;
+    let t3 = t2 * t;
+    [
+        0.5 * (-t3 + 2.0 * t2 - t),
+        0.5 * (3.0 * t3 - 5.0 * t2 + 2.0),
+        0.5 * (-3.0 * t3 + 4.0 * t2 + t),
+        0.5 * (t3 - t2),
+    ]
+}
+
+/// Maps a possibly out-of-range grid index onto `[0, GRID_PERIOD)` using
+/// the 360° periodicity of the backbone dihedrals.
+#[inline]
+fn wrap_index(idx: isize) -> usize {
+    idx.rem_euclid(GRID_PERIOD as isize) as usize
+}
+
+impl Interpolator for Bicubic {
+    fn interpolate<const N: usize, const R: usize>(
+        table: &Table<N, R>,
+        keys: &[[u8; N]; R],
+        phi: f32,
+        psi: f32,
+    ) -> [Rotamer<N>; R] {
+        let (lo_phi, frac_phi) = angle_to_grid(phi);
+        let (lo_psi, frac_psi) = angle_to_grid(psi);
+        let w_phi = catmull_rom(frac_phi);
+        let w_psi = catmull_rom(frac_psi);
+
+        let phi_idx: [usize; 4] =
+            core::array::from_fn(|a| wrap_index(lo_phi as isize + a as isize - 1));
+        let psi_idx: [usize; 4] =
+            core::array::from_fn(|b| wrap_index(lo_psi as isize + b as isize - 1));
+
+        core::array::from_fn(|k| {
+            let mut prob = 0.0_f32;
+            let mut sin_sum = [0.0_f32; N];
+            let mut cos_sum = [0.0_f32; N];
+            let mut sigma = [0.0_f32; N];
+            let mut sigma_min = [f32::INFINITY; N];
+            let mut sigma_max = [0.0_f32; N];
+
+            for (a, &pi) in phi_idx.iter().enumerate() {
+                for (b, &si) in psi_idx.iter().enumerate() {
+                    let w = w_phi[a] * w_psi[b];
+                    let entry = &table.cell(pi, si)[k];
+                    prob = arith::mul_add(w, entry.prob, prob);
+                    sin_sum = arith::mul_add_lanes(w, &entry.chi_sin, sin_sum);
+                    cos_sum = arith::mul_add_lanes(w, &entry.chi_cos, cos_sum);
+                    sigma = arith::mul_add_lanes(w, &entry.chi_sigma, sigma);
+                    for i in 0..N {
+                        sigma_min[i] = sigma_min[i].min(entry.chi_sigma[i]);
+                        sigma_max[i] = sigma_max[i].max(entry.chi_sigma[i]);
+                    }
+                }
+            }
+
+            Rotamer {
+                r: keys[k],
+                prob: prob.max(0.0),
+                chi_mean: core::array::from_fn(|i| atan2f(sin_sum[i], cos_sum[i]) * RAD_TO_DEG),
+                chi_sigma: core::array::from_fn(|i| sigma[i].max(sigma_min[i]).min(sigma_max[i])),
+            }
+        })
+    }
+}
+
+/// Bilinear evaluation at (`phi`, `psi`) of the four corner cells around it,
+/// fetched from outside a [`Table`] and ordered as in [`Bilinear`];
+/// identical to [`build_iter`] with [`Bilinear`] on the full table.
+pub(crate) fn build_iter_from_corners<const N: usize, const R: usize>(
+    corners: [&[GridEntry<N>; R]; 4],
+    keys: &[[u8; N]; R],
+    phi: f32,
+    psi: f32,
+) -> RotamerIter<N, R> {
+    let (_, frac_phi) = angle_to_grid(phi);
+    let (_, frac_psi) = angle_to_grid(psi);
+    normalized_corners(corners, keys, frac_phi, frac_psi)
+}
+
+/// Bilinear evaluation inside grid cell (`lo_phi`, `lo_psi`) at fractional
+/// offsets (`frac_phi`, `frac_psi`), as returned by
+/// [`angle_to_grid`]; identical to [`build_iter`] with [`Bilinear`] at the
+/// corresponding angles.
+///
+/// # Panics
+///
+/// Panics if `lo_phi` or `lo_psi` exceeds `GRID_COUNT - 2`.
+pub(crate) fn build_iter_at<const N: usize, const R: usize>(
+    table: &Table<N, R>,
+    keys: &[[u8; N]; R],
+    lo_phi: usize,
+    lo_psi: usize,
+    frac_phi: f32,
+    frac_psi: f32,
+) -> RotamerIter<N, R> {
+    assert!(
+        lo_phi < GRID_COUNT - 1 && lo_psi < GRID_COUNT - 1,
+        "grid cell ({lo_phi}, {lo_psi}) out of range for {} cells per axis",
+        GRID_COUNT - 1
     );
+    let corners = [
+        table.cell(lo_phi, lo_psi),
+        table.cell(lo_phi + 1, lo_psi),
+        table.cell(lo_phi, lo_psi + 1),
+        table.cell(lo_phi + 1, lo_psi + 1),
+    ];
+    normalized_corners(
+        corners,
+        keys,
+        frac_phi.clamp(0.0, 1.0),
+        frac_psi.clamp(0.0, 1.0),
+    )
+}
+
+/// Bilinear combination of four corner cells at fractional offsets
+/// (`frac_phi`, `frac_psi`), re-normalized.
+#[inline]
+fn normalized_corners<const N: usize, const R: usize>(
+    corners: [&[GridEntry<N>; R]; 4],
+    keys: &[[u8; N]; R],
+    frac_phi: f32,
+    frac_psi: f32,
+) -> RotamerIter<N, R> {
+    let mut items = bilinear_corners(corners, keys, bilinear_weights(frac_phi, frac_psi));
+    let prob_sum = prob_sum(&items);
+    normalize(&mut items, prob_sum);
+    RotamerIter::from_items(items)
+}
+
+/// Build a [`RotamerIter`] by evaluating the table with interpolator `I` and
+/// re-normalizing the resulting probabilities.
+///
+/// `keys` contains the rotamer bin indices shared across all grid cells for
+/// this residue.
+#[inline]
+pub fn build_iter<I: Interpolator, const N: usize, const R: usize>(
+    table: &Table<N, R>,
+    keys: &[[u8; N]; R],
+    phi: f32,
+    psi: f32,
+) -> RotamerIter<N, R> {
+    build_iter_with::<I, N, R>(table, keys, phi, psi, Normalization::Renormalize)
+}
+
+/// [`build_iter`] with the probabilities treated by `normalization`.
+pub(crate) fn build_iter_with<I: Interpolator, const N: usize, const R: usize>(
+    table: &Table<N, R>,
+    keys: &[[u8; N]; R],
+    phi: f32,
+    psi: f32,
+    normalization: Normalization,
+) -> RotamerIter<N, R> {
+    #[cfg(all(feature = "no-panic", not(debug_assertions)))]
+    let guard = error::NoPanic;
+
+    let mut items = I::interpolate(table, keys, phi, psi);
+    if let Some(prob_sum) = normalizing_sum(&items, normalization) {
+        debug_assert!(
+            prob_sum.is_finite() && prob_sum > 0.0,
+            "prob_sum must be finite and positive, got {prob_sum}"
+        );
+        normalize(&mut items, prob_sum);
+    }
+
+    #[cfg(all(feature = "no-panic", not(debug_assertions)))]
+    core::mem::forget(guard);
+
+    RotamerIter {
+        items,
+        idx: 0,
+        len: R,
+    }
+}
+
+/// Fallible counterpart of [`build_iter`]: rejects non-finite angles and
+/// probabilities that cannot be re-normalized instead of asserting.
+#[inline]
+pub fn try_build_iter<I: Interpolator, const N: usize, const R: usize>(
+    table: &Table<N, R>,
+    keys: &[[u8; N]; R],
+    phi: f32,
+    psi: f32,
+) -> Result<RotamerIter<N, R>, Error> {
+    try_build_iter_with::<I, N, R>(table, keys, phi, psi, Normalization::Renormalize)
+}
+
+/// [`try_build_iter`] with the probabilities treated by `normalization`.
+/// Under [`Normalization::Raw`] the total is checked all the same, so every
+/// policy rejects the same queries.
+pub(crate) fn try_build_iter_with<I: Interpolator, const N: usize, const R: usize>(
+    table: &Table<N, R>,
+    keys: &[[u8; N]; R],
+    phi: f32,
+    psi: f32,
+    normalization: Normalization,
+) -> Result<RotamerIter<N, R>, Error> {
+    // Validated before the guard: an early return would drop it.
+    error::check_angles(phi, psi)?;
+
+    #[cfg(all(feature = "no-panic", not(debug_assertions)))]
+    let guard = error::NoPanic;
+
+    let mut items = I::interpolate(table, keys, phi, psi);
+    let checked = normalizing_sum(&items, normalization).unwrap_or_else(|| prob_sum(&items));
+    if !(checked.is_finite() && checked > 0.0) {
+        #[cfg(all(feature = "no-panic", not(debug_assertions)))]
+        core::mem::forget(guard);
+        return Err(Error::DegenerateDistribution);
+    }
+    if normalization != Normalization::Raw {
+        normalize(&mut items, checked);
+    }
+
+    #[cfg(all(feature = "no-panic", not(debug_assertions)))]
+    core::mem::forget(guard);
+
+    Ok(RotamerIter {
+        items,
+        idx: 0,
+        len: R,
+    })
+}
+
+/// The total `items` are divided by under `normalization`, or `None` if
+/// the raw probabilities are kept.
+#[inline]
+fn normalizing_sum<const N: usize, const R: usize>(
+    items: &[Rotamer<N>; R],
+    normalization: Normalization,
+) -> Option<f32> {
+    match normalization {
+        Normalization::Renormalize => Some(prob_sum(items)),
+        Normalization::Compensated => {
+            let probs: [f32; R] = core::array::from_fn(|k| items[k].prob);
+            Some(arith::compensated_sum(&probs))
+        }
+        Normalization::Raw => None,
+    }
+}
+
+/// Sum of the interpolated rotamer probabilities.
+#[inline]
+fn prob_sum<const N: usize, const R: usize>(items: &[Rotamer<N>; R]) -> f32 {
+    let probs: [f32; R] = core::array::from_fn(|k| items[k].prob);
+    arith::sum(&probs)
+}
+
+/// Re-normalizes probabilities so that Σ prob = 1.0.
+#[inline]
+fn normalize<const N: usize, const R: usize>(items: &mut [Rotamer<N>; R], prob_sum: f32) {
     let inv = 1.0 / prob_sum;
-    for rot in &mut items {
+    for rot in items {
         rot.prob *= inv;
     }
-
-    RotamerIter { items, idx: 0 }
 }
 
 #[cfg(test)]
@@ -209,7 +1194,7 @@ mod tests {
     #[test]
     fn test_chi_mean_from_sc_no_wrap() {
         let (s, c) = deg_to_sc(60.0);
-        let mean = chi_mean_from_sc([0.25; 4], [s; 4], [c; 4]);
+        let [mean] = chi_mean_from_sc([0.25; 4], [&[s]; 4], [&[c]; 4]);
         assert_relative_eq!(mean, 60.0, epsilon = 0.01);
     }
 
@@ -217,7 +1202,11 @@ mod tests {
     fn test_chi_mean_from_sc_wrap_around() {
         let (s0, c0) = deg_to_sc(170.0);
         let (s1, c1) = deg_to_sc(-170.0);
-        let mean = chi_mean_from_sc([0.5, 0.5, 0.0, 0.0], [s0, s1, 0.0, 0.0], [c0, c1, 0.0, 0.0]);
+        let [mean] = chi_mean_from_sc(
+            [0.5, 0.5, 0.0, 0.0],
+            [&[s0], &[s1], &[0.0], &[0.0]],
+            [&[c0], &[c1], &[0.0], &[0.0]],
+        );
         assert!(mean.abs() > 170.0, "expected |mean| > 170°, got {mean}");
     }
 
@@ -233,6 +1222,112 @@ mod tests {
         assert_relative_eq!(result, 10.0, epsilon = 1e-6);
     }
 
+    fn ramp_cells() -> [[GridEntry<1>; 1]; GRID_PERIOD * GRID_PERIOD] {
+        core::array::from_fn(|k| {
+            let (phi_idx, psi_idx) = (k / GRID_PERIOD, k % GRID_PERIOD);
+            let (s, c) = deg_to_sc(grid_angle(phi_idx));
+            [GridEntry {
+                prob: 1.0 + phi_idx as f32 + 100.0 * psi_idx as f32,
+                chi_sin: [s],
+                chi_cos: [c],
+                chi_sigma: [5.0 + psi_idx as f32],
+            }]
+        })
+    }
+
+    #[test]
+    fn test_catmull_rom_partition_of_unity() {
+        for t in [0.0, 0.25, 0.5, 0.75, 1.0] {
+            let w = catmull_rom(t);
+            assert_relative_eq!(w.iter().sum::<f32>(), 1.0, epsilon = 1e-6);
+        }
+        assert_eq!(catmull_rom(0.0), [0.0, 1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_wrap_index_periodic() {
+        assert_eq!(wrap_index(-1), 35);
+        assert_eq!(wrap_index(0), 0);
+        assert_eq!(wrap_index(36), 0);
+        assert_eq!(wrap_index(37), 1);
+    }
+
+    #[test]
+    fn test_nearest_picks_closest_cell() {
+        let cells = ramp_cells();
+        let table = Table::dense(&cells);
+        let [rot] = Nearest::interpolate(&table, &[[1]], -174.0, -176.0);
+        assert_relative_eq!(rot.prob, 1.0 + 1.0 + 0.0, epsilon = 1e-6);
+        assert_relative_eq!(rot.chi_mean[0], -170.0, epsilon = 0.01);
+        assert_relative_eq!(rot.chi_sigma[0], 5.0, epsilon = 1e-6);
+    }
+
+    #[cfg(feature = "fast-chi")]
+    #[test]
+    fn test_fast_bilinear_unwraps_linearly() {
+        let cells = ramp_cells();
+        let chi: [[[i16; 1]; 1]; GRID_PERIOD * GRID_PERIOD] =
+            core::array::from_fn(|k| [[(grid_angle(k / GRID_PERIOD) * 100.0) as i16]]);
+        let tables = [Table::dense(&cells).with_chi(&chi), Table::dense(&cells)];
+        for table in &tables {
+            let [rot] = FastBilinear::interpolate(table, &[[1]], 175.0, 12.0);
+            let [lin] = Bilinear::interpolate(table, &[[1]], 175.0, 12.0);
+            assert_relative_eq!(rot.chi_mean[0], 175.0, epsilon = 1e-4);
+            assert_eq!((rot.prob, rot.chi_sigma), (lin.prob, lin.chi_sigma));
+
+            let [rot] = FastBilinear::interpolate(table, &[[1]], -177.5, 12.0);
+            assert_relative_eq!(rot.chi_mean[0], -177.5, epsilon = 1e-4);
+        }
+        assert_eq!(
+            linear_chi_mean([0.5, 0.5, 0.0, 0.0], [17_990, -17_990, 0, 0]),
+            180.0
+        );
+        assert_eq!(linear_chi_mean([0.25; 4], [-17_000; 4]), -170.0);
+    }
+
+    #[test]
+    fn test_bicubic_exact_at_grid_points() {
+        let cells = ramp_cells();
+        let table = Table::dense(&cells);
+        let [rot] = Bicubic::interpolate(&table, &[[1]], -60.0, 30.0);
+        let [lin] = Bilinear::interpolate(&table, &[[1]], -60.0, 30.0);
+        assert_relative_eq!(rot.prob, lin.prob, epsilon = 1e-3);
+        assert_relative_eq!(rot.chi_mean[0], lin.chi_mean[0], epsilon = 0.01);
+        assert_relative_eq!(rot.chi_sigma[0], lin.chi_sigma[0], epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_try_build_iter_errors() {
+        struct Zero;
+        impl Interpolator for Zero {
+            fn interpolate<const N: usize, const R: usize>(
+                table: &Table<N, R>,
+                keys: &[[u8; N]; R],
+                phi: f32,
+                psi: f32,
+            ) -> [Rotamer<N>; R] {
+                let mut items = Nearest::interpolate(table, keys, phi, psi);
+                for rot in &mut items {
+                    rot.prob = 0.0;
+                }
+                items
+            }
+        }
+
+        let cells = ramp_cells();
+        let table = Table::dense(&cells);
+        let ok = try_build_iter::<Bilinear, 1, 1>(&table, &[[1]], -60.0, 30.0).unwrap();
+        assert_relative_eq!(ok.as_slice()[0].prob, 1.0, epsilon = 1e-6);
+        assert_eq!(
+            try_build_iter::<Bilinear, 1, 1>(&table, &[[1]], f32::NAN, 30.0).err(),
+            Some(Error::NonFiniteAngle)
+        );
+        assert_eq!(
+            try_build_iter::<Zero, 1, 1>(&table, &[[1]], -60.0, 30.0).err(),
+            Some(Error::DegenerateDistribution)
+        );
+    }
+
     #[test]
     fn test_rotamer_iter_exact_size() {
         let items = [
@@ -249,7 +1344,11 @@ mod tests {
                 chi_sigma: [12.0],
             },
         ];
-        let mut iter = RotamerIter { items, idx: 0 };
+        let mut iter = RotamerIter {
+            items,
+            idx: 0,
+            len: 2,
+        };
         assert_eq!(iter.len(), 2);
         let _ = iter.next();
         assert_eq!(iter.len(), 1);
@@ -257,4 +1356,186 @@ mod tests {
         assert_eq!(iter.len(), 0);
         assert!(iter.next().is_none());
     }
+
+    #[test]
+    fn test_bilinear_detail_matches_rotamers() {
+        use crate::residue::{Leu, Residue};
+
+        let detail = Leu::rotamers_detailed(-63.0, 178.0);
+        assert_eq!((detail.phi_idx, detail.psi_idx), (11, 35));
+        assert_relative_eq!(detail.weights.iter().sum::<f32>(), 1.0, epsilon = 1e-6);
+        assert!(detail.result.eq(Leu::rotamers(-63.0, 178.0)));
+
+        let [c00, _, _, c11] = detail.corners;
+        assert!(c00.eq(Leu::rotamers_with::<Nearest>(-70.0, 170.0)));
+        assert!(c11.eq(Leu::rotamers_with::<Nearest>(-60.0, 180.0)));
+    }
+
+    #[test]
+    fn test_corner_spread() {
+        use crate::residue::{Arg, Residue};
+
+        let mut detail = Arg::rotamers_detailed(-63.0, 147.5);
+        let spread = detail.corner_spread();
+        for (k, &s) in spread.iter().enumerate() {
+            let probs = detail.corners.each_ref().map(|c| c.as_slice()[k].prob);
+            let lo = probs.iter().copied().fold(f32::INFINITY, f32::min);
+            let hi = probs.iter().copied().fold(0.0, f32::max);
+            assert_eq!(s, hi - lo);
+        }
+        assert_eq!(
+            detail.max_corner_spread(),
+            spread.iter().copied().fold(0.0, f32::max)
+        );
+        assert!(detail.max_corner_spread() > 0.0);
+
+        detail.corners[2].next();
+        assert_eq!(detail.corner_spread(), spread);
+    }
+
+    #[test]
+    fn test_visit_matches_rotamers() {
+        use crate::residue::{Arg, Residue, Val};
+
+        for (phi, psi) in [(-63.0, 147.5), (180.0, -180.0), (-65.0, -40.0)] {
+            let mut expected = Arg::rotamers(phi, psi);
+            Arg::visit_rotamers(phi, psi, |rot| assert_eq!(Some(*rot), expected.next()));
+            assert_eq!(expected.len(), 0);
+        }
+        let mut count = 0;
+        Val::visit_rotamers(-60.0, -40.0, |_| count += 1);
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn test_uncertainty() {
+        use crate::residue::{Arg, Residue};
+
+        let detail = Arg::rotamers_detailed(-63.0, 147.5);
+        let spread = detail.corner_spread();
+        for (u, &s) in detail.uncertainty().iter().zip(&spread) {
+            // A weighted standard deviation never exceeds half the range.
+            assert!(u.prob <= 0.5 * s + 1e-6, "{} > {s} / 2", u.prob);
+        }
+        assert!(detail.uncertainty().iter().any(|u| u.prob > 0.0));
+
+        // Halfway along φ on a node row, two corners share the weight
+        // equally, so σ is exactly half their difference.
+        let detail = Arg::rotamers_detailed(-65.0, 150.0);
+        let [u, ..] = detail.uncertainty();
+        let p = detail.corners.each_ref().map(|c| c.as_slice()[0].prob);
+        assert!((u.prob - 0.5 * (p[0] - p[1]).abs()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_interpolation_error() {
+        use crate::residue::{Leu, Residue};
+
+        let (phi, psi) = (-63.0, 147.5);
+        let linear = Leu::rotamers(phi, psi);
+        let cubic = Leu::rotamers_with::<Bicubic>(phi, psi);
+        let largest = linear
+            .diff(&cubic)
+            .as_slice()
+            .iter()
+            .map(|e| e.delta().abs())
+            .fold(0.0, f32::max);
+        assert_eq!(Leu::interpolation_error(phi, psi), largest);
+        assert!(largest > 0.0);
+        for (phi, psi) in [(-180.0, -180.0), (0.0, 0.0), (180.0, 50.0)] {
+            assert!(Leu::interpolation_error(phi, psi) < 1e-6, "({phi}, {psi})");
+        }
+    }
+
+    #[test]
+    fn test_seam_reads_wrapped_cells() {
+        use crate::residue::{Arg, Residue};
+
+        // The +180° grid points are stored once, as −180°.
+        assert!(Arg::rotamers(180.0, -40.0).eq(Arg::rotamers(-180.0, -40.0)));
+        assert!(Arg::rotamers(-65.0, 180.0).eq(Arg::rotamers(-65.0, -180.0)));
+        assert!(Arg::rotamers(180.0, 180.0).eq(Arg::rotamers(-180.0, -180.0)));
+        let last = Arg::rotamers_at(35, 35, 1.0, 1.0);
+        assert!(last.eq(Arg::rotamers_at(0, 0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_rotamers_at_matches_rotamers() {
+        use crate::residue::{Arg, Residue};
+
+        for (phi, psi) in [(-63.0, 178.0), (-180.0, 180.0), (57.5, -121.25), (0.0, 0.0)] {
+            let (lo_phi, frac_phi) = angle_to_grid(phi);
+            let (lo_psi, frac_psi) = angle_to_grid(psi);
+            let at = Arg::rotamers_at(lo_phi, lo_psi, frac_phi, frac_psi);
+            assert!(at.eq(Arg::rotamers(phi, psi)), "({phi}, {psi})");
+        }
+        // Fractions are clamped into the cell.
+        assert!(Arg::rotamers_at(3, 4, 1.5, -0.5).eq(Arg::rotamers_at(3, 4, 1.0, 0.0)));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_rotamers_at_rejects_last_grid_index() {
+        use crate::residue::{Residue, Val};
+
+        Val::rotamers_at(36, 0, 0.0, 0.0);
+    }
+
+    #[test]
+    fn test_clone_and_reset() {
+        use crate::residue::{Leu, Residue};
+
+        let mut iter = Leu::rotamers(-65.0, -40.0);
+        iter.next();
+        let copy = iter.clone();
+        assert_eq!(copy.len(), iter.len());
+        assert!(copy.eq(iter.clone()));
+
+        iter.by_ref().for_each(drop);
+        iter.reset();
+        assert!(iter.eq(Leu::rotamers(-65.0, -40.0)));
+    }
+
+    #[test]
+    fn test_renormalize_masked() {
+        use crate::residue::{Residue, Val};
+
+        let full = Val::rotamers(-65.0, -40.0);
+        let mut rots = full.clone();
+        rots.next();
+        rots.renormalize_masked(&[true, false]).unwrap();
+        assert_eq!(rots.len(), 1);
+        let only = rots.next().unwrap();
+        assert_eq!(only.r, full.as_slice()[1].r);
+        assert_eq!(only.prob, 1.0);
+
+        let mut rots = full.clone();
+        assert_eq!(
+            rots.renormalize_masked(&[true; 2]),
+            Err(Error::MaskLengthMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            rots.renormalize_masked(&[false; 3]),
+            Err(Error::DegenerateDistribution)
+        );
+        assert!(rots.clone().eq(full.clone()));
+        rots.renormalize_masked(&[true; 3]).unwrap();
+        for (a, b) in rots.zip(full) {
+            assert_relative_eq!(a.prob, b.prob, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_borrowed_iteration_matches_owned() {
+        use crate::residue::{Gln, Residue};
+
+        let mut iter = Gln::rotamers(-65.0, -40.0);
+        iter.next();
+        assert_eq!(iter.iter().len(), iter.len());
+        assert!(iter.iter().copied().eq(iter.clone()));
+        assert_eq!(iter.as_slice().first(), iter.clone().next().as_ref());
+    }
 }
@@ -0,0 +1,459 @@
+use core::fmt;
+use core::iter::FusedIterator;
+use core::str::FromStr;
+
+use crate::error::Error;
+use crate::identity::ResidueInfo;
+use crate::residue::*;
+use crate::rotamer::Rotamer;
+
+/// Largest rotamer count of any residue type.
+const MAX_ROTAMERS: usize = {
+    let mut max = 0;
+    let mut i = 0;
+    while i < crate::ALL_RESIDUES.len() {
+        if crate::ALL_RESIDUES[i].n_rotamers > max {
+            max = crate::ALL_RESIDUES[i].n_rotamers;
+        }
+        i += 1;
+    }
+    max
+};
+
+/// Residue type chosen at runtime, one variant per [`Residue`] type, in the
+/// order of [`ALL_RESIDUES`](crate::ALL_RESIDUES).
+///
+/// For residues only known once a file is parsed, such as the residue
+/// names of a PDB file, `ResidueKind` queries the library without a type
+/// parameter. Rotamers come back through [`DynRotamers`] with χ arrays
+/// padded to four slots, as in `ResidueRotamers`.
+/// Code that knows its residue type at compile time should keep using the
+/// [`Residue`] types, which avoid the dispatch and the padding.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Leu, Residue, ResidueKind};
+///
+/// let kind: ResidueKind = "leu".parse().unwrap();
+/// assert_eq!(kind, ResidueKind::Leu);
+/// assert_eq!((kind.name(), kind.n_chi(), kind.n_rotamers()), ("LEU", 2, 9));
+///
+/// let dynamic: Vec<_> = kind.rotamers(-65.0, -40.0).collect();
+/// let typed: Vec<_> = Leu::rotamers(-65.0, -40.0).collect();
+/// assert_eq!(dynamic.len(), typed.len());
+/// assert_eq!(dynamic[0].chi_mean[..2], typed[0].chi_mean);
+/// assert_eq!(dynamic[0].chi_mean[2..], [0.0, 0.0]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ResidueKind {
+    /// Arginine; see [`Arg`].
+    Arg,
+    /// Asparagine; see [`Asn`].
+    Asn,
+    /// Aspartate; see [`Asp`].
+    Asp,
+    /// Cis-proline; see [`Cpr`].
+    Cpr,
+    /// Disulfide-bonded cysteine; see [`Cyd`].
+    Cyd,
+    /// Free (non-disulfide) cysteine; see [`Cyh`].
+    Cyh,
+    /// Combined cysteine pool; see [`Cys`].
+    Cys,
+    /// Glutamine; see [`Gln`].
+    Gln,
+    /// Glutamate; see [`Glu`].
+    Glu,
+    /// Histidine; see [`His`].
+    His,
+    /// Isoleucine; see [`Ile`].
+    Ile,
+    /// Leucine; see [`Leu`].
+    Leu,
+    /// Lysine; see [`Lys`].
+    Lys,
+    /// Methionine; see [`Met`].
+    Met,
+    /// Phenylalanine; see [`Phe`].
+    Phe,
+    /// Combined proline pool; see [`Pro`].
+    Pro,
+    /// Serine; see [`Ser`].
+    Ser,
+    /// Threonine; see [`Thr`].
+    Thr,
+    /// Trans-proline; see [`Tpr`].
+    Tpr,
+    /// Tryptophan; see [`Trp`].
+    Trp,
+    /// Tyrosine; see [`Tyr`].
+    Tyr,
+    /// Valine; see [`Val`].
+    Val,
+}
+
+impl ResidueKind {
+    /// Every residue kind, in the order of
+    /// [`ALL_RESIDUES`](crate::ALL_RESIDUES).
+    pub const ALL: [Self; 22] = [
+        Self::Arg,
+        Self::Asn,
+        Self::Asp,
+        Self::Cpr,
+        Self::Cyd,
+        Self::Cyh,
+        Self::Cys,
+        Self::Gln,
+        Self::Glu,
+        Self::His,
+        Self::Ile,
+        Self::Leu,
+        Self::Lys,
+        Self::Met,
+        Self::Phe,
+        Self::Pro,
+        Self::Ser,
+        Self::Thr,
+        Self::Tpr,
+        Self::Trp,
+        Self::Tyr,
+        Self::Val,
+    ];
+
+    /// Kind of the residue with this name or alias, matched as by
+    /// [`ResidueInfo::lookup`].
+    ///
+    /// # Errors
+    ///
+    /// [`Error::UnknownResidue`] if no residue type has that name or alias.
+    pub fn from_name(name: &str) -> Result<Self, Error> {
+        let info = ResidueInfo::lookup(name)?;
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.name() == info.name)
+            .ok_or(Error::UnknownResidue)
+    }
+
+    /// Runtime metadata of this residue type.
+    #[inline]
+    pub const fn info(self) -> &'static ResidueInfo {
+        &crate::ALL_RESIDUES[self as usize]
+    }
+
+    /// Three-letter library name, as in [`Residue::NAME`].
+    #[inline]
+    pub const fn name(self) -> &'static str {
+        self.info().name
+    }
+
+    /// Number of χ angles, as in [`Residue::N_CHI`].
+    #[inline]
+    pub const fn n_chi(self) -> usize {
+        self.info().n_chi
+    }
+
+    /// Rotamers per grid cell, as in [`Residue::N_ROTAMERS`].
+    #[inline]
+    pub const fn n_rotamers(self) -> usize {
+        self.info().n_rotamers
+    }
+
+    /// Rotamers at the given backbone dihedral angles, as
+    /// [`Residue::rotamers`] returns them for this residue type.
+    pub fn rotamers(self, phi: f32, psi: f32) -> DynRotamers {
+        let mut out = DynRotamers::empty(self);
+        macro_rules! fill {
+            ($Res:ident, $n:literal, $r:literal) => {
+                if self == Self::$Res {
+                    out.extend(<$Res as Residue>::rotamers(phi, psi));
+                    return out;
+                }
+            };
+        }
+        for_all_residues!(fill);
+        unreachable!("every ResidueKind is a residue type")
+    }
+
+    /// Fallible counterpart of [`rotamers`](Self::rotamers), as
+    /// [`Residue::try_rotamers`].
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NonFiniteAngle`] if `phi` or `psi` is NaN or infinite.
+    pub fn try_rotamers(self, phi: f32, psi: f32) -> Result<DynRotamers, Error> {
+        let mut out = DynRotamers::empty(self);
+        macro_rules! fill {
+            ($Res:ident, $n:literal, $r:literal) => {
+                if self == Self::$Res {
+                    out.extend(<$Res as Residue>::try_rotamers(phi, psi)?);
+                    return Ok(out);
+                }
+            };
+        }
+        for_all_residues!(fill);
+        unreachable!("every ResidueKind is a residue type")
+    }
+}
+
+/// Structural context of one residue that selects among the library's
+/// variants of cysteine and proline, for [`ResidueKind::resolve`].
+///
+/// `None` means the context is unknown, e.g. a model without SSBOND
+/// records; [`Default`] leaves both unknown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ResidueContext {
+    /// Whether the residue's Sγ is in a disulfide bond.
+    pub disulfide: Option<bool>,
+    /// Whether the peptide bond preceding the residue is cis, |ω| < 90°;
+    /// see [`cis_probability`](crate::cis_probability) when ω is uncertain.
+    pub cis_omega: Option<bool>,
+}
+
+impl ResidueKind {
+    /// Resolve a residue name from a structure file and its structural
+    /// context to the library type that should score it, the one place
+    /// that decides between the cysteine and proline variants.
+    ///
+    /// The name is first matched as by [`ResidueInfo::lookup`], so force
+    /// field names such as `CYX` already select a variant. Then:
+    ///
+    /// | Name resolves to      | Context                      | Kind  |
+    /// |-----------------------|------------------------------|-------|
+    /// | `CYS`, `CYD`, `CYH`   | `disulfide: Some(true)`      | `Cyd` |
+    /// | `CYS`, `CYD`, `CYH`   | `disulfide: Some(false)`     | `Cyh` |
+    /// | `PRO`, `CPR`, `TPR`   | `cis_omega: Some(true)`      | `Cpr` |
+    /// | `PRO`, `CPR`, `TPR`   | `cis_omega: Some(false)`     | `Tpr` |
+    ///
+    /// A known context overrides the variant the name implies, since bonds
+    /// and torsions observed in the structure are more reliable than a
+    /// residue label. With the context unknown the name stands, so plain
+    /// `CYS` and `PRO` keep the pooled libraries. Every other residue
+    /// ignores the context.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::UnknownResidue`] if no residue type has that name or alias.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{ResidueContext, ResidueKind};
+    ///
+    /// let unknown = ResidueContext::default();
+    /// assert_eq!(ResidueKind::resolve("CYS", unknown), Ok(ResidueKind::Cys));
+    /// assert_eq!(ResidueKind::resolve("CYX", unknown), Ok(ResidueKind::Cyd));
+    ///
+    /// let bonded = ResidueContext { disulfide: Some(true), ..unknown };
+    /// assert_eq!(ResidueKind::resolve("CYS", bonded), Ok(ResidueKind::Cyd));
+    ///
+    /// let cis = ResidueContext { cis_omega: Some(true), ..unknown };
+    /// assert_eq!(ResidueKind::resolve("PRO", cis), Ok(ResidueKind::Cpr));
+    /// assert_eq!(ResidueKind::resolve("LEU", cis), Ok(ResidueKind::Leu));
+    /// ```
+    pub fn resolve(name: &str, context: ResidueContext) -> Result<Self, Error> {
+        let kind = Self::from_name(name)?;
+        let cysteine = matches!(kind, Self::Cys | Self::Cyd | Self::Cyh);
+        let proline = matches!(kind, Self::Pro | Self::Cpr | Self::Tpr);
+        Ok(match (context.disulfide, context.cis_omega) {
+            (Some(true), _) if cysteine => Self::Cyd,
+            (Some(false), _) if cysteine => Self::Cyh,
+            (_, Some(true)) if proline => Self::Cpr,
+            (_, Some(false)) if proline => Self::Tpr,
+            _ => kind,
+        })
+    }
+}
+
+impl FromStr for ResidueKind {
+    type Err = Error;
+
+    #[inline]
+    fn from_str(name: &str) -> Result<Self, Error> {
+        Self::from_name(name)
+    }
+}
+
+impl fmt::Display for ResidueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Rotamers of a [`ResidueKind`], with χ arrays padded to four slots.
+///
+/// Produced by [`ResidueKind::rotamers`]. Yields the rotamers in table
+/// order without allocating; slots past [`n_chi`](Self::n_chi) hold `0`
+/// bin indices and `0.0` angles.
+#[derive(Debug, Clone)]
+pub struct DynRotamers {
+    kind: ResidueKind,
+    rotamers: [Rotamer<4>; MAX_ROTAMERS],
+    front: usize,
+    len: usize,
+}
+
+impl DynRotamers {
+    fn empty(kind: ResidueKind) -> Self {
+        Self {
+            kind,
+            rotamers: [Rotamer {
+                r: [0; 4],
+                prob: 0.0,
+                chi_mean: [0.0; 4],
+                chi_sigma: [0.0; 4],
+            }; MAX_ROTAMERS],
+            front: 0,
+            len: 0,
+        }
+    }
+
+    fn extend<const N: usize>(&mut self, rotamers: impl Iterator<Item = Rotamer<N>>) {
+        for rot in rotamers {
+            self.rotamers[self.len] = rot.padded();
+            self.len += 1;
+        }
+    }
+
+    /// Residue type of these rotamers.
+    #[inline]
+    pub fn kind(&self) -> ResidueKind {
+        self.kind
+    }
+
+    /// Number of meaningful χ slots, as in [`ResidueKind::n_chi`].
+    #[inline]
+    pub fn n_chi(&self) -> usize {
+        self.kind.n_chi()
+    }
+
+    /// The rotamers not yet yielded.
+    #[inline]
+    pub fn as_slice(&self) -> &[Rotamer<4>] {
+        &self.rotamers[self.front..self.len]
+    }
+}
+
+impl Iterator for DynRotamers {
+    type Item = Rotamer<4>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Rotamer<4>> {
+        let rot = *self.as_slice().first()?;
+        self.front += 1;
+        Some(rot)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let n = self.len - self.front;
+        (n, Some(n))
+    }
+}
+
+impl ExactSizeIterator for DynRotamers {}
+
+impl FusedIterator for DynRotamers {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_matches_registry() {
+        let mut kinds = ResidueKind::ALL.iter();
+        macro_rules! check {
+            ($Res:ident, $n:literal, $r:literal) => {
+                let kind = *kinds.next().unwrap();
+                assert_eq!(kind, ResidueKind::$Res);
+                assert_eq!(*kind.info(), ResidueInfo::of::<$Res>());
+                assert_eq!(ResidueKind::from_name(<$Res as Residue>::NAME), Ok(kind));
+            };
+        }
+        for_all_residues!(check);
+        assert!(kinds.next().is_none());
+        assert_eq!(MAX_ROTAMERS, 108);
+    }
+
+    #[test]
+    fn test_rotamers_match_typed_queries() {
+        for kind in ResidueKind::ALL {
+            let rotamers = kind.rotamers(-120.0, 130.0);
+            assert_eq!(rotamers.len(), kind.n_rotamers());
+            assert_eq!(rotamers.n_chi(), kind.n_chi());
+            assert!(
+                rotamers
+                    .as_slice()
+                    .iter()
+                    .all(|rot| rot.r[kind.n_chi()..].iter().all(|&bin| bin == 0))
+            );
+        }
+        let arg = ResidueKind::Arg.rotamers(-65.0, -40.0);
+        assert!(arg.eq(Arg::rotamers(-65.0, -40.0).map(Rotamer::padded)));
+        assert_eq!(
+            ResidueKind::Cyd
+                .try_rotamers(f32::NAN, 0.0)
+                .map(|r| r.len()),
+            Err(Error::NonFiniteAngle)
+        );
+        assert_eq!("HOH".parse::<ResidueKind>(), Err(Error::UnknownResidue));
+        assert_eq!(ResidueKind::from_name("hid"), Ok(ResidueKind::His));
+    }
+
+    #[test]
+    fn test_resolve_decision_table() {
+        let ctx = |disulfide, cis_omega| ResidueContext {
+            disulfide,
+            cis_omega,
+        };
+        for name in ["CYS", "cym", "CYD", "CYX", "CYH"] {
+            assert_eq!(
+                ResidueKind::resolve(name, ctx(Some(true), None)),
+                Ok(ResidueKind::Cyd)
+            );
+            assert_eq!(
+                ResidueKind::resolve(name, ctx(Some(false), Some(true))),
+                Ok(ResidueKind::Cyh)
+            );
+            assert_eq!(
+                ResidueKind::resolve(name, ctx(None, Some(true))),
+                ResidueKind::from_name(name)
+            );
+        }
+        for name in ["PRO", "CPR", "TPR"] {
+            assert_eq!(
+                ResidueKind::resolve(name, ctx(None, Some(true))),
+                Ok(ResidueKind::Cpr)
+            );
+            assert_eq!(
+                ResidueKind::resolve(name, ctx(Some(true), Some(false))),
+                Ok(ResidueKind::Tpr)
+            );
+            assert_eq!(
+                ResidueKind::resolve(name, ResidueContext::default()),
+                ResidueKind::from_name(name)
+            );
+        }
+        for kind in ResidueKind::ALL {
+            let family = matches!(
+                kind,
+                ResidueKind::Cys
+                    | ResidueKind::Cyd
+                    | ResidueKind::Cyh
+                    | ResidueKind::Pro
+                    | ResidueKind::Cpr
+                    | ResidueKind::Tpr
+            );
+            if !family {
+                assert_eq!(
+                    ResidueKind::resolve(kind.name(), ctx(Some(true), Some(true))),
+                    Ok(kind)
+                );
+            }
+        }
+        assert_eq!(
+            ResidueKind::resolve("GLY", ResidueContext::default()),
+            Err(Error::UnknownResidue)
+        );
+    }
+}
@@ -10,14 +10,104 @@
 //!
 //! ## Features
 //!
-//! - **Zero startup latency.** The entire ~28 MB rotamer database is embedded in `.rodata` at compile time via `build.rs`. No file I/O, no deserialization, no lazy initialization.
+//! - **Zero startup latency.** The entire ~27 MB rotamer database is embedded in `.rodata` at compile time via `build.rs`. No file I/O, no deserialization, no lazy initialization.
+//! - **Footprint report.** `Residue::TABLE_BYTES`, `TABLE_FOOTPRINT`, and `TOTAL_TABLE_BYTES` expose the static data size per residue and in total, so embedded and WASM builds can budget flash or bundle size programmatically.
+//! - **Output fingerprint.** `fingerprint()` hashes the exact bits of every residue's interpolated rotamers over a fixed 12 × 12 set of off-grid (φ, ψ) points into one `u64`, and `verify_fingerprint(expected)` returns `Error::DataMismatch` when a deployed binary does not reproduce a value recorded from a trusted build — a cheap runtime check that the library behaves exactly as expected.
+//! - **χ1-only reduced library (`chi1` feature).** Generates `Chi1<R>` residue types whose tables collapse every residue to its χ1 bins with marginalized probabilities — a tiny library for coarse placement and teaching, usable alongside or instead of the full tables.
+//! - **Side-by-side libraries (`alt-data` feature).** Generates a second complete set of tables from the CSV named by `DUNBRACK_ALT_DATA` — another smoothing level, or an older release converted to the same schema — as `Alt<R>` residue types, aliased under their usual names in the `alt` module, so migration studies can query and diff both libraries in one binary.
+//! - **`alloc` convenience APIs (`alloc` feature).** `Residue::rotamers_vec`, `Residue::sorted_rotamers_vec`, `Query::run_vec`, and `collect_all_residues` return `Vec`s directly; the default build stays allocation-free.
+//! - **Adaptive fine-grid cache (`alloc` feature).** `FineGridCache::<R>::new(subdivisions, threshold)` counts queries per 10° grid cell and, once a cell turns hot, precomputes its rotamers on a finer sub-grid, so repeated queries in packing hot spots become slice lookups snapped to the nearest sub-grid node.
+//! - **Global rotamer indexing (`alloc` feature).** `RotamerIndex` numbers the candidate rotamers at every packing position contiguously, mapping `(position, local)` to a global index and back, so packers keep one-body energies, pruning flags, and assignments in flat arrays.
+//! - **Packing interaction graph (`alloc` feature).** `InteractionGraph` holds one node per position (candidate one-body energies and pruning flags) and one user-filled `PairTable` per interacting pair, with `prune_where` hooks and `total_energy` scoring — a shared, tested base for DEE, simulated-annealing, and Monte Carlo packers.
+//! - **Self-energy hooks (`alloc` feature).** `SelfEnergy::new().library_weight(w).term(clash).term(backbone).compute(&rotamers)` combines the library term `−ln p` with user callbacks into one energy per rotamer, aligned with the rotamer set and ready for `InteractionGraph::add_node`.
+//! - **Energy units.** `EnergyUnits` (dimensionless kT, kcal/mol at T, or kJ/mol at T) is accepted by every energy API — `SelfEnergy::units`, `RestraintScale::in_units`, `Residue::reference_energy` — with `kt`, `from_kt`, `to_kt`, and `convert` helpers, so multi-tool scoring pipelines convert once instead of mixing units.
+//! - **Loop side-chain sampling (`alloc` feature).** `sample_loop(&positions, seed)` draws reproducible χ arrays for every `(residue, φ, ψ)` position of a remodeled loop at once; `sample_loop_avoiding` additionally rejects draws that fail a user clash test against the positions already placed.
+//! - **Seeded rotamer assignment (`alloc` feature).** `assign_rotamers(&positions, seed)` picks one discrete rotamer per `(residue, φ, ψ)` position of a sequence, weighted by the library at that backbone, reproducibly from the seed, for generating decoy structures and test fixtures.
+//! - **CSV round-trip export (`std` feature).** `write_csv` re-emits the embedded tables in the exact CSV schema `build.rs` reads, with χ means recovered from the stored sin/cos pairs, so external audits can diff the shipped binary data against the source file row by row.
+//! - **Flat record stream.** `records()` walks every rotamer of every residue type at all 1,369 grid points as `Record { residue, phi, psi, r, prob, chi_mean, chi_sigma }` values (per-χ fields padded to four slots, with `bins()`/`chis()`/`sigmas()` slices), lazily and without allocating, so analytics jobs can stream the whole library into a database or dataframe without writing 22 typed loops.
+//! - **Library summaries.** `summarize::<R>()` and `summarize_all()` reduce each residue's 1,296 distinct grid cells to a plain `ResidueSummary`: mean, minimum, and maximum probability of the top rotamer, probability-weighted mean σ per χ, and the entropy range, so reports and sanity dashboards don't traverse the raw tables.
+//! - **Probability isocontours (`alloc` feature).** `isocontours::<R>(&r, level)` runs marching squares over the 37 × 37 grid for one rotamer and returns `Contour` polylines in (φ, ψ) degrees, closed or ending on the plane's border, for plotting which backbone regions support that side-chain state.
+//! - **Heatmap figures (`viz` feature).** `Heatmap::probability::<R>(&r)` and `Heatmap::entropy::<R>()` map one rotamer's probability or the rotamer entropy over the Ramachandran plane and render it with `to_svg` (axes, title, colour bar) or `to_png`, dependency-free, for publication-ready Ramachandran-style figures.
+//! - **Torsion restraints.** `Rotamer::restraints` turns a chosen rotamer, and `RotamerIter::restraints` a pruned set, into typed `TorsionRestraint`s per χ: the four dihedral atoms, a target angle, a flat-bottom width from σ, and a force constant `kT / σ²` in the energy unit of a user `RestraintScale`, bridging the library to restrained refinement and minimization.
+//! - **Rosetta constraint export (`std` feature).** `write_rosetta_constraints` writes `TorsionRestraint`s keyed by pose residue number as Rosetta `Dihedral` constraints (`CIRCULARHARMONIC` for plain harmonic restraints, `PERIODICBOUNDED` for flat-bottom ones, angles in radians), so Rosetta protocols can use this crate's priors, customized or not.
+//! - **SCWRL4 rotamer export (`std` feature).** `write_scwrl_rotamers` writes the (pruned) rotamer set of a sequence position in the column layout of SCWRL4's `bbdep02.May.lib`, under a `#` comment recording the position and exact backbone, so packing results can be compared head to head with SCWRL or fed into hybrid workflows.
+//! - **MD restraint export (`std` feature).** `write_gromacs_restraints` writes `TorsionRestraint`s as a GROMACS `[ dihedral_restraints ]` block and `write_openmm_restraints` as a serialized OpenMM `CustomTorsionForce`, both with the same flat-bottom harmonic potential, so MD practitioners can restrain modeled side chains during equilibration.
+//! - **Startup precomputation (`std` feature).** `precompute(progress)` builds per-grid-node entropy, log-probability, and probability-sorted rotamer-order maps for every residue in parallel on all cores, reporting progress through a callback, for long-running servers that prefer paying the cost once up front.
+//! - **Memory-mapped table files (`mmap` feature).** `write_table_file` dumps the embedded primary tables in their in-memory layout behind a validated header, and `MappedTables::open(path)` maps such a file — checking magic, version, byte order, and every residue's table shape — and answers `rotamers::<R>` and `query::<R>` straight from the mapping (`Chi1<R>` and `Alt<R>` variants report `Error::UnknownResidue`), so many processes in a container share one copy of the data through the page cache.
+//! - **Tensor interop (`candle` feature).** `RotamerIter::to_tensors(&device)` and `RotamerTensors::stack(&sets, &device)` turn rotamer sets — single or batched by backbone — into `candle_core::Tensor`s of bins, probabilities, χ means, and σ, and `TableTensors::of::<Leu>(&device)` exposes a whole grid table, so deep-learning structure models can take Dunbrack priors without a NumPy detour.
+//! - **HTTP query service (`server` feature).** The opt-in `dunbrack-server` binary answers `GET /rotamers?res=ARG&phi=-65&psi=-43` with the rotamer set as JSON (bins, probabilities, χ means and σ), so non-Rust services can use the library without bindings: `cargo run --release --features server --bin dunbrack-server -- 127.0.0.1:8080`.
+//! - **External table storage.** Implement `TableStorage::read_cell` for SPI flash, an SD card, or any other medium — cells are requested by a `TableKey` of residue name, library, and table shape, so `Chi1<R>` and `Alt<R>` never alias `R` — and `StoredTables::<S, SLOTS>` answers `rotamers::<R>` by fetching only the four grid cells each query needs, keeping the most recently used cells in a small fixed RAM cache — no allocator, and no need to map the full tables on flash-starved microcontrollers.
+//! - **Binary rotamer-set encoding.** `RotamerIter::encode` / `decode` (and `to_bytes` with `alloc`) use a compact, versioned little-endian format — an 8-byte header plus fixed-size records that map straight onto a NumPy structured dtype — so multi-process pipelines can pass results over pipes or shared memory without JSON overhead.
 //! - **Zero heap allocation.** Every query returns a `RotamerIter<N, R>` — a stack-allocated array of exactly `R` `Rotamer<N>` values. No `Vec`, no `Box`, no allocator required.
 //! - **`#![no_std]` compatible.** No standard library, no libm linkage. Usable in embedded firmware, OS kernels, and WASM environments.
 //! - **Type-safe χ dimensionality.** The number of χ angles per residue is a compile-time constant `N` encoded in `Rotamer<N>` and `RotamerIter<N, R>`. There are no padding zeros, no runtime bounds checks, no wrong-length arrays.
+//! - **Residue identity constants.** `Residue::ONE_LETTER` and `Residue::ALIASES` (e.g. `"HIE"` for `His`, `"CYX"` for `Cyd`) let generic code render and parse residue names without a lookup crate.
+//! - **Residue registry.** `ALL_RESIDUES` lists a `ResidueInfo` (name, one-letter code, χ count, rotamer count) for every supported type, and `ResidueInfo::lookup` finds one by name or alias, so CLIs, FFI layers, and UIs can enumerate capabilities at runtime without macros.
+//! - **Runtime residue kinds.** `ResidueKind` names each of the 22 residue types as an enum value, parsed from a name or alias (`"hie".parse()`), with `name()`, `n_chi()`, `n_rotamers()`, and `rotamers(phi, psi)` returning a `DynRotamers` iterator of four-slot rotamers without allocating — for residues only known at runtime, e.g. read from a PDB file.
+//! - **Residue variant resolution.** `ResidueKind::resolve(name, ResidueContext { disulfide, cis_omega })` is the one documented decision function mapping a structure-file residue name and its observed context to the library type — `Cyd`/`Cyh` for bonded or free cysteine, `Cpr`/`Tpr` for cis or trans proline, and the pooled `Cys`/`Pro` when the context is unknown — instead of scattered caller-side logic.
+//! - **Packed rotamer ids.** `RotamerId` packs a residue type and bin key into a `u32` (`new::<R>(&r)`, `from_u32`, `to_u32`), with stable ordering and cheap hashing, so packers and caches can key flat maps and arrays by rotamer identity across a whole structure.
+//! - **Bin counts per χ.** `Residue::BINS_PER_CHI` gives the number of discrete bins of every χ (3 for rotameric χ, 6 or 12 for semi-rotameric terminal χ, 2/1/1 for the proline ring), for enumerating the theoretical rotamer space and validating bin keys from external data.
+//! - **Bin boundaries.** `Residue::BIN_RANGES` gives the angular range of every bin of every χ as a `BinRange` (g+ = [0°, 120°), t = [120°, 240°), g− = [240°, 360°) for rotameric χ; nominal 30° ranges for semi-rotameric χ), and `Residue::classify_chi` bins a measured angle with them, so classification, reports, and cross-tool comparisons share one definition.
+//! - **Checked bin conversions.** `ChiBin::try_from(raw)` turns a raw `u8` bin index into `GPlus`, `Trans`, or `GMinus`, returning `Error::InvalidBinKey` for anything else, and `Residue::chi_bin(i, raw)` also rejects χ angles that are not rotameric — so third-party rotamer assignments are validated once on ingestion instead of silently indexing the wrong bin.
+//! - **Bin-assignment validation.** `Residue::bin_discrepancy(r, chis)` returns how far each χ value lies outside the range of its assigned bin, catching shifted numbering, swapped g+/g−, and other convention bugs when importing rotamer assignments from other software.
+//! - **Masked renormalization.** `RotamerIter::renormalize_masked(mask)` keeps only the rotamers a mask allows — e.g. those compatible with a disulfide or a bound ligand — and rescales them to conditional probabilities summing to 1.
+//! - **Cysteine blending.** `blend_cysteine(phi, psi, p_disulfide)` mixes the disulfide-bonded (`Cyd`) and free (`Cyh`) libraries with a disulfide probability from a predictor, instead of forcing a hard choice between the two tables.
+//! - **Cis/trans proline blending.** `blend_proline(phi, psi, p_cis)` mixes the cis (`Cpr`) and trans (`Tpr`) libraries by a cis population, and `cis_probability(omega, sigma)` derives that population from an uncertain ω angle, for ensembles and predicted models where the peptide-bond isomer is not known definitively.
+//! - **Physicochemical classes.** Marker traits `Aromatic`, `Charged`, `Polar`, `Hydrophobic`, and `Small` (IMGT classification) let generic algorithms bound their type parameters, e.g. `fn f<R: Aromatic>()`, and `Residue::CLASS` lets them branch on chemistry with `R::CLASS.contains(Class::POLAR)` — no hard-coded name lists.
+//! - **Side-chain topology.** `Residue::TOPOLOGY` lists each residue's heavy atoms with elements and parent atoms, ring-closing bonds, and the four atoms defining every χ — one authoritative topology for structure builders, χ measurement, and bindings.
+//! - **Ideal side-chain geometry.** `Residue::IDEAL_GEOMETRY` gives Engh–Huber bond lengths, bond angles, and χ-driven or fixed dihedrals for every side-chain heavy atom, in topology order, and `InternalCoord::place` converts one entry to Cartesian coordinates — everything needed to build a rotamer onto a backbone.
+//! - **Reference-state energies.** `Residue::REFERENCE_ENERGIES` holds `−ln ⟨p⟩` of every rotamer averaged over the Ramachandran grid, generated at build time, for design scoring functions that need a normalized Dunbrack term.
+//! - **Default rotamers.** `Residue::DEFAULT_ROTAMER` is a generated const holding each residue's backbone-averaged most common rotamer (χ means and σ averaged over the grid), so quick structure initialization can place sensible side chains with no runtime query at all.
+//! - **Gradient maps (`gradients` feature).** `Residue::PROB_GRADIENTS` holds `(∂p/∂φ, ∂p/∂ψ)` for every rotamer at every Ramachandran grid node, generated at build time by periodic central differences, with `GradientMap::at` interpolating between nodes — for analyzing how strongly each rotamer couples to backbone geometry.
 //! - **Bilinear interpolation with circular χ means.** `Residue::rotamers(phi, psi)` bilinearly interpolates across the four surrounding grid cells. χ means are computed via circular weighted mean (sin/cos decomposition), correctly handling the ±180° wraparound. Probabilities are re-normalized to Σ = 1.0 after interpolation.
+//! - **Pluggable interpolation.** `Residue::rotamers_with::<I>(phi, psi)` evaluates the table with any `Interpolator` — `Bilinear` (the default), `Nearest`, `Bicubic`, or a user-provided scheme — so experimental interpolation can be tested without forking the crate.
+//! - **Interpolation intermediates.** `Residue::rotamers_detailed(phi, psi)` returns the bilinear result together with the chosen grid indices, the four corner weights, and the raw corner rotamer values, for validating the crate against reference implementations or tracking down discrepancies.
+//! - **Interpolation sensitivity.** `BilinearDetail::corner_spread()` and `max_corner_spread()` report how far each rotamer's raw probability varies over the four corners of a `rotamers_detailed` query, flagging steep regions of the Ramachandran map where the interpolated result is sensitive to small backbone changes.
+//! - **Callback visitation.** `R::visit_rotamers(phi, psi, |rot| ...)` interpolates and hands each rotamer to a closure as soon as it is computed, bit-identical to `rotamers` but without building the set, so stack use does not grow with the rotamer count.
+//! - **χ marginal density curves.** `RotamerIter::chi_density::<P>(chi_index)` and `R::chi_density::<P>(phi, psi, chi_index)` sample the wrapped-Gaussian mixture of one χ at `P` points over [−180°, 180°), in probability per degree, for plotting and for fitting experimental torsion distributions.
+//! - **χ order parameters.** `RotamerIter::chi_order_parameters()` and `R::chi_order_parameters(phi, psi)` give the dihedral order parameter S² = |E[e^{iχ}]|² of each χ over the full mixture, counting both the scatter between rotamers and the σ within each — a library-based prediction to compare with NMR side-chain order parameters.
+//! - **Interpolation uncertainty.** `BilinearDetail::uncertainty()` gives, per rotamer, the bilinear-weighted standard deviation of the four corner probabilities and χ means around the interpolated values, so downstream Bayesian models can treat library values as noisy rather than exact.
+//! - **Normalization policy.** `Query::normalization` selects `Normalization::Renormalize` (the default Σ = 1 correction), `Raw` (keep the raw interpolated probabilities, whose total `RotamerIter::prob_sum` reports), or `Compensated` (re-normalize by a Neumaier-compensated sum), for statistical applications that need the probabilities before the correction.
+//! - **Trig-free χ interpolation (`fast-chi` feature).** `rotamers_with::<FastBilinear>` interpolates χ means linearly in degrees with branch-based ±360° unwrapping from embedded centidegree tables, skipping every `sin`/`cos`/`atan2`; probabilities and σ are unchanged and χ stays within 0.01° of `Bilinear` wherever the four corners agree within 10°.
+//! - **Interpolation error estimate.** `Residue::interpolation_error(phi, psi)` reports the largest per-rotamer probability difference between bilinear and bicubic evaluation — zero at grid points — so pipelines can flag backbone regions where the 10° grid is too coarse for their accuracy needs.
+//! - **Queries by grid cell.** `Residue::rotamers_at(lo_phi, lo_psi, frac_phi, frac_psi)` interpolates inside an already quantized grid cell, skipping the angle-to-grid conversion for batch pipelines that bucket residues by cell, with results identical to `Residue::rotamers`.
+//! - **Grid quantization helpers.** `angle_to_grid`, `grid_angle`, `nearest_grid_index`, and `snap_to_grid` convert between angles and grid indices, and `grid_neighbors(phi, psi)` lists the four surrounding grid points with their bilinear weights, exposing the quantization the interpolation path uses internally.
+//! - **Grid neighbourhoods.** `R::neighborhood(phi, psi, radius_deg)` iterates over every grid point within an angular radius of a backbone, wrapping across ±180°, yielding a `GridCell` with its indices, distance, and raw rotamers — so smoothing, local averaging, and sensitivity analyses need no manual index arithmetic.
+//! - **Bilinear weights.** `grid_weights(phi, psi)` returns the lower grid indices and the four corner weights exactly as the crate computes them, so downstream code interpolating its own per-cell quantities stays numerically consistent with `Residue::rotamers`.
+//! - **GPU table export.** `Residue::write_gpu_table(&mut buf)` flattens a residue's table into a little-endian structure-of-arrays layout (256-byte-aligned arrays, rotamer slots padded to multiples of 4) described by the `#[repr(C)]` `GpuTableLayout`, so CUDA or wgpu packing kernels read exactly the values the CPU path interpolates.
+//! - **Guaranteed `Rotamer` layout.** `Rotamer::<N>::SIZE`, `ALIGN`, and the `R_OFFSET`, `PROB_OFFSET`, `CHI_MEAN_OFFSET`, and `CHI_SIGMA_OFFSET` constants document the `#[repr(C)]` layout as a stable API, and compile-time assertions check them against the compiler for N = 1 to 4, so FFI layers and binary formats can rely on it across releases.
+//! - **Query builder.** `Query::new(phi, psi).interpolation(Mode::Bicubic).prune(0.01).sorted(true).run::<Arg>()` composes interpolation scheme, probability pruning, and ordering without multiplying function variants.
+//! - **Progressive queries.** `Query::progressive::<R>()` answers at once from the nearest grid point, and `refine()` / `finish()` step through bilinear up to the query's interpolation on demand, so interactive viewers stay responsive while backbone torsion sliders are dragged.
+//! - **Out-of-range policies.** `Query::out_of_range(OutOfRange::Wrap)` selects how backbone angles outside [−180°, 180°] are treated — clamped (the default), wrapped by periodicity, rejected with `Error::AngleOutOfRange`, or answered with NaN — since pipelines differ in what they expect from out-of-domain input.
+//! - **NaN propagation.** `Query::propagate_nan(true)` answers NaN φ or ψ — typically a backbone with missing atoms — with a NaN-filled rotamer set from both `run` and `try_run`, so such residues stay visibly undefined instead of receiving plausible-looking rotamers for a clamped angle.
+//! - **Quasi-random ensemble sampling.** `RotamerIter::sobol(seed)` draws side-chain conformations from a digitally shifted Sobol sequence — better coverage than i.i.d. draws, and deterministic given the seed and draw index. `RotamerIter::stratified(count, min_prob, seed)` guarantees every rotamer above a threshold at least one draw before filling the rest proportionally.
+//! - **Whole-distribution χ statistics.** `Residue::mean_chis(phi, psi)` and `Residue::chi_circular_variance(phi, psi)` summarize the full rotamer mixture as one expected conformation plus its circular spread, for coarse-grained side-chain models. `RotamerIter::chi_moments()` exposes the first and second circular moments (resultant lengths, circular std) for order-parameter-like quantities.
+//! - **Aggregate χ uncertainty.** `RotamerIter::weighted_sigma()` returns a `ChiSpread` per χ — the probability-weighted mean σ, the spread between rotamer means, and their combination in degrees — as a scalar "how floppy is this side chain here" measure for flexibility profiling.
+//! - **Backbone-ensemble averaging.** `Residue::ensemble_rotamers(&[(phi, psi, weight)])` averages the rotamer distribution over weighted NMR models or MD frames in one call, with circular averaging of χ means and σ widened by the spread of those means across the ensemble.
+//! - **Backbone-uncertainty marginalization.** `Residue::rotamers_uncertain(phi, psi, sigma_phi, sigma_psi)` integrates the library over Gaussian φ/ψ uncertainty by 5 × 5 Gauss–Hermite quadrature, giving softer, more honest rotamer probabilities for low-resolution or predicted structures.
+//! - **Mutation comparison.** `compare::<A, B>(phi, psi)` reports the χ1-bin Jensen–Shannon divergence, entropy change, and best-rotamer change between two residue types at the same backbone, packaged for design tools evaluating point mutations.
+//! - **Chain flexibility tracks.** `flexibility_profile(&[("LEU", phi, psi), …])` maps a whole chain to per-position rotamer entropy and effective rotamer count `exp(H)` in one call (`alloc` feature), treating residues without rotamers, such as Gly and Ala, as rigid — the flexibility track analysis dashboards show alongside a structure.
+//! - **Cross-residue χ1 comparison.** `compare_chi1(["PHE", "TYR", …], phi, psi)` returns the normalized χ1-bin marginals of several residue types at one backbone, their pairwise Pearson correlations, each type's preferred bin, and the bin they all share if any — for judging whether a set of mutations preserves local packing geometry.
+//! - **Rotamer set diffs.** `RotamerIter::diff(&after)` compares two sets of the same residue, e.g. before and after a backbone move, bin key by bin key: keys that appeared, disappeared, or are common to both, per-bin probability deltas, `changed(tolerance)`, and the total variation distance — so incremental packers know what to recompute.
+//! - **Bin transition matrices.** `RotamerIter::transition(&after)` aligns two sets of one residue type by bin key and returns a `BinTransition`: the minimal-flow coupling between their normalized distributions, with `losing()` and `gaining()` bins, per-pair `flow(from, to)` and row-normalized `probability(from, to)`, and every `BinFlow` — for tracing side-chain repacking along a backbone morph or trajectory.
+//! - **Multi-conformer occupancies.** `RotamerIter::conformers::<K>(step)` returns the `K` most probable rotamers as `Conformer`s with altloc labels `A`, `B`, … and occupancies renormalized over them and rounded to multiples of `step` (e.g. 0.01) by the largest-remainder method, so they sum to exactly 1.00 — ready for building alternate-conformation models in crystallographic refinement.
+//! - **Rotamer-recovery benchmark.** `rotamer_recovery::<R>(natives, Policy::MostProbable)` scores a prediction policy against native `(φ, ψ, χ)` records with the literature-standard statistics — χ1 and χ1+2 within 40°, all-χ, and rotamer-bin match rates, with symmetric terminal χ handled — so method developers can benchmark in one call.
+//! - **χ quantiles.** `Residue::chi_quantile(phi, psi, chi_index, q)` inverts the marginal CDF of a χ angle (a mixture of wrapped Gaussians), for confidence bands and flat-bottom restraints.
+//! - **Panic-free `try_` variants (`no-panic` feature).** `Residue::try_rotamers`, `try_rotamers_with`, `try_chi_quantile`, `Query::try_run`, and `RotamerIter::try_chi_quantile` report NaN angles, out-of-range χ indices, and invalid quantile levels as a typed `Error` instead of clamping or panicking. With the `no-panic` feature, optimized unwinding builds (`codegen-units = 1` or LTO) fail to link unless the compiler proves that `rotamers`, `rotamers_with` for the built-in interpolators, and the `try_` variants cannot panic — for safety-certified firmware.
+//! - **Wrap-aware χ intervals.** `Rotamer::chi_interval(i, k_sigma)` returns `mean ± kσ` bounds that correctly straddle ±180°, ready for torsion restraint ranges.
+//! - **Flexible rotamers.** `RotamerIter::flex(k_sigma)` and `Rotamer::flex` widen rotamers into SCWRL-style `FlexRotamer`s — the χ means plus one `mean ± kσ` `BinRange` per χ — with `contains`, `distance`, `clamp`, and `overlaps` helpers for soft-packing algorithms.
 //! - **Precomputed (sin χ, cos χ) in the static table.** `build.rs` stores sin/cos pairs rather than raw angles, eliminating 8N trigonometric calls per query (4 sin + 4 cos per χ angle, per corner cell).
+//! - **Compact grid storage.** Tables keep only the 36 distinct φ and ψ grid points (the +180° row and column repeat −180° and are read through a periodic index), and `build.rs` stores bit-identical cells once behind a 36×36 cell index wherever that shrinks a residue's data; a lookup costs one extra indirection.
+//! - **Delta-encoded tables (`delta-tables` feature).** The primary tables quantized to the source precision, each cell stored as varint differences from its ψ neighbor: about 5.5 MB against ~27 MB of `f32` tables. With `delta-tables`, `build.rs` embeds every table it can encode losslessly this way instead of as `f32` values, and each residue's table is decoded into the heap on first use, bit for bit the table the default build embeds. Independently of the feature, `write_delta_tables` (`std` feature) writes the same encoding as a file, and `DeltaTables` decodes such a file row by row as the `TableStorage` of a `StoredTables`, so firmware that queries only through `StoredTables` can keep the library compressed in off-chip flash.
 //! - **Custom branchless `atan2f`.** A two-stage argument-reduction + degree-7 Taylor polynomial implementation with zero conditional branches and ±0.002° maximum error — 25× more accurate than the 0.05° precision requirement, with no libm dependency.
-//! - **Compile-time data integrity.** `build.rs` asserts seven invariants before emitting any code: rotamer count, per-row non-negative probabilities, probability sums, per-χ positive standard deviations, φ/ψ = ±180° periodicity, and bin index consistency across all 1,369 grid cells. Compilation fails loudly on data corruption.
+//! - **Reproducible or fast arithmetic (`strict-math` / `fast-math` features).** The default build evaluates every interpolation sum strictly left to right with no fused multiply-add, so results are bit-identical across platforms and compilers; `strict-math` pins this even if another dependent enables `fast-math`, which splits sums into independent lanes and uses hardware FMA where the target has it, at the cost of last-ulp differences.
+//! - **WASM SIMD (`wasm-simd` feature).** On `wasm32` targets built with `simd128`, the bilinear and bicubic kernels evaluate all χ lanes of the four-corner gather in one 128-bit vector. Results stay bit-identical to the scalar path, so browser packing demos get the speedup without changing answers.
+//! - **Diagnostic logging (`trace` feature).** Emits `log` events under the `dunbrack` target for every table lookup (residue, φ, ψ, interpolation scheme), `Query` run, and pruning pass (threshold, rotamers kept), plus `debug`-level events for input rejected by the `try_` variants — so services embedding the crate can diagnose performance and correctness issues in production. Without the feature no logging code is compiled.
+//! - **Usage metrics (`metrics` feature).** Relaxed atomic counters record table lookups per residue type, `FineGridCache` and `StoredTables` cache hits and misses, and rotamers considered and kept by pruning; `Metrics::snapshot()` reads them all (with `cache_hit_ratio` and `prune_ratio` helpers) and `Metrics::reset()` zeroes them, so services can export Prometheus-style metrics about how the library is exercised.
+//! - **Benchmark workloads (`bench-util` feature).** `Workload::new(seed)` yields an endless, reproducible stream of `WorkloadQuery { residue, phi, psi }` with residue types at their PDB frequencies and backbones from a helix/strand/PPII/αL Ramachandran mixture, so pipelines built on the library can be benchmarked against representative input.
+//! - **Compile-time data integrity.** `build.rs` validates the CSV before emitting any code: the header, every field (finite numbers, φ/ψ on the 10° grid, probabilities in [0, 1], χ in [−180°, 180°]), known residue names, one row per rotamer with no duplicate bin keys, probability sums, per-χ positive standard deviations, φ/ψ = ±180° periodicity, and bin index consistency across all 1,369 grid cells. Compilation fails with the offending CSV line and column, or residue and grid cell, instead of generating subtly broken tables from a custom or corrupted CSV.
+//! - **Build-time data download (`fetch-data` feature).** `build.rs` downloads the library CSV instead of reading `data/dunbrack-2010.lib.csv`, and refuses to use it unless its SHA-256 matches the pinned hash. Set `DUNBRACK_DATA_URL` and `DUNBRACK_DATA_SHA256` to build against another distribution, such as a different smoothing level, in the same CSV schema; the verified file is cached in `OUT_DIR`, so rebuilds stay offline.
+//! - **Typed generic code (`generic-const-exprs` feature, nightly).** `TypedResidue` ties `Residue::Rot`, `Iter`, and `Chis` to `Rotamer<{ N_CHI }>`, `RotamerIter<{ N_CHI }, { N_ROTAMERS }>`, and `[f32; N_CHI]`, so generic code reads `rot.chi_mean[i]` directly instead of going through an accessor trait over `R::Rot`.
 //! - **`for_all_residues!` macro.** A generated declarative macro for writing generic code over all 22 residue types without runtime dispatch.
 //!
 //! ---
@@ -29,7 +119,7 @@
 //! dunbrack = "0.1.0"
 //! ```
 //!
-//! **Note:** `build.rs` reads `data/dunbrack-2010.lib.csv` (740,629 rows) and generates ~28 MB of static Rust source. Initial compilation takes 15–30 seconds depending on hardware.
+//! **Note:** `build.rs` reads `data/dunbrack-2010.lib.csv` (740,629 rows) and writes the tables as ~27 MB of binary blobs (about 5.5 MB with `delta-tables`) embedded via `include_bytes!`, alongside a small generated Rust source file. Only the build script pays the CSV parsing cost; the crate itself compiles in seconds.
 //!
 //! ---
 //!
@@ -220,21 +310,175 @@
 //! ```
 
 #![no_std]
+#![cfg_attr(
+    feature = "generic-const-exprs",
+    feature(generic_const_exprs),
+    allow(incomplete_features)
+)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+mod arith;
+mod bins;
+#[cfg(feature = "alloc")]
+mod cache;
+mod codec;
+mod diff;
+mod ensemble;
+mod error;
+mod flex;
+mod footprint;
+#[cfg(feature = "gradients")]
+mod gradient;
 mod grid;
 mod interp;
 mod math;
+#[cfg(feature = "std")]
+mod md;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod occupancy;
+#[cfg(feature = "alloc")]
+mod packing;
+mod query;
+mod recovery;
 mod residue;
+mod restraint;
+#[cfg(feature = "std")]
+mod rosetta;
 mod rotamer;
+mod sample;
+#[cfg(feature = "std")]
+mod scwrl;
 mod sealed;
+mod stats;
+#[cfg(feature = "candle")]
+mod tensor;
+#[cfg(feature = "trace")]
+mod trace;
+mod units;
 
 // Generated static tables and trait implementations.
 include!(concat!(env!("OUT_DIR"), "/tables.rs"));
 
-pub use interp::RotamerIter;
-pub use residue::Residue;
+// Declared after the generated tables so `for_all_residues!` is in scope.
+mod class;
+#[cfg(feature = "alloc")]
+mod collect;
+mod compare;
+#[cfg(feature = "alloc")]
+mod contour;
+mod delta;
+#[cfg(feature = "std")]
+mod export;
+mod fingerprint;
+#[cfg(feature = "alloc")]
+mod flexibility;
+mod geometry;
+mod gpu;
+mod id;
+mod identity;
+mod kind;
+#[cfg(feature = "alloc")]
+mod loops;
+#[cfg(feature = "mmap")]
+mod mapped;
+#[cfg(feature = "std")]
+mod precompute;
+mod records;
+mod storage;
+mod summary;
+mod topology;
+#[cfg(feature = "viz")]
+mod viz;
+#[cfg(feature = "bench-util")]
+mod workload;
+
+pub use bins::{BinDiscrepancy, BinRange, ChiBin};
+#[cfg(feature = "alloc")]
+pub use cache::FineGridCache;
+pub use class::{Aromatic, Charged, Class, Hydrophobic, Polar, Small};
+pub use codec::ENCODING_VERSION;
+#[cfg(feature = "alloc")]
+pub use collect::{ResidueRotamers, collect_all_residues};
+pub use compare::{Chi1Comparison, Comparison, compare, compare_chi1};
+#[cfg(feature = "alloc")]
+pub use contour::{Contour, isocontours};
+pub use delta::DeltaTables;
+#[cfg(feature = "std")]
+pub use delta::write_delta_tables;
+pub use diff::{BinDelta, BinFlow, BinTransition, RotamerDiff};
+pub use ensemble::{blend_cysteine, blend_proline, cis_probability};
+pub use error::{DunbrackError, Error};
+#[cfg(feature = "std")]
+pub use export::write_csv;
+pub use fingerprint::{fingerprint, verify_fingerprint};
+pub use flex::FlexRotamer;
+#[cfg(feature = "alloc")]
+pub use flexibility::{Flexibility, flexibility_profile};
+pub use footprint::TableFootprint;
+pub use geometry::{InternalCoord, Torsion};
+pub use gpu::{GPU_ARRAY_ALIGN, GpuTableLayout};
+#[cfg(feature = "gradients")]
+pub use gradient::GradientMap;
+pub use grid::{
+    GRID_COUNT, GRID_MIN, GRID_PERIOD, GRID_STEP, GridCell, GridPoint, Neighborhood, angle_to_grid,
+    grid_angle, grid_neighbors, grid_weights, nearest_grid_index, snap_to_grid,
+};
+pub use id::RotamerId;
+pub use identity::{ResidueInfo, canonical_name};
+#[cfg(feature = "fast-chi")]
+pub use interp::FastBilinear;
+pub use interp::{
+    Bicubic, Bilinear, BilinearDetail, GridEntry, Interpolator, Nearest, RotamerIter, Table,
+    Uncertainty,
+};
+pub use kind::{DynRotamers, ResidueContext, ResidueKind};
+#[cfg(feature = "alloc")]
+pub use loops::{LoopPosition, assign_rotamers, sample_loop, sample_loop_avoiding};
+#[cfg(feature = "mmap")]
+pub use mapped::{MappedTables, write_table_file};
+#[cfg(feature = "std")]
+pub use md::{write_gromacs_restraints, write_openmm_restraints};
+#[cfg(feature = "metrics")]
+pub use metrics::Metrics;
+pub use occupancy::{Conformer, MultiConformer};
+#[cfg(feature = "alloc")]
+pub use packing::{InteractionGraph, PairTable, RotamerIndex, SelfEnergy};
+#[cfg(feature = "std")]
+pub use precompute::{DerivedMaps, Precomputed, precompute};
+pub use query::{Mode, Normalization, OutOfRange, Progressive, Query};
+pub use records::{Record, Records, records};
+pub use recovery::{Policy, RECOVERY_TOLERANCE, RecoveryStats, rotamer_recovery};
+#[cfg(feature = "alt-data")]
+pub use residue::Alt;
+#[cfg(feature = "chi1")]
+pub use residue::Chi1;
+#[cfg(feature = "generic-const-exprs")]
+pub use residue::TypedResidue;
 pub use residue::{
     Arg, Asn, Asp, Cpr, Cyd, Cyh, Cys, Gln, Glu, His, Ile, Leu, Lys, Met, Phe, Pro, Ser, Thr, Tpr,
     Trp, Tyr, Val,
 };
+pub use residue::{Library, Residue};
+pub use restraint::{RestraintScale, TorsionRestraint};
+#[cfg(feature = "std")]
+pub use rosetta::write_rosetta_constraints;
 pub use rotamer::Rotamer;
+pub use sample::{Sample, SobolSampler, StratifiedSampler};
+#[cfg(feature = "std")]
+pub use scwrl::write_scwrl_rotamers;
+pub use stats::{ChiMoments, ChiSpread};
+pub use storage::{StoredTables, TableKey, TableStorage};
+pub use summary::{ResidueSummary, summarize, summarize_all};
+#[cfg(feature = "candle")]
+pub use tensor::{RotamerTensors, TableTensors};
+pub use topology::{Atom, Element, Topology};
+pub use units::EnergyUnits;
+#[cfg(feature = "viz")]
+pub use viz::{Heatmap, HeatmapKind};
+#[cfg(feature = "bench-util")]
+pub use workload::{Workload, WorkloadQuery};
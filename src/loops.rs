@@ -0,0 +1,301 @@
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use crate::error::Error;
+use crate::identity::ResidueInfo;
+use crate::residue::*;
+use crate::rotamer::Rotamer;
+use crate::sample::{Sample, splitmix64};
+
+/// One position of a remodeled loop: its residue type and backbone
+/// dihedral angles in degrees.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoopPosition {
+    /// Residue type, an entry of [`ALL_RESIDUES`](crate::ALL_RESIDUES).
+    pub residue: &'static ResidueInfo,
+    /// Backbone φ in degrees.
+    pub phi: f32,
+    /// Backbone ψ in degrees.
+    pub psi: f32,
+}
+
+/// Draw one side-chain conformation for every position of a loop, in
+/// order, with χ arrays padded to four slots.
+///
+/// Each position draws from its own Sobol sampler (see
+/// [`RotamerIter::sobol`](crate::RotamerIter::sobol)), scrambled by `seed`
+/// and the position index, so the result is reproducible and positions
+/// with the same residue type and backbone still draw independently.
+/// Requires the `alloc` feature.
+///
+/// # Errors
+///
+/// [`Error::NonFiniteAngle`] if any φ or ψ is NaN or infinite;
+/// [`Error::UnknownResidue`] if a residue is not named in
+/// [`ALL_RESIDUES`](crate::ALL_RESIDUES).
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{LoopPosition, ResidueInfo, sample_loop};
+///
+/// let stretch = [("LEU", -65.0, -40.0), ("SER", -80.0, 150.0), ("ARG", -120.0, 130.0)]
+///     .map(|(name, phi, psi)| LoopPosition {
+///         residue: ResidueInfo::lookup(name).unwrap(),
+///         phi,
+///         psi,
+///     });
+/// let chis = sample_loop(&stretch, 7).unwrap();
+/// assert_eq!(chis.len(), 3);
+/// assert_eq!(chis[1].chi[1..], [0.0; 3]); // Ser has one χ
+/// ```
+pub fn sample_loop(positions: &[LoopPosition], seed: u64) -> Result<Vec<Sample<4>>, Error> {
+    sample_loop_avoiding(positions, seed, 1, |_, _, _| false)
+}
+
+/// Like [`sample_loop`], but reject conformations that fail a clash test,
+/// so the loop's side chains are placed consistently with each other and
+/// their environment.
+///
+/// Positions are placed in order. For position `i`, `clashes(i, candidate,
+/// placed)` sees a candidate conformation and the conformations already
+/// accepted for positions `0..i`, and returns `true` to reject it; up to
+/// `max_attempts` successive Sobol draws are tried, so more probable
+/// rotamers are offered first in proportion to their probability.
+/// Requires the `alloc` feature.
+///
+/// # Errors
+///
+/// As for [`sample_loop`], plus [`Error::NoAcceptableSample`] if all
+/// `max_attempts` draws of a position clash.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{LoopPosition, ResidueInfo, sample_loop_avoiding};
+///
+/// let val = ResidueInfo::lookup("VAL").unwrap();
+/// let stretch = [LoopPosition { residue: val, phi: -65.0, psi: -40.0 }; 3];
+///
+/// // A toy clash test: neighbours may not share a χ1 bin.
+/// let chis = sample_loop_avoiding(&stretch, 1, 64, |_, candidate, placed| {
+///     placed.last().is_some_and(|prev| prev.r[0] == candidate.r[0])
+/// })
+/// .unwrap();
+/// assert!(chis.windows(2).all(|w| w[0].r[0] != w[1].r[0]));
+/// ```
+pub fn sample_loop_avoiding(
+    positions: &[LoopPosition],
+    seed: u64,
+    max_attempts: u32,
+    mut clashes: impl FnMut(usize, &Sample<4>, &[Sample<4>]) -> bool,
+) -> Result<Vec<Sample<4>>, Error> {
+    let mut placed = Vec::with_capacity(positions.len());
+    for (i, pos) in positions.iter().enumerate() {
+        // Decorrelate positions with the golden-ratio increment.
+        let pos_seed = seed.wrapping_add((i as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+        let accepted = draws(pos, pos_seed, max_attempts)?
+            .find(|candidate| !clashes(i, candidate, &placed))
+            .ok_or(Error::NoAcceptableSample { position: i })?;
+        placed.push(accepted);
+    }
+    Ok(placed)
+}
+
+/// Assign one discrete rotamer to every position of a sequence, drawn in
+/// proportion to its library probability at the position's backbone, for
+/// reproducible decoy structures and test fixtures.
+///
+/// The draw depends only on `seed`, the position index, and the position
+/// itself, so the same sequence and seed always give the same assignment.
+/// Returned rotamers carry their interpolated χ means, σ, and probability
+/// at the position, padded to four χ slots. Requires the `alloc` feature.
+///
+/// # Errors
+///
+/// As for [`sample_loop`].
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{LoopPosition, ResidueInfo, assign_rotamers};
+///
+/// let helix: Vec<_> = ["LEU", "GLU", "LYS", "ARG", "VAL", "TRP"]
+///     .iter()
+///     .map(|name| LoopPosition {
+///         residue: ResidueInfo::lookup(name).unwrap(),
+///         phi: -63.0,
+///         psi: -43.0,
+///     })
+///     .collect();
+/// let rotamers = assign_rotamers(&helix, 2024).unwrap();
+/// assert_eq!(rotamers.len(), 6);
+/// assert_eq!(rotamers, assign_rotamers(&helix, 2024).unwrap());
+/// assert!(rotamers.iter().all(|rot| rot.prob > 0.0));
+/// ```
+pub fn assign_rotamers(positions: &[LoopPosition], seed: u64) -> Result<Vec<Rotamer<4>>, Error> {
+    positions
+        .iter()
+        .enumerate()
+        .map(|(i, pos)| {
+            let mut state = seed.wrapping_add((i as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15));
+            let u = (splitmix64(&mut state) >> 40) as f32 * (1.0 / (1u32 << 24) as f32);
+            pick(pos, u)
+        })
+        .collect()
+}
+
+/// The rotamer of one position at cumulative probability fraction `u` in
+/// \[0, 1), padded to four χ slots.
+fn pick(pos: &LoopPosition, u: f32) -> Result<Rotamer<4>, Error> {
+    macro_rules! dispatch {
+        ($Res:ident, $n:literal, $r:literal) => {
+            if pos.residue.name == <$Res as Residue>::NAME {
+                let rotamers = <$Res as Residue>::try_rotamers(pos.phi, pos.psi)?;
+                let mut target = u * rotamers.total_prob();
+                let slice = rotamers.as_slice();
+                let chosen = slice
+                    .iter()
+                    .find(|rot| {
+                        target -= rot.prob;
+                        target < 0.0
+                    })
+                    .or_else(|| slice.iter().rev().find(|rot| rot.prob > 0.0))
+                    .unwrap_or(&slice[0]);
+                return Ok(chosen.padded());
+            }
+        };
+    }
+    for_all_residues!(dispatch);
+    Err(Error::UnknownResidue)
+}
+
+/// The first `count` Sobol draws of one position, padded to four χ slots,
+/// drawn as the caller takes them.
+fn draws(
+    pos: &LoopPosition,
+    seed: u64,
+    count: u32,
+) -> Result<Box<dyn Iterator<Item = Sample<4>>>, Error> {
+    macro_rules! dispatch {
+        ($Res:ident, $n:literal, $r:literal) => {
+            if pos.residue.name == <$Res as Residue>::NAME {
+                let sampler = <$Res as Residue>::try_rotamers(pos.phi, pos.psi)?.sobol(seed);
+                return Ok(Box::new(
+                    (0..count).filter_map(move |k| sampler.get(k)).map(|s| {
+                        let mut padded = Sample {
+                            r: [0; 4],
+                            chi: [0.0; 4],
+                        };
+                        padded.r[..$n].copy_from_slice(&s.r);
+                        padded.chi[..$n].copy_from_slice(&s.chi);
+                        padded
+                    }),
+                ));
+            }
+        };
+    }
+    for_all_residues!(dispatch);
+    Err(Error::UnknownResidue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(name: &str, phi: f32, psi: f32) -> LoopPosition {
+        LoopPosition {
+            residue: ResidueInfo::lookup(name).unwrap(),
+            phi,
+            psi,
+        }
+    }
+
+    #[test]
+    fn test_every_residue_type_samples() {
+        let stretch: Vec<_> = crate::ALL_RESIDUES
+            .iter()
+            .map(|residue| LoopPosition {
+                residue,
+                phi: -65.0,
+                psi: -40.0,
+            })
+            .collect();
+        let chis = sample_loop(&stretch, 3).unwrap();
+        for (sample, pos) in chis.iter().zip(&stretch) {
+            let n = pos.residue.n_chi;
+            assert!(sample.r[..n].iter().all(|&bin| bin > 0));
+            assert_eq!(sample.r[n..], [0; 4][n..]);
+            assert!(
+                sample.chi[..n]
+                    .iter()
+                    .all(|chi| (-180.0..180.0).contains(chi))
+            );
+        }
+        assert_eq!(sample_loop(&stretch, 3), Ok(chis));
+    }
+
+    #[test]
+    fn test_positions_draw_independently() {
+        let stretch = [position("ARG", -65.0, -40.0); 4];
+        let chis = sample_loop(&stretch, 0).unwrap();
+        assert!(chis.windows(2).all(|w| w[0] != w[1]));
+    }
+
+    #[test]
+    fn test_assignment_follows_library_weights() {
+        // Val in a helix: trans (r = 2) dominates.
+        let stretch = [position("VAL", -63.0, -43.0); 2000];
+        let rotamers = assign_rotamers(&stretch, 11).unwrap();
+        let p_trans = Val::rotamers(-63.0, -43.0)
+            .find(|rot| rot.r == [2])
+            .unwrap()
+            .prob;
+        let trans = rotamers.iter().filter(|rot| rot.r[0] == 2).count() as f32;
+        assert!((trans / 2000.0 - p_trans).abs() < 0.05, "{trans}");
+        assert_ne!(assign_rotamers(&stretch, 12).unwrap(), rotamers);
+        assert_eq!(
+            assign_rotamers(&[position("SER", 0.0, f32::INFINITY)], 0),
+            Err(Error::NonFiniteAngle)
+        );
+    }
+
+    #[test]
+    fn test_clash_test_sees_placed_positions() {
+        let stretch = [
+            position("LEU", -65.0, -40.0),
+            position("VAL", -120.0, 130.0),
+        ];
+        let mut seen = Vec::new();
+        let chis = sample_loop_avoiding(&stretch, 5, 16, |i, _, placed| {
+            seen.push((i, placed.len()));
+            false
+        })
+        .unwrap();
+        assert_eq!(seen, [(0, 0), (1, 1)]);
+        assert_eq!(chis, sample_loop(&stretch, 5).unwrap());
+
+        assert_eq!(
+            sample_loop_avoiding(&stretch, 5, 16, |i, _, _| i == 1),
+            Err(Error::NoAcceptableSample { position: 1 })
+        );
+        assert_eq!(
+            sample_loop(&[position("SER", f32::NAN, 0.0)], 0),
+            Err(Error::NonFiniteAngle)
+        );
+    }
+
+    #[test]
+    fn test_draws_stop_at_first_acceptable() {
+        // Drawing all u32::MAX attempts up front would never finish.
+        let stretch = [position("ARG", -65.0, -40.0)];
+        let mut tried = 0;
+        let chis = sample_loop_avoiding(&stretch, 9, u32::MAX, |_, _, _| {
+            tried += 1;
+            tried < 3
+        })
+        .unwrap();
+        assert_eq!(tried, 3);
+        assert_eq!(chis.len(), 1);
+    }
+}
@@ -0,0 +1,409 @@
+use std::fs::File;
+use std::io::{self, Write};
+use std::path::Path;
+use std::vec::Vec;
+
+use memmap2::Mmap;
+
+use crate::error::Error;
+use crate::export::Row;
+use crate::grid::GRID_PERIOD;
+use crate::interp::{GridEntry, Table};
+use crate::query::Query;
+use crate::residue::*;
+
+const MAGIC: &[u8; 4] = b"DBTF";
+
+/// Version byte written by [`write_table_file`] and required by
+/// [`MappedTables::open`].
+const VERSION: u8 = 2;
+
+/// Written in native byte order; reads back differently on a host of the
+/// other endianness.
+const BYTE_ORDER_MARK: u32 = 0x0102_0304;
+
+const HEADER_LEN: usize = 16;
+const ENTRY_LEN: usize = 16;
+
+/// Table offsets are multiples of this, so every table is aligned for
+/// `f32` reads in a page-aligned mapping.
+const TABLE_ALIGN: usize = 16;
+
+/// Shape of one residue's table: name, χ count, rotamer count.
+type Shape = (&'static str, usize, usize);
+
+/// Shapes of the compiled-in primary residue types, in
+/// [`for_all_residues!`](crate::for_all_residues) order.
+fn shapes() -> Vec<Shape> {
+    let mut shapes = Vec::with_capacity(22);
+    macro_rules! push {
+        ($Res:ident, $n:literal, $r:literal) => {
+            shapes.push((<$Res as Residue>::NAME, $n, $r));
+        };
+    }
+    for_all_residues!(push);
+    shapes
+}
+
+/// Bytes of the table of a residue with `n_chi` χ angles and `n_rotamers`
+/// rotamers.
+fn table_len(n_chi: usize, n_rotamers: usize) -> usize {
+    GRID_PERIOD * GRID_PERIOD * n_rotamers * (1 + 3 * n_chi) * 4
+}
+
+/// Write every embedded primary rotamer table to `out` in the table file
+/// format read by [`MappedTables::open`].
+///
+/// Only the [`Library::Primary`] tables are written; the `Chi1<R>` and
+/// `Alt<R>` variants share their residue names and stay embedded.
+///
+/// The tables are written in native byte order as dense grids of 36 × 36
+/// cells, φ-major, each cell laid out in memory as the embedded cells are,
+/// after a 16-byte header and a 16-byte directory entry per residue type:
+///
+/// | Offset | Size | Field                                      |
+/// |--------|------|--------------------------------------------|
+/// | 0      | 4    | magic `b"DBTF"`                            |
+/// | 4      | 1    | format version (`2`)                       |
+/// | 5      | 1    | residue type count                         |
+/// | 6      | 2    | reserved, zero                             |
+/// | 8      | 4    | byte-order mark `0x01020304` (`u32`)       |
+/// | 12     | 4    | reserved, zero                             |
+///
+/// Each directory entry holds the residue name (4 bytes, NUL-padded), the
+/// χ count (`u8`), a reserved byte, the rotamer count (`u16`), and the
+/// table's offset and length in bytes (`u32` each). Tables start at
+/// 16-byte-aligned offsets.
+///
+/// Requires the `mmap` feature.
+///
+/// # Errors
+///
+/// Returns any error reported by `out`.
+pub fn write_table_file<W: Write>(out: W) -> io::Result<()> {
+    let mut out = io::BufWriter::new(out);
+    let shapes = shapes();
+    let mut header = [0_u8; HEADER_LEN];
+    header[..4].copy_from_slice(MAGIC);
+    header[4] = VERSION;
+    header[5] = shapes.len() as u8;
+    header[8..12].copy_from_slice(&BYTE_ORDER_MARK.to_ne_bytes());
+    out.write_all(&header)?;
+
+    let directory_end = HEADER_LEN + shapes.len() * ENTRY_LEN;
+    let mut offset = directory_end.next_multiple_of(TABLE_ALIGN);
+    for &(name, n_chi, n_rotamers) in &shapes {
+        let len = table_len(n_chi, n_rotamers);
+        let mut entry = [0_u8; ENTRY_LEN];
+        entry[..name.len()].copy_from_slice(name.as_bytes());
+        entry[4] = n_chi as u8;
+        entry[6..8].copy_from_slice(&(n_rotamers as u16).to_ne_bytes());
+        entry[8..12].copy_from_slice(&(offset as u32).to_ne_bytes());
+        entry[12..16].copy_from_slice(&(len as u32).to_ne_bytes());
+        out.write_all(&entry)?;
+        offset = (offset + len).next_multiple_of(TABLE_ALIGN);
+    }
+    out.write_all(
+        &[0; TABLE_ALIGN][..directory_end.next_multiple_of(TABLE_ALIGN) - directory_end],
+    )?;
+
+    let mut result = Ok(());
+    macro_rules! write_table {
+        ($Res:ident, $n:literal, $r:literal) => {
+            <$Res as crate::sealed::Sealed>::visit_rows(&mut |row| {
+                // The +180° rows repeat the −180° ones and are not stored.
+                let stored = row.phi_idx < GRID_PERIOD && row.psi_idx < GRID_PERIOD;
+                if stored && result.is_ok() {
+                    result = write_entry(&mut out, &row, $n);
+                }
+            });
+            let len = table_len($n, $r);
+            if result.is_ok() {
+                result =
+                    out.write_all(&[0; TABLE_ALIGN][..len.next_multiple_of(TABLE_ALIGN) - len]);
+            }
+        };
+    }
+    for_all_residues!(write_table);
+    result?;
+    out.flush()
+}
+
+/// Writes one `GridEntry` with `n_chi` χ angles in native byte order.
+fn write_entry(out: &mut impl Write, row: &Row, n_chi: usize) -> io::Result<()> {
+    out.write_all(&row.prob.to_ne_bytes())?;
+    for values in [&row.chi_sin, &row.chi_cos, &row.chi_sigma] {
+        for value in &values[..n_chi] {
+            out.write_all(&value.to_ne_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// Rotamer tables memory-mapped from a file written by
+/// [`write_table_file`].
+///
+/// Every process that maps the same file shares one copy of the data
+/// through the operating system's page cache, even across different
+/// binaries, each of which would otherwise page in its own embedded copy.
+/// The embedded statics are still linked into a binary that queries them
+/// through [`Residue`] as well. Queries are evaluated directly on the
+/// mapped bytes and match the embedded tables exactly. Table files hold the
+/// primary library only, so the `Chi1<R>` and `Alt<R>` variant types are
+/// reported as [`Error::UnknownResidue`].
+///
+/// Requires the `mmap` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{MappedTables, Query, Residue, Val, write_table_file};
+///
+/// let path = std::env::temp_dir().join("dunbrack-doc-tables.bin");
+/// write_table_file(std::fs::File::create(&path)?)?;
+///
+/// let tables = MappedTables::open(&path)?;
+/// assert!(tables.rotamers::<Val>(-60.0, -40.0)?.eq(Val::rotamers(-60.0, -40.0)));
+/// let pruned = tables.query::<Val>(&Query::new(-60.0, -40.0).prune(0.1))?;
+/// assert!(pruned.len() < 3);
+/// # std::fs::remove_file(&path)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct MappedTables {
+    map: Mmap,
+    /// Shape and byte range of each residue's table, in
+    /// `for_all_residues!` order.
+    tables: Vec<(Shape, usize, usize)>,
+}
+
+impl MappedTables {
+    /// Map the table file at `path` and validate its layout against the
+    /// compiled-in residue types.
+    ///
+    /// # Errors
+    ///
+    /// Any I/O error from opening or mapping the file, or an error of kind
+    /// [`io::ErrorKind::InvalidData`] wrapping an [`Error`]:
+    /// [`Error::MalformedEncoding`] if the magic is wrong or the file is
+    /// truncated or misaligned, [`Error::UnsupportedVersion`] for another
+    /// format version, and [`Error::DataMismatch`] if the byte order or
+    /// any residue's table shape differs from this build.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the mapping is read-only. Modifying or truncating the
+        // file while it is mapped is undefined behavior, as with every
+        // memory-mapped file; table files are meant to be written once and
+        // deployed read-only.
+        let map = unsafe { Mmap::map(&file)? };
+        let tables =
+            validate(&map).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        Ok(Self { map, tables })
+    }
+
+    /// Bilinearly interpolated rotamers of residue type `R`, as
+    /// [`Residue::rotamers`] computes them from the embedded tables.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::UnknownResidue`] if the file holds no table of `R`, as for
+    /// the `Chi1<R>` and `Alt<R>` variants.
+    pub fn rotamers<R: Residue>(&self, phi: f32, psi: f32) -> Result<R::Iter, Error> {
+        self.query::<R>(&Query::new(phi, psi))
+    }
+
+    /// Execute a configured [`Query`] for residue type `R`, as
+    /// [`Query::run`] does on the embedded tables.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::UnknownResidue`] if the file holds no table of `R`, as for
+    /// the `Chi1<R>` and `Alt<R>` variants.
+    pub fn query<R: Residue>(&self, query: &Query) -> Result<R::Iter, Error> {
+        let shape = (R::NAME, R::N_CHI, R::N_ROTAMERS);
+        let &(_, start, end) = self
+            .tables
+            .iter()
+            .filter(|_| R::LIBRARY == Library::Primary)
+            .find(|&&(stored, _, _)| stored == shape)
+            .ok_or(Error::UnknownResidue)?;
+        Ok(R::mapped_query(&self.map[start..end], query))
+    }
+}
+
+/// Checks the header and directory of a table file, returning each
+/// residue's shape and byte range.
+fn validate(bytes: &[u8]) -> Result<Vec<(Shape, usize, usize)>, Error> {
+    if bytes.len() < HEADER_LEN || &bytes[..4] != MAGIC {
+        return Err(Error::MalformedEncoding);
+    }
+    if bytes[4] != VERSION {
+        return Err(Error::UnsupportedVersion(bytes[4]));
+    }
+    let u32_at =
+        |at: usize| u32::from_ne_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+    if u32_at(8) != BYTE_ORDER_MARK {
+        return Err(Error::DataMismatch);
+    }
+    let shapes = shapes();
+    if usize::from(bytes[5]) != shapes.len() {
+        return Err(Error::DataMismatch);
+    }
+    if bytes.len() < HEADER_LEN + shapes.len() * ENTRY_LEN {
+        return Err(Error::MalformedEncoding);
+    }
+
+    let mut tables = Vec::with_capacity(shapes.len());
+    for (i, &shape) in shapes.iter().enumerate() {
+        let (name, n_chi, n_rotamers) = shape;
+        let at = HEADER_LEN + i * ENTRY_LEN;
+        let entry = &bytes[at..at + ENTRY_LEN];
+        let stored_name = &entry[..4];
+        let stored_rotamers = usize::from(u16::from_ne_bytes([entry[6], entry[7]]));
+        if &stored_name[..name.len()] != name.as_bytes()
+            || stored_name[name.len()..].iter().any(|&b| b != 0)
+            || usize::from(entry[4]) != n_chi
+            || stored_rotamers != n_rotamers
+        {
+            return Err(Error::DataMismatch);
+        }
+        let (offset, len) = (u32_at(at + 8) as usize, u32_at(at + 12) as usize);
+        if len != table_len(n_chi, n_rotamers)
+            || offset % TABLE_ALIGN != 0
+            || offset.checked_add(len).is_none_or(|end| end > bytes.len())
+        {
+            return Err(Error::MalformedEncoding);
+        }
+        tables.push((shape, offset, offset + len));
+    }
+    Ok(tables)
+}
+
+/// Reinterprets a validated table slice as a dense [`Table`].
+///
+/// # Panics
+///
+/// Panics if `bytes` is not exactly one table long or not aligned for
+/// `f32`, which [`validate`], the shape lookup of [`MappedTables::query`],
+/// and the page alignment of the mapping rule out.
+pub(crate) fn table<const N: usize, const R: usize>(bytes: &[u8]) -> Table<'_, N, R> {
+    type Cell<const N: usize, const R: usize> = [GridEntry<N>; R];
+    let cells = GRID_PERIOD * GRID_PERIOD;
+    assert_eq!(bytes.len(), cells * core::mem::size_of::<Cell<N, R>>());
+    assert_eq!(
+        bytes
+            .as_ptr()
+            .align_offset(core::mem::align_of::<Cell<N, R>>()),
+        0
+    );
+    // SAFETY: the length and alignment match `cells` cells, which consist
+    // solely of `f32` fields (`GridEntry` is `#[repr(C)]`), so every bit
+    // pattern is a valid value, and the borrow keeps the bytes alive.
+    let cells = unsafe { core::slice::from_raw_parts(bytes.as_ptr().cast::<Cell<N, R>>(), cells) };
+    Table::dense(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::Mode;
+    use std::path::PathBuf;
+
+    fn temp_file(tag: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(std::format!(
+            "dunbrack-mapped-{tag}-{}.bin",
+            std::process::id()
+        ));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn table_file() -> Vec<u8> {
+        let mut bytes = Vec::new();
+        write_table_file(&mut bytes).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn test_mapped_queries_match_embedded() {
+        let path = temp_file("match", &table_file());
+        let tables = MappedTables::open(&path).unwrap();
+        let query = Query::new(-63.0, 141.0)
+            .interpolation(Mode::Bicubic)
+            .sorted(true);
+        assert!(tables.query::<Arg>(&query).unwrap().eq(query.run::<Arg>()));
+        assert!(
+            tables
+                .rotamers::<Trp>(55.0, 40.0)
+                .unwrap()
+                .eq(Trp::rotamers(55.0, 40.0))
+        );
+        assert!(
+            tables
+                .rotamers::<Cpr>(-75.0, 150.0)
+                .unwrap()
+                .eq(Cpr::rotamers(-75.0, 150.0))
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "chi1")]
+    #[test]
+    fn test_chi1_variants_are_not_mapped() {
+        let path = temp_file("chi1", &table_file());
+        let tables = MappedTables::open(&path).unwrap();
+        assert_eq!(
+            tables.rotamers::<Chi1<Arg>>(-65.0, -43.0).err(),
+            Some(Error::UnknownResidue)
+        );
+        assert_eq!(
+            tables.rotamers::<Chi1<Val>>(-65.0, -43.0).err(),
+            Some(Error::UnknownResidue)
+        );
+        assert!(tables.rotamers::<Arg>(-65.0, -43.0).is_ok());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[cfg(feature = "alt-data")]
+    #[test]
+    fn test_alt_variants_are_not_mapped() {
+        let path = temp_file("alt", &table_file());
+        let tables = MappedTables::open(&path).unwrap();
+        assert_eq!(
+            tables.rotamers::<Alt<Leu>>(-65.0, -43.0).err(),
+            Some(Error::UnknownResidue)
+        );
+        assert!(
+            tables
+                .rotamers::<Leu>(-65.0, -43.0)
+                .unwrap()
+                .eq(Leu::rotamers(-65.0, -43.0))
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let bytes = table_file();
+        let reason = |bytes: &[u8]| validate(bytes).err();
+        assert_eq!(reason(&bytes[..100]), Some(Error::MalformedEncoding));
+        assert_eq!(
+            reason(&bytes[..bytes.len() - 4]),
+            Some(Error::MalformedEncoding)
+        );
+
+        let mut bad = bytes.clone();
+        bad[4] = 1;
+        assert_eq!(reason(&bad), Some(Error::UnsupportedVersion(1)));
+        let mut bad = bytes.clone();
+        bad[8..12].reverse();
+        assert_eq!(reason(&bad), Some(Error::DataMismatch));
+        let mut bad = bytes.clone();
+        bad[HEADER_LEN + 4] += 1;
+        assert_eq!(reason(&bad), Some(Error::DataMismatch));
+
+        let path = temp_file("bad", &bad);
+        let err = MappedTables::open(&path).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -40,6 +40,208 @@ pub fn atan2f(y: f32, x: f32) -> f32 {
     f32::copysign(if x < 0.0 { PI - r } else { r }, y)
 }
 
+/// Computes the natural logarithm of `x` by splitting off the binary exponent
+/// and evaluating `ln(m) = 2·atanh((m − 1)/(m + 1))` on the mantissa with a
+/// degree-9 odd series.
+///
+/// # Precondition
+///
+/// `x` is positive and normal.
+#[inline]
+pub fn lnf(x: f32) -> f32 {
+    use core::f32::consts::{LN_2, SQRT_2};
+
+    let bits = x.to_bits();
+    let mut e = ((bits >> 23) & 0xff) as i32 - 127;
+    let mut m = f32::from_bits((bits & 0x007f_ffff) | 0x3f80_0000);
+    if m > SQRT_2 {
+        m *= 0.5;
+        e += 1;
+    }
+
+    let s = (m - 1.0) / (m + 1.0);
+    let s2 = s * s;
+    let p = 2.0 * s * (1.0 + s2 * (1.0 / 3.0 + s2 * (1.0 / 5.0 + s2 * (1.0 / 7.0 + s2 / 9.0))));
+
+    e as f32 * LN_2 + p
+}
+
+/// Computes the Shannon entropy `−Σ p·ln p` of a discrete distribution in
+/// nats, skipping zero probabilities.
+#[inline]
+pub fn entropy(probs: impl IntoIterator<Item = f32>) -> f32 {
+    let mut h = 0.0_f32;
+    for p in probs {
+        if p > 0.0 {
+            h -= p * lnf(p);
+        }
+    }
+    h
+}
+
+/// Computes `√x` from a bit-level initial estimate refined by three Newton
+/// steps.
+///
+/// # Precondition
+///
+/// `x` is non-negative and finite.
+#[inline]
+pub fn sqrtf(x: f32) -> f32 {
+    if x == 0.0 {
+        return 0.0;
+    }
+    let mut y = f32::from_bits(0x1fbd_1df5 + (x.to_bits() >> 1));
+    y = 0.5 * (y + x / y);
+    y = 0.5 * (y + x / y);
+    0.5 * (y + x / y)
+}
+
+/// Computes the standard normal quantile `Φ⁻¹(p)` with Acklam's rational
+/// approximation (central region plus two tails), evaluated in `f64` to
+/// avoid cancellation in the central polynomial.
+///
+/// # Precondition
+///
+/// `p ∈ (0, 1)`.
+pub fn probit(p: f32) -> f32 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f32 = 0.02425;
+
+    let tail = |q: f32| {
+        let t = sqrtf(-2.0 * lnf(q)) as f64;
+        ((((((C[0] * t + C[1]) * t + C[2]) * t + C[3]) * t + C[4]) * t + C[5])
+            / ((((D[0] * t + D[1]) * t + D[2]) * t + D[3]) * t + 1.0)) as f32
+    };
+
+    if p < P_LOW {
+        tail(p)
+    } else if p > 1.0 - P_LOW {
+        -tail(1.0 - p)
+    } else {
+        let q = p as f64 - 0.5;
+        let r = q * q;
+        ((((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)) as f32
+    }
+}
+
+/// Wraps an angle in degrees into [−180°, 180°).
+#[inline]
+pub fn wrap_deg(deg: f32) -> f32 {
+    let mut w = (deg + 180.0) % 360.0;
+    if w < 0.0 {
+        w += 360.0;
+    }
+    if w >= 360.0 { -180.0 } else { w - 180.0 }
+}
+
+/// Computes `(sin x, cos x)` for an angle `x` in degrees.
+///
+/// Wraps into \[−180°, 180°), reduces to `t ∈ [−π/4, π/4]` by quarter turns,
+/// and evaluates degree-7 / degree-8 Taylor polynomials on `t`. Maximum
+/// error is about 4×10⁻⁷.
+#[inline]
+pub fn sincos_deg(x: f32) -> (f32, f32) {
+    use core::f32::consts::{FRAC_2_PI, FRAC_PI_2};
+
+    let r = wrap_deg(x) * (core::f32::consts::PI / 180.0);
+    let q = if r >= 0.0 {
+        (r * FRAC_2_PI + 0.5) as i32
+    } else {
+        (r * FRAC_2_PI - 0.5) as i32
+    };
+    let t = r - q as f32 * FRAC_PI_2;
+    let t2 = t * t;
+
+    let s = t * (1.0 + t2 * (-1.0 / 6.0 + t2 * (1.0 / 120.0 + t2 * (-1.0 / 5040.0))));
+    let c = 1.0 + t2 * (-0.5 + t2 * (1.0 / 24.0 + t2 * (-1.0 / 720.0 + t2 / 40320.0)));
+
+    match q & 3 {
+        0 => (s, c),
+        1 => (c, -s),
+        2 => (-s, -c),
+        _ => (-c, s),
+    }
+}
+
+/// Computes `eˣ` by splitting `x = k·ln 2 + r` (Cody–Waite) with `|r| ≤ ln 2 / 2` and
+/// evaluating a degree-6 Taylor polynomial on `r`. Returns `0.0` below the
+/// normal `f32` range.
+///
+/// # Precondition
+///
+/// `x ≤ 88`.
+#[inline]
+pub fn expf(x: f32) -> f32 {
+    use core::f32::consts::LOG2_E;
+
+    // ln 2 split so that `k · LN2_HI` is exact for the `k` that occur.
+    const LN2_HI: f32 = 0.693_145_75;
+    const LN2_LO: f32 = 1.428_606_8e-6;
+
+    if x < -87.0 {
+        return 0.0;
+    }
+    let y = x * LOG2_E;
+    let k = if y >= 0.0 {
+        (y + 0.5) as i32
+    } else {
+        (y - 0.5) as i32
+    };
+    let r = (x - k as f32 * LN2_HI) - k as f32 * LN2_LO;
+    let p = 1.0
+        + r * (1.0
+            + r * (0.5 + r * (1.0 / 6.0 + r * (1.0 / 24.0 + r * (1.0 / 120.0 + r / 720.0)))));
+    p * f32::from_bits(((k + 127) as u32) << 23)
+}
+
+/// Computes the standard normal CDF `Φ(z)` via the Abramowitz–Stegun 7.1.26
+/// approximation of `erfc` (absolute error below 1.5×10⁻⁷).
+pub fn normal_cdf(z: f32) -> f32 {
+    const P: f32 = 0.327_591_1;
+    const A: [f32; 5] = [
+        0.254_829_6,
+        -0.284_496_74,
+        1.421_413_7,
+        -1.453_152_1,
+        1.061_405_4,
+    ];
+
+    let x = z.abs() * core::f32::consts::FRAC_1_SQRT_2;
+    let t = 1.0 / (1.0 + P * x);
+    let poly = t * (A[0] + t * (A[1] + t * (A[2] + t * (A[3] + t * A[4]))));
+    let tail = 0.5 * poly * expf(-x * x);
+    if z >= 0.0 { 1.0 - tail } else { tail }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,4 +316,73 @@ mod tests {
             "max atan2f error {max_err:.2e} rad exceeds 5e-5 bound (~0.003°)"
         );
     }
+
+    #[test]
+    fn test_lnf_accuracy() {
+        for k in 1..2000 {
+            let x = k as f32 * 0.013;
+            assert_relative_eq!(lnf(x), x.ln(), epsilon = 2e-6, max_relative = 2e-6);
+        }
+        assert_relative_eq!(lnf(1e-7), 1e-7_f32.ln(), max_relative = 1e-6);
+    }
+
+    #[test]
+    fn test_entropy() {
+        assert_relative_eq!(entropy([0.25; 4]), 4.0_f32.ln(), epsilon = 1e-6);
+        assert_relative_eq!(entropy([0.5, 0.0, 0.5]), 2.0_f32.ln(), epsilon = 1e-6);
+        assert_eq!(entropy([1.0]), 0.0);
+        assert_eq!(entropy([]), 0.0);
+    }
+
+    #[test]
+    fn test_sqrtf_accuracy() {
+        for k in 0..2000 {
+            let x = k as f32 * 0.37;
+            assert_relative_eq!(sqrtf(x), x.sqrt(), max_relative = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_probit_known_values() {
+        assert_relative_eq!(probit(0.5), 0.0, epsilon = 1e-6);
+        assert_relative_eq!(probit(0.975), 1.959_964, epsilon = 1e-4);
+        assert_relative_eq!(probit(0.025), -1.959_964, epsilon = 1e-4);
+        assert_relative_eq!(probit(0.001), -3.090_232, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_wrap_deg() {
+        assert_relative_eq!(wrap_deg(190.0), -170.0, epsilon = 1e-4);
+        assert_relative_eq!(wrap_deg(-190.0), 170.0, epsilon = 1e-4);
+        assert_relative_eq!(wrap_deg(180.0), -180.0, epsilon = 1e-4);
+        assert_relative_eq!(wrap_deg(45.0), 45.0, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_sincos_deg_accuracy() {
+        for k in -7200..=7200 {
+            let deg = k as f32 * 0.1;
+            let (s, c) = sincos_deg(deg);
+            let rad = (deg as f64).to_radians();
+            assert_relative_eq!(s, rad.sin() as f32, epsilon = 1e-6);
+            assert_relative_eq!(c, rad.cos() as f32, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_expf_accuracy() {
+        for k in -800..=800 {
+            let x = k as f32 * 0.1;
+            assert_relative_eq!(expf(x), x.exp(), max_relative = 1e-6);
+        }
+        assert_eq!(expf(-100.0), 0.0);
+    }
+
+    #[test]
+    fn test_normal_cdf_known_values() {
+        assert_relative_eq!(normal_cdf(0.0), 0.5, epsilon = 1e-6);
+        assert_relative_eq!(normal_cdf(1.959_964), 0.975, epsilon = 1e-6);
+        assert_relative_eq!(normal_cdf(-1.0), 0.158_655_25, epsilon = 1e-6);
+        assert_relative_eq!(normal_cdf(probit(0.3)), 0.3, epsilon = 1e-5);
+    }
 }
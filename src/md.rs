@@ -0,0 +1,187 @@
+use std::io::{self, Write};
+
+use crate::restraint::TorsionRestraint;
+
+/// Degrees-to-radians conversion factor.
+const DEG_TO_RAD: f32 = core::f32::consts::PI / 180.0;
+
+/// Energy expression of the OpenMM `CustomTorsionForce`: the flat-bottom
+/// harmonic of [`TorsionRestraint::energy`] with a wrap-safe angle
+/// difference.
+const OPENMM_ENERGY: &str =
+    "0.5*k*max(0, d - dtheta0)^2; d = abs(atan2(sin(theta - theta0), cos(theta - theta0)))";
+
+/// Write χ restraints as a GROMACS `[ dihedral_restraints ]` topology
+/// block.
+///
+/// Each item pairs the 1-based topology atom numbers of the restraint's
+/// [`atoms`](TorsionRestraint::atoms) with the restraint. Lines carry
+/// function type 1, the target `phi` and flat-bottom half-width `dphi` in
+/// degrees, and the force constant `kfac`. GROMACS's potential,
+/// `½ · kfac · (|Δφ| − dphi)²` beyond the flat bottom, is the one of
+/// [`TorsionRestraint::energy`], so build the restraints with
+/// [`RestraintScale::in_units`](crate::RestraintScale::in_units) and
+/// [`EnergyUnits::kj_per_mol`](crate::EnergyUnits::kj_per_mol) to get
+/// `kfac` in the kJ mol⁻¹ rad⁻² GROMACS expects.
+///
+/// Requires the `std` feature.
+///
+/// # Errors
+///
+/// Returns any error reported by `out`.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{EnergyUnits, Residue, RestraintScale, Val, write_gromacs_restraints};
+///
+/// let scale = RestraintScale::in_units(EnergyUnits::kj_per_mol(300.0));
+/// let rot = Val::rotamers(-65.0, -43.0).next().unwrap();
+/// let [chi1] = rot.restraints(&Val::TOPOLOGY, &scale);
+///
+/// let mut itp = Vec::new();
+/// write_gromacs_restraints(&mut itp, [([5, 7, 9, 11], chi1)]).unwrap();
+/// let text = String::from_utf8(itp).unwrap();
+/// assert!(text.starts_with("[ dihedral_restraints ]\n"));
+/// assert!(text.lines().last().unwrap().trim_start().starts_with("5     7     9    11     1"));
+/// ```
+pub fn write_gromacs_restraints<W, I>(out: W, restraints: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = ([usize; 4], TorsionRestraint)>,
+{
+    let mut out = io::BufWriter::new(out);
+    writeln!(out, "[ dihedral_restraints ]")?;
+    writeln!(
+        out,
+        ";    ai    aj    ak    al  type        phi       dphi         kfac"
+    )?;
+    for ([ai, aj, ak, al], restraint) in restraints {
+        writeln!(
+            out,
+            "{ai:>7} {aj:>5} {ak:>5} {al:>5} {:>5} {:>10.3} {:>10.3} {:>12.4}  ; chi{} {}",
+            1,
+            restraint.target,
+            restraint.half_width,
+            restraint.force_constant,
+            restraint.chi_index + 1,
+            restraint.atoms.join("-")
+        )?;
+    }
+    out.flush()
+}
+
+/// Write χ restraints as an OpenMM `CustomTorsionForce` in the XML form of
+/// `XmlSerializer`, ready for `XmlSerializer.deserialize` and
+/// `System.addForce`.
+///
+/// Each item pairs the 0-based system particle indices of the restraint's
+/// [`atoms`](TorsionRestraint::atoms) with the restraint. Per-torsion
+/// parameters are `theta0` and `dtheta0` in radians and `k` in energy per
+/// rad², and the energy expression is the flat-bottom harmonic of
+/// [`TorsionRestraint::energy`]; OpenMM works in kJ/mol, so build the
+/// restraints with
+/// [`RestraintScale::in_units`](crate::RestraintScale::in_units) and
+/// [`EnergyUnits::kj_per_mol`](crate::EnergyUnits::kj_per_mol).
+///
+/// Requires the `std` feature.
+///
+/// # Errors
+///
+/// Returns any error reported by `out`.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{EnergyUnits, Leu, Residue, RestraintScale, write_openmm_restraints};
+///
+/// let scale = RestraintScale::in_units(EnergyUnits::kj_per_mol(300.0));
+/// let rot = Leu::rotamers(-65.0, -43.0).next().unwrap();
+/// let [chi1, chi2] = rot.restraints(&Leu::TOPOLOGY, &scale);
+///
+/// let mut xml = Vec::new();
+/// write_openmm_restraints(&mut xml, [([0, 2, 4, 5], chi1), ([2, 4, 5, 6], chi2)]).unwrap();
+/// let text = String::from_utf8(xml).unwrap();
+/// assert!(text.starts_with("<Force type=\"CustomTorsionForce\""));
+/// assert_eq!(text.matches("<Torsion ").count(), 2);
+/// ```
+pub fn write_openmm_restraints<W, I>(out: W, restraints: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = ([usize; 4], TorsionRestraint)>,
+{
+    let mut out = io::BufWriter::new(out);
+    writeln!(
+        out,
+        "<Force type=\"CustomTorsionForce\" energy=\"{OPENMM_ENERGY}\" forceGroup=\"0\" \
+         name=\"DunbrackChiRestraints\" usesPeriodic=\"0\" version=\"3\">"
+    )?;
+    writeln!(out, "\t<PerTorsionParameters>")?;
+    for name in ["theta0", "dtheta0", "k"] {
+        writeln!(out, "\t\t<Parameter name=\"{name}\"/>")?;
+    }
+    writeln!(out, "\t</PerTorsionParameters>")?;
+    writeln!(out, "\t<GlobalParameters/>")?;
+    writeln!(out, "\t<EnergyParameterDerivatives/>")?;
+    writeln!(out, "\t<Torsions>")?;
+    for ([p1, p2, p3, p4], restraint) in restraints {
+        writeln!(
+            out,
+            "\t\t<Torsion p1=\"{p1}\" p2=\"{p2}\" p3=\"{p3}\" p4=\"{p4}\" \
+             param1=\"{:.6}\" param2=\"{:.6}\" param3=\"{:.4}\"/>",
+            restraint.target * DEG_TO_RAD,
+            restraint.half_width * DEG_TO_RAD,
+            restraint.force_constant
+        )?;
+    }
+    writeln!(out, "\t</Torsions>")?;
+    writeln!(out, "</Force>")?;
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Residue as _, Ser};
+    use crate::restraint::RestraintScale;
+    use crate::rotamer::Rotamer;
+    use crate::units::EnergyUnits;
+
+    fn chi1() -> TorsionRestraint {
+        let rot = Rotamer {
+            r: [1],
+            prob: 1.0,
+            chi_mean: [62.0],
+            chi_sigma: [10.0],
+        };
+        let scale = RestraintScale::in_units(EnergyUnits::kj_per_mol(300.0));
+        let [chi1] = rot.restraints(&Ser::TOPOLOGY, &scale);
+        chi1
+    }
+
+    #[test]
+    fn test_gromacs_line() {
+        let mut itp = std::vec::Vec::new();
+        write_gromacs_restraints(&mut itp, [([1, 5, 7, 9], chi1())]).unwrap();
+        let text = std::string::String::from_utf8(itp).unwrap();
+        let line = text.lines().nth(2).unwrap();
+        let fields: std::vec::Vec<_> = line.split_whitespace().collect();
+        assert_eq!(&fields[..7], ["1", "5", "7", "9", "1", "62.000", "10.000"]);
+        // kT / σ² at 300 K in kJ/mol, σ = 10°.
+        let k: f32 = fields[7].parse().unwrap();
+        assert!((k - 81.87).abs() < 0.05, "{k}");
+        assert_eq!(&fields[8..], [";", "chi1", "N-CA-CB-OG"]);
+    }
+
+    #[test]
+    fn test_openmm_torsion() {
+        let mut xml = std::vec::Vec::new();
+        write_openmm_restraints(&mut xml, [([0, 4, 6, 8], chi1())]).unwrap();
+        let text = std::string::String::from_utf8(xml).unwrap();
+        assert!(text.contains(
+            "<Torsion p1=\"0\" p2=\"4\" p3=\"6\" p4=\"8\" param1=\"1.082104\" param2=\"0.174533\""
+        ));
+        assert!(text.trim_end().ends_with("</Force>"));
+        assert_eq!(text.matches("<Parameter ").count(), 3);
+    }
+}
@@ -0,0 +1,212 @@
+//! Process-wide usage counters maintained with the `metrics` feature.
+//!
+//! Every counter is a relaxed `AtomicU64`, so recording costs one
+//! uncontended atomic add and never blocks. Counters only ever grow until
+//! [`Metrics::reset`]; a [`Metrics::snapshot`] taken while other threads
+//! query is not an atomic cut across counters, which is adequate for
+//! monitoring.
+
+use core::sync::atomic::{AtomicU64, Ordering};
+
+use crate::residue::Residue;
+
+/// Number of residue types, one query counter each.
+const N_RESIDUES: usize = crate::ALL_RESIDUES.len();
+
+static QUERIES: [AtomicU64; N_RESIDUES] = [const { AtomicU64::new(0) }; N_RESIDUES];
+static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static CACHE_MISSES: AtomicU64 = AtomicU64::new(0);
+static PRUNE_PASSES: AtomicU64 = AtomicU64::new(0);
+static PRUNE_CONSIDERED: AtomicU64 = AtomicU64::new(0);
+static PRUNE_KEPT: AtomicU64 = AtomicU64::new(0);
+
+/// Counts one table lookup of the residue at `index` in
+/// [`ALL_RESIDUES`](crate::ALL_RESIDUES).
+#[inline]
+pub(crate) fn lookup(index: usize) {
+    QUERIES[index].fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counts one cache access of a [`FineGridCache`](crate::FineGridCache) or
+/// [`StoredTables`](crate::StoredTables).
+#[inline]
+pub(crate) fn cache(hit: bool) {
+    let counter = if hit { &CACHE_HITS } else { &CACHE_MISSES };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counts a pruning pass that kept `kept` of `total` rotamers.
+#[inline]
+pub(crate) fn pruned(total: usize, kept: usize) {
+    PRUNE_PASSES.fetch_add(1, Ordering::Relaxed);
+    PRUNE_CONSIDERED.fetch_add(total as u64, Ordering::Relaxed);
+    PRUNE_KEPT.fetch_add(kept as u64, Ordering::Relaxed);
+}
+
+/// Snapshot of the process-wide usage counters.
+///
+/// Table lookups are counted per residue type by every rotamer query
+/// ([`Residue::rotamers_with`] and everything built on it, [`Query`] runs,
+/// the `try_` variants, [`Residue::rotamers_at`], and
+/// [`Residue::rotamers_detailed`]), including those of the `Chi1` and
+/// `Alt` wrappers of a type.
+/// Cache counters cover the sub-grid cache of
+/// [`FineGridCache`](crate::FineGridCache) and the cell cache of
+/// [`StoredTables`](crate::StoredTables); pruning counters cover every
+/// query run with a positive [`Query::prune`] threshold.
+///
+/// Requires the `metrics` feature.
+///
+/// [`Query`]: crate::Query
+/// [`Query::prune`]: crate::Query::prune
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Leu, Metrics, Query, Residue};
+///
+/// let before = Metrics::snapshot();
+/// Leu::rotamers(-65.0, -43.0);
+/// Query::new(-65.0, -43.0).prune(0.05).run::<Leu>();
+/// let after = Metrics::snapshot();
+///
+/// assert!(after.queries_of::<Leu>() >= before.queries_of::<Leu>() + 2);
+/// assert!(after.prune_passes > before.prune_passes);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Metrics {
+    /// Table lookups per residue type, in the order of
+    /// [`ALL_RESIDUES`](crate::ALL_RESIDUES).
+    pub queries: [u64; N_RESIDUES],
+    /// Cache accesses served without evaluating or reading a table cell.
+    pub cache_hits: u64,
+    /// Cache accesses that evaluated or read the table.
+    pub cache_misses: u64,
+    /// Queries that pruned their rotamer set.
+    pub prune_passes: u64,
+    /// Rotamers entering those pruning passes.
+    pub rotamers_considered: u64,
+    /// Rotamers kept by those pruning passes.
+    pub rotamers_kept: u64,
+}
+
+impl Metrics {
+    /// Current value of every counter.
+    pub fn snapshot() -> Self {
+        Self {
+            queries: core::array::from_fn(|i| QUERIES[i].load(Ordering::Relaxed)),
+            cache_hits: CACHE_HITS.load(Ordering::Relaxed),
+            cache_misses: CACHE_MISSES.load(Ordering::Relaxed),
+            prune_passes: PRUNE_PASSES.load(Ordering::Relaxed),
+            rotamers_considered: PRUNE_CONSIDERED.load(Ordering::Relaxed),
+            rotamers_kept: PRUNE_KEPT.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Set every counter back to zero, e.g. after exporting a snapshot as
+    /// deltas.
+    pub fn reset() {
+        for counter in &QUERIES {
+            counter.store(0, Ordering::Relaxed);
+        }
+        for counter in [
+            &CACHE_HITS,
+            &CACHE_MISSES,
+            &PRUNE_PASSES,
+            &PRUNE_CONSIDERED,
+            &PRUNE_KEPT,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Table lookups of residue type `R`.
+    pub fn queries_of<R: Residue>(&self) -> u64 {
+        crate::ALL_RESIDUES
+            .iter()
+            .position(|info| info.name == R::NAME)
+            .map_or(0, |i| self.queries[i])
+    }
+
+    /// Table lookups over all residue types.
+    pub fn total_queries(&self) -> u64 {
+        self.queries.iter().sum()
+    }
+
+    /// Fraction of cache accesses that were hits, or `None` before the
+    /// first access.
+    pub fn cache_hit_ratio(&self) -> Option<f64> {
+        let total = self.cache_hits + self.cache_misses;
+        (total > 0).then(|| self.cache_hits as f64 / total as f64)
+    }
+
+    /// Fraction of rotamers kept by pruning, or `None` before the first
+    /// pruning pass.
+    pub fn prune_ratio(&self) -> Option<f64> {
+        (self.rotamers_considered > 0)
+            .then(|| self.rotamers_kept as f64 / self.rotamers_considered as f64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::Query;
+    use crate::residue::{Residue, Trp};
+
+    // Counters are process-wide and other tests query concurrently, so
+    // these only check that counts grow by at least the expected amount.
+
+    #[test]
+    fn test_lookups_counted_per_residue() {
+        let before = Metrics::snapshot();
+        for _ in 0..3 {
+            Trp::rotamers(-65.0, -43.0);
+        }
+        Trp::query(&Query::new(60.0, 40.0));
+        let after = Metrics::snapshot();
+        assert!(after.queries_of::<Trp>() >= before.queries_of::<Trp>() + 4);
+        assert!(after.total_queries() >= before.total_queries() + 4);
+    }
+
+    #[test]
+    fn test_prune_counters() {
+        let before = Metrics::snapshot();
+        let kept = Query::new(-65.0, -43.0).prune(0.1).run::<Trp>().len() as u64;
+        let after = Metrics::snapshot();
+        assert!(after.prune_passes > before.prune_passes);
+        assert!(after.rotamers_considered >= before.rotamers_considered + Trp::N_ROTAMERS as u64);
+        assert!(after.rotamers_kept >= before.rotamers_kept + kept);
+        let ratio = after.prune_ratio().unwrap();
+        assert!((0.0..=1.0).contains(&ratio));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn test_cache_counters() {
+        let mut cache = crate::cache::FineGridCache::<Trp>::new(2, 2);
+        let before = Metrics::snapshot();
+        for _ in 0..4 {
+            cache.rotamers(-65.0, -43.0);
+        }
+        let after = Metrics::snapshot();
+        // The first access is cold; the second refines the cell.
+        assert!(after.cache_misses > before.cache_misses);
+        assert!(after.cache_hits >= before.cache_hits + 3);
+    }
+
+    #[test]
+    fn test_empty_ratios() {
+        let empty = Metrics {
+            queries: [0; N_RESIDUES],
+            cache_hits: 0,
+            cache_misses: 0,
+            prune_passes: 0,
+            rotamers_considered: 0,
+            rotamers_kept: 0,
+        };
+        assert_eq!(empty.cache_hit_ratio(), None);
+        assert_eq!(empty.prune_ratio(), None);
+        assert_eq!(empty.total_queries(), 0);
+    }
+}
@@ -0,0 +1,264 @@
+use crate::interp::RotamerIter;
+use crate::rotamer::Rotamer;
+
+/// One alternate conformation of a multi-conformer side-chain model.
+///
+/// Produced by [`RotamerIter::conformers`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Conformer<const N: usize> {
+    /// PDB alternate location indicator: `'A'` for the most occupied
+    /// conformer, then `'B'`, `'C'`, ….
+    pub altloc: char,
+    /// Occupancy, a whole number of occupancy steps; the occupancies of a
+    /// [`MultiConformer`] sum to 1.
+    pub occupancy: f32,
+    /// The library rotamer, with its probability as interpolated.
+    pub rotamer: Rotamer<N>,
+}
+
+/// Top rotamers of one position with crystallographic occupancies, ready
+/// to build an alternate-conformation model.
+///
+/// Produced by [`RotamerIter::conformers`]. Holds at most `K` conformers,
+/// most occupied first. Alternate locations run from `'A'` to `'Z'`, so `K`
+/// may not exceed 26:
+///
+/// ```compile_fail
+/// use dunbrack::{Arg, Residue};
+///
+/// let model = Arg::rotamers(-65.0, -40.0).conformers::<27>(0.01);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MultiConformer<const N: usize, const K: usize> {
+    entries: [Conformer<N>; K],
+    len: usize,
+}
+
+impl<const N: usize, const K: usize> MultiConformer<N, K> {
+    /// Evaluated by [`RotamerIter::conformers`] to reject more conformers
+    /// than there are alternate location letters.
+    const ENOUGH_ALTLOCS: () = assert!(K <= 26, "at most 26 conformers have an altloc letter");
+
+    /// The conformers, most occupied first.
+    #[inline]
+    pub fn as_slice(&self) -> &[Conformer<N>] {
+        &self.entries[..self.len]
+    }
+
+    /// Number of conformers kept.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no conformer was kept: the rotamer set was empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<const N: usize, const R: usize> RotamerIter<N, R> {
+    /// The `K` most probable remaining rotamers as alternate conformers,
+    /// with their probabilities renormalized over the `K` and rounded to
+    /// multiples of `step`, e.g. `0.01` for the two-decimal occupancies of
+    /// PDB and mmCIF files.
+    ///
+    /// Rounding uses the largest-remainder method on whole steps, so the
+    /// occupancies sum to exactly `1 / step` steps and every conformer gets
+    /// the nearest step count consistent with that total. Conformers that
+    /// round to zero occupancy are dropped, and the rest are labelled `'A'`,
+    /// `'B'`, … by descending occupancy; ties keep the order of
+    /// [`Query::sorted`](crate::Query::sorted).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is not in (0, 1] or does not divide 1 into a whole
+    /// number of steps (within 10⁻⁴), as 0.03 does not.
+    ///
+    /// Fails to compile if `K` exceeds 26, the number of altloc letters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Leu, Residue};
+    ///
+    /// let model = Leu::rotamers(-65.0, -40.0).conformers::<3>(0.01);
+    /// let text: Vec<String> = model
+    ///     .as_slice()
+    ///     .iter()
+    ///     .map(|c| format!("{} {:.2}", c.altloc, c.occupancy))
+    ///     .collect();
+    /// assert_eq!(text[0].chars().next(), Some('A'));
+    ///
+    /// // Written with two decimals, the occupancies add up to 1.00.
+    /// let steps: u32 = text.iter().map(|t| t[2..].replace('.', "").parse::<u32>().unwrap()).sum();
+    /// assert_eq!(steps, 100);
+    /// ```
+    pub fn conformers<const K: usize>(&self, step: f32) -> MultiConformer<N, K> {
+        let () = MultiConformer::<N, K>::ENOUGH_ALTLOCS;
+        assert!(
+            step > 0.0 && step <= 1.0,
+            "occupancy step must be in (0, 1]"
+        );
+        let steps = ((1.0 / step + 0.5) as u32).max(1);
+        assert!(
+            (steps as f32 * step - 1.0).abs() < 1e-4,
+            "occupancy step must divide 1"
+        );
+        let mut sorted = self.clone();
+        sorted.sort_by_prob();
+        let top = &sorted.as_slice()[..K.min(sorted.len())];
+        let mut out = MultiConformer {
+            entries: [Conformer {
+                altloc: 'A',
+                occupancy: 0.0,
+                rotamer: Rotamer {
+                    r: [0; N],
+                    prob: 0.0,
+                    chi_mean: [0.0; N],
+                    chi_sigma: [0.0; N],
+                },
+            }; K],
+            len: 0,
+        };
+        let total: f32 = top.iter().map(|rot| rot.prob).sum();
+        if top.is_empty() || total <= 0.0 {
+            return out;
+        }
+
+        // Largest remainder: floor every share, then hand the leftover steps
+        // to the largest fractional parts, earlier conformers first on ties.
+        let mut counts = [0_u32; K];
+        let mut remainders = [0.0_f32; K];
+        for (i, rot) in top.iter().enumerate() {
+            let share = rot.prob / total * steps as f32;
+            counts[i] = share as u32;
+            remainders[i] = share - counts[i] as f32;
+        }
+        let assigned: u32 = counts.iter().sum();
+        for _ in assigned..steps {
+            let mut best = 0;
+            for i in 1..top.len() {
+                if remainders[i] > remainders[best] {
+                    best = i;
+                }
+            }
+            counts[best] += 1;
+            remainders[best] = f32::NEG_INFINITY;
+        }
+
+        let mut order: [usize; K] = core::array::from_fn(|i| i);
+        order[..top.len()].sort_unstable_by(|&a, &b| counts[b].cmp(&counts[a]).then(a.cmp(&b)));
+        for &i in &order[..top.len()] {
+            if counts[i] == 0 {
+                break;
+            }
+            out.entries[out.len] = Conformer {
+                altloc: char::from(b'A' + out.len as u8),
+                occupancy: counts[i] as f32 / steps as f32,
+                rotamer: top[i],
+            };
+            out.len += 1;
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::interp::RotamerIter;
+    use crate::query::Query;
+    use crate::residue::{Arg, Residue, Val};
+    use crate::rotamer::Rotamer;
+    use approx::assert_relative_eq;
+
+    fn set(probs: [f32; 3]) -> RotamerIter<1, 3> {
+        let base = Val::rotamers(-65.0, -40.0);
+        let mut items: [Rotamer<1>; 3] = core::array::from_fn(|i| base.as_slice()[i]);
+        for (rot, prob) in items.iter_mut().zip(probs) {
+            rot.prob = prob;
+        }
+        RotamerIter::from_parts(items, 3)
+    }
+
+    #[test]
+    fn test_largest_remainder_rounding() {
+        // Shares of 33.3 steps each: the leftover step goes to the first.
+        let even = set([1.0, 1.0, 1.0]).conformers::<3>(0.01);
+        let occupancies: [f32; 3] = core::array::from_fn(|i| even.as_slice()[i].occupancy);
+        assert_eq!(occupancies, [0.34, 0.33, 0.33]);
+
+        // 99.5, 0.4, 0.1 steps: the minor conformers round away.
+        let skewed = set([0.995, 0.004, 0.001]).conformers::<3>(0.01);
+        assert_eq!(skewed.len(), 1);
+        assert_eq!(skewed.as_slice()[0].occupancy, 1.0);
+
+        // Tenths, keeping only the top two of three.
+        let tenths = set([0.2, 0.5, 0.3]).conformers::<2>(0.1);
+        let [a, b] = [tenths.as_slice()[0], tenths.as_slice()[1]];
+        assert_eq!(
+            (a.altloc, a.rotamer.prob, b.altloc, b.rotamer.prob),
+            ('A', 0.5, 'B', 0.3)
+        );
+        assert_relative_eq!(a.occupancy, 0.6, epsilon = 1e-6);
+        assert_relative_eq!(b.occupancy, 0.4, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn test_steps_sum_to_one() {
+        let model = Arg::rotamers(-65.0, -40.0).conformers::<6>(0.01);
+        assert!(!model.is_empty() && model.len() <= 6);
+        let steps: f32 = model.as_slice().iter().map(|c| c.occupancy * 100.0).sum();
+        assert_relative_eq!(steps, 100.0, epsilon = 1e-3);
+        assert!(
+            model
+                .as_slice()
+                .windows(2)
+                .all(|w| w[0].occupancy >= w[1].occupancy)
+        );
+        let best = Query::new(-65.0, -40.0)
+            .sorted(true)
+            .run::<Arg>()
+            .next()
+            .unwrap();
+        assert_eq!(model.as_slice()[0].rotamer, best);
+
+        let empty = Query::new(-65.0, -40.0).prune(2.0).run::<Arg>();
+        assert!(empty.conformers::<4>(0.01).is_empty());
+    }
+
+    #[test]
+    fn test_every_altloc_letter() {
+        let base = Arg::rotamers(-65.0, -40.0);
+        let items: [Rotamer<4>; Arg::N_ROTAMERS] = core::array::from_fn(|i| Rotamer {
+            prob: 1.0,
+            ..base.as_slice()[i]
+        });
+        let model = RotamerIter::from_parts(items, base.len()).conformers::<26>(0.01);
+        assert_eq!(model.len(), 26);
+        assert_eq!(model.as_slice()[0].altloc, 'A');
+        assert_eq!(model.as_slice()[25].altloc, 'Z');
+    }
+
+    #[test]
+    fn test_dividing_steps() {
+        for step in [1.0, 0.5, 0.25, 0.1, 0.05, 0.01, 0.001] {
+            let model = Arg::rotamers(-65.0, -40.0).conformers::<4>(step);
+            let total: f32 = model.as_slice().iter().map(|c| c.occupancy).sum();
+            assert_relative_eq!(total, 1.0, epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "occupancy step must be in (0, 1]")]
+    fn test_invalid_step() {
+        Val::rotamers(-65.0, -40.0).conformers::<2>(0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "occupancy step must divide 1")]
+    fn test_non_dividing_step() {
+        Val::rotamers(-65.0, -40.0).conformers::<2>(0.03);
+    }
+}
@@ -0,0 +1,763 @@
+//! Bookkeeping shared by side-chain packers.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::Range;
+
+use crate::interp::RotamerIter;
+use crate::math::lnf;
+use crate::rotamer::Rotamer;
+use crate::units::EnergyUnits;
+
+/// Contiguous global numbering of the candidate rotamers at a sequence of
+/// packing positions.
+///
+/// Position `p` owns the global indices `offset(p)..offset(p) + count(p)`,
+/// so per-rotamer data for a whole structure (one-body energies, pruning
+/// flags, the current assignment) fits in flat arrays of length
+/// [`len`](Self::len), and pairwise tables can be addressed by global
+/// index. Requires the `alloc` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Leu, Residue, RotamerIndex, Val};
+///
+/// let mut index = RotamerIndex::new();
+/// let leu = index.push(Leu::rotamers(-65.0, -40.0).len());
+/// let val = index.push(Val::rotamers(-120.0, 130.0).len());
+/// assert_eq!((leu, val), (0..9, 9..12));
+///
+/// let energies = vec![0.0_f32; index.len()];
+/// assert_eq!(index.global(1, 2), Some(11));
+/// assert_eq!(index.locate(11), Some((1, 2)));
+/// assert_eq!(energies[index.range(1)].len(), 3);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RotamerIndex {
+    /// `offsets[p]` is the first global index of position `p`; the last
+    /// entry is the total count.
+    offsets: Vec<usize>,
+}
+
+impl RotamerIndex {
+    /// An index with no positions.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            offsets: alloc::vec![0],
+        }
+    }
+
+    /// An index over positions with the given candidate counts, in order.
+    pub fn from_counts(counts: impl IntoIterator<Item = usize>) -> Self {
+        let mut index = Self::new();
+        for count in counts {
+            index.push(count);
+        }
+        index
+    }
+
+    /// Append a position with `count` candidate rotamers, returning its
+    /// global index range.
+    pub fn push(&mut self, count: usize) -> Range<usize> {
+        let start = self.len();
+        self.offsets.push(start + count);
+        start..start + count
+    }
+
+    /// Number of positions.
+    #[inline]
+    pub fn positions(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Total number of candidate rotamers over all positions.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.offsets[self.offsets.len() - 1]
+    }
+
+    /// Whether there are no candidate rotamers at all.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// First global index of `position`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position >= self.positions()`.
+    #[inline]
+    pub fn offset(&self, position: usize) -> usize {
+        self.range(position).start
+    }
+
+    /// Number of candidate rotamers at `position`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position >= self.positions()`.
+    #[inline]
+    pub fn count(&self, position: usize) -> usize {
+        self.range(position).len()
+    }
+
+    /// Global index range of the candidates at `position`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position >= self.positions()`.
+    #[inline]
+    pub fn range(&self, position: usize) -> Range<usize> {
+        assert!(
+            position < self.positions(),
+            "position {position} out of range for {} positions",
+            self.positions()
+        );
+        self.offsets[position]..self.offsets[position + 1]
+    }
+
+    /// Global index of candidate `local` at `position`, or `None` if either
+    /// is out of range.
+    #[inline]
+    pub fn global(&self, position: usize, local: usize) -> Option<usize> {
+        if position >= self.positions() {
+            return None;
+        }
+        let range = self.offsets[position]..self.offsets[position + 1];
+        (local < range.len()).then(|| range.start + local)
+    }
+
+    /// The `(position, local)` pair of a global index, or `None` if it is
+    /// not below [`len`](Self::len). Found by binary search over the
+    /// offsets.
+    pub fn locate(&self, global: usize) -> Option<(usize, usize)> {
+        if global >= self.len() {
+            return None;
+        }
+        // The last position whose offset is at most `global`; positions
+        // with no candidates share their offset with the next one.
+        let position = self.offsets.partition_point(|&offset| offset <= global) - 1;
+        Some((position, global - self.offsets[position]))
+    }
+
+    /// Global index ranges of every position, in order.
+    pub fn ranges(&self) -> impl ExactSizeIterator<Item = Range<usize>> + '_ {
+        self.offsets.windows(2).map(|w| w[0]..w[1])
+    }
+}
+
+impl Default for RotamerIndex {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromIterator<usize> for RotamerIndex {
+    fn from_iter<I: IntoIterator<Item = usize>>(counts: I) -> Self {
+        Self::from_counts(counts)
+    }
+}
+
+/// Pairwise energies between the candidates of two positions, a dense
+/// `rows × cols` table in row-major order.
+///
+/// Rows index the candidates of the edge's lower-numbered position and
+/// columns those of the higher one; [`InteractionGraph::pair_energy`]
+/// handles the orientation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairTable {
+    rows: usize,
+    cols: usize,
+    energies: Vec<f32>,
+}
+
+impl PairTable {
+    /// A table of zeros.
+    fn zeros(rows: usize, cols: usize) -> Self {
+        Self {
+            rows,
+            cols,
+            energies: alloc::vec![0.0; rows * cols],
+        }
+    }
+
+    /// Number of rows (candidates of the lower position).
+    #[inline]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Number of columns (candidates of the higher position).
+    #[inline]
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Energy of row candidate `i` with column candidate `j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is out of range.
+    #[inline]
+    pub fn get(&self, i: usize, j: usize) -> f32 {
+        self.energies[self.at(i, j)]
+    }
+
+    /// Set the energy of row candidate `i` with column candidate `j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is out of range.
+    #[inline]
+    pub fn set(&mut self, i: usize, j: usize, energy: f32) {
+        let at = self.at(i, j);
+        self.energies[at] = energy;
+    }
+
+    /// Energies of row candidate `i` against every column candidate.
+    #[inline]
+    pub fn row(&self, i: usize) -> &[f32] {
+        &self.energies[i * self.cols..(i + 1) * self.cols]
+    }
+
+    /// Every energy in row-major order, for bulk filling.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [f32] {
+        &mut self.energies
+    }
+
+    fn at(&self, i: usize, j: usize) -> usize {
+        assert!(
+            i < self.rows && j < self.cols,
+            "pair ({i}, {j}) out of range for a {}×{} table",
+            self.rows,
+            self.cols
+        );
+        i * self.cols + j
+    }
+}
+
+/// Interaction graph for side-chain packing: one node per position holding
+/// its candidate rotamers, one-body energies, and pruning flags, and one
+/// edge per interacting pair of positions holding a user-filled
+/// [`PairTable`].
+///
+/// Nodes are numbered in insertion order and their candidates by local
+/// index, in the order of the rotamer set they came from; a shared
+/// [`RotamerIndex`] gives every candidate a global index as well. The graph
+/// does no energy evaluation of its own: dead-end elimination, simulated
+/// annealing, or Monte Carlo packers fill the tables, prune through
+/// [`prune`](Self::prune) or [`prune_where`](Self::prune_where), and score
+/// assignments with [`total_energy`](Self::total_energy). Requires the
+/// `alloc` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{InteractionGraph, Leu, Residue, Val};
+///
+/// let mut graph = InteractionGraph::new();
+/// let leu = graph.add_node(Leu::rotamers(-65.0, -40.0).map(|r| -r.prob.ln()));
+/// let val = graph.add_node(Val::rotamers(-120.0, 130.0).map(|r| -r.prob.ln()));
+///
+/// // A clash between Leu candidate 0 and Val candidate 2.
+/// graph.add_edge(leu, val).set(0, 2, 10.0);
+/// assert_eq!(graph.pair_energy(val, 2, leu, 0), 10.0);
+///
+/// // Prune everything far above the best one-body energy at each node.
+/// let pruned = graph.prune_where(|g, node, r| {
+///     let best = g.one_body(node).iter().copied().fold(f32::INFINITY, f32::min);
+///     g.one_body(node)[r] > best + 5.0
+/// });
+/// assert!(pruned > 0);
+/// let assignment: Vec<usize> = (0..graph.nodes())
+///     .map(|node| graph.alive(node).next().unwrap())
+///     .collect();
+/// assert!(graph.total_energy(&assignment).is_finite());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InteractionGraph {
+    index: RotamerIndex,
+    one_body: Vec<f32>,
+    alive: Vec<bool>,
+    edges: Vec<Edge>,
+    /// `(neighbor, edge)` pairs of every node.
+    adjacency: Vec<Vec<(usize, usize)>>,
+}
+
+/// One edge, stored with `lo < hi`.
+#[derive(Debug, Clone)]
+struct Edge {
+    lo: usize,
+    hi: usize,
+    table: PairTable,
+}
+
+impl InteractionGraph {
+    /// An empty graph.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a node whose candidates have the given one-body energies, e.g.
+    /// `−ln p` of a rotamer set, returning its node number. Every candidate
+    /// starts alive.
+    pub fn add_node(&mut self, one_body: impl IntoIterator<Item = f32>) -> usize {
+        let before = self.one_body.len();
+        self.one_body.extend(one_body);
+        let count = self.one_body.len() - before;
+        self.alive.resize(self.one_body.len(), true);
+        self.index.push(count);
+        self.adjacency.push(Vec::new());
+        self.index.positions() - 1
+    }
+
+    /// Number of nodes.
+    #[inline]
+    pub fn nodes(&self) -> usize {
+        self.index.positions()
+    }
+
+    /// Global numbering of every candidate.
+    #[inline]
+    pub fn index(&self) -> &RotamerIndex {
+        &self.index
+    }
+
+    /// One-body energies of the candidates of `node`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is out of range.
+    #[inline]
+    pub fn one_body(&self, node: usize) -> &[f32] {
+        &self.one_body[self.index.range(node)]
+    }
+
+    /// Mutable one-body energies of the candidates of `node`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is out of range.
+    #[inline]
+    pub fn one_body_mut(&mut self, node: usize) -> &mut [f32] {
+        let range = self.index.range(node);
+        &mut self.one_body[range]
+    }
+
+    /// The pair table between nodes `a` and `b`, created filled with zeros
+    /// if the edge does not exist yet. Rows are the candidates of the
+    /// lower-numbered node.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `a == b` or either node is out of range.
+    pub fn add_edge(&mut self, a: usize, b: usize) -> &mut PairTable {
+        assert!(a != b, "an edge needs two distinct nodes, got {a} twice");
+        let (lo, hi) = (a.min(b), a.max(b));
+        let edge = match self.find_edge(lo, hi) {
+            Some(edge) => edge,
+            None => {
+                let table = PairTable::zeros(self.index.count(lo), self.index.count(hi));
+                self.edges.push(Edge { lo, hi, table });
+                let edge = self.edges.len() - 1;
+                self.adjacency[lo].push((hi, edge));
+                self.adjacency[hi].push((lo, edge));
+                edge
+            }
+        };
+        &mut self.edges[edge].table
+    }
+
+    /// The pair table between nodes `a` and `b`, if they share an edge.
+    /// Rows are the candidates of the lower-numbered node.
+    pub fn edge(&self, a: usize, b: usize) -> Option<&PairTable> {
+        let edge = self.find_edge(a.min(b), a.max(b))?;
+        Some(&self.edges[edge].table)
+    }
+
+    /// Number of edges.
+    #[inline]
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Every edge as `(lo, hi, table)` with `lo < hi`, in insertion order.
+    pub fn edges(&self) -> impl ExactSizeIterator<Item = (usize, usize, &PairTable)> {
+        self.edges.iter().map(|e| (e.lo, e.hi, &e.table))
+    }
+
+    /// Nodes sharing an edge with `node`, in edge insertion order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is out of range.
+    pub fn neighbors(&self, node: usize) -> impl ExactSizeIterator<Item = usize> + '_ {
+        self.adjacency[node].iter().map(|&(neighbor, _)| neighbor)
+    }
+
+    /// Pair energy of candidate `ra` of node `a` with candidate `rb` of
+    /// node `b`, in either order; `0.0` when the nodes share no edge.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a candidate index is out of range for an existing edge.
+    pub fn pair_energy(&self, a: usize, ra: usize, b: usize, rb: usize) -> f32 {
+        match self.edge(a, b) {
+            Some(table) if a < b => table.get(ra, rb),
+            Some(table) => table.get(rb, ra),
+            None => 0.0,
+        }
+    }
+
+    /// Whether candidate `r` of `node` has not been pruned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` or `r` is out of range.
+    #[inline]
+    pub fn is_alive(&self, node: usize, r: usize) -> bool {
+        self.alive[self.global(node, r)]
+    }
+
+    /// Local indices of the unpruned candidates of `node`, ascending.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` is out of range.
+    pub fn alive(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        self.alive[self.index.range(node)]
+            .iter()
+            .enumerate()
+            .filter_map(|(r, &alive)| alive.then_some(r))
+    }
+
+    /// Prune candidate `r` of `node`, returning whether it was alive.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `node` or `r` is out of range.
+    pub fn prune(&mut self, node: usize, r: usize) -> bool {
+        let global = self.global(node, r);
+        core::mem::replace(&mut self.alive[global], false)
+    }
+
+    /// Prune every alive candidate `(node, r)` for which `rule` returns
+    /// `true`, returning how many were pruned.
+    ///
+    /// `rule` sees the graph as it was before this call, so one sweep of a
+    /// dead-end elimination criterion is order-independent; repeat until
+    /// it returns 0 to reach a fixed point.
+    pub fn prune_where(&mut self, mut rule: impl FnMut(&Self, usize, usize) -> bool) -> usize {
+        let mut doomed = Vec::new();
+        for node in 0..self.nodes() {
+            for r in self.alive(node) {
+                if rule(self, node, r) {
+                    doomed.push(self.global(node, r));
+                }
+            }
+        }
+        for &global in &doomed {
+            self.alive[global] = false;
+        }
+        doomed.len()
+    }
+
+    /// Total energy of a full assignment, one local candidate index per
+    /// node: the sum of the chosen one-body energies and of every edge's
+    /// pair energy. Pruning flags are not consulted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `assignment` does not have one entry per node or an entry
+    /// is out of range.
+    pub fn total_energy(&self, assignment: &[usize]) -> f32 {
+        assert_eq!(
+            assignment.len(),
+            self.nodes(),
+            "assignment needs one candidate per node"
+        );
+        let one_body: f32 = assignment
+            .iter()
+            .enumerate()
+            .map(|(node, &r)| self.one_body[self.global(node, r)])
+            .sum();
+        let pairs: f32 = self
+            .edges
+            .iter()
+            .map(|e| e.table.get(assignment[e.lo], assignment[e.hi]))
+            .sum();
+        one_body + pairs
+    }
+
+    /// Global index of candidate `r` of `node`.
+    fn global(&self, node: usize, r: usize) -> usize {
+        self.index
+            .global(node, r)
+            .unwrap_or_else(|| panic!("candidate {r} of node {node} out of range"))
+    }
+
+    fn find_edge(&self, lo: usize, hi: usize) -> Option<usize> {
+        self.adjacency
+            .get(lo)?
+            .iter()
+            .find(|&&(neighbor, _)| neighbor == hi)
+            .map(|&(_, edge)| edge)
+    }
+}
+
+/// Builder for the self energies of one packing position: a weighted
+/// library term `−ln p` plus any number of user terms, such as internal
+/// clashes or side-chain–backbone interactions, evaluated per rotamer.
+///
+/// [`compute`](Self::compute) returns one energy per rotamer, aligned with
+/// [`RotamerIter::as_slice`], ready for [`InteractionGraph::add_node`].
+/// Probabilities are floored at [`f32::MIN_POSITIVE`], so the library term
+/// stays finite (at most about 87.3) even for rotamers of probability zero.
+/// Requires the `alloc` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{InteractionGraph, Leu, Residue, SelfEnergy};
+///
+/// let rotamers = Leu::rotamers(-65.0, -40.0);
+/// let energies = SelfEnergy::new()
+///     .library_weight(0.5)
+///     // Penalize χ1 near g+, standing in for a clash check.
+///     .term(|rot| if rot.r[0] == 1 { 3.0 } else { 0.0 })
+///     .compute(&rotamers);
+/// assert_eq!(energies.len(), rotamers.len());
+///
+/// let mut graph = InteractionGraph::new();
+/// graph.add_node(energies);
+/// ```
+pub struct SelfEnergy<'a, const N: usize> {
+    library_weight: f32,
+    units: EnergyUnits,
+    terms: Vec<Term<'a, N>>,
+}
+
+/// A boxed user term of [`SelfEnergy`].
+type Term<'a, const N: usize> = Box<dyn FnMut(&Rotamer<N>) -> f32 + 'a>;
+
+impl<'a, const N: usize> SelfEnergy<'a, N> {
+    /// A builder with library weight `1.0` and no user terms.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            library_weight: 1.0,
+            units: EnergyUnits::Kt,
+            terms: Vec::new(),
+        }
+    }
+
+    /// Scale the library term `−ln p` by `weight` (default: `1.0`; `0.0`
+    /// drops it).
+    #[inline]
+    pub fn library_weight(mut self, weight: f32) -> Self {
+        self.library_weight = weight;
+        self
+    }
+
+    /// Express the library term in `units` (default: [`EnergyUnits::Kt`]),
+    /// converting `−ln p` from kT; user terms must already return energies
+    /// in these units.
+    #[inline]
+    pub fn units(mut self, units: EnergyUnits) -> Self {
+        self.units = units;
+        self
+    }
+
+    /// Add a user term, called once per rotamer by every
+    /// [`compute`](Self::compute) and summed into its energy.
+    pub fn term(mut self, term: impl FnMut(&Rotamer<N>) -> f32 + 'a) -> Self {
+        self.terms.push(Box::new(term));
+        self
+    }
+
+    /// Self energy of every rotamer not yet yielded by `rotamers`, in the
+    /// order of [`RotamerIter::as_slice`].
+    pub fn compute<const R: usize>(&mut self, rotamers: &RotamerIter<N, R>) -> Vec<f32> {
+        rotamers
+            .as_slice()
+            .iter()
+            .map(|rot| {
+                let library = self.units.from_kt(-lnf(rot.prob.max(f32::MIN_POSITIVE)));
+                let user: f32 = self.terms.iter_mut().map(|term| term(rot)).sum();
+                self.library_weight * library + user
+            })
+            .collect()
+    }
+}
+
+impl<const N: usize> Default for SelfEnergy<'_, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> fmt::Debug for SelfEnergy<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SelfEnergy")
+            .field("library_weight", &self.library_weight)
+            .field("units", &self.units)
+            .field("terms", &self.terms.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Leu, Residue, Val};
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_global_and_locate_are_inverse() {
+        let index = RotamerIndex::from_counts([3, 0, 75, 1]);
+        assert_eq!((index.positions(), index.len()), (4, 79));
+        for (position, range) in index.ranges().enumerate() {
+            assert_eq!(range, index.range(position));
+            for local in 0..range.len() {
+                let global = index.global(position, local).unwrap();
+                assert_eq!(index.locate(global), Some((position, local)));
+            }
+            assert_eq!(index.global(position, range.len()), None);
+        }
+        assert_eq!(index.locate(79), None);
+        assert_eq!(index.global(4, 0), None);
+        assert_eq!(index.offset(2), 3);
+        assert_eq!(index.count(1), 0);
+    }
+
+    #[test]
+    fn test_empty_index() {
+        let index: RotamerIndex = core::iter::empty().collect();
+        assert!(index.is_empty());
+        assert_eq!(index.positions(), 0);
+        assert_eq!(index.locate(0), None);
+        assert_eq!(index, RotamerIndex::default());
+    }
+
+    fn three_node_graph() -> InteractionGraph {
+        let mut graph = InteractionGraph::new();
+        graph.add_node([0.0, 1.0]);
+        graph.add_node([0.5, 0.0, 2.0]);
+        graph.add_node([0.0]);
+        let table = graph.add_edge(1, 0);
+        assert_eq!((table.rows(), table.cols()), (2, 3));
+        table.set(1, 2, -4.0);
+        graph
+            .add_edge(2, 1)
+            .as_mut_slice()
+            .copy_from_slice(&[1.0, 2.0, 3.0]);
+        graph
+    }
+
+    #[test]
+    fn test_graph_edges_and_energies() {
+        let mut graph = three_node_graph();
+        assert_eq!((graph.nodes(), graph.edge_count()), (3, 2));
+        assert_eq!(graph.index().len(), 6);
+        assert_eq!(graph.neighbors(1).collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(graph.pair_energy(0, 1, 1, 2), -4.0);
+        assert_eq!(graph.pair_energy(1, 2, 0, 1), -4.0);
+        assert_eq!(graph.pair_energy(0, 1, 2, 0), 0.0);
+        // Rows belong to the lower node, 1, with three candidates.
+        let table = graph.edge(2, 1).unwrap();
+        assert_eq!((table.rows(), table.cols()), (3, 1));
+        assert_eq!(table.row(2), [3.0]);
+        assert_eq!(graph.pair_energy(2, 0, 1, 1), 2.0);
+        // Re-adding an edge returns the existing table.
+        assert_eq!(graph.add_edge(0, 1).get(1, 2), -4.0);
+        assert_eq!(graph.edge_count(), 2);
+
+        assert_eq!(graph.total_energy(&[1, 2, 0]), 1.0 + 2.0 + 0.0 - 4.0 + 3.0);
+        graph.one_body_mut(2)[0] = 1.0;
+        assert_eq!(graph.total_energy(&[0, 0, 0]), 0.5 + 1.0 + 1.0);
+    }
+
+    #[test]
+    fn test_pruning_hooks() {
+        let mut graph = three_node_graph();
+        assert!(graph.prune(1, 0));
+        assert!(!graph.prune(1, 0));
+        assert!(!graph.is_alive(1, 0));
+        assert_eq!(graph.alive(1).collect::<Vec<_>>(), [1, 2]);
+
+        // Each sweep sees the graph before it: only candidates with a
+        // one-body energy above 0.5 go, whatever their order.
+        let pruned = graph.prune_where(|g, node, r| g.one_body(node)[r] > 0.5);
+        assert_eq!(pruned, 2);
+        assert_eq!(graph.alive(0).collect::<Vec<_>>(), [0]);
+        assert_eq!(graph.alive(1).collect::<Vec<_>>(), [1]);
+        assert_eq!(graph.prune_where(|_, _, _| false), 0);
+    }
+
+    #[test]
+    fn test_self_energies() {
+        let rotamers = Leu::rotamers(-65.0, -40.0);
+        let library = SelfEnergy::new().compute(&rotamers);
+        for (energy, rot) in library.iter().zip(rotamers.as_slice()) {
+            assert_relative_eq!(*energy, -lnf(rot.prob), epsilon = 1e-6);
+        }
+
+        let mut calls = 0;
+        let mut builder = SelfEnergy::new()
+            .library_weight(0.0)
+            .term(|rot: &Rotamer<2>| f32::from(rot.r[0]))
+            .term(|_| {
+                calls += 1;
+                0.5
+            });
+        let energies = builder.compute(&rotamers);
+        drop(builder);
+        assert_eq!(calls, rotamers.len());
+        for (energy, rot) in energies.iter().zip(rotamers.as_slice()) {
+            assert_eq!(*energy, f32::from(rot.r[0]) + 0.5);
+        }
+    }
+
+    #[test]
+    fn test_self_energy_units() {
+        let rotamers = Val::rotamers(-65.0, -40.0);
+        let kt = SelfEnergy::new().compute(&rotamers);
+        let kcal = EnergyUnits::kcal_per_mol(300.0);
+        let converted = SelfEnergy::new().units(kcal).compute(&rotamers);
+        for (a, b) in kt.iter().zip(&converted) {
+            assert_relative_eq!(kcal.from_kt(*a), *b, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_self_energy_of_zero_probability_is_finite() {
+        let mut rotamers = Val::rotamers(-65.0, -40.0);
+        rotamers.next();
+        let mut items = [rotamers.as_slice()[0]; 2];
+        items[1].prob = 0.0;
+        let energies = SelfEnergy::new().compute(&RotamerIter::from_items(items));
+        assert!(energies[1].is_finite() && energies[1] > energies[0]);
+        // Only the rotamers not yet yielded are scored.
+        assert_eq!(SelfEnergy::new().compute(&rotamers).len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "distinct nodes")]
+    fn test_self_edge_panics() {
+        three_node_graph().add_edge(1, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_range_panics_past_last_position() {
+        RotamerIndex::from_counts([2]).range(1);
+    }
+}
@@ -0,0 +1,265 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::vec::Vec;
+
+use crate::export::Row;
+use crate::grid::GRID_COUNT;
+use crate::math::{self, lnf};
+use crate::residue::*;
+
+const CELLS: usize = GRID_COUNT * GRID_COUNT;
+
+/// Raw table visitor of one residue type, see [`Sealed::visit_rows`](crate::sealed::Sealed::visit_rows).
+type Visit = fn(&mut dyn FnMut(Row));
+
+/// Derived per-grid-node maps of one residue type, built by [`precompute`].
+///
+/// Every map is indexed by `(phi_idx, psi_idx)` grid node, with angles
+/// `GRID_MIN + idx × GRID_STEP`, and rotamers are numbered in table order,
+/// as yielded by [`Residue::rotamers`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct DerivedMaps {
+    name: &'static str,
+    n_rotamers: usize,
+    entropy: Vec<f32>,
+    log_prob: Vec<f32>,
+    sorted: Vec<u16>,
+}
+
+impl DerivedMaps {
+    /// Library name of the residue type, as in [`Residue::NAME`].
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Shannon entropy of the rotamer distribution at a grid node, in nats.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is not below [`GRID_COUNT`](crate::GRID_COUNT).
+    pub fn entropy(&self, phi_idx: usize, psi_idx: usize) -> f32 {
+        self.entropy[cell(phi_idx, psi_idx)]
+    }
+
+    /// Natural log of each rotamer's probability at a grid node, in table
+    /// order; `−∞` for zero probabilities.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is not below [`GRID_COUNT`](crate::GRID_COUNT).
+    pub fn log_probs(&self, phi_idx: usize, psi_idx: usize) -> &[f32] {
+        let start = cell(phi_idx, psi_idx) * self.n_rotamers;
+        &self.log_prob[start..start + self.n_rotamers]
+    }
+
+    /// Table indices of the rotamers at a grid node by descending
+    /// probability, ties broken by table index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an index is not below [`GRID_COUNT`](crate::GRID_COUNT).
+    pub fn sorted_order(&self, phi_idx: usize, psi_idx: usize) -> &[u16] {
+        let start = cell(phi_idx, psi_idx) * self.n_rotamers;
+        &self.sorted[start..start + self.n_rotamers]
+    }
+
+    /// Builds the maps from one residue's raw table rows.
+    fn build(name: &'static str, n_rotamers: usize, visit: Visit) -> Self {
+        let mut probs = Vec::with_capacity(CELLS * n_rotamers);
+        visit(&mut |row| probs.push(row.prob));
+        debug_assert_eq!(probs.len(), CELLS * n_rotamers);
+
+        let mut entropy = Vec::with_capacity(CELLS);
+        let mut log_prob = Vec::with_capacity(probs.len());
+        let mut sorted = Vec::with_capacity(probs.len());
+        for cell in probs.chunks_exact(n_rotamers) {
+            entropy.push(math::entropy(cell.iter().copied()));
+            log_prob.extend(
+                cell.iter()
+                    .map(|&p| if p > 0.0 { lnf(p) } else { f32::NEG_INFINITY }),
+            );
+            let start = sorted.len();
+            sorted.extend(0..n_rotamers as u16);
+            sorted[start..].sort_by(|&a, &b| {
+                cell[usize::from(b)]
+                    .total_cmp(&cell[usize::from(a)])
+                    .then(a.cmp(&b))
+            });
+        }
+        Self {
+            name,
+            n_rotamers,
+            entropy,
+            log_prob,
+            sorted,
+        }
+    }
+}
+
+/// Derived maps of every residue type, built by [`precompute`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Precomputed {
+    residues: Vec<DerivedMaps>,
+}
+
+impl Precomputed {
+    /// The maps of residue type `R`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val, precompute};
+    ///
+    /// let maps = precompute(|_, _| {});
+    /// let val = maps.get::<Val>();
+    /// let best = usize::from(val.sorted_order(12, 14)[0]);
+    /// let rots: Vec<_> = Val::rotamers(-60.0, -40.0).collect();
+    /// assert!(rots.iter().all(|r| r.prob <= rots[best].prob));
+    /// ```
+    pub fn get<R: Residue>(&self) -> &DerivedMaps {
+        self.residues
+            .iter()
+            .find(|maps| maps.name == R::NAME)
+            .expect("every residue type is precomputed")
+    }
+
+    /// The maps of every residue type, in
+    /// [`for_all_residues!`](crate::for_all_residues) order.
+    ///
+    /// The iterator is exact-size, fused, and double-ended, like the
+    /// per-residue [`RotamerIter`](crate::RotamerIter).
+    pub fn iter(&self) -> core::slice::Iter<'_, DerivedMaps> {
+        self.residues.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Precomputed {
+    type Item = &'a DerivedMaps;
+    type IntoIter = core::slice::Iter<'a, DerivedMaps>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Build the derived per-grid-node maps (entropy, log-probabilities, and
+/// probability-sorted rotamer order) of every residue type up front, in
+/// parallel on all available cores.
+///
+/// For long-running servers that prefer paying this cost once at startup
+/// over computing the same quantities per query. `progress(done, total)`
+/// is called from the worker threads after each residue type finishes.
+///
+/// Requires the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// let finished = AtomicUsize::new(0);
+/// let maps = dunbrack::precompute(|_done, _total| {
+///     finished.fetch_add(1, Ordering::Relaxed);
+/// });
+/// assert_eq!(finished.into_inner(), maps.iter().count());
+/// ```
+pub fn precompute(progress: impl Fn(usize, usize) + Sync) -> Precomputed {
+    let mut jobs: Vec<(&'static str, usize, Visit)> = Vec::new();
+    macro_rules! push {
+        ($Res:ident, $n:literal, $r:literal) => {
+            jobs.push((
+                <$Res as Residue>::NAME,
+                $r,
+                <$Res as crate::sealed::Sealed>::visit_rows,
+            ));
+        };
+    }
+    for_all_residues!(push);
+
+    let total = jobs.len();
+    let next = AtomicUsize::new(0);
+    let done = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(total));
+    let workers = thread::available_parallelism()
+        .map_or(1, |n| n.get())
+        .min(total);
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(&(name, n_rotamers, visit)) = jobs.get(i) else {
+                        break;
+                    };
+                    let maps = DerivedMaps::build(name, n_rotamers, visit);
+                    results.lock().unwrap().push((i, maps));
+                    progress(done.fetch_add(1, Ordering::Relaxed) + 1, total);
+                }
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|&(i, _)| i);
+    Precomputed {
+        residues: results.into_iter().map(|(_, maps)| maps).collect(),
+    }
+}
+
+#[inline]
+fn cell(phi_idx: usize, psi_idx: usize) -> usize {
+    assert!(
+        phi_idx < GRID_COUNT && psi_idx < GRID_COUNT,
+        "grid index ({phi_idx}, {psi_idx}) out of range"
+    );
+    phi_idx * GRID_COUNT + psi_idx
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::{Mode, Query};
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_maps_match_grid_queries() {
+        let maps = precompute(|_, _| {});
+        assert_eq!(maps.iter().len(), 22);
+        assert_eq!(maps.iter().next_back().map(DerivedMaps::name), Some("VAL"));
+        let leu = maps.get::<Leu>();
+        assert_eq!(leu.name(), "LEU");
+        let (phi_idx, psi_idx) = (11, 14);
+        let rots: Vec<_> = Query::new(-70.0, -40.0)
+            .interpolation(Mode::Nearest)
+            .run::<Leu>()
+            .collect();
+        for (ln, rot) in leu.log_probs(phi_idx, psi_idx).iter().zip(&rots) {
+            assert_relative_eq!(ln.exp(), rot.prob, epsilon = 1e-5);
+        }
+        let order = leu.sorted_order(phi_idx, psi_idx);
+        assert!(
+            order
+                .windows(2)
+                .all(|w| rots[usize::from(w[0])].prob >= rots[usize::from(w[1])].prob)
+        );
+        let entropy: f32 = rots.iter().map(|r| -r.prob * r.prob.ln()).sum();
+        assert_relative_eq!(leu.entropy(phi_idx, psi_idx), entropy, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn test_progress_reaches_total() {
+        let calls = Mutex::new(Vec::new());
+        precompute(|done, total| calls.lock().unwrap().push((done, total)));
+        let mut calls = calls.into_inner().unwrap();
+        calls.sort();
+        assert_eq!(calls.len(), 22);
+        assert_eq!(calls.last(), Some(&(22, 22)));
+    }
+
+    #[test]
+    #[should_panic(expected = "out of range")]
+    fn test_index_out_of_range_panics() {
+        precompute(|_, _| {}).get::<Val>().entropy(GRID_COUNT, 0);
+    }
+}
@@ -0,0 +1,693 @@
+use crate::error::Error;
+use crate::grid::GRID_MIN;
+use crate::interp::{
+    Bicubic, Bilinear, Nearest, RotamerIter, Table, build_iter_with, try_build_iter_with,
+};
+use crate::math::wrap_deg;
+use crate::residue::Residue;
+use crate::rotamer::Rotamer;
+
+/// Interpolation scheme selected at runtime by a [`Query`].
+///
+/// Each variant corresponds to one of the built-in
+/// [`Interpolator`](crate::Interpolator) implementations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Mode {
+    /// Nearest-grid-point lookup, see [`Nearest`].
+    Nearest,
+    /// Bilinear interpolation, see [`Bilinear`].
+    #[default]
+    Bilinear,
+    /// Bicubic (Catmull–Rom) interpolation, see [`Bicubic`].
+    Bicubic,
+}
+
+/// How a [`Query`] treats backbone angles outside \[−180°, 180°\], selected
+/// with [`Query::out_of_range`].
+///
+/// Angles inside the range are evaluated as given under every policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum OutOfRange {
+    /// Clamp to the nearest end of the range, as [`Residue::rotamers`]
+    /// does.
+    #[default]
+    Clamp,
+    /// Wrap by the 360° periodicity of the dihedrals, so 190° is read as
+    /// −170°.
+    Wrap,
+    /// Reject the query: [`Query::try_run`] returns
+    /// [`Error::AngleOutOfRange`] ([`Error::NonFiniteAngle`] for NaN or an
+    /// infinity) and [`Query::run`] panics.
+    Error,
+    /// Answer with every rotamer's probability, χ means, and σ set to NaN,
+    /// skipping pruning and sorting, so the bad input propagates through
+    /// downstream arithmetic instead of being silently replaced.
+    Nan,
+}
+
+/// How a [`Query`] treats the interpolated probabilities before pruning,
+/// selected with [`Query::normalization`].
+///
+/// Interpolated probabilities sum to slightly less or more than 1.0; the
+/// library's own cells are only normalized to the precision of the data
+/// file, and each scheme mixes them differently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Normalization {
+    /// Divide by the sum so the set sums to 1.0, as
+    /// [`Residue::rotamers`] does.
+    #[default]
+    Renormalize,
+    /// Keep the raw interpolated probabilities, whose total
+    /// [`RotamerIter::prob_sum`] reports, for statistical applications
+    /// that model the correction themselves.
+    Raw,
+    /// Divide by a compensated (Neumaier) sum, so the set sums to 1.0 to
+    /// within one rounding whatever the feature set's summation order.
+    Compensated,
+}
+
+/// Builder for rotamer queries that need more than the defaults of
+/// [`Residue::rotamers`].
+///
+/// Options compose freely and are applied in a fixed order: out-of-range
+/// handling, interpolation, normalization, pruning, then sorting. A
+/// query without any option set yields exactly what [`Residue::rotamers`]
+/// yields.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Arg, Mode, Query};
+///
+/// let rots: Vec<_> = Query::new(-65.0, -43.0)
+///     .interpolation(Mode::Bilinear)
+///     .prune(0.01)
+///     .sorted(true)
+///     .run::<Arg>()
+///     .collect();
+///
+/// assert!(rots.len() < 75);
+/// assert!(rots.windows(2).all(|w| w[0].prob >= w[1].prob));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Query {
+    phi: f32,
+    psi: f32,
+    mode: Mode,
+    min_prob: f32,
+    sorted: bool,
+    out_of_range: OutOfRange,
+    propagate_nan: bool,
+    normalization: Normalization,
+}
+
+impl Query {
+    /// Start a query at the given backbone dihedral angles, in degrees.
+    #[inline]
+    pub const fn new(phi: f32, psi: f32) -> Self {
+        Self {
+            phi,
+            psi,
+            mode: Mode::Bilinear,
+            min_prob: 0.0,
+            sorted: false,
+            out_of_range: OutOfRange::Clamp,
+            propagate_nan: false,
+            normalization: Normalization::Renormalize,
+        }
+    }
+
+    /// Select the interpolation scheme (default: [`Mode::Bilinear`]).
+    #[inline]
+    pub const fn interpolation(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Select how the interpolated probabilities are normalized (default:
+    /// [`Normalization::Renormalize`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Arg, Normalization, Query};
+    ///
+    /// let raw = Query::new(-63.0, 147.5).normalization(Normalization::Raw).run::<Arg>();
+    /// let total = raw.prob_sum();
+    /// assert!(total > 0.9 && total < 1.1);
+    ///
+    /// let normalized = Query::new(-63.0, 147.5).run::<Arg>();
+    /// for (a, b) in raw.zip(normalized) {
+    ///     assert!((a.prob / total - b.prob).abs() < 1e-6);
+    /// }
+    /// ```
+    #[inline]
+    pub const fn normalization(mut self, policy: Normalization) -> Self {
+        self.normalization = policy;
+        self
+    }
+
+    /// Drop rotamers whose probability is below `min_prob` (default: `0.0`,
+    /// keep everything).
+    ///
+    /// The retained probabilities are not re-normalized, so their sum reports
+    /// the probability mass covered by the pruned set.
+    #[inline]
+    pub const fn prune(mut self, min_prob: f32) -> Self {
+        self.min_prob = min_prob;
+        self
+    }
+
+    /// Yield rotamers by descending probability instead of bin-index order
+    /// (default: `false`).
+    #[inline]
+    pub const fn sorted(mut self, sorted: bool) -> Self {
+        self.sorted = sorted;
+        self
+    }
+
+    /// Select how angles outside \[−180°, 180°\] are handled (default:
+    /// [`OutOfRange::Clamp`]).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Error, OutOfRange, Query, Val};
+    ///
+    /// let wrapped = Query::new(-60.0, 320.0).out_of_range(OutOfRange::Wrap);
+    /// assert!(wrapped.run::<Val>().eq(Query::new(-60.0, -40.0).run::<Val>()));
+    ///
+    /// let strict = Query::new(-60.0, 320.0).out_of_range(OutOfRange::Error);
+    /// assert_eq!(strict.try_run::<Val>().err(), Some(Error::AngleOutOfRange));
+    ///
+    /// let nan = Query::new(-60.0, 320.0).out_of_range(OutOfRange::Nan);
+    /// assert!(nan.run::<Val>().all(|rot| rot.prob.is_nan()));
+    /// ```
+    #[inline]
+    pub const fn out_of_range(mut self, policy: OutOfRange) -> Self {
+        self.out_of_range = policy;
+        self
+    }
+
+    /// Answer NaN φ or ψ with the NaN-filled rotamer set of
+    /// [`OutOfRange::Nan`], from both [`run`](Self::run) and
+    /// [`try_run`](Self::try_run) (default: `false`).
+    ///
+    /// Backbones with missing atoms usually reach the library as NaN
+    /// dihedrals; with this option their rotamers stay visibly undefined
+    /// instead of being evaluated at a clamped angle or rejected. Other
+    /// angles, infinities included, follow the [`OutOfRange`] policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Query, Val};
+    ///
+    /// let query = Query::new(f32::NAN, -40.0).propagate_nan(true);
+    /// let rotamers = query.try_run::<Val>().unwrap();
+    /// assert_eq!(rotamers.len(), 3);
+    /// assert!(rotamers.flat_map(|rot| rot.chi_mean).all(f32::is_nan));
+    /// ```
+    #[inline]
+    pub const fn propagate_nan(mut self, propagate: bool) -> Self {
+        self.propagate_nan = propagate;
+        self
+    }
+
+    /// Execute the query for residue type `R`.
+    ///
+    /// # Panics
+    ///
+    /// Panics wherever [`try_run`](Self::try_run) would return an error: if
+    /// φ or ψ is infinite, or NaN without
+    /// [`propagate_nan`](Self::propagate_nan), under any policy but
+    /// [`OutOfRange::Nan`], or if one is outside \[−180°, 180°\] under
+    /// [`OutOfRange::Error`].
+    #[inline]
+    pub fn run<R: Residue>(self) -> R::Iter {
+        R::query(&self)
+    }
+
+    /// Execute the query for residue type `R`, reporting invalid input as an
+    /// [`Error`] instead of clamping or asserting.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NonFiniteAngle`] if φ or ψ is infinite, or NaN without
+    /// [`propagate_nan`](Self::propagate_nan), under any policy but
+    /// [`OutOfRange::Nan`], which answers with NaN instead;
+    /// [`Error::AngleOutOfRange`] if one is outside \[−180°, 180°\] under
+    /// [`OutOfRange::Error`]; [`Error::DegenerateDistribution`] if the
+    /// interpolated probabilities cannot be re-normalized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Error, Query, Val};
+    ///
+    /// assert_eq!(Query::new(-60.0, -40.0).try_run::<Val>().unwrap().len(), 3);
+    /// assert_eq!(
+    ///     Query::new(-60.0, f32::INFINITY).try_run::<Val>().err(),
+    ///     Some(Error::NonFiniteAngle),
+    /// );
+    /// ```
+    #[inline]
+    pub fn try_run<R: Residue>(self) -> Result<R::Iter, Error> {
+        R::try_query(&self)
+    }
+
+    /// Start a coarse-to-fine evaluation for residue type `R`: the
+    /// nearest-grid answer is computed now, and the selected interpolation
+    /// only when [`Progressive::refine`] or [`Progressive::finish`] asks for
+    /// it.
+    ///
+    /// # Panics
+    ///
+    /// As for [`run`](Self::run).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Mode, Query, Trp};
+    ///
+    /// let query = Query::new(-63.0, -41.0).interpolation(Mode::Bicubic);
+    /// let mut answer = query.progressive::<Trp>();
+    /// assert_eq!(answer.mode(), Mode::Nearest);
+    /// let quick_best = answer.rotamers().as_slice().iter().map(|r| r.prob).fold(0.0, f32::max);
+    /// assert!(quick_best > 0.0);
+    ///
+    /// // Slider released: refine to the full answer.
+    /// while answer.refine() {}
+    /// assert!(answer.is_final());
+    /// assert!(answer.finish().eq(query.run::<Trp>()));
+    /// ```
+    #[inline]
+    pub fn progressive<R: Residue>(self) -> Progressive<R> {
+        Progressive {
+            query: self,
+            mode: Mode::Nearest,
+            rotamers: self.interpolation(Mode::Nearest).run::<R>(),
+        }
+    }
+}
+
+/// Query answer refined in stages, for interactive tools that must respond
+/// at once while a backbone angle is being dragged and can afford the full
+/// interpolation only once it settles.
+///
+/// Starts from the nearest-grid-point answer and steps through
+/// [`Mode::Bilinear`] to [`Mode::Bicubic`], stopping at the interpolation
+/// selected on the [`Query`]; every other option of the query applies at
+/// each stage. Produced by [`Query::progressive`].
+pub struct Progressive<R: Residue> {
+    query: Query,
+    mode: Mode,
+    rotamers: R::Iter,
+}
+
+impl<R: Residue> Progressive<R> {
+    /// Rotamers of the current stage.
+    #[inline]
+    pub fn rotamers(&self) -> &R::Iter {
+        &self.rotamers
+    }
+
+    /// Interpolation scheme of the current stage.
+    #[inline]
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Whether the current stage uses the query's own interpolation, so
+    /// refining further changes nothing.
+    #[inline]
+    pub fn is_final(&self) -> bool {
+        self.mode == self.query.mode
+    }
+
+    /// Evaluate the next stage, returning `false` if the answer was already
+    /// final.
+    pub fn refine(&mut self) -> bool {
+        if self.is_final() {
+            return false;
+        }
+        self.mode = match self.mode {
+            Mode::Nearest => Mode::Bilinear,
+            Mode::Bilinear | Mode::Bicubic => Mode::Bicubic,
+        };
+        self.rotamers = self.query.interpolation(self.mode).run::<R>();
+        true
+    }
+
+    /// Refine to the final stage and return its rotamers.
+    pub fn finish(mut self) -> R::Iter {
+        while self.refine() {}
+        self.rotamers
+    }
+}
+
+impl<R: Residue> core::fmt::Debug for Progressive<R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Progressive")
+            .field("residue", &R::NAME)
+            .field("query", &self.query)
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+/// Evaluate `query` against one residue's static table.
+pub fn run<const N: usize, const R: usize>(
+    table: &Table<N, R>,
+    keys: &[[u8; N]; R],
+    query: &Query,
+) -> RotamerIter<N, R> {
+    let (phi, psi) = match angles(query) {
+        Ok(Some(angles)) => angles,
+        Ok(None) => return nan_set(keys),
+        Err(Error::NonFiniteAngle) => panic!(
+            "backbone angles ({}, {}) are not finite",
+            query.phi, query.psi
+        ),
+        Err(_) => panic!(
+            "backbone angles ({}, {}) outside [-180, 180]",
+            query.phi, query.psi
+        ),
+    };
+    let iter = match query.mode {
+        Mode::Nearest => {
+            build_iter_with::<Nearest, N, R>(table, keys, phi, psi, query.normalization)
+        }
+        Mode::Bilinear => {
+            build_iter_with::<Bilinear, N, R>(table, keys, phi, psi, query.normalization)
+        }
+        Mode::Bicubic => {
+            build_iter_with::<Bicubic, N, R>(table, keys, phi, psi, query.normalization)
+        }
+    };
+    finish(iter, query)
+}
+
+/// Fallible counterpart of [`run`].
+pub fn try_run<const N: usize, const R: usize>(
+    table: &Table<N, R>,
+    keys: &[[u8; N]; R],
+    query: &Query,
+) -> Result<RotamerIter<N, R>, Error> {
+    let Some((phi, psi)) = angles(query)? else {
+        return Ok(nan_set(keys));
+    };
+    let iter = match query.mode {
+        Mode::Nearest => {
+            try_build_iter_with::<Nearest, N, R>(table, keys, phi, psi, query.normalization)
+        }
+        Mode::Bilinear => {
+            try_build_iter_with::<Bilinear, N, R>(table, keys, phi, psi, query.normalization)
+        }
+        Mode::Bicubic => {
+            try_build_iter_with::<Bicubic, N, R>(table, keys, phi, psi, query.normalization)
+        }
+    }?;
+    Ok(finish(iter, query))
+}
+
+/// The angles `query` is evaluated at under its [`OutOfRange`] policy, or
+/// `None` if it is answered with NaN.
+///
+/// Shared by [`run`] and [`try_run`], so both treat every input alike:
+/// non-finite angles are answered with NaN under [`OutOfRange::Nan`] (and
+/// NaN ones under [`propagate_nan`](Query::propagate_nan)), and are
+/// [`Error::NonFiniteAngle`] under every other policy.
+fn angles(query: &Query) -> Result<Option<(f32, f32)>, Error> {
+    let (phi, psi) = (query.phi, query.psi);
+    if query.propagate_nan && (phi.is_nan() || psi.is_nan()) {
+        return Ok(None);
+    }
+    let inside = |angle: f32| (GRID_MIN..=-GRID_MIN).contains(&angle);
+    if inside(phi) && inside(psi) {
+        return Ok(Some((phi, psi)));
+    }
+    match query.out_of_range {
+        OutOfRange::Nan => Ok(None),
+        _ if !phi.is_finite() || !psi.is_finite() => Err(Error::NonFiniteAngle),
+        // Evaluation clamps by itself.
+        OutOfRange::Clamp => Ok(Some((phi, psi))),
+        OutOfRange::Wrap => {
+            let wrap = |angle: f32| {
+                if inside(angle) {
+                    angle
+                } else {
+                    wrap_deg(angle)
+                }
+            };
+            Ok(Some((wrap(phi), wrap(psi))))
+        }
+        OutOfRange::Error => Err(Error::AngleOutOfRange),
+    }
+}
+
+/// The full rotamer set of `keys` with every value NaN, the answer under
+/// [`OutOfRange::Nan`].
+fn nan_set<const N: usize, const R: usize>(keys: &[[u8; N]; R]) -> RotamerIter<N, R> {
+    RotamerIter::from_items(core::array::from_fn(|k| Rotamer {
+        r: keys[k],
+        prob: f32::NAN,
+        chi_mean: [f32::NAN; N],
+        chi_sigma: [f32::NAN; N],
+    }))
+}
+
+/// Applies the pruning and sorting options of `query`.
+#[inline]
+fn finish<const N: usize, const R: usize>(
+    mut iter: RotamerIter<N, R>,
+    query: &Query,
+) -> RotamerIter<N, R> {
+    if query.min_prob > 0.0 {
+        #[cfg(any(feature = "trace", feature = "metrics"))]
+        let total = iter.len();
+        iter.prune(query.min_prob);
+        #[cfg(feature = "trace")]
+        crate::trace::pruned(query.min_prob, total, iter.len());
+        #[cfg(feature = "metrics")]
+        crate::metrics::pruned(total, iter.len());
+    }
+    if query.sorted {
+        iter.sort_by_prob();
+    }
+    iter
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Arg, Leu, Val};
+
+    #[test]
+    fn test_default_query_matches_rotamers() {
+        let a: [_; 3] = core::array::from_fn({
+            let mut it = Query::new(-60.0, -40.0).run::<Val>();
+            move |_| it.next().unwrap()
+        });
+        let b: [_; 3] = core::array::from_fn({
+            let mut it = Val::rotamers(-60.0, -40.0);
+            move |_| it.next().unwrap()
+        });
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_prune_drops_low_probability() {
+        let iter = Query::new(-65.0, -43.0).prune(0.01).run::<Arg>();
+        assert!(iter.len() < 75);
+        for rot in iter {
+            assert!(rot.prob >= 0.01);
+        }
+    }
+
+    #[test]
+    fn test_sorted_descending() {
+        let mut prev = f32::INFINITY;
+        for rot in Query::new(-65.0, -43.0).sorted(true).run::<Arg>() {
+            assert!(rot.prob <= prev);
+            prev = rot.prob;
+        }
+    }
+
+    #[test]
+    fn test_normalization_policies() {
+        let query = Query::new(-63.0, 147.5);
+        let raw = query.normalization(Normalization::Raw).run::<Arg>();
+        let renormalized = query.run::<Arg>();
+        let compensated = query.normalization(Normalization::Compensated).run::<Arg>();
+        let total = raw.prob_sum();
+        assert!(total != 1.0);
+        for ((a, b), c) in raw.zip(renormalized).zip(compensated) {
+            assert!((a.prob / total - b.prob).abs() < 1e-6);
+            assert!((b.prob - c.prob).abs() < 1e-6);
+            assert_eq!((a.chi_mean, b.chi_sigma), (c.chi_mean, a.chi_sigma));
+        }
+
+        // Pruning applies to the raw values.
+        let pruned = query
+            .normalization(Normalization::Raw)
+            .prune(0.05)
+            .try_run::<Arg>()
+            .unwrap();
+        assert!(pruned.as_slice().iter().all(|rot| rot.prob >= 0.05));
+    }
+
+    #[test]
+    fn test_prune_everything() {
+        let iter = Query::new(-65.0, -43.0).prune(2.0).run::<Val>();
+        assert_eq!(iter.len(), 0);
+    }
+
+    #[test]
+    fn test_out_of_range_policies() {
+        let query = |phi, psi, policy| Query::new(phi, psi).out_of_range(policy);
+
+        // In-range angles are unaffected by the policy.
+        for policy in [
+            OutOfRange::Clamp,
+            OutOfRange::Wrap,
+            OutOfRange::Error,
+            OutOfRange::Nan,
+        ] {
+            let run = query(-65.0, 180.0, policy).try_run::<Arg>().unwrap();
+            assert!(run.eq(Arg::rotamers(-65.0, 180.0)));
+        }
+
+        let clamped = query(-200.0, 43.0, OutOfRange::Clamp).run::<Arg>();
+        assert!(clamped.eq(Arg::rotamers(-180.0, 43.0)));
+        let wrapped = query(-200.0, 403.0, OutOfRange::Wrap).run::<Arg>();
+        assert!(wrapped.eq(Arg::rotamers(160.0, 43.0)));
+
+        assert_eq!(
+            query(-65.0, 181.0, OutOfRange::Error)
+                .try_run::<Arg>()
+                .err(),
+            Some(Error::AngleOutOfRange)
+        );
+        // Non-finite input is reported as such under every policy but
+        // `Nan`, which answers it with NaN from both entry points.
+        for policy in [OutOfRange::Clamp, OutOfRange::Wrap] {
+            for (phi, psi) in [(f32::INFINITY, 0.0), (-65.0, f32::NAN)] {
+                assert_eq!(
+                    query(phi, psi, policy).try_run::<Arg>().err(),
+                    Some(Error::NonFiniteAngle)
+                );
+            }
+        }
+        for (phi, psi) in [(f32::NEG_INFINITY, 0.0), (-65.0, f32::NAN)] {
+            let query = query(phi, psi, OutOfRange::Nan);
+            let a = query.try_run::<Arg>().unwrap();
+            assert_eq!(a.len(), 75);
+            assert!(a.flat_map(|rot| rot.chi_mean).all(f32::is_nan));
+            assert!(query.run::<Arg>().all(|rot| rot.prob.is_nan()));
+        }
+
+        let nan = query(-65.0, 181.0, OutOfRange::Nan)
+            .prune(0.1)
+            .sorted(true)
+            .run::<Arg>();
+        assert_eq!(nan.len(), 75);
+        assert!(
+            nan.clone()
+                .zip(Arg::rotamers(0.0, 0.0))
+                .all(|(a, b)| a.r == b.r)
+        );
+        assert!(nan.flat_map(|rot| rot.chi_mean).all(f32::is_nan));
+    }
+
+    #[test]
+    #[should_panic(expected = "outside [-180, 180]")]
+    fn test_out_of_range_error_panics_in_run() {
+        Query::new(-65.0, 190.0)
+            .out_of_range(OutOfRange::Error)
+            .run::<Val>();
+    }
+
+    #[test]
+    #[should_panic(expected = "backbone angles (NaN, -40) are not finite")]
+    fn test_nan_error_panics_in_run() {
+        Query::new(f32::NAN, -40.0)
+            .out_of_range(OutOfRange::Error)
+            .run::<Val>();
+    }
+
+    #[test]
+    #[should_panic(expected = "backbone angles (-65, inf) are not finite")]
+    fn test_infinite_error_panics_in_run() {
+        Query::new(-65.0, f32::INFINITY)
+            .out_of_range(OutOfRange::Error)
+            .run::<Val>();
+    }
+
+    #[test]
+    #[should_panic(expected = "not finite")]
+    fn test_infinite_wrap_panics_in_run() {
+        Query::new(f32::INFINITY, -40.0)
+            .out_of_range(OutOfRange::Wrap)
+            .run::<Val>();
+    }
+
+    #[test]
+    fn test_propagate_nan() {
+        for (phi, psi) in [(f32::NAN, 0.0), (-65.0, f32::NAN)] {
+            let query = Query::new(phi, psi).propagate_nan(true).prune(0.1);
+            for policy in [OutOfRange::Clamp, OutOfRange::Error] {
+                let query = query.out_of_range(policy);
+                let a = query.try_run::<Arg>().unwrap();
+                assert_eq!(a.len(), 75);
+                assert!(a.clone().all(|rot| rot.prob.is_nan()));
+                assert!(a.flat_map(|rot| rot.chi_sigma).all(f32::is_nan));
+                assert_eq!(query.run::<Arg>().len(), 75);
+            }
+        }
+        // Infinities and finite angles are unaffected.
+        let query = Query::new(f32::INFINITY, 0.0).propagate_nan(true);
+        assert_eq!(query.try_run::<Val>().err(), Some(Error::NonFiniteAngle));
+        let query = Query::new(-65.0, -43.0).propagate_nan(true);
+        assert!(query.run::<Val>().eq(Val::rotamers(-65.0, -43.0)));
+    }
+
+    #[test]
+    fn test_try_run_matches_run() {
+        let query = Query::new(-65.0, -43.0).prune(0.01).sorted(true);
+        let a = query.try_run::<Arg>().unwrap();
+        let b = query.run::<Arg>();
+        assert!(a.eq(b));
+        assert_eq!(
+            Query::new(f32::NAN, 0.0).try_run::<Arg>().err(),
+            Some(Error::NonFiniteAngle)
+        );
+    }
+
+    #[test]
+    fn test_progressive_stages() {
+        let query = Query::new(-57.0, -47.0)
+            .interpolation(Mode::Bicubic)
+            .prune(0.01);
+        let mut answer = query.progressive::<Leu>();
+        assert_eq!(
+            answer.rotamers().as_slice(),
+            query.interpolation(Mode::Nearest).run::<Leu>().as_slice()
+        );
+        assert!(answer.refine());
+        assert_eq!(answer.mode(), Mode::Bilinear);
+        assert_eq!(
+            answer.rotamers().as_slice(),
+            query.interpolation(Mode::Bilinear).run::<Leu>().as_slice()
+        );
+        assert!(answer.refine());
+        assert!(!answer.refine());
+        assert!(answer.finish().eq(query.run::<Leu>()));
+
+        let nearest = Query::new(0.0, 0.0).interpolation(Mode::Nearest);
+        let answer = nearest.progressive::<Leu>();
+        assert!(answer.is_final());
+        let bilinear = Query::new(0.0, 0.0).progressive::<Leu>().finish();
+        assert!(bilinear.eq(Query::new(0.0, 0.0).run::<Leu>()));
+    }
+}
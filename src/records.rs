@@ -0,0 +1,240 @@
+use crate::grid::{GRID_COUNT, grid_angle};
+use crate::identity::ResidueInfo;
+use crate::interp::Table;
+use crate::math::atan2f;
+use crate::residue::*;
+
+/// One rotamer of one residue type at one backbone grid point, with per-χ
+/// fields padded to four slots.
+///
+/// Slots past [`ResidueInfo::n_chi`] hold zero; [`bins`](Self::bins),
+/// [`chis`](Self::chis), and [`sigmas`](Self::sigmas) return only the
+/// meaningful ones.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Record {
+    /// Residue type, an entry of [`ALL_RESIDUES`](crate::ALL_RESIDUES).
+    pub residue: &'static ResidueInfo,
+    /// Backbone φ of the grid point in degrees.
+    pub phi: f32,
+    /// Backbone ψ of the grid point in degrees.
+    pub psi: f32,
+    /// 1-based bin index of each χ.
+    pub r: [u8; 4],
+    /// Probability of the rotamer at this grid point.
+    pub prob: f32,
+    /// Mean of each χ in degrees, in \[−180°, 180°\].
+    pub chi_mean: [f32; 4],
+    /// Standard deviation of each χ in degrees.
+    pub chi_sigma: [f32; 4],
+}
+
+impl Record {
+    /// Bin indices of the residue's χ angles.
+    #[inline]
+    pub fn bins(&self) -> &[u8] {
+        &self.r[..self.residue.n_chi]
+    }
+
+    /// Mean χ angles in degrees.
+    #[inline]
+    pub fn chis(&self) -> &[f32] {
+        &self.chi_mean[..self.residue.n_chi]
+    }
+
+    /// χ standard deviations in degrees.
+    #[inline]
+    pub fn sigmas(&self) -> &[f32] {
+        &self.chi_sigma[..self.residue.n_chi]
+    }
+}
+
+/// Entry `k` of grid cell (`phi_idx`, `psi_idx`) of `table` as a [`Record`].
+pub(crate) fn record<const N: usize, const R: usize>(
+    residue: &'static ResidueInfo,
+    table: &Table<N, R>,
+    keys: &[[u8; N]; R],
+    phi_idx: usize,
+    psi_idx: usize,
+    k: usize,
+) -> Record {
+    let entry = &table.cell(phi_idx, psi_idx)[k];
+    let mut record = Record {
+        residue,
+        phi: grid_angle(phi_idx),
+        psi: grid_angle(psi_idx),
+        r: [0; 4],
+        prob: entry.prob,
+        chi_mean: [0.0; 4],
+        chi_sigma: [0.0; 4],
+    };
+    record.r[..N].copy_from_slice(&keys[k]);
+    for i in 0..N {
+        record.chi_mean[i] = atan2f(entry.chi_sin[i], entry.chi_cos[i]).to_degrees();
+    }
+    record.chi_sigma[..N].copy_from_slice(&entry.chi_sigma);
+    record
+}
+
+/// Reads one entry of a residue's table; see [`Sealed::grid_record`](crate::sealed::Sealed::grid_record).
+type RecordFn = fn(&'static ResidueInfo, usize, usize, usize) -> Record;
+
+/// The [`Sealed::grid_record`](crate::sealed::Sealed::grid_record) of the residue type named `name`.
+fn record_fn(name: &str) -> RecordFn {
+    macro_rules! pick {
+        ($Res:ident, $n:literal, $r:literal) => {
+            if name == <$Res as Residue>::NAME {
+                return <$Res as crate::sealed::Sealed>::grid_record;
+            }
+        };
+    }
+    for_all_residues!(pick);
+    unreachable!("every ALL_RESIDUES entry is a residue type")
+}
+
+/// Iterator over every rotamer of every residue type at every grid point,
+/// returned by [`records`].
+#[derive(Debug, Clone)]
+pub struct Records {
+    residue: usize,
+    phi_idx: usize,
+    psi_idx: usize,
+    k: usize,
+    read: RecordFn,
+    remaining: usize,
+}
+
+/// Stream the whole library as flat [`Record`]s, without one typed loop per
+/// residue type.
+///
+/// Residues follow [`ALL_RESIDUES`](crate::ALL_RESIDUES) order, and each
+/// covers all [`GRID_COUNT`]² grid points, the +180° ones included, φ-major
+/// with rotamers in table order, like the source CSV; the 740,629 records
+/// are produced lazily without allocating. χ means are recovered from the
+/// stored `(sin χ, cos χ)` pairs with the crate's `atan2f`.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Residue, Val, records};
+///
+/// assert_eq!(records().len(), 740_629);
+///
+/// let val = records().filter(|rec| rec.residue.name == Val::NAME);
+/// let mut total = 0.0;
+/// for rec in val.filter(|rec| rec.phi == -60.0 && rec.psi == -40.0) {
+///     assert_eq!(rec.chis().len(), 1);
+///     total += rec.prob;
+/// }
+/// assert!((total - 1.0).abs() < 1e-4);
+/// ```
+pub fn records() -> Records {
+    let remaining = crate::ALL_RESIDUES
+        .iter()
+        .map(|info| info.n_rotamers * GRID_COUNT * GRID_COUNT)
+        .sum();
+    Records {
+        residue: 0,
+        phi_idx: 0,
+        psi_idx: 0,
+        k: 0,
+        read: record_fn(crate::ALL_RESIDUES[0].name),
+        remaining,
+    }
+}
+
+impl Iterator for Records {
+    type Item = Record;
+
+    fn next(&mut self) -> Option<Record> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let residue = &crate::ALL_RESIDUES[self.residue];
+        let record = (self.read)(residue, self.phi_idx, self.psi_idx, self.k);
+        self.remaining -= 1;
+
+        self.k += 1;
+        if self.k == residue.n_rotamers {
+            self.k = 0;
+            self.psi_idx += 1;
+            if self.psi_idx == GRID_COUNT {
+                self.psi_idx = 0;
+                self.phi_idx += 1;
+                if self.phi_idx == GRID_COUNT {
+                    self.phi_idx = 0;
+                    self.residue += 1;
+                    if let Some(next) = crate::ALL_RESIDUES.get(self.residue) {
+                        self.read = record_fn(next.name);
+                    }
+                }
+            }
+        }
+        Some(record)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl ExactSizeIterator for Records {}
+
+impl core::iter::FusedIterator for Records {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Leu, Residue};
+
+    #[test]
+    fn test_counts_per_residue() {
+        let mut counts = [0usize; 22];
+        let mut last = 0;
+        for rec in records() {
+            let i = crate::ALL_RESIDUES
+                .iter()
+                .position(|info| info == rec.residue)
+                .unwrap();
+            assert!(i >= last, "residues out of order");
+            last = i;
+            counts[i] += 1;
+        }
+        for (info, n) in crate::ALL_RESIDUES.iter().zip(counts) {
+            assert_eq!(
+                n,
+                info.n_rotamers * GRID_COUNT * GRID_COUNT,
+                "{}",
+                info.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches_typed_query_at_grid_points() {
+        let recs = records()
+            .filter(|rec| rec.residue.name == Leu::NAME)
+            .filter(|rec| rec.phi == -60.0 && rec.psi == 140.0);
+        let mut n = 0;
+        for (rec, rot) in recs.zip(Leu::rotamers(-60.0, 140.0)) {
+            assert_eq!(rec.bins(), &rot.r[..]);
+            assert!((rec.prob - rot.prob).abs() < 1e-6);
+            for i in 0..Leu::N_CHI {
+                assert!((rec.chis()[i] - rot.chi_mean[i]).abs() < 1e-3);
+                assert!((rec.sigmas()[i] - rot.chi_sigma[i]).abs() < 1e-4);
+            }
+            assert_eq!(rec.r[2..], [0, 0]);
+            n += 1;
+        }
+        assert_eq!(n, Leu::N_ROTAMERS);
+    }
+
+    #[test]
+    fn test_exact_size() {
+        let mut it = records();
+        let n = it.len();
+        it.next();
+        assert_eq!(it.len(), n - 1);
+        assert_eq!(it.size_hint(), (n - 1, Some(n - 1)));
+    }
+}
@@ -0,0 +1,243 @@
+use crate::interp::RotamerIter;
+use crate::math::wrap_deg;
+use crate::residue::Residue;
+
+/// Largest χ deviation, in degrees, counted as recovered.
+pub const RECOVERY_TOLERANCE: f32 = 40.0;
+
+/// How a side-chain conformation is predicted from the library at a native
+/// backbone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Policy {
+    /// The most probable rotamer's χ means.
+    #[default]
+    MostProbable,
+    /// The probability-weighted circular mean of every χ over the whole
+    /// distribution (see [`RotamerIter::mean_chis`]), binned to the nearest
+    /// rotamer.
+    MixtureMean,
+}
+
+/// Rotamer-recovery counts over a set of native side chains.
+///
+/// Produced by [`rotamer_recovery`]; counts from several residue types can
+/// be pooled with [`merge`](Self::merge).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct RecoveryStats {
+    /// Native side chains evaluated.
+    pub records: usize,
+    /// Records whose predicted χ1 is within [`RECOVERY_TOLERANCE`] of the
+    /// native.
+    pub chi1: usize,
+    /// Records with at least two χ angles.
+    pub chi12_records: usize,
+    /// Records with at least two χ angles whose χ1 and χ2 are both
+    /// recovered.
+    pub chi12: usize,
+    /// Records whose every χ is recovered.
+    pub all_chi: usize,
+    /// Records whose predicted rotamer bin equals the native's, taking the
+    /// native bin to be the library rotamer with the nearest χ means.
+    pub bin: usize,
+}
+
+impl RecoveryStats {
+    /// Adds the counts of `other` to `self`.
+    pub fn merge(&mut self, other: &Self) {
+        self.records += other.records;
+        self.chi1 += other.chi1;
+        self.chi12_records += other.chi12_records;
+        self.chi12 += other.chi12;
+        self.all_chi += other.all_chi;
+        self.bin += other.bin;
+    }
+
+    /// Fraction of records with χ1 recovered, or `0.0` with no records.
+    pub fn chi1_rate(&self) -> f32 {
+        rate(self.chi1, self.records)
+    }
+
+    /// Fraction of records with two or more χ angles that have χ1 and χ2
+    /// recovered, or `0.0` with no such records.
+    pub fn chi12_rate(&self) -> f32 {
+        rate(self.chi12, self.chi12_records)
+    }
+
+    /// Fraction of records with every χ recovered.
+    pub fn all_chi_rate(&self) -> f32 {
+        rate(self.all_chi, self.records)
+    }
+
+    /// Fraction of records whose rotamer bin is recovered.
+    pub fn bin_rate(&self) -> f32 {
+        rate(self.bin, self.records)
+    }
+}
+
+fn rate(hits: usize, total: usize) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        hits as f32 / total as f32
+    }
+}
+
+/// Benchmark rotamer prediction with `policy` against native side chains
+/// of residue type `R`.
+///
+/// Each native is a `(phi, psi, chis)` record in degrees. The library is
+/// queried at the native backbone with [`Residue::rotamers`], a
+/// conformation is predicted, and each χ counts as recovered when it lies
+/// within [`RECOVERY_TOLERANCE`] (40°) of the native, the literature
+/// standard. The terminal χ of Asp, Glu, Phe, and Tyr is compared modulo
+/// 180° because its two end atoms are chemically equivalent.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Policy, Val, rotamer_recovery};
+///
+/// // Val in a helix: trans is most probable, so a g− native is missed.
+/// let natives = [(-65.0, -40.0, [175.0]), (-65.0, -40.0, [-60.0])];
+/// let stats = rotamer_recovery::<Val>(natives, Policy::MostProbable);
+/// assert_eq!((stats.records, stats.chi1, stats.bin), (2, 1, 1));
+/// assert_eq!(stats.chi1_rate(), 0.5);
+/// ```
+pub fn rotamer_recovery<R: Residue>(
+    natives: impl IntoIterator<Item = (f32, f32, R::Chis)>,
+    policy: Policy,
+) -> RecoveryStats {
+    let mut stats = RecoveryStats::default();
+    for (phi, psi, chis) in natives {
+        let case = R::recovery_case(phi, psi, &chis, policy);
+        let recovered = &case.recovered[..case.n_chi];
+        stats.records += 1;
+        stats.chi1 += usize::from(recovered[0]);
+        if case.n_chi >= 2 {
+            stats.chi12_records += 1;
+            stats.chi12 += usize::from(recovered[0] && recovered[1]);
+        }
+        stats.all_chi += usize::from(recovered.iter().all(|&ok| ok));
+        stats.bin += usize::from(case.bin_match);
+    }
+    stats
+}
+
+/// Outcome of one native side chain, used by [`rotamer_recovery`].
+#[derive(Debug, Clone, Copy)]
+pub struct Case {
+    n_chi: usize,
+    recovered: [bool; 4],
+    bin_match: bool,
+}
+
+/// Scores one native side chain against the full rotamer set at its
+/// backbone.
+pub(crate) fn case<const N: usize, const R: usize>(
+    iter: RotamerIter<N, R>,
+    name: &str,
+    native: &[f32; N],
+    policy: Policy,
+) -> Case {
+    let symmetric = symmetric_chi(name).filter(|&i| i < N);
+    let diff = |i: usize, a: f32, b: f32| {
+        let d = wrap_deg(a - b).abs();
+        if symmetric == Some(i) {
+            d.min(180.0 - d)
+        } else {
+            d
+        }
+    };
+    let distance = |chis: &[f32; N], target: &[f32; N]| -> f32 {
+        (0..N)
+            .map(|i| diff(i, chis[i], target[i]))
+            .map(|d| d * d)
+            .sum()
+    };
+    let nearest = |target: &[f32; N]| {
+        iter.as_slice()
+            .iter()
+            .min_by(|a, b| distance(&a.chi_mean, target).total_cmp(&distance(&b.chi_mean, target)))
+            .map(|rot| rot.r)
+    };
+
+    let (chis, bin) = match policy {
+        Policy::MostProbable => {
+            let best = iter
+                .as_slice()
+                .iter()
+                .max_by(|a, b| a.prob.total_cmp(&b.prob))
+                .map(|rot| (rot.chi_mean, Some(rot.r)));
+            best.unwrap_or(([0.0; N], None))
+        }
+        Policy::MixtureMean => {
+            let mean = iter.mean_chis();
+            (mean, nearest(&mean))
+        }
+    };
+
+    let mut recovered = [false; 4];
+    for i in 0..N.min(4) {
+        recovered[i] = diff(i, chis[i], native[i]) <= RECOVERY_TOLERANCE;
+    }
+    Case {
+        n_chi: N,
+        recovered,
+        bin_match: bin.is_some() && bin == nearest(native),
+    }
+}
+
+/// Index of the χ whose end atoms are symmetric under a 180° flip.
+fn symmetric_chi(name: &str) -> Option<usize> {
+    match name {
+        "ASP" | "PHE" | "TYR" => Some(1),
+        "GLU" => Some(2),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Asp, Leu, Val};
+
+    #[test]
+    fn test_native_at_best_rotamer_is_recovered() {
+        let best = Leu::rotamers(-65.0, -40.0)
+            .max_by(|a, b| a.prob.total_cmp(&b.prob))
+            .unwrap();
+        let stats = rotamer_recovery::<Leu>([(-65.0, -40.0, best.chi_mean)], Policy::MostProbable);
+        assert_eq!(
+            stats,
+            RecoveryStats {
+                records: 1,
+                chi1: 1,
+                chi12_records: 1,
+                chi12: 1,
+                all_chi: 1,
+                bin: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn test_symmetric_terminal_chi() {
+        let best = Asp::rotamers(-65.0, -40.0)
+            .max_by(|a, b| a.prob.total_cmp(&b.prob))
+            .unwrap();
+        let flipped = [best.chi_mean[0], wrap_deg(best.chi_mean[1] + 180.0)];
+        let stats = rotamer_recovery::<Asp>([(-65.0, -40.0, flipped)], Policy::MostProbable);
+        assert_eq!(stats.chi12, 1);
+    }
+
+    #[test]
+    fn test_merge_and_rates() {
+        let mut a = rotamer_recovery::<Val>([(-65.0, -40.0, [175.0])], Policy::MixtureMean);
+        let b = rotamer_recovery::<Leu>([(-65.0, -40.0, [60.0, 60.0])], Policy::MostProbable);
+        a.merge(&b);
+        assert_eq!(a.records, 2);
+        assert_eq!(a.chi12_records, 1);
+        assert_eq!(a.chi12_rate(), 0.0);
+        assert_eq!(RecoveryStats::default().bin_rate(), 0.0);
+    }
+}
@@ -1,3 +1,7 @@
+use crate::class::Class;
+use crate::error::Error;
+use crate::interp::{Bilinear, BilinearDetail, Interpolator};
+use crate::query::Query;
 use crate::sealed::Sealed;
 
 /// Backbone-dependent rotamer library interface.
@@ -28,12 +32,187 @@ pub trait Residue: Sealed + Copy + 'static {
     /// Three-letter residue name (uppercase ASCII, e.g. `"ARG"`, `"VAL"`).
     const NAME: &'static str;
 
+    /// IUPAC one-letter code (e.g. `'R'`, `'V'`).
+    ///
+    /// The cysteine and proline variants share `'C'` and `'P'` with their
+    /// combined pools.
+    const ONE_LETTER: char;
+
+    /// Alternate three-letter names that denote this residue type, such as
+    /// protonation-state names from force fields (`"HID"`, `"HIE"`, `"HIP"`
+    /// for [`His`]) or `"CYX"` for [`Cyd`]. Never contains [`NAME`](Self::NAME);
+    /// no alias is shared between two types.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{His, Residue};
+    ///
+    /// fn matches<R: Residue>(name: &str) -> bool {
+    ///     name == R::NAME || R::ALIASES.contains(&name)
+    /// }
+    ///
+    /// assert!(matches::<His>("HIE"));
+    /// assert_eq!(His::ONE_LETTER, 'H');
+    /// ```
+    const ALIASES: &'static [&'static str];
+
+    /// Number of discrete bins of each χ, [`N_CHI`](Self::N_CHI) entries
+    /// in χ order. Bin indices in [`Rotamer::r`](crate::Rotamer::r) run
+    /// from 1 to this count.
+    ///
+    /// Rotameric χ angles have 3 bins (g+, t, g−); the semi-rotameric
+    /// terminal χ of Asn, Asp, Gln, Glu, His, Phe, Trp, and Tyr has 6 or
+    /// 12; the proline ring χ has 2 bins for χ1 and 1 for χ2 and χ3. The
+    /// product over χ is the theoretical rotamer space, which can exceed
+    /// [`N_ROTAMERS`](Self::N_ROTAMERS) when the library omits
+    /// combinations (73 of 81 for Lys).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Asn, Lys, Residue};
+    ///
+    /// assert_eq!(Asn::BINS_PER_CHI, [3, 12]);
+    /// let space: usize = Lys::BINS_PER_CHI.iter().map(|&b| usize::from(b)).product();
+    /// assert_eq!((space, Lys::N_ROTAMERS), (81, 73));
+    ///
+    /// // Validate an imported bin key.
+    /// let valid = |r: &[u8]| r.iter().zip(Asn::BINS_PER_CHI).all(|(&b, &n)| (1..=n).contains(&b));
+    /// assert!(valid(&[2, 12]) && !valid(&[2, 13]));
+    /// ```
+    const BINS_PER_CHI: &'static [u8];
+
+    /// Angular range of every bin of every χ: [`N_CHI`](Self::N_CHI)
+    /// slices in χ order, the `k − 1`-th range of χ `i` holding bin `k`, so
+    /// `BIN_RANGES[i].len() == BINS_PER_CHI[i]`. Each χ's ranges tile its
+    /// full period.
+    ///
+    /// Rotameric χ angles use g+ = \[0°, 120°), t = \[120°, 240°), and
+    /// g− = \[240°, 360°) ([`BinRange::G_PLUS`](crate::BinRange::G_PLUS)
+    /// and siblings); proline χ1 splits at 0° into \[0°, 180°) and
+    /// \[180°, 360°), and its χ2 and χ3 have one full-circle bin. Every
+    /// tabulated χ mean of these lies in its bin's range, which the build
+    /// script verifies.
+    ///
+    /// Semi-rotameric terminal χ angles are split into nominal 30° ranges
+    /// over 360°, or over 180° for the symmetric χ of Asp, Glu, Phe, and
+    /// Tyr. The library's own semi-rotameric bins shift with χ1 and the
+    /// backbone, so an interpolated χ mean can lie a range or two away
+    /// from its bin; treat these boundaries as the reporting convention,
+    /// not as a reconstruction of the fit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{BinRange, Phe, Residue, Val};
+    ///
+    /// assert_eq!(Val::BIN_RANGES, [[BinRange::G_PLUS, BinRange::TRANS, BinRange::G_MINUS]]);
+    /// let ring = Phe::BIN_RANGES[1];
+    /// assert_eq!(ring.len(), 6);
+    /// assert_eq!((ring[0].width(), ring[0].period), (30.0, 180.0));
+    /// ```
+    const BIN_RANGES: &'static [&'static [crate::bins::BinRange]];
+
+    /// Physicochemical classes of this residue type.
+    ///
+    /// The same classification is available as marker traits
+    /// ([`Aromatic`](crate::Aromatic), [`Charged`](crate::Charged),
+    /// [`Polar`](crate::Polar), [`Hydrophobic`](crate::Hydrophobic),
+    /// [`Small`](crate::Small)) for bounding type parameters; this constant
+    /// lets generic code branch on chemistry instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Class, Leu, Residue, Ser};
+    ///
+    /// fn buried_first<R: Residue>() -> bool {
+    ///     R::CLASS.contains(Class::HYDROPHOBIC)
+    /// }
+    ///
+    /// assert!(buried_first::<Leu>());
+    /// assert!(!buried_first::<Ser>());
+    /// ```
+    const CLASS: Class;
+
+    /// Reference energy of every rotamer, `−ln ⟨p⟩` in units of kT, aligned
+    /// with the table order yielded by [`rotamers`](Self::rotamers); see
+    /// [`reference_energy`](Self::reference_energy) for other units.
+    ///
+    /// `⟨p⟩` is the rotamer's probability averaged uniformly over the
+    /// 36 × 36 unique (φ, ψ) grid cells, so `−ln p(φ, ψ) − REFERENCE_ENERGIES[k]`
+    /// gives a backbone-normalized Dunbrack term without sweeping the grid at
+    /// startup. Rotamers that never occur have `+∞`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// assert_eq!(Val::REFERENCE_ENERGIES.len(), Val::N_ROTAMERS);
+    /// // Trans (r = 2) is the most common Val rotamer overall.
+    /// let e = Val::REFERENCE_ENERGIES;
+    /// assert!(e[1] < e[0] && e[1] < e[2]);
+    /// ```
+    const REFERENCE_ENERGIES: &'static [f32];
+
+    /// Backbone-averaged most common rotamer, for placing a sensible side
+    /// chain without any query.
+    ///
+    /// This is the rotamer with the highest probability averaged uniformly
+    /// over the 36 × 36 unique (φ, ψ) grid cells (the lowest
+    /// [`REFERENCE_ENERGIES`](Self::REFERENCE_ENERGIES) entry); `prob` holds
+    /// that average, χ means are probability-weighted circular means over
+    /// the cells, and σ the probability-weighted mean σ. Generated at build
+    /// time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Leu, Residue};
+    ///
+    /// const LEU: <Leu as Residue>::Rot = Leu::DEFAULT_ROTAMER;
+    /// // mt: χ1 ≈ −65°, χ2 ≈ 175°.
+    /// assert_eq!(LEU.r, [3, 2]);
+    /// assert!((LEU.chi_mean[0] + 65.0).abs() < 10.0);
+    /// ```
+    const DEFAULT_ROTAMER: Self::Rot;
+
+    /// Probability gradients `(∂p/∂φ, ∂p/∂ψ)` of every rotamer across the
+    /// Ramachandran grid.
+    ///
+    /// Requires the `gradients` feature.
+    #[cfg(feature = "gradients")]
+    const PROB_GRADIENTS: crate::gradient::GradientMap;
+
+    /// Side-chain heavy atoms, bonds, and χ-defining atom quadruples.
+    ///
+    /// The cysteine and proline variants share the topology of [`Cys`] and
+    /// [`Pro`]. See [`Topology`](crate::Topology).
+    const TOPOLOGY: crate::topology::Topology;
+
+    /// Ideal internal coordinates of every side-chain heavy atom, in
+    /// [`TOPOLOGY`](Self::TOPOLOGY) atom order, for building the side chain
+    /// from a backbone and a set of χ angles.
+    ///
+    /// The cysteine and proline variants share the geometry of [`Cys`] and
+    /// [`Pro`]. See [`InternalCoord`](crate::InternalCoord).
+    const IDEAL_GEOMETRY: &'static [crate::geometry::InternalCoord];
+
+    /// Bytes of static data (grid table plus bin index keys) this residue
+    /// type embeds in the binary.
+    const TABLE_BYTES: usize;
+
     /// Concrete rotamer type, always `Rotamer<{N_CHI}>`.
     type Rot: Copy + 'static;
 
     /// Concrete iterator type, always `RotamerIter<{N_CHI}, {N_ROTAMERS}>`.
     type Iter: Iterator<Item = Self::Rot> + ExactSizeIterator;
 
+    /// Per-χ value array, always `[f32; N_CHI]`.
+    type Chis: Copy + 'static;
+
     /// Return an iterator of bilinearly interpolated rotamers at the given
     /// backbone dihedral angles.
     ///
@@ -50,7 +229,574 @@ pub trait Residue: Sealed + Copy + 'static {
     /// assert_eq!(rots.len(), 3);
     /// assert!(rots[0].prob > 0.0);
     /// ```
-    fn rotamers(phi: f32, psi: f32) -> Self::Iter;
+    #[inline]
+    fn rotamers(phi: f32, psi: f32) -> Self::Iter {
+        Self::rotamers_with::<Bilinear>(phi, psi)
+    }
+
+    /// Return an iterator of rotamers evaluated with the interpolation scheme
+    /// `I` at the given backbone dihedral angles.
+    ///
+    /// Probabilities are re-normalized to sum to 1.0 after interpolation,
+    /// whichever scheme is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Bilinear, Nearest, Residue, Val};
+    ///
+    /// // At exact grid points, nearest-neighbor and bilinear lookups agree.
+    /// let a: Vec<_> = Val::rotamers_with::<Nearest>(-60.0, -40.0).collect();
+    /// let b: Vec<_> = Val::rotamers_with::<Bilinear>(-60.0, -40.0).collect();
+    /// assert!((a[0].prob - b[0].prob).abs() < 1e-6);
+    /// ```
+    fn rotamers_with<I: Interpolator>(phi: f32, psi: f32) -> Self::Iter;
+
+    /// Evaluate the rotamers at the given backbone dihedral angles like
+    /// [`rotamers`](Self::rotamers), handing each to `f` as soon as it is
+    /// interpolated instead of building the whole set first.
+    ///
+    /// Rotamers are visited in bin-index order and are bit-identical to the
+    /// ones [`rotamers`](Self::rotamers) yields; the probability sum is
+    /// taken in a first pass, so nothing per rotamer is buffered and the
+    /// stack does not grow with [`N_ROTAMERS`](Self::N_ROTAMERS). Suits
+    /// callbacks across FFI boundaries and consumers that fold the set as
+    /// it streams.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Arg, Residue};
+    ///
+    /// let (mut count, mut best) = (0, 0.0_f32);
+    /// Arg::visit_rotamers(-65.0, -40.0, |rot| {
+    ///     count += 1;
+    ///     best = best.max(rot.prob);
+    /// });
+    /// assert_eq!(count, Arg::N_ROTAMERS);
+    /// assert_eq!(best, Arg::rotamers(-65.0, -40.0).map(|r| r.prob).fold(0.0, f32::max));
+    /// ```
+    fn visit_rotamers(phi: f32, psi: f32, f: impl FnMut(&Self::Rot));
+
+    /// Return the bilinearly interpolated rotamers together with the grid
+    /// indices, weights, and raw corner values they were computed from.
+    ///
+    /// `result` is identical to [`rotamers`](Self::rotamers); the extra
+    /// four corner sets cost four more table reads, so use this only for
+    /// validation and debugging.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// let detail = Val::rotamers_detailed(-65.0, -40.0);
+    /// assert_eq!((detail.phi_idx, detail.psi_idx), (11, 14));
+    /// assert_eq!(detail.corner_angles()[0], (-70.0, -40.0));
+    /// assert_eq!(detail.weights, [0.5, 0.5, 0.0, 0.0]);
+    ///
+    /// // The probabilities are the weighted corner probabilities, re-normalized.
+    /// let p = |set: &mut <Val as Residue>::Iter| set.next().unwrap().prob;
+    /// let [mut a, mut b, ..] = detail.corners;
+    /// let mixed = 0.5 * p(&mut a) + 0.5 * p(&mut b);
+    /// let first = Val::rotamers(-65.0, -40.0).next().unwrap().prob;
+    /// assert!((mixed - first).abs() < 1e-3);
+    /// ```
+    fn rotamers_detailed(phi: f32, psi: f32) -> BilinearDetail<Self::Iter>;
+
+    /// Estimate the interpolation error of [`rotamers`](Self::rotamers) at
+    /// the given backbone dihedral angles: the largest probability
+    /// difference between bilinear and bicubic evaluation over all
+    /// rotamers.
+    ///
+    /// The bicubic result follows the curvature of the surrounding grid, so
+    /// large values flag backbone regions where the 10° grid is too coarse
+    /// for linear interpolation to be trusted. The estimate is zero at grid
+    /// points, where both schemes return the stored values. For the
+    /// per-rotamer differences, [`diff`](crate::RotamerIter::diff) the two
+    /// sets from [`rotamers_with`](Self::rotamers_with).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Arg, Residue};
+    ///
+    /// assert_eq!(Arg::interpolation_error(-60.0, -40.0), 0.0);
+    /// let err = Arg::interpolation_error(-65.0, -43.0);
+    /// assert!(err > 0.0 && err < 0.1);
+    /// ```
+    fn interpolation_error(phi: f32, psi: f32) -> f32;
+
+    /// Iterate over the grid points within `radius_deg` of (`phi`, `psi`),
+    /// with each point's raw rotamers, for smoothing, local averaging, and
+    /// sensitivity analyses without manual index arithmetic.
+    ///
+    /// Distance is Euclidean in degrees over the periodic (φ, ψ) torus, so
+    /// neighbourhoods wrap across ±180°, and every grid point appears once
+    /// (index 36 is index 0). Angles are clamped to \[−180.0, 180.0\]
+    /// first; a NaN angle or negative radius yields no points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// // The four points around φ = −65°, ψ = −45°; nine around a node.
+    /// let near: Vec<_> = Val::neighborhood(-65.0, -45.0, 15.0).collect();
+    /// assert_eq!(near.len(), 4);
+    /// assert_eq!(Val::neighborhood(-60.0, -40.0, 15.0).count(), 9);
+    ///
+    /// // Inverse-distance-weighted local mean of the best probability.
+    /// let (mut sum, mut norm) = (0.0, 0.0);
+    /// for cell in Val::neighborhood(-60.0, -40.0, 25.0) {
+    ///     let w = 1.0 / (1.0 + cell.distance);
+    ///     sum += w * cell.rotamers.map(|rot| rot.prob).fold(0.0, f32::max);
+    ///     norm += w;
+    /// }
+    /// assert!(sum / norm > 0.5);
+    /// ```
+    #[inline]
+    fn neighborhood(phi: f32, psi: f32, radius_deg: f32) -> crate::grid::Neighborhood<Self> {
+        crate::grid::Neighborhood::new(phi, psi, radius_deg)
+    }
+
+    /// Return the bilinearly interpolated rotamers inside an already
+    /// quantized grid cell, skipping the angle-to-grid conversion.
+    ///
+    /// `lo_phi` and `lo_psi` are the lower grid indices of the cell, in
+    /// \[0, 35\], and `frac_phi`, `frac_psi` the fractional offsets within
+    /// it, clamped to \[0, 1\]: the angle `GRID_MIN + (lo + frac) ·
+    /// GRID_STEP`. For batch pipelines that bucket residues by cell, the
+    /// result is identical to [`rotamers`](Self::rotamers) at that angle.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lo_phi` or `lo_psi` is greater than 35.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{GRID_MIN, GRID_STEP, Residue, Val};
+    ///
+    /// // φ = −65°, ψ = −40°: cell (11, 14), halfway along φ.
+    /// let at = Val::rotamers_at(11, 14, 0.5, 0.0);
+    /// assert!(at.eq(Val::rotamers(-65.0, -40.0)));
+    /// assert_eq!(GRID_MIN + (11.0 + 0.5) * GRID_STEP, -65.0);
+    /// ```
+    fn rotamers_at(lo_phi: usize, lo_psi: usize, frac_phi: f32, frac_psi: f32) -> Self::Iter;
+
+    /// Write the full table of this residue type into `out` as a flat,
+    /// padded structure of arrays for upload to GPU buffers, returning its
+    /// [`GpuTableLayout`](crate::GpuTableLayout).
+    ///
+    /// The values are the ones every CPU query interpolates, so CUDA or
+    /// wgpu packing kernels see the same library. Size `out` with
+    /// [`GpuTableLayout::of`](crate::GpuTableLayout::of)`::<Self>().len()`;
+    /// any bytes past that are left untouched.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::BufferTooSmall`] if `out` is shorter than the table.
+    fn write_gpu_table(out: &mut [u8]) -> Result<crate::gpu::GpuTableLayout, Error>;
+
+    /// Fallible counterpart of [`rotamers`](Self::rotamers).
+    ///
+    /// Never panics; with the `no-panic` feature, optimized builds fail to
+    /// link if that cannot be proven.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NonFiniteAngle`] if `phi` or `psi` is NaN or infinite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Error, Residue, Val};
+    ///
+    /// assert_eq!(Val::try_rotamers(-60.0, -40.0).unwrap().len(), 3);
+    /// assert_eq!(Val::try_rotamers(f32::NAN, -40.0).err(), Some(Error::NonFiniteAngle));
+    /// ```
+    #[inline]
+    fn try_rotamers(phi: f32, psi: f32) -> Result<Self::Iter, Error> {
+        Self::try_rotamers_with::<Bilinear>(phi, psi)
+    }
+
+    /// Bin index (from 1) of the angle `chi`, in degrees, for the χ at
+    /// `chi_index` (0-based), under [`BIN_RANGES`](Self::BIN_RANGES).
+    ///
+    /// Returns `None` if `chi_index` is not below [`N_CHI`](Self::N_CHI) or
+    /// `chi` is not finite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Leu, Residue};
+    ///
+    /// // A measured side chain: χ1 = −62°, χ2 = 171° is the g−/t rotamer.
+    /// assert_eq!(Leu::classify_chi(0, -62.0), Some(3));
+    /// assert_eq!(Leu::classify_chi(1, 171.0), Some(2));
+    /// ```
+    fn classify_chi(chi_index: usize, chi: f32) -> Option<u8> {
+        let ranges = Self::BIN_RANGES.get(chi_index)?;
+        let pos = ranges.iter().position(|range| range.contains(chi))?;
+        Some(pos as u8 + 1)
+    }
+
+    /// Check a bin key `r` against measured or imported χ values `chis`,
+    /// both with one entry per χ, under [`BIN_RANGES`](Self::BIN_RANGES),
+    /// returning how far each χ lies outside its assigned bin.
+    ///
+    /// Useful for catching convention bugs — shifted bin numbering,
+    /// swapped g+/g−, χ in \[0°, 360°) misread — when importing rotamer
+    /// assignments from other software.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidBinKey`] if `r` or `chis` does not have
+    /// [`N_CHI`](Self::N_CHI) entries or a bin index is outside
+    /// `1..=BINS_PER_CHI`; [`Error::NonFiniteAngle`] if a χ is NaN or
+    /// infinite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Leu, Residue};
+    ///
+    /// // A g− χ1 mislabelled g+ by an importer lies 60° outside g+.
+    /// let d = Leu::bin_discrepancy(&[1, 2], &[-60.0, 175.0]).unwrap();
+    /// assert_eq!(d.as_slice(), [60.0, 0.0]);
+    /// assert!(!d.is_within(0.0));
+    /// assert!(Leu::bin_discrepancy(&[3, 2], &[-60.0, 175.0]).unwrap().is_within(0.0));
+    /// ```
+    fn bin_discrepancy(r: &[u8], chis: &[f32]) -> Result<crate::bins::BinDiscrepancy, Error> {
+        crate::bins::discrepancy(Self::BIN_RANGES, r, chis)
+    }
+
+    /// Named bin of the raw bin index `bin` for the χ at `chi_index`
+    /// (0-based), for validating rotamer assignments read from external
+    /// data.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::ChiIndexOutOfRange`] if `chi_index` is not below
+    /// [`N_CHI`](Self::N_CHI); [`Error::InvalidBinKey`] if that χ is not
+    /// rotameric (semi-rotameric terminal χ, proline ring) or `bin` is not
+    /// 1, 2, or 3.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{ChiBin, Error, Phe, Residue};
+    ///
+    /// assert_eq!(Phe::chi_bin(0, 3), Ok(ChiBin::GMinus));
+    /// // Phe χ2 is semi-rotameric: its bin 3 is not gauche−.
+    /// assert_eq!(Phe::chi_bin(1, 3), Err(Error::InvalidBinKey));
+    /// ```
+    fn chi_bin(chi_index: usize, bin: u8) -> Result<crate::bins::ChiBin, Error> {
+        let ranges = Self::BIN_RANGES
+            .get(chi_index)
+            .ok_or(Error::ChiIndexOutOfRange {
+                index: chi_index,
+                n_chi: Self::N_CHI,
+            })?;
+        if !crate::bins::is_rotameric(ranges) {
+            return Err(Error::InvalidBinKey);
+        }
+        crate::bins::ChiBin::try_from(bin)
+    }
+
+    /// [`REFERENCE_ENERGIES`](Self::REFERENCE_ENERGIES) entry of rotamer
+    /// `k` (table order), converted into `units`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k >= N_ROTAMERS`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{EnergyUnits, Residue, Val};
+    ///
+    /// let kcal = EnergyUnits::kcal_per_mol(298.15);
+    /// let e = Val::reference_energy(1, kcal);
+    /// assert!((e - Val::REFERENCE_ENERGIES[1] * kcal.kt()).abs() < 1e-6);
+    /// ```
+    fn reference_energy(k: usize, units: crate::units::EnergyUnits) -> f32 {
+        units.from_kt(Self::REFERENCE_ENERGIES[k])
+    }
+
+    /// Fallible counterpart of [`rotamers_with`](Self::rotamers_with).
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NonFiniteAngle`] if `phi` or `psi` is NaN or infinite;
+    /// [`Error::DegenerateDistribution`] if `I` yields probabilities that
+    /// cannot be re-normalized.
+    fn try_rotamers_with<I: Interpolator>(phi: f32, psi: f32) -> Result<Self::Iter, Error>;
+
+    /// Execute a configured [`Query`] for this residue type.
+    ///
+    /// Equivalent to [`Query::run`], which is usually more convenient.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Query, Residue, Val};
+    ///
+    /// let best = Val::query(&Query::new(-60.0, -40.0).sorted(true)).next().unwrap();
+    /// assert_eq!(best.r, [2]);
+    /// ```
+    fn query(query: &Query) -> Self::Iter;
+
+    /// Fallible counterpart of [`query`](Self::query); see [`Query::try_run`].
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NonFiniteAngle`] if the query's φ or ψ is infinite, or NaN
+    /// without [`propagate_nan`](Query::propagate_nan), under any
+    /// [`OutOfRange`](crate::OutOfRange) policy but `Nan`;
+    /// [`Error::AngleOutOfRange`] if one is outside \[−180°, 180°\] under
+    /// [`OutOfRange::Error`](crate::OutOfRange::Error);
+    /// [`Error::DegenerateDistribution`] if the interpolated probabilities
+    /// cannot be re-normalized.
+    fn try_query(query: &Query) -> Result<Self::Iter, Error>;
+
+    /// Return the rotamer distribution averaged over a weighted ensemble of
+    /// backbone conformations, such as NMR models or MD frames.
+    ///
+    /// `samples` holds `(phi, psi, weight)` triples; weights need not be
+    /// normalized. Probabilities are the weighted mean of the bilinearly
+    /// interpolated probabilities at each sample. χ means are circular
+    /// means weighted by each sample's weight times the rotamer's
+    /// probability there, and σ adds the circular spread of those means to
+    /// the average within-sample variance. Rotamers are yielded in table
+    /// order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a weight is negative or not finite, or if the weights do
+    /// not have a positive sum (including an empty ensemble).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// let frames = [(-65.0, -40.0, 1.0), (-120.0, 130.0, 1.0)];
+    /// let avg: Vec<_> = Val::ensemble_rotamers(&frames).collect();
+    /// let helix: Vec<_> = Val::rotamers(-65.0, -40.0).collect();
+    /// let sheet: Vec<_> = Val::rotamers(-120.0, 130.0).collect();
+    /// let expected = 0.5 * (helix[0].prob + sheet[0].prob);
+    /// assert!((avg[0].prob - expected).abs() < 1e-6);
+    /// ```
+    fn ensemble_rotamers(samples: &[(f32, f32, f32)]) -> Self::Iter;
+
+    /// Return the rotamer distribution marginalized over Gaussian
+    /// uncertainty in the backbone: φ ~ N(`phi`, `sigma_phi`²) and
+    /// ψ ~ N(`psi`, `sigma_psi`²), all in degrees.
+    ///
+    /// The integral is evaluated by 5 × 5 Gauss–Hermite quadrature over
+    /// nodes up to 2.86 σ away, wrapped periodically, and the samples are
+    /// combined as in [`ensemble_rotamers`](Self::ensemble_rotamers). The
+    /// result is softer and more honest than a point query for
+    /// low-resolution or predicted structures; with both σ zero it reduces
+    /// to [`rotamers`](Self::rotamers).
+    ///
+    /// # Panics
+    ///
+    /// Panics if a σ is negative or not finite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// let sharp = Val::rotamers(-65.0, -40.0).map(|r| r.prob).fold(0.0, f32::max);
+    /// let soft = Val::rotamers_uncertain(-65.0, -40.0, 20.0, 20.0)
+    ///     .map(|r| r.prob)
+    ///     .fold(0.0, f32::max);
+    /// assert!(soft < sharp);
+    /// ```
+    fn rotamers_uncertain(phi: f32, psi: f32, sigma_phi: f32, sigma_psi: f32) -> Self::Iter;
+
+    /// Return the probability-weighted circular mean of each χ angle over
+    /// the whole rotamer distribution at the given backbone dihedral angles.
+    ///
+    /// See [`RotamerIter::mean_chis`](crate::RotamerIter::mean_chis).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// let [chi1] = Val::mean_chis(-60.0, -40.0);
+    /// assert!(chi1.abs() > 150.0);
+    /// ```
+    fn mean_chis(phi: f32, psi: f32) -> Self::Chis;
+
+    /// Return the circular variance of each χ angle over the whole rotamer
+    /// distribution at the given backbone dihedral angles.
+    ///
+    /// See [`RotamerIter::chi_circular_variance`](crate::RotamerIter::chi_circular_variance).
+    fn chi_circular_variance(phi: f32, psi: f32) -> Self::Chis;
+
+    /// Return the dihedral order parameter S² of each χ angle over the
+    /// whole rotamer distribution at the given backbone dihedral angles.
+    ///
+    /// See [`RotamerIter::chi_order_parameters`](crate::RotamerIter::chi_order_parameters).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Leu, Residue};
+    ///
+    /// let [s2_chi1, s2_chi2] = Leu::chi_order_parameters(-65.0, -43.0);
+    /// assert!(s2_chi1 > 0.0 && s2_chi1 <= 1.0);
+    /// assert!(s2_chi2 > 0.0 && s2_chi2 <= 1.0);
+    /// ```
+    fn chi_order_parameters(phi: f32, psi: f32) -> Self::Chis;
+
+    /// Return the marginal density of χ angle `chi_index` at the given
+    /// backbone dihedral angles, sampled at `P` points over \[−180°, 180°).
+    ///
+    /// See [`RotamerIter::chi_density`](crate::RotamerIter::chi_density).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chi_index >= N_CHI`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Ser};
+    ///
+    /// let curve = Ser::chi_density::<72>(-65.0, -40.0, 0);
+    /// assert!(curve.iter().all(|&d| d >= 0.0));
+    /// ```
+    fn chi_density<const P: usize>(phi: f32, psi: f32, chi_index: usize) -> [f32; P];
+
+    /// Return quantile `q` of the marginal distribution of χ angle
+    /// `chi_index` at the given backbone dihedral angles.
+    ///
+    /// See [`RotamerIter::chi_quantile`](crate::RotamerIter::chi_quantile).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chi_index >= N_CHI`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Leu, Residue};
+    ///
+    /// let median = Leu::chi_quantile(-65.0, -40.0, 0, 0.5);
+    /// assert!((-180.0..=180.0).contains(&median));
+    /// ```
+    fn chi_quantile(phi: f32, psi: f32, chi_index: usize, q: f32) -> f32;
+
+    /// Fallible counterpart of [`chi_quantile`](Self::chi_quantile).
+    ///
+    /// # Errors
+    ///
+    /// [`Error::NonFiniteAngle`] if `phi` or `psi` is NaN or infinite;
+    /// [`Error::ChiIndexOutOfRange`] if `chi_index >= N_CHI`;
+    /// [`Error::InvalidQuantile`] if `q` is NaN or outside \[0, 1\].
+    fn try_chi_quantile(phi: f32, psi: f32, chi_index: usize, q: f32) -> Result<f32, Error>;
+
+    /// Collect the rotamers at the given backbone dihedral angles into a
+    /// `Vec`, in table order.
+    ///
+    /// Requires the `alloc` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// assert_eq!(Val::rotamers_vec(-60.0, -40.0).len(), 3);
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn rotamers_vec(phi: f32, psi: f32) -> alloc::vec::Vec<Self::Rot> {
+        Self::rotamers(phi, psi).collect()
+    }
+
+    /// Collect the rotamers with probability at least `min_prob` into a
+    /// `Vec`, most probable first.
+    ///
+    /// Shorthand for
+    /// `Query::new(phi, psi).prune(min_prob).sorted(true).run_vec::<Self>()`.
+    /// Requires the `alloc` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// let best = Val::sorted_rotamers_vec(-60.0, -40.0, 0.0)[0];
+    /// assert_eq!(best.r, [2]);
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn sorted_rotamers_vec(phi: f32, psi: f32, min_prob: f32) -> alloc::vec::Vec<Self::Rot> {
+        Query::new(phi, psi)
+            .prune(min_prob)
+            .sorted(true)
+            .run::<Self>()
+            .collect()
+    }
+}
+
+/// [`Residue`] whose associated types are tied to its constants by the
+/// compiler: `Rot = Rotamer<{ N_CHI }>`,
+/// `Iter = RotamerIter<{ N_CHI }, { N_ROTAMERS }>`, and
+/// `Chis = [f32; N_CHI]`.
+///
+/// Every residue type implements it. Generic code bounded by `TypedResidue`
+/// reads `rot.chi_mean[i]` and the other [`Rotamer`](crate::Rotamer) fields
+/// directly, with no accessor trait over `R::Rot`. The equalities cannot be
+/// stated on [`Residue`] itself, as a trait may not constrain its own
+/// associated types by its own constants.
+///
+/// Requires the `generic-const-exprs` feature and a nightly compiler; the
+/// calling crate also enables `#![feature(generic_const_exprs)]` and bounds
+/// both constants with `where [(); R::N_CHI]:, [(); R::N_ROTAMERS]:`.
+///
+/// # Examples
+///
+/// ```
+/// #![feature(generic_const_exprs)]
+/// # #![allow(incomplete_features)]
+/// use dunbrack::{Leu, Residue, TypedResidue};
+///
+/// fn mean_chi1<R: TypedResidue>(phi: f32, psi: f32) -> f32
+/// where
+///     [(); R::N_CHI]:,
+///     [(); R::N_ROTAMERS]:,
+/// {
+///     R::rotamers(phi, psi).map(|rot| rot.prob * rot.chi_mean[0]).sum()
+/// }
+///
+/// assert!(mean_chi1::<Leu>(-65.0, -43.0) < 0.0);
+/// ```
+#[cfg(feature = "generic-const-exprs")]
+pub trait TypedResidue:
+    Residue<
+        Rot = crate::rotamer::Rotamer<{ Self::N_CHI }>,
+        Iter = crate::interp::RotamerIter<{ Self::N_CHI }, { Self::N_ROTAMERS }>,
+        Chis = [f32; Self::N_CHI],
+    >
+{
+}
+
+#[cfg(feature = "generic-const-exprs")]
+impl<R> TypedResidue for R where
+    R: Residue<
+            Rot = crate::rotamer::Rotamer<{ R::N_CHI }>,
+            Iter = crate::interp::RotamerIter<{ R::N_CHI }, { R::N_ROTAMERS }>,
+            Chis = [f32; R::N_CHI],
+        >
+{
 }
 
 /// Arginine (4 χ angles, 75 rotamers).
@@ -140,3 +886,79 @@ pub struct Tyr;
 /// Valine (1 χ angle, 3 rotamers).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Val;
+
+/// χ1-only view of residue type `R` (1 χ angle, one rotamer per χ1 bin).
+///
+/// Every grid cell of `R` is collapsed at build time to its χ1 bins:
+/// probabilities are summed over the rotamers sharing a bin, the χ1 mean is
+/// their probability-weighted circular mean, and σ is the standard deviation
+/// of that mixture. The result is a tiny table adequate for coarse placement
+/// and teaching. It implements [`Residue`] like any other type, and a binary
+/// that only queries `Chi1` types lets the linker drop the full tables.
+///
+/// Requires the `chi1` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Arg, Chi1, Residue};
+///
+/// assert_eq!(<Chi1<Arg>>::N_ROTAMERS, 3);
+/// let total: f32 = Chi1::<Arg>::rotamers(-65.0, -43.0).map(|r| r.prob).sum();
+/// assert!((total - 1.0).abs() < 1e-5);
+/// ```
+#[cfg(feature = "chi1")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Chi1<R>(core::marker::PhantomData<R>);
+
+/// Residue type `R` backed by the alternative library.
+///
+/// The build generates a second, complete set of tables from the CSV named
+/// by the `DUNBRACK_ALT_DATA` environment variable — another smoothing
+/// level of the 2010 library, or an older release converted to the same
+/// schema — next to the primary tables. `Alt<R>` implements [`Residue`]
+/// over those tables, so both libraries can be queried in one binary and
+/// compared point by point; the [`alt`](crate::alt) module aliases every
+/// type under its primary name.
+///
+/// The χ count and residue metadata are those of `R`. The rotamer count
+/// is taken from the alternative data, which may enumerate a different set
+/// of rotamers; when the counts agree, the rotamer sets of `R` and `Alt<R>`
+/// share one type and compare directly with [`RotamerIter::diff`].
+///
+/// Requires the `alt-data` feature.
+///
+/// [`RotamerIter::diff`]: crate::RotamerIter::diff
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Alt, Arg, Residue};
+///
+/// let total: f32 = Alt::<Arg>::rotamers(-65.0, -43.0).map(|r| r.prob).sum();
+/// assert!((total - 1.0).abs() < 1e-3);
+///
+/// // Probability shift of the most likely primary rotamer.
+/// let top = Arg::rotamers(-65.0, -43.0).max_by(|a, b| a.prob.total_cmp(&b.prob)).unwrap();
+/// let alt = dunbrack::alt::Arg::rotamers(-65.0, -43.0).find(|r| r.r == top.r);
+/// let shift = alt.map_or(top.prob, |r| (r.prob - top.prob).abs());
+/// assert!(shift <= 1.0);
+/// ```
+#[cfg(feature = "alt-data")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Alt<R>(core::marker::PhantomData<R>);
+
+/// Table set backing a [`Residue`] type.
+///
+/// The `Chi1<R>` and `Alt<R>` variants share [`NAME`](Residue::NAME) with
+/// `R` but not its tables, so tables kept outside the binary are told
+/// apart by library and shape as well as by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Library {
+    /// The primary tables of the plain residue types.
+    Primary,
+    /// The χ1-only reduced tables of `Chi1<R>` (`chi1` feature).
+    Chi1,
+    /// The alternative tables of `Alt<R>` (`alt-data` feature).
+    Alt,
+}
@@ -0,0 +1,267 @@
+use crate::interp::RotamerIter;
+use crate::math::wrap_deg;
+use crate::rotamer::Rotamer;
+use crate::topology::Topology;
+use crate::units::EnergyUnits;
+
+/// Degrees-to-radians conversion factor.
+const DEG_TO_RAD: f32 = core::f32::consts::PI / 180.0;
+
+/// How rotamer statistics map onto restraint parameters.
+///
+/// A χ with standard deviation σ becomes a flat-bottom harmonic restraint
+/// whose flat region spans `width_sigmas · σ` either side of the target and
+/// whose force constant is `kt / σ²` (σ in radians): the curvature of
+/// `−kT ln p` for a Gaussian of width σ, so a restraint is as stiff as the
+/// library is certain. `kt` sets the energy unit; build it from an
+/// [`EnergyUnits`] with [`in_units`](Self::in_units).
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{EnergyUnits, RestraintScale};
+///
+/// let scale = RestraintScale::in_units(EnergyUnits::kcal_per_mol(298.15)).width_sigmas(0.5);
+/// assert!((scale.kt - 0.5925).abs() < 1e-4);
+/// assert_eq!(RestraintScale::default().width_sigmas, 1.0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RestraintScale {
+    /// Thermal energy in the caller's energy unit.
+    pub kt: f32,
+    /// Half-width of the flat bottom, in units of σ.
+    pub width_sigmas: f32,
+}
+
+impl RestraintScale {
+    /// Scale with thermal energy `kt` and a one-σ flat bottom.
+    pub const fn new(kt: f32) -> Self {
+        Self {
+            kt,
+            width_sigmas: 1.0,
+        }
+    }
+
+    /// Scale with energies in `units` and a one-σ flat bottom.
+    pub fn in_units(units: EnergyUnits) -> Self {
+        Self::new(units.kt())
+    }
+
+    /// Set the flat-bottom half-width in units of σ; `0.0` gives a plain
+    /// harmonic restraint.
+    pub const fn width_sigmas(mut self, width_sigmas: f32) -> Self {
+        self.width_sigmas = width_sigmas;
+        self
+    }
+
+    /// The restraint on χ `chi_index` with the given target and σ, in
+    /// degrees.
+    fn restraint(
+        &self,
+        topology: &Topology,
+        chi_index: usize,
+        target: f32,
+        sigma: f32,
+    ) -> TorsionRestraint {
+        let sigma_rad = sigma * DEG_TO_RAD;
+        TorsionRestraint {
+            chi_index,
+            atoms: topology.chi_atoms[chi_index],
+            target,
+            sigma,
+            half_width: (self.width_sigmas * sigma).min(180.0),
+            force_constant: if sigma > 0.0 {
+                self.kt / (sigma_rad * sigma_rad)
+            } else {
+                0.0
+            },
+        }
+    }
+}
+
+impl Default for RestraintScale {
+    /// Energies in units of kT, with a one-σ flat bottom.
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+/// Flat-bottom harmonic dihedral restraint on one χ angle, for restrained
+/// refinement or minimization.
+///
+/// The energy is zero within `half_width` of `target` and
+/// `½ · force_constant · (|Δχ| − half_width)²` beyond it, with Δχ wrapped
+/// into \[−180°, 180°) and converted to radians.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TorsionRestraint {
+    /// Index of the restrained χ, 0-based.
+    pub chi_index: usize,
+    /// Atoms `(a, b, c, d)` of the dihedral, as in
+    /// [`Topology::chi_atoms`].
+    pub atoms: [&'static str; 4],
+    /// Target angle in degrees, in \[−180°, 180°\].
+    pub target: f32,
+    /// Standard deviation the restraint was derived from, in degrees.
+    pub sigma: f32,
+    /// Half-width of the flat bottom in degrees.
+    pub half_width: f32,
+    /// Harmonic force constant in energy per rad², zero for an empty
+    /// rotamer set.
+    pub force_constant: f32,
+}
+
+impl TorsionRestraint {
+    /// Restraint energy at dihedral `chi` in degrees, in the unit of
+    /// [`RestraintScale::kt`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Leu, Residue, RestraintScale};
+    ///
+    /// let best = Leu::rotamers(-65.0, -43.0).max_by(|a, b| a.prob.total_cmp(&b.prob)).unwrap();
+    /// let [chi1, _] = best.restraints(&Leu::TOPOLOGY, &RestraintScale::default());
+    /// assert_eq!(chi1.energy(chi1.target), 0.0);
+    /// assert!(chi1.energy(chi1.target + 3.0 * chi1.sigma) > 1.0);
+    /// ```
+    pub fn energy(&self, chi: f32) -> f32 {
+        let excess = (wrap_deg(chi - self.target).abs() - self.half_width).max(0.0) * DEG_TO_RAD;
+        0.5 * self.force_constant * excess * excess
+    }
+}
+
+impl<const N: usize> Rotamer<N> {
+    /// Dihedral restraints holding each χ at this rotamer's mean, with
+    /// widths and force constants from its σ as set by `scale`.
+    ///
+    /// `topology` supplies the restrained atoms; pass the residue's
+    /// [`Residue::TOPOLOGY`](crate::Residue::TOPOLOGY).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `topology` does not define `N` χ angles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Phe, Residue, RestraintScale};
+    ///
+    /// let rot = Phe::rotamers(-65.0, -43.0).next().unwrap();
+    /// let restraints = rot.restraints(&Phe::TOPOLOGY, &RestraintScale::new(0.593));
+    /// assert_eq!(restraints[1].atoms, ["CA", "CB", "CG", "CD1"]);
+    /// assert_eq!(restraints[0].target, rot.chi_mean[0]);
+    /// assert_eq!(restraints[0].half_width, rot.chi_sigma[0]);
+    /// ```
+    pub fn restraints(&self, topology: &Topology, scale: &RestraintScale) -> [TorsionRestraint; N] {
+        check_topology::<N>(topology);
+        core::array::from_fn(|i| scale.restraint(topology, i, self.chi_mean[i], self.chi_sigma[i]))
+    }
+}
+
+impl<const N: usize, const R: usize> RotamerIter<N, R> {
+    /// Dihedral restraints holding each χ within the remaining rotamers,
+    /// e.g. a pruned set.
+    ///
+    /// Targets are the probability-weighted circular means
+    /// ([`mean_chis`](Self::mean_chis)) and σ is the total spread
+    /// ([`ChiSpread::total`](crate::ChiSpread::total)), so the restraint
+    /// widens to cover every retained rotamer. Sets spanning distinct χ
+    /// basins are better restrained one rotamer at a time with
+    /// [`Rotamer::restraints`]. An empty set yields zero force constants.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `topology` does not define `N` χ angles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Query, Residue, RestraintScale, Val};
+    ///
+    /// let kept = Query::new(-65.0, -43.0).prune(0.5).run::<Val>();
+    /// let [chi1] = kept.restraints(&Val::TOPOLOGY, &RestraintScale::default());
+    /// assert!(chi1.target.abs() > 150.0); // trans dominates
+    /// assert!(chi1.force_constant > 0.0);
+    /// ```
+    pub fn restraints(&self, topology: &Topology, scale: &RestraintScale) -> [TorsionRestraint; N] {
+        check_topology::<N>(topology);
+        let targets = self.mean_chis();
+        let spread = self.weighted_sigma();
+        core::array::from_fn(|i| scale.restraint(topology, i, targets[i], spread[i].total))
+    }
+}
+
+/// Checks that `topology` defines `N` χ angles.
+fn check_topology<const N: usize>(topology: &Topology) {
+    assert_eq!(
+        topology.chi_atoms.len(),
+        N,
+        "topology defines {} χ angles, rotamer has {N}",
+        topology.chi_atoms.len()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::Query;
+    use crate::residue::{Arg, Leu, Residue, Val};
+
+    #[test]
+    fn test_force_constant_tracks_sigma() {
+        let rot = Rotamer {
+            r: [1, 1],
+            prob: 1.0,
+            chi_mean: [-60.0, 170.0],
+            chi_sigma: [10.0, 20.0],
+        };
+        let [a, b] = rot.restraints(&Leu::TOPOLOGY, &RestraintScale::new(2.0));
+        assert!((a.force_constant / b.force_constant - 4.0).abs() < 1e-4);
+        let sigma_rad = 10.0 * DEG_TO_RAD;
+        assert!((a.force_constant - 2.0 / (sigma_rad * sigma_rad)).abs() < 1e-2);
+        assert_eq!(b.atoms, ["CA", "CB", "CG", "CD1"]);
+    }
+
+    #[test]
+    fn test_energy_is_flat_then_harmonic_and_wraps() {
+        let rot = Rotamer {
+            r: [2],
+            prob: 1.0,
+            chi_mean: [175.0],
+            chi_sigma: [10.0],
+        };
+        let [chi1] = rot.restraints(&Val::TOPOLOGY, &RestraintScale::default());
+        assert_eq!(chi1.energy(-175.0), 0.0); // 10° across ±180°
+        let one_sigma = chi1.energy(175.0 + 20.0);
+        assert!((one_sigma - 0.5).abs() < 1e-3, "{one_sigma}");
+        assert!((chi1.energy(175.0 - 30.0) - 2.0).abs() < 1e-3);
+
+        let harmonic = RestraintScale::default().width_sigmas(0.0);
+        let [chi1] = rot.restraints(&Val::TOPOLOGY, &harmonic);
+        assert!((chi1.energy(185.0) - 0.5).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_set_restraints_cover_members() {
+        let set = Query::new(-65.0, -43.0).prune(0.05).run::<Arg>();
+        let restraints = set.restraints(&Arg::TOPOLOGY, &RestraintScale::default());
+        let single = set.clone().next().unwrap();
+        for (i, r) in restraints.iter().enumerate() {
+            assert!(r.sigma >= single.chi_sigma[i] * 0.5);
+            assert_eq!(r.chi_index, i);
+        }
+
+        let empty = Query::new(-65.0, -43.0).prune(1.1).run::<Val>();
+        let [chi1] = empty.restraints(&Val::TOPOLOGY, &RestraintScale::default());
+        assert_eq!(chi1.force_constant, 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "χ angles")]
+    fn test_topology_mismatch_panics() {
+        Val::rotamers(-65.0, -43.0)
+            .next()
+            .unwrap()
+            .restraints(&Leu::TOPOLOGY, &RestraintScale::default());
+    }
+}
@@ -0,0 +1,107 @@
+use std::io::{self, Write};
+
+use crate::restraint::TorsionRestraint;
+
+/// Degrees-to-radians conversion factor.
+const DEG_TO_RAD: f32 = core::f32::consts::PI / 180.0;
+
+/// Write χ restraints as a Rosetta constraint file, one `Dihedral` line per
+/// restraint.
+///
+/// Each item pairs a Rosetta pose residue number (1-based) with a
+/// restraint of that residue. Plain harmonic restraints (zero
+/// [`half_width`](TorsionRestraint::half_width)) become
+/// `CIRCULARHARMONIC x0 sd`; flat-bottom ones become
+/// `PERIODICBOUNDED 2π lb ub sd 0.5 chiN`, zero between the bounds. All
+/// angles are in radians and `sd` is the restraint's σ, so Rosetta's
+/// `((x − x0) / sd)²` penalty matches [`TorsionRestraint::energy`] up to
+/// the constraint weight of the scoring function.
+///
+/// Requires the `std` feature.
+///
+/// # Errors
+///
+/// Returns any error reported by `out`.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Leu, Residue, RestraintScale, write_rosetta_constraints};
+///
+/// let rot = Leu::rotamers(-65.0, -43.0).next().unwrap();
+/// let scale = RestraintScale::default().width_sigmas(0.0);
+/// let restraints = rot.restraints(&Leu::TOPOLOGY, &scale);
+///
+/// let mut cst = Vec::new();
+/// write_rosetta_constraints(&mut cst, restraints.map(|r| (12, r))).unwrap();
+/// let text = String::from_utf8(cst).unwrap();
+/// assert!(text.starts_with("Dihedral N 12 CA 12 CB 12 CG 12 CIRCULARHARMONIC "));
+/// assert_eq!(text.lines().count(), 2);
+/// ```
+pub fn write_rosetta_constraints<W, I>(out: W, restraints: I) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = (u32, TorsionRestraint)>,
+{
+    let mut out = io::BufWriter::new(out);
+    for (seqpos, restraint) in restraints {
+        write!(out, "Dihedral")?;
+        for atom in restraint.atoms {
+            write!(out, " {atom} {seqpos}")?;
+        }
+        let x0 = restraint.target * DEG_TO_RAD;
+        let sd = restraint.sigma * DEG_TO_RAD;
+        if restraint.half_width > 0.0 {
+            let half = restraint.half_width * DEG_TO_RAD;
+            writeln!(
+                out,
+                " PERIODICBOUNDED {:.6} {:.6} {:.6} {sd:.6} 0.5 chi{}",
+                core::f32::consts::TAU,
+                x0 - half,
+                x0 + half,
+                restraint.chi_index + 1
+            )?;
+        } else {
+            writeln!(out, " CIRCULARHARMONIC {x0:.6} {sd:.6}")?;
+        }
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Phe, Residue as _};
+    use crate::restraint::RestraintScale;
+    use crate::rotamer::Rotamer;
+
+    #[test]
+    fn test_flat_bottom_lines() {
+        let rot = Rotamer {
+            r: [1, 2],
+            prob: 1.0,
+            chi_mean: [-60.0, 90.0],
+            chi_sigma: [10.0, 20.0],
+        };
+        let restraints = rot.restraints(&Phe::TOPOLOGY, &RestraintScale::default());
+        let mut cst = std::vec::Vec::new();
+        write_rosetta_constraints(&mut cst, restraints.map(|r| (7, r))).unwrap();
+        let text = std::string::String::from_utf8(cst).unwrap();
+        let lines: std::vec::Vec<_> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "Dihedral N 7 CA 7 CB 7 CG 7 PERIODICBOUNDED 6.283185 -1.221730 -0.872665 0.174533 0.5 chi1"
+        );
+        assert_eq!(
+            lines[1],
+            "Dihedral CA 7 CB 7 CG 7 CD1 7 PERIODICBOUNDED 6.283185 1.221730 1.919862 0.349066 0.5 chi2"
+        );
+    }
+
+    #[test]
+    fn test_empty_input_writes_nothing() {
+        let mut cst = std::vec::Vec::new();
+        write_rosetta_constraints(&mut cst, []).unwrap();
+        assert!(cst.is_empty());
+    }
+}
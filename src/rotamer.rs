@@ -1,3 +1,5 @@
+use crate::math::wrap_deg;
+
 /// A backbone-dependent rotamer entry.
 ///
 /// Each rotamer describes a discrete side-chain conformation at a specific
@@ -11,6 +13,21 @@
 /// compiler versions. This is beneficial for reproducible binary output
 /// and cache-line alignment predictability.
 ///
+/// The layout is part of the public API and will not change within a
+/// major version: `r` at offset 0, `prob` at `N` rounded up to a multiple
+/// of 4, then `chi_mean` and `chi_sigma` back to back, with alignment 4
+/// and no trailing padding. [`SIZE`](Self::SIZE), [`ALIGN`](Self::ALIGN),
+/// and the `*_OFFSET` constants spell this out for FFI declarations and
+/// binary formats; compile-time assertions check them against the
+/// compiler's layout for every χ count the library uses (1 to 4).
+///
+/// | `N` | `prob` | `chi_mean` | `chi_sigma` | size |
+/// |-----|--------|------------|-------------|------|
+/// | 1   | 4      | 8          | 12          | 16   |
+/// | 2   | 4      | 8          | 16          | 24   |
+/// | 3   | 4      | 8          | 20          | 32   |
+/// | 4   | 4      | 8          | 24          | 40   |
+///
 /// # Examples
 ///
 /// ```
@@ -53,6 +70,100 @@ pub struct Rotamer<const N: usize> {
     pub chi_sigma: [f32; N],
 }
 
+impl<const N: usize> Rotamer<N> {
+    /// Size of the struct in bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::Rotamer;
+    ///
+    /// assert_eq!(Rotamer::<4>::SIZE, core::mem::size_of::<Rotamer<4>>());
+    /// assert_eq!(Rotamer::<4>::SIZE, 40);
+    /// ```
+    pub const SIZE: usize = Self::CHI_SIGMA_OFFSET + 4 * N;
+
+    /// Alignment of the struct in bytes.
+    pub const ALIGN: usize = 4;
+
+    /// Byte offset of [`r`](Self::r).
+    pub const R_OFFSET: usize = 0;
+
+    /// Byte offset of [`prob`](Self::prob).
+    pub const PROB_OFFSET: usize = N.next_multiple_of(4);
+
+    /// Byte offset of [`chi_mean`](Self::chi_mean).
+    pub const CHI_MEAN_OFFSET: usize = Self::PROB_OFFSET + 4;
+
+    /// Byte offset of [`chi_sigma`](Self::chi_sigma).
+    pub const CHI_SIGMA_OFFSET: usize = Self::CHI_MEAN_OFFSET + 4 * N;
+
+    /// Whether the layout constants match the compiler's layout.
+    const fn layout_holds() -> bool {
+        use core::mem::{align_of, offset_of, size_of};
+
+        size_of::<Self>() == Self::SIZE
+            && align_of::<Self>() == Self::ALIGN
+            && offset_of!(Self, r) == Self::R_OFFSET
+            && offset_of!(Self, prob) == Self::PROB_OFFSET
+            && offset_of!(Self, chi_mean) == Self::CHI_MEAN_OFFSET
+            && offset_of!(Self, chi_sigma) == Self::CHI_SIGMA_OFFSET
+    }
+
+    /// Wrap-aware interval `mean ± k_sigma · σ` for χ angle `i`, in degrees.
+    ///
+    /// Both bounds are wrapped into \[−180°, 180°). When the interval
+    /// straddles ±180° the lower bound is numerically greater than the
+    /// upper bound, and the interval runs from `lo` up through ±180° to
+    /// `hi`. An interval spanning the full circle (`k_sigma · σ ≥ 180°`) is
+    /// returned as `(−180.0, 180.0)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i >= N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::Rotamer;
+    ///
+    /// let rot = Rotamer { r: [2], prob: 1.0, chi_mean: [175.0], chi_sigma: [10.0] };
+    /// let (lo, hi) = rot.chi_interval(0, 2.0);
+    /// assert!((lo - 155.0).abs() < 1e-4);
+    /// assert!((hi - -165.0).abs() < 1e-4); // wraps past +180°
+    /// ```
+    pub fn chi_interval(&self, i: usize, k_sigma: f32) -> (f32, f32) {
+        let half = (k_sigma * self.chi_sigma[i]).abs();
+        if half >= 180.0 {
+            return (-180.0, 180.0);
+        }
+        let mean = self.chi_mean[i];
+        (wrap_deg(mean - half), wrap_deg(mean + half))
+    }
+
+    /// Copies the rotamer into four χ slots, zero-filling the unused ones.
+    pub(crate) fn padded(self) -> Rotamer<4> {
+        let mut out = Rotamer {
+            r: [0; 4],
+            prob: self.prob,
+            chi_mean: [0.0; 4],
+            chi_sigma: [0.0; 4],
+        };
+        out.r[..N].copy_from_slice(&self.r);
+        out.chi_mean[..N].copy_from_slice(&self.chi_mean);
+        out.chi_sigma[..N].copy_from_slice(&self.chi_sigma);
+        out
+    }
+}
+
+const _: () = assert!(
+    Rotamer::<1>::layout_holds()
+        && Rotamer::<2>::layout_holds()
+        && Rotamer::<3>::layout_holds()
+        && Rotamer::<4>::layout_holds(),
+    "Rotamer layout differs from its documented constants"
+);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -87,4 +198,42 @@ mod tests {
         assert_relative_eq!(rot.chi_mean[0], 175.0, epsilon = 1e-6);
         assert_relative_eq!(rot.chi_sigma[0], 8.5, epsilon = 1e-6);
     }
+
+    #[test]
+    fn test_chi_interval_no_wrap() {
+        let rot = Rotamer {
+            r: [1],
+            prob: 1.0,
+            chi_mean: [60.0],
+            chi_sigma: [10.0],
+        };
+        let (lo, hi) = rot.chi_interval(0, 1.5);
+        assert_relative_eq!(lo, 45.0, epsilon = 1e-4);
+        assert_relative_eq!(hi, 75.0, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn test_chi_interval_wraps_below_minus_180() {
+        let rot = Rotamer {
+            r: [1],
+            prob: 1.0,
+            chi_mean: [-170.0],
+            chi_sigma: [10.0],
+        };
+        let (lo, hi) = rot.chi_interval(0, 2.0);
+        assert_relative_eq!(lo, 170.0, epsilon = 1e-4);
+        assert_relative_eq!(hi, -150.0, epsilon = 1e-4);
+        assert!(lo > hi);
+    }
+
+    #[test]
+    fn test_chi_interval_full_circle() {
+        let rot = Rotamer {
+            r: [1],
+            prob: 1.0,
+            chi_mean: [0.0],
+            chi_sigma: [100.0],
+        };
+        assert_eq!(rot.chi_interval(0, 2.0), (-180.0, 180.0));
+    }
 }
@@ -0,0 +1,445 @@
+use crate::interp::RotamerIter;
+use crate::math::{probit, wrap_deg};
+use crate::rotamer::Rotamer;
+
+/// Largest number of χ angles of any residue type.
+const MAX_CHI: usize = 4;
+
+/// Sobol dimensions used per draw: one to pick the rotamer, one per χ angle.
+const DIMS: usize = MAX_CHI + 1;
+
+/// One side-chain conformation drawn from a rotamer distribution.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Residue, Val};
+///
+/// let s = Val::rotamers(-60.0, -40.0).sobol(7).next().unwrap();
+/// assert!((1..=3).contains(&s.r[0]));
+/// assert!((-180.0..180.0).contains(&s.chi[0]));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample<const N: usize> {
+    /// Bin indices of the rotamer the conformation was drawn from.
+    pub r: [u8; N],
+    /// χ angles in degrees, drawn from the rotamer's Gaussian and wrapped
+    /// into \[−180°, 180°).
+    pub chi: [f32; N],
+}
+
+/// Low-discrepancy sampler over a computed rotamer set.
+///
+/// Draw `i` maps point `i` of a digitally shifted Sobol sequence to a
+/// conformation: the first coordinate selects a rotamer by inverting the
+/// cumulative probability, the remaining coordinates place each χ angle via
+/// the inverse normal CDF around the rotamer's mean and standard deviation.
+/// Compared to independent draws, any run of `2ᵏ` consecutive draws hits each
+/// rotamer within one draw of its expected count and covers the χ Gaussians
+/// evenly.
+///
+/// Draws depend only on the rotamer set, the seed, and the draw index, so
+/// ensembles are reproducible and can be generated out of order with
+/// [`get`](Self::get). Pruned rotamers are never drawn; the remaining
+/// probabilities are re-weighted to sum to one.
+///
+/// Produced by [`RotamerIter::sobol`].
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Leu, Residue};
+///
+/// let ensemble: Vec<_> = Leu::rotamers(-65.0, -40.0).sobol(42).take(64).collect();
+/// let again: Vec<_> = Leu::rotamers(-65.0, -40.0).sobol(42).take(64).collect();
+/// assert_eq!(ensemble, again);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SobolSampler<const N: usize, const R: usize> {
+    items: [Rotamer<N>; R],
+    cdf: [f32; R],
+    len: usize,
+    shift: [u32; DIMS],
+    index: u32,
+}
+
+impl<const N: usize, const R: usize> RotamerIter<N, R> {
+    /// Turn the remaining rotamers into a Sobol ensemble sampler whose
+    /// scrambling is determined by `seed`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// let sampler = Val::rotamers(-60.0, -40.0).sobol(0);
+    /// assert_eq!(sampler.get(5), sampler.clone().nth(5));
+    /// ```
+    pub fn sobol(self, seed: u64) -> SobolSampler<N, R> {
+        let (items, len) = copy_remaining(&self);
+        let mut cdf = [0.0_f32; R];
+        let mut acc = 0.0_f32;
+        for (c, rot) in cdf.iter_mut().zip(&items[..len]) {
+            acc += rot.prob;
+            *c = acc;
+        }
+
+        SobolSampler {
+            items,
+            cdf,
+            len,
+            shift: seed_shift(seed),
+            index: 0,
+        }
+    }
+
+    /// Turn the remaining rotamers into a stratified sampler yielding exactly
+    /// `count` conformations.
+    ///
+    /// Every rotamer with probability at least `min_prob` receives one draw
+    /// first; the remaining draws are then shared among all rotamers in
+    /// proportion to their probabilities (largest-remainder rounding). If
+    /// `count` is smaller than the number of such rotamers, the `count` most
+    /// probable ones receive one draw each. χ angles within each rotamer are
+    /// placed with the same scrambled Sobol points as [`sobol`](Self::sobol).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Met, Residue};
+    ///
+    /// // A small ensemble still visits every Met rotamer above 1%.
+    /// let rots: Vec<_> = Met::rotamers(-65.0, -40.0).filter(|r| r.prob >= 0.01).collect();
+    /// let draws: Vec<_> = Met::rotamers(-65.0, -40.0).stratified(32, 0.01, 0).collect();
+    /// assert_eq!(draws.len(), 32);
+    /// assert!(rots.iter().all(|rot| draws.iter().any(|d| d.r == rot.r)));
+    /// ```
+    pub fn stratified(self, count: usize, min_prob: f32, seed: u64) -> StratifiedSampler<N, R> {
+        let (items, len) = copy_remaining(&self);
+        let rots = &items[..len];
+        let mut counts = [0usize; R];
+
+        let eligible = |rot: &Rotamer<N>| rot.prob > 0.0 && rot.prob >= min_prob;
+        let n_eligible = rots.iter().filter(|rot| eligible(rot)).count();
+
+        if count <= n_eligible {
+            let mut order: [usize; R] = core::array::from_fn(|k| k);
+            order[..len]
+                .sort_unstable_by(|&a, &b| rots[b].prob.total_cmp(&rots[a].prob).then(a.cmp(&b)));
+            for &k in &order[..count] {
+                counts[k] = 1;
+            }
+        } else {
+            let rest = count - n_eligible;
+            let total: f32 = rots.iter().map(|rot| rot.prob).sum();
+            let mut frac = [0.0_f32; R];
+            let mut assigned = 0;
+            for (k, rot) in rots.iter().enumerate() {
+                let quota = if total > 0.0 {
+                    rest as f32 * rot.prob / total
+                } else {
+                    0.0
+                };
+                let whole = (quota as usize).min(rest - assigned);
+                counts[k] = whole + eligible(rot) as usize;
+                frac[k] = quota - whole as f32;
+                assigned += whole;
+            }
+            for _ in assigned..rest {
+                let k = (0..len)
+                    .max_by(|&a, &b| frac[a].total_cmp(&frac[b]).then(b.cmp(&a)))
+                    .unwrap_or(0);
+                counts[k] += 1;
+                frac[k] = f32::NEG_INFINITY;
+            }
+        }
+
+        StratifiedSampler {
+            items,
+            counts,
+            len,
+            shift: seed_shift(seed),
+            rot: 0,
+            drawn: 0,
+            remaining: if len == 0 { 0 } else { count },
+        }
+    }
+}
+
+/// Copies the rotamers an iterator has not yet yielded into a fixed buffer.
+fn copy_remaining<const N: usize, const R: usize>(
+    iter: &RotamerIter<N, R>,
+) -> ([Rotamer<N>; R], usize) {
+    let rest = iter.as_slice();
+    let mut items = [Rotamer {
+        r: [0; N],
+        prob: 0.0,
+        chi_mean: [0.0; N],
+        chi_sigma: [0.0; N],
+    }; R];
+    items[..rest.len()].copy_from_slice(rest);
+    (items, rest.len())
+}
+
+/// Derives the per-dimension digital shift of the Sobol sequence from a seed.
+fn seed_shift(seed: u64) -> [u32; DIMS] {
+    let mut state = seed;
+    core::array::from_fn(|_| (splitmix64(&mut state) >> 32) as u32)
+}
+
+/// Places the χ angles of `rot` using coordinates `1..=N` of a Sobol point.
+fn draw<const N: usize>(rot: &Rotamer<N>, point: &[f32; DIMS]) -> Sample<N> {
+    let chi = core::array::from_fn(|i| {
+        wrap_deg(rot.chi_mean[i] + rot.chi_sigma[i] * probit(point[i + 1]))
+    });
+    Sample { r: rot.r, chi }
+}
+
+impl<const N: usize, const R: usize> SobolSampler<N, R> {
+    /// Return draw number `index`, or `None` if the rotamer set is empty.
+    pub fn get(&self, index: u32) -> Option<Sample<N>> {
+        if self.len == 0 {
+            return None;
+        }
+        let point = sobol_point(index, &self.shift);
+
+        let target = point[0] * self.cdf[self.len - 1];
+        let k = self.cdf[..self.len - 1].partition_point(|&c| c <= target);
+        Some(draw(&self.items[k], &point))
+    }
+}
+
+impl<const N: usize, const R: usize> Iterator for SobolSampler<N, R> {
+    type Item = Sample<N>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Sample<N>> {
+        let sample = self.get(self.index)?;
+        self.index = self.index.wrapping_add(1);
+        Some(sample)
+    }
+}
+
+/// Stratified sampler over a computed rotamer set.
+///
+/// Yields a fixed number of conformations grouped by rotamer, in the order
+/// the rotamers were yielded by the originating iterator. Produced by
+/// [`RotamerIter::stratified`].
+#[derive(Debug, Clone)]
+pub struct StratifiedSampler<const N: usize, const R: usize> {
+    items: [Rotamer<N>; R],
+    counts: [usize; R],
+    len: usize,
+    shift: [u32; DIMS],
+    rot: usize,
+    drawn: usize,
+    remaining: usize,
+}
+
+impl<const N: usize, const R: usize> StratifiedSampler<N, R> {
+    /// Number of draws allotted to each rotamer, aligned with the rotamers
+    /// of the originating iterator.
+    #[inline]
+    pub fn counts(&self) -> &[usize] {
+        &self.counts[..self.len]
+    }
+}
+
+impl<const N: usize, const R: usize> Iterator for StratifiedSampler<N, R> {
+    type Item = Sample<N>;
+
+    fn next(&mut self) -> Option<Sample<N>> {
+        while self.rot < self.len && self.drawn == self.counts[self.rot] {
+            self.rot += 1;
+            self.drawn = 0;
+        }
+        if self.rot == self.len {
+            return None;
+        }
+        let point = sobol_point(self.drawn as u32, &self.shift);
+        self.drawn += 1;
+        self.remaining -= 1;
+        Some(draw(&self.items[self.rot], &point))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<const N: usize, const R: usize> ExactSizeIterator for StratifiedSampler<N, R> {}
+
+impl<const N: usize, const R: usize> core::iter::FusedIterator for StratifiedSampler<N, R> {}
+
+/// Joe–Kuo primitive polynomials `(degree, coefficients)` and initial
+/// direction numbers for Sobol dimensions 2 through 5.
+const JOE_KUO: [(usize, u32, [u32; 3]); DIMS - 1] = [
+    (1, 0, [1, 0, 0]),
+    (2, 1, [1, 3, 0]),
+    (3, 1, [1, 3, 1]),
+    (3, 2, [1, 1, 1]),
+];
+
+/// Direction numbers `V[d][j]` for the 32 bits of each Sobol dimension.
+const DIRECTIONS: [[u32; 32]; DIMS] = {
+    let mut v = [[0u32; 32]; DIMS];
+    let mut j = 0;
+    while j < 32 {
+        v[0][j] = 1 << (31 - j);
+        j += 1;
+    }
+    let mut d = 1;
+    while d < DIMS {
+        let (s, a, m) = JOE_KUO[d - 1];
+        let mut j = 0;
+        while j < 32 {
+            v[d][j] = if j < s {
+                m[j] << (31 - j)
+            } else {
+                let mut x = v[d][j - s] ^ (v[d][j - s] >> s);
+                let mut k = 1;
+                while k < s {
+                    if (a >> (s - 1 - k)) & 1 == 1 {
+                        x ^= v[d][j - k];
+                    }
+                    k += 1;
+                }
+                x
+            };
+            j += 1;
+        }
+        d += 1;
+    }
+    v
+};
+
+/// Computes point `index` of the digitally shifted Sobol sequence, mapped to
+/// the open unit cube so every coordinate is a valid [`probit`] argument.
+fn sobol_point(index: u32, shift: &[u32; DIMS]) -> [f32; DIMS] {
+    core::array::from_fn(|d| {
+        let mut x = shift[d];
+        let mut bits = index;
+        let mut j = 0;
+        while bits != 0 {
+            if bits & 1 == 1 {
+                x ^= DIRECTIONS[d][j];
+            }
+            bits >>= 1;
+            j += 1;
+        }
+        ((x >> 8) as f32 + 0.5) * (1.0 / (1u32 << 24) as f32)
+    })
+}
+
+/// Advances a SplitMix64 state and returns the next output.
+pub(crate) fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Arg, Residue, Val};
+
+    #[test]
+    fn test_directions_second_dimension() {
+        // Dimension 2 has m = 1, 3, 5, 15, 17, 51 (polynomial x + 1).
+        let m = [1u32, 3, 5, 15, 17, 51];
+        for (j, &mj) in m.iter().enumerate() {
+            assert_eq!(DIRECTIONS[1][j], mj << (31 - j));
+        }
+    }
+
+    #[test]
+    fn test_sobol_deterministic() {
+        let a = Arg::rotamers(-65.0, -43.0).sobol(9);
+        let b = Arg::rotamers(-65.0, -43.0).sobol(9);
+        for i in 0..100 {
+            assert_eq!(a.get(i), b.get(i));
+        }
+    }
+
+    #[test]
+    fn test_sobol_seed_changes_draws() {
+        let a = Arg::rotamers(-65.0, -43.0).sobol(1);
+        let b = Arg::rotamers(-65.0, -43.0).sobol(2);
+        assert!((0..16).any(|i| a.get(i) != b.get(i)));
+    }
+
+    #[test]
+    fn test_sobol_rotamer_counts_match_probabilities() {
+        let n = 1024;
+        let probs: [f32; 3] = {
+            let mut it = Val::rotamers(-60.0, -40.0);
+            core::array::from_fn(|_| it.next().unwrap().prob)
+        };
+        let mut counts = [0usize; 3];
+        for s in Val::rotamers(-60.0, -40.0).sobol(3).take(n) {
+            counts[s.r[0] as usize - 1] += 1;
+        }
+        for k in 0..3 {
+            let expected = probs[k] * n as f32;
+            assert!(
+                (counts[k] as f32 - expected).abs() <= 2.0,
+                "rotamer {k}: {} draws, expected {expected}",
+                counts[k]
+            );
+        }
+    }
+
+    #[test]
+    fn test_sobol_chi_within_range() {
+        for s in Arg::rotamers(-65.0, -43.0).sobol(5).take(500) {
+            for chi in s.chi {
+                assert!((-180.0..180.0).contains(&chi), "chi {chi} out of range");
+            }
+        }
+    }
+
+    #[test]
+    fn test_sobol_empty_set() {
+        let mut iter = Val::rotamers(-60.0, -40.0);
+        iter.prune(2.0);
+        assert!(iter.sobol(0).next().is_none());
+    }
+
+    #[test]
+    fn test_stratified_exact_count() {
+        for count in [0, 1, 5, 75, 200] {
+            let sampler = Arg::rotamers(-65.0, -43.0).stratified(count, 0.01, 0);
+            assert_eq!(sampler.len(), count);
+            assert_eq!(sampler.counts().iter().sum::<usize>(), count);
+            assert_eq!(sampler.count(), count);
+        }
+    }
+
+    #[test]
+    fn test_stratified_covers_threshold_rotamers() {
+        let sampler = Arg::rotamers(-65.0, -43.0).stratified(40, 0.005, 1);
+        for (rot, &n) in Arg::rotamers(-65.0, -43.0).zip(sampler.counts()) {
+            if rot.prob >= 0.005 {
+                assert!(n >= 1, "rotamer {:?} (p={}) not drawn", rot.r, rot.prob);
+            }
+        }
+    }
+
+    #[test]
+    fn test_stratified_fewer_draws_than_rotamers_takes_most_probable() {
+        let sampler = Val::rotamers(-60.0, -40.0).stratified(1, 0.0, 0);
+        assert_eq!(sampler.counts(), &[0, 1, 0]);
+    }
+
+    #[test]
+    fn test_stratified_remainder_is_proportional() {
+        let sampler = Val::rotamers(-60.0, -40.0).stratified(1003, 0.0, 0);
+        for (rot, &n) in Val::rotamers(-60.0, -40.0).zip(sampler.counts()) {
+            let expected = 1.0 + rot.prob * 1000.0;
+            assert!((n as f32 - expected).abs() <= 1.0, "{n} vs {expected}");
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use std::io::{self, Write};
+
+use crate::rotamer::Rotamer;
+
+/// Write the rotamer set of one sequence position in the column layout of
+/// the backbone-dependent library SCWRL4 reads (`bbdep02.May.lib`).
+///
+/// A `#` comment line records the position, residue, and exact backbone;
+/// each rotamer then follows on one line as
+/// `res φ ψ count r1 r2 r3 r4 prob χ1..χ4 σ1..σ4`, with φ and ψ rounded to
+/// whole degrees, a zero observation count, and χ slots past `N` zero
+/// filled, as in the library file. Rotamers are written in the order
+/// given; pass a [`Query::sorted`](crate::Query::sorted) set to match the
+/// library's descending-probability order. Call once per position to build
+/// a file covering a whole chain, for head-to-head comparisons and hybrid
+/// workflows with SCWRL.
+///
+/// Requires the `std` feature.
+///
+/// # Errors
+///
+/// Returns any error reported by `out`.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Leu, Query, Residue, write_scwrl_rotamers};
+///
+/// let set = Query::new(-65.0, -43.0).prune(0.05).sorted(true).run::<Leu>();
+/// let mut lib = Vec::new();
+/// write_scwrl_rotamers(&mut lib, 12, Leu::NAME, -65.0, -43.0, set.clone()).unwrap();
+///
+/// let text = String::from_utf8(lib).unwrap();
+/// let mut lines = text.lines();
+/// assert_eq!(lines.next(), Some("# position 12 LEU phi -65.0 psi -43.0"));
+/// assert!(lines.next().unwrap().starts_with("LEU   -65  -43     0  3  2  0  0  0.57"));
+/// assert_eq!(lines.count(), set.len() - 1);
+/// ```
+pub fn write_scwrl_rotamers<W, const N: usize>(
+    out: W,
+    seqpos: u32,
+    residue: &str,
+    phi: f32,
+    psi: f32,
+    rotamers: impl IntoIterator<Item = Rotamer<N>>,
+) -> io::Result<()>
+where
+    W: Write,
+{
+    let mut out = io::BufWriter::new(out);
+    writeln!(
+        out,
+        "# position {seqpos} {residue} phi {phi:.1} psi {psi:.1}"
+    )?;
+    for rot in rotamers {
+        let rot = rot.padded();
+        let [r1, r2, r3, r4] = rot.r;
+        write!(
+            out,
+            "{residue:<3} {phi:>5.0} {psi:>4.0}     0 {r1:>2} {r2:>2} {r3:>2} {r4:>2} {:>9.6}",
+            rot.prob
+        )?;
+        for chi in rot.chi_mean {
+            write!(out, " {chi:>7.1}")?;
+        }
+        for sigma in rot.chi_sigma {
+            write!(out, " {sigma:>6.1}")?;
+        }
+        writeln!(out)?;
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_columns_and_padding() {
+        let rot = Rotamer {
+            r: [3],
+            prob: 0.25,
+            chi_mean: [-62.54],
+            chi_sigma: [9.0],
+        };
+        let mut lib = std::vec::Vec::new();
+        write_scwrl_rotamers(&mut lib, 1, "SER", -180.0, 175.4, [rot]).unwrap();
+        let text = std::string::String::from_utf8(lib).unwrap();
+        assert_eq!(
+            text,
+            "# position 1 SER phi -180.0 psi 175.4\n\
+             SER  -180  175     0  3  0  0  0  0.250000   -62.5     0.0     0.0     0.0    9.0    0.0    0.0    0.0\n"
+        );
+    }
+}
@@ -1,2 +1,50 @@
+use crate::residue::{Library, Residue};
+
 /// Sealed trait to prevent external implementations of [`Residue`](crate::Residue).
-pub trait Sealed {}
+///
+/// It also carries the table plumbing of the generated implementations,
+/// which is not part of the public [`Residue`] interface.
+pub trait Sealed {
+    /// Table set backing the type.
+    const LIBRARY: Library;
+
+    /// Evaluation of cells read by a [`StoredTables`](crate::StoredTables).
+    fn stored_rotamers(cells: [&[f32]; 4], phi: f32, psi: f32) -> <Self as Residue>::Iter
+    where
+        Self: Residue;
+
+    /// Query evaluation against a mapped table backing
+    /// [`MappedTables`](crate::MappedTables).
+    #[cfg(feature = "mmap")]
+    fn mapped_query(table: &[u8], query: &crate::query::Query) -> <Self as Residue>::Iter
+    where
+        Self: Residue;
+
+    /// Distribution summary backing [`compare`](crate::compare).
+    fn profile(phi: f32, psi: f32) -> crate::compare::Profile;
+
+    /// Single-record scoring backing
+    /// [`rotamer_recovery`](crate::rotamer_recovery).
+    fn recovery_case(
+        phi: f32,
+        psi: f32,
+        native: &<Self as Residue>::Chis,
+        policy: crate::recovery::Policy,
+    ) -> crate::recovery::Case
+    where
+        Self: Residue;
+
+    /// Entry `k` of grid cell (`phi_idx`, `psi_idx`) backing
+    /// [`records`](crate::records).
+    fn grid_record(
+        residue: &'static crate::identity::ResidueInfo,
+        phi_idx: usize,
+        psi_idx: usize,
+        k: usize,
+    ) -> crate::records::Record;
+
+    /// Raw table access backing [`write_csv`](crate::write_csv) and
+    /// [`precompute`](crate::precompute).
+    #[cfg(feature = "std")]
+    fn visit_rows(f: &mut dyn FnMut(crate::export::Row));
+}
@@ -0,0 +1,731 @@
+use crate::error::Error;
+use crate::interp::RotamerIter;
+use crate::math::{atan2f, expf, lnf, normal_cdf, sincos_deg, sqrtf, wrap_deg};
+
+/// Radians-to-degrees conversion factor.
+const RAD_TO_DEG: f32 = 180.0 / core::f32::consts::PI;
+
+/// Degrees-to-radians conversion factor.
+const DEG_TO_RAD: f32 = core::f32::consts::PI / 180.0;
+
+/// `1 / √(2π)`, the peak of the standard normal density.
+const INV_SQRT_2PI: f32 = 0.398_942_3;
+
+/// Circular moments of one χ angle over a full rotamer mixture.
+///
+/// Produced by [`RotamerIter::chi_moments`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChiMoments {
+    /// Mean direction in degrees, in (−180°, 180°]; `0.0` when the
+    /// resultant length is zero.
+    pub mean: f32,
+    /// Mean resultant length `R̄₁ = |E[e^{iχ}]|`, in \[0, 1\].
+    pub resultant_length: f32,
+    /// Length of the second trigonometric moment `R̄₂ = |E[e^{2iχ}]|`, in
+    /// \[0, 1\]. Large values with a small `R̄₁` indicate two opposed modes.
+    pub second_resultant_length: f32,
+    /// Circular variance `1 − R̄₁`.
+    pub circular_variance: f32,
+    /// Circular standard deviation `√(−2 ln R̄₁)`, in degrees; infinite when
+    /// `R̄₁ = 0`.
+    pub circular_std: f32,
+}
+
+/// Probability-weighted spread of one χ angle over a rotamer set, split
+/// into the width of each rotamer and the scatter between rotamers.
+///
+/// Produced by [`RotamerIter::weighted_sigma`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChiSpread {
+    /// Probability-weighted mean of the rotamers' σ, in degrees.
+    pub within: f32,
+    /// Probability-weighted root-mean-square deviation of the rotamers'
+    /// χ means from their circular mean, in degrees, wrapped so it never
+    /// exceeds 180°.
+    pub between: f32,
+    /// `√(within² + between²)`, a single "how floppy is this χ" figure in
+    /// degrees.
+    pub total: f32,
+}
+
+impl<const N: usize, const R: usize> RotamerIter<N, R> {
+    /// Probability-weighted spread of each χ angle over the remaining
+    /// rotamers: the mean σ, the spread of the rotamer means, and their
+    /// combination, for flexibility profiling.
+    ///
+    /// Probabilities are normalized by their sum, so pruned sets are
+    /// summarized over what they retain; an empty set yields zeros.
+    /// Unlike [`chi_moments`](Self::chi_moments), the result stays in
+    /// degrees and separates the two sources of spread.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Arg, Residue};
+    ///
+    /// let spread = Arg::rotamers(-65.0, -43.0).weighted_sigma();
+    /// // The terminal χ₄ of Arg is floppier than χ₁.
+    /// assert!(spread[3].total > spread[0].total);
+    /// assert!(spread.iter().all(|s| s.total >= s.within.max(s.between)));
+    /// ```
+    pub fn weighted_sigma(&self) -> [ChiSpread; N] {
+        let total = self.total_prob();
+        if total <= 0.0 {
+            return [ChiSpread {
+                within: 0.0,
+                between: 0.0,
+                total: 0.0,
+            }; N];
+        }
+        core::array::from_fn(|i| {
+            let (mut s, mut c, mut within) = (0.0_f32, 0.0_f32, 0.0_f32);
+            for rot in self.as_slice() {
+                let (si, ci) = sincos_deg(rot.chi_mean[i]);
+                s += rot.prob * si;
+                c += rot.prob * ci;
+                within += rot.prob * rot.chi_sigma[i];
+            }
+            let center = atan2f(s, c) * RAD_TO_DEG;
+            let square: f32 = self
+                .as_slice()
+                .iter()
+                .map(|rot| {
+                    let d = wrap_deg(rot.chi_mean[i] - center);
+                    rot.prob * d * d
+                })
+                .sum();
+            let within = within / total;
+            let between = sqrtf(square / total).min(180.0);
+            ChiSpread {
+                within,
+                between,
+                total: sqrtf(within * within + between * between),
+            }
+        })
+    }
+
+    /// Probability-weighted circular mean of each χ angle over the remaining
+    /// rotamers, in degrees.
+    ///
+    /// Each rotamer contributes a wrapped Gaussian around its χ mean, so the
+    /// result is the mean direction of the full mixture: rotamers with a
+    /// wide σ pull less than tight ones of equal probability. Returns a value
+    /// in (−180°, 180°]; an angle whose mixture has no preferred direction
+    /// (resultant length 0) yields `0.0`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// // Val in a helix is dominated by the trans (χ₁ ≈ 175°) rotamer.
+    /// let [chi1] = Val::rotamers(-60.0, -40.0).mean_chis();
+    /// assert!(chi1.abs() > 150.0);
+    /// ```
+    pub fn mean_chis(&self) -> [f32; N] {
+        let (s, c) = self.first_moment();
+        core::array::from_fn(|i| {
+            if s[i] == 0.0 && c[i] == 0.0 {
+                0.0
+            } else {
+                atan2f(s[i], c[i]) * RAD_TO_DEG
+            }
+        })
+    }
+
+    /// Circular variance `1 − R̄` of each χ angle over the remaining rotamers,
+    /// where `R̄` is the mean resultant length of the full mixture.
+    ///
+    /// Ranges from 0 (all mass at one angle) to 1 (no preferred direction).
+    /// Includes both the spread between rotamer means and the σ of every
+    /// rotamer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// let [var] = Val::rotamers(-60.0, -40.0).chi_circular_variance();
+    /// assert!(var > 0.0 && var < 0.5);
+    /// ```
+    pub fn chi_circular_variance(&self) -> [f32; N] {
+        let (s, c) = self.first_moment();
+        let total = self.total_prob();
+        core::array::from_fn(|i| {
+            let len = if total > 0.0 {
+                sqrtf(s[i] * s[i] + c[i] * c[i]) / total
+            } else {
+                0.0
+            };
+            (1.0 - len).clamp(0.0, 1.0)
+        })
+    }
+
+    /// Dihedral order parameter `S² = |E[e^{iχ}]|²` of each χ angle over
+    /// the remaining rotamers, a library-based prediction to compare with
+    /// side-chain order parameters measured by NMR relaxation.
+    ///
+    /// This is the squared mean resultant length of the full mixture (the
+    /// dihedral order parameter of Hyberts et al., 1992), so it falls with
+    /// both the scatter between rotamer means and the σ of each rotamer:
+    /// 1 for a rigid χ, 0 for a χ with no preferred direction. The result
+    /// is normalized by the total remaining probability; an empty set
+    /// yields zeros.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Arg, Residue};
+    ///
+    /// let s2 = Arg::rotamers(-65.0, -43.0).chi_order_parameters();
+    /// // Order decays along the side chain.
+    /// assert!(s2[0] > s2[3]);
+    /// assert!(s2.iter().all(|&s| (0.0..=1.0).contains(&s)));
+    /// ```
+    pub fn chi_order_parameters(&self) -> [f32; N] {
+        self.chi_circular_variance().map(|var| {
+            let len = 1.0 - var;
+            len * len
+        })
+    }
+
+    /// First and second circular moments of each χ angle over the remaining
+    /// rotamers.
+    ///
+    /// Like [`mean_chis`](Self::mean_chis), every rotamer contributes a
+    /// wrapped Gaussian, so the moments describe the full χ mixture at this
+    /// backbone point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Arg, Residue};
+    ///
+    /// let m = Arg::rotamers(-65.0, -43.0).chi_moments();
+    /// // χ₁ of Arg is far more ordered than the terminal χ₄.
+    /// assert!(m[0].resultant_length > m[3].resultant_length);
+    /// assert!(m[0].circular_std < m[3].circular_std);
+    /// ```
+    pub fn chi_moments(&self) -> [ChiMoments; N] {
+        let (s1, c1) = self.trig_moment(1.0);
+        let (s2, c2) = self.trig_moment(2.0);
+        let total = self.total_prob();
+        core::array::from_fn(|i| {
+            let (r1, r2) = if total > 0.0 {
+                (
+                    sqrtf(s1[i] * s1[i] + c1[i] * c1[i]) / total,
+                    sqrtf(s2[i] * s2[i] + c2[i] * c2[i]) / total,
+                )
+            } else {
+                (0.0, 0.0)
+            };
+            let r1 = r1.min(1.0);
+            let r2 = r2.min(1.0);
+            let circular_std = if r1 >= 1.0 {
+                0.0
+            } else if r1 > 0.0 {
+                sqrtf(-2.0 * lnf(r1)) * RAD_TO_DEG
+            } else {
+                f32::INFINITY
+            };
+            ChiMoments {
+                mean: if r1 > 0.0 {
+                    atan2f(s1[i], c1[i]) * RAD_TO_DEG
+                } else {
+                    0.0
+                },
+                resultant_length: r1,
+                second_resultant_length: r2,
+                circular_variance: 1.0 - r1,
+                circular_std,
+            }
+        })
+    }
+
+    /// Cumulative probability that χ angle `chi_index` lies in
+    /// \[−180°, `deg`), under the mixture of wrapped Gaussians formed by the
+    /// remaining rotamers.
+    ///
+    /// `deg` is clamped to \[−180°, 180°\]. The result is normalized by the
+    /// total remaining probability, so it runs from 0 to 1 even for a
+    /// pruned set.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chi_index >= N`.
+    pub fn chi_cdf(&self, chi_index: usize, deg: f32) -> f32 {
+        assert!(
+            chi_index < N,
+            "chi_index {chi_index} out of range for {N} χ angles"
+        );
+        self.cdf_at(chi_index, deg)
+    }
+
+    /// Fallible counterpart of [`chi_cdf`](Self::chi_cdf).
+    ///
+    /// # Errors
+    ///
+    /// [`Error::ChiIndexOutOfRange`] if `chi_index >= N`;
+    /// [`Error::NonFiniteAngle`] if `deg` is NaN.
+    pub fn try_chi_cdf(&self, chi_index: usize, deg: f32) -> Result<f32, Error> {
+        check_chi_index::<N>(chi_index)?;
+        if deg.is_nan() {
+            return Err(Error::NonFiniteAngle);
+        }
+        Ok(self.cdf_at(chi_index, deg))
+    }
+
+    /// [`chi_cdf`](Self::chi_cdf) without the range check on `chi_index`.
+    fn cdf_at(&self, chi_index: usize, deg: f32) -> f32 {
+        let x = deg.clamp(-180.0, 180.0);
+        let total = self.total_prob();
+        if total <= 0.0 {
+            return 0.0;
+        }
+        let mut acc = 0.0_f32;
+        for rot in self.as_slice() {
+            let mu = rot.chi_mean[chi_index];
+            let sigma = rot.chi_sigma[chi_index];
+            // Three wraps cover every σ in the library (all well below 120°).
+            let mut mass = 0.0_f32;
+            for wrap in [-360.0, 0.0, 360.0] {
+                mass +=
+                    normal_cdf((x - mu + wrap) / sigma) - normal_cdf((-180.0 - mu + wrap) / sigma);
+            }
+            acc += rot.prob * mass;
+        }
+        (acc / total).clamp(0.0, 1.0)
+    }
+
+    /// Quantile of χ angle `chi_index`: the angle `x` in \[−180°, 180°\] at
+    /// which [`chi_cdf`](Self::chi_cdf) reaches `q`.
+    ///
+    /// The distribution is cut at ±180°, so quantiles are measured from
+    /// −180° upward. `q` is clamped to \[0, 1\]. Solved by bisection to
+    /// well below 0.001°.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chi_index >= N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Residue, Val};
+    ///
+    /// let rots = Val::rotamers(-60.0, -40.0);
+    /// let lo = rots.chi_quantile(0, 0.25);
+    /// let hi = rots.chi_quantile(0, 0.75);
+    /// assert!(lo < hi);
+    /// assert!((rots.chi_cdf(0, hi) - 0.75).abs() < 1e-4);
+    /// ```
+    pub fn chi_quantile(&self, chi_index: usize, q: f32) -> f32 {
+        assert!(
+            chi_index < N,
+            "chi_index {chi_index} out of range for {N} χ angles"
+        );
+        self.quantile_at(chi_index, q.clamp(0.0, 1.0))
+    }
+
+    /// Fallible counterpart of [`chi_quantile`](Self::chi_quantile).
+    ///
+    /// # Errors
+    ///
+    /// [`Error::ChiIndexOutOfRange`] if `chi_index >= N`;
+    /// [`Error::InvalidQuantile`] if `q` is NaN or outside \[0, 1\].
+    pub fn try_chi_quantile(&self, chi_index: usize, q: f32) -> Result<f32, Error> {
+        check_chi_index::<N>(chi_index)?;
+        if !(0.0..=1.0).contains(&q) {
+            return Err(Error::InvalidQuantile);
+        }
+        Ok(self.quantile_at(chi_index, q))
+    }
+
+    /// Bisection behind [`chi_quantile`](Self::chi_quantile), for an
+    /// in-range `chi_index` and `q`.
+    fn quantile_at(&self, chi_index: usize, q: f32) -> f32 {
+        let mut lo = -180.0_f32;
+        let mut hi = 180.0_f32;
+        for _ in 0..32 {
+            let mid = 0.5 * (lo + hi);
+            if self.cdf_at(chi_index, mid) < q {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        0.5 * (lo + hi)
+    }
+
+    /// Marginal density of χ angle `chi_index`, sampled at the `P` evenly
+    /// spaced angles `−180° + 360° · i / P` covering \[−180°, 180°), under
+    /// the mixture of wrapped Gaussians formed by the remaining rotamers.
+    ///
+    /// Values are probability per degree, normalized by the total remaining
+    /// probability like [`chi_cdf`](Self::chi_cdf), so a Riemann sum
+    /// `Σ density · 360 / P` is 1 up to sampling error; an empty set yields
+    /// zeros. For plotting, and for fitting experimental torsion
+    /// histograms against the library.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chi_index >= N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use dunbrack::{Leu, Residue};
+    ///
+    /// let rots = Leu::rotamers(-65.0, -40.0);
+    /// let density = rots.chi_density::<360>(1);
+    /// let mass: f32 = density.iter().sum();
+    /// assert!((mass - 1.0).abs() < 1e-3);
+    ///
+    /// // Leu χ2 peaks near trans (180°) or g+ (60°), not at 0°.
+    /// let peak = (0..360).max_by(|&a, &b| density[a].total_cmp(&density[b])).unwrap();
+    /// assert!(density[peak] > 10.0 * density[180]);
+    /// ```
+    pub fn chi_density<const P: usize>(&self, chi_index: usize) -> [f32; P] {
+        assert!(
+            chi_index < N,
+            "chi_index {chi_index} out of range for {N} χ angles"
+        );
+        self.density_at(chi_index)
+    }
+
+    /// Fallible counterpart of [`chi_density`](Self::chi_density).
+    ///
+    /// # Errors
+    ///
+    /// [`Error::ChiIndexOutOfRange`] if `chi_index >= N`.
+    pub fn try_chi_density<const P: usize>(&self, chi_index: usize) -> Result<[f32; P], Error> {
+        check_chi_index::<N>(chi_index)?;
+        Ok(self.density_at(chi_index))
+    }
+
+    /// [`chi_density`](Self::chi_density) without the range check on
+    /// `chi_index`.
+    fn density_at<const P: usize>(&self, chi_index: usize) -> [f32; P] {
+        let mut density = [0.0_f32; P];
+        let total = self.total_prob();
+        if total <= 0.0 {
+            return density;
+        }
+        let step = 360.0 / P as f32;
+        for rot in self.as_slice() {
+            let mu = rot.chi_mean[chi_index];
+            let sigma = rot.chi_sigma[chi_index];
+            let scale = rot.prob * INV_SQRT_2PI / (total * sigma);
+            for (i, d) in density.iter_mut().enumerate() {
+                let x = -180.0 + step * i as f32;
+                // Three wraps cover every σ in the library, as in `cdf_at`.
+                let mut g = 0.0_f32;
+                for wrap in [-360.0, 0.0, 360.0] {
+                    let z = (x - mu + wrap) / sigma;
+                    g += expf(-0.5 * z * z);
+                }
+                *d += scale * g;
+            }
+        }
+        density
+    }
+
+    /// Sum of the remaining rotamer probabilities.
+    pub(crate) fn total_prob(&self) -> f32 {
+        self.as_slice().iter().map(|rot| rot.prob).sum()
+    }
+
+    /// Unnormalized first trigonometric moment of each χ angle, see
+    /// [`trig_moment`](Self::trig_moment).
+    #[inline]
+    pub(crate) fn first_moment(&self) -> ([f32; N], [f32; N]) {
+        self.trig_moment(1.0)
+    }
+
+    /// Unnormalized trigonometric moment of order `p`,
+    /// `Σ pₖ ρₖ (sin pμₖ, cos pμₖ)`, of each χ angle, with
+    /// `ρₖ = exp(−p²σₖ²/2)` the order-`p` resultant length of a wrapped
+    /// Gaussian.
+    pub(crate) fn trig_moment(&self, order: f32) -> ([f32; N], [f32; N]) {
+        let mut sin_sum = [0.0_f32; N];
+        let mut cos_sum = [0.0_f32; N];
+        for rot in self.as_slice() {
+            for i in 0..N {
+                let (s, c) = sincos_deg(order * rot.chi_mean[i]);
+                let sigma = order * rot.chi_sigma[i] * DEG_TO_RAD;
+                let w = rot.prob * expf(-0.5 * sigma * sigma);
+                sin_sum[i] += w * s;
+                cos_sum[i] += w * c;
+            }
+        }
+        (sin_sum, cos_sum)
+    }
+}
+
+/// Checks that `chi_index` addresses one of the `N` χ angles.
+#[inline]
+fn check_chi_index<const N: usize>(chi_index: usize) -> Result<(), Error> {
+    if chi_index < N {
+        Ok(())
+    } else {
+        Err(Error::ChiIndexOutOfRange {
+            index: chi_index,
+            n_chi: N,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::Error;
+    use crate::interp::RotamerIter;
+    use crate::residue::{Arg, Residue};
+    use crate::rotamer::Rotamer;
+    use approx::assert_relative_eq;
+
+    fn single(mean: f32, sigma: f32) -> RotamerIter<1, 1> {
+        RotamerIter::from_items([Rotamer {
+            r: [1],
+            prob: 1.0,
+            chi_mean: [mean],
+            chi_sigma: [sigma],
+        }])
+    }
+
+    #[test]
+    fn test_single_rotamer_mean_is_its_mean() {
+        let iter = single(-170.0, 10.0);
+        assert_relative_eq!(iter.mean_chis()[0], -170.0, epsilon = 0.01);
+    }
+
+    #[test]
+    fn test_single_rotamer_variance_from_sigma() {
+        let iter = single(60.0, 10.0);
+        let sigma = 10.0_f32.to_radians();
+        let expected = 1.0 - (-0.5 * sigma * sigma).exp();
+        assert_relative_eq!(iter.chi_circular_variance()[0], expected, epsilon = 1e-5);
+    }
+
+    #[test]
+    fn test_mean_wraps_across_180() {
+        let iter = RotamerIter::from_items([
+            Rotamer {
+                r: [1],
+                prob: 0.5,
+                chi_mean: [170.0],
+                chi_sigma: [10.0],
+            },
+            Rotamer {
+                r: [2],
+                prob: 0.5,
+                chi_mean: [-170.0],
+                chi_sigma: [10.0],
+            },
+        ]);
+        assert!(iter.mean_chis()[0].abs() > 179.9);
+    }
+
+    #[test]
+    fn test_variance_in_unit_interval() {
+        for v in Arg::rotamers(-65.0, -43.0).chi_circular_variance() {
+            assert!((0.0..=1.0).contains(&v));
+        }
+    }
+
+    #[test]
+    fn test_trait_matches_iter() {
+        assert_eq!(
+            Arg::mean_chis(-65.0, -43.0),
+            Arg::rotamers(-65.0, -43.0).mean_chis()
+        );
+    }
+
+    #[test]
+    fn test_moments_of_single_rotamer() {
+        let [m] = single(30.0, 10.0).chi_moments();
+        let sigma = 10.0_f32.to_radians();
+        assert_relative_eq!(m.mean, 30.0, epsilon = 0.01);
+        assert_relative_eq!(
+            m.resultant_length,
+            (-0.5 * sigma * sigma).exp(),
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(
+            m.second_resultant_length,
+            (-2.0 * sigma * sigma).exp(),
+            epsilon = 1e-5
+        );
+        assert_relative_eq!(m.circular_std, 10.0, epsilon = 0.01);
+    }
+
+    #[test]
+    fn test_moments_of_opposed_modes() {
+        let iter = RotamerIter::from_items([
+            Rotamer {
+                r: [1],
+                prob: 0.5,
+                chi_mean: [0.0],
+                chi_sigma: [5.0],
+            },
+            Rotamer {
+                r: [2],
+                prob: 0.5,
+                chi_mean: [180.0],
+                chi_sigma: [5.0],
+            },
+        ]);
+        let [m] = iter.chi_moments();
+        assert!(m.resultant_length < 1e-5);
+        assert!(m.second_resultant_length > 0.9);
+        assert!(m.circular_std > 300.0);
+    }
+
+    #[test]
+    fn test_moments_agree_with_mean_and_variance() {
+        let iter = Arg::rotamers(-65.0, -43.0);
+        let moments = iter.chi_moments();
+        let means = iter.mean_chis();
+        let vars = iter.chi_circular_variance();
+        for i in 0..4 {
+            assert_relative_eq!(moments[i].mean, means[i], epsilon = 1e-4);
+            assert_relative_eq!(moments[i].circular_variance, vars[i], epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_weighted_sigma_of_single_rotamer() {
+        let [spread] = single(-170.0, 12.0).weighted_sigma();
+        assert_relative_eq!(spread.within, 12.0);
+        assert!(spread.between < 1e-3);
+        assert_relative_eq!(spread.total, 12.0, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_weighted_sigma_between_wraps() {
+        // Means 20° apart across ±180°, with unequal σ and weights.
+        let iter = RotamerIter::from_items([
+            Rotamer {
+                r: [1],
+                prob: 0.3,
+                chi_mean: [170.0],
+                chi_sigma: [10.0],
+            },
+            Rotamer {
+                r: [2],
+                prob: 0.3,
+                chi_mean: [-170.0],
+                chi_sigma: [20.0],
+            },
+        ]);
+        let [spread] = iter.weighted_sigma();
+        assert_relative_eq!(spread.within, 15.0, epsilon = 1e-4);
+        assert_relative_eq!(spread.between, 10.0, epsilon = 1e-3);
+        assert_relative_eq!(spread.total, 325.0_f32.sqrt(), epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_cdf_endpoints() {
+        let iter = Arg::rotamers(-65.0, -43.0);
+        for i in 0..4 {
+            assert_relative_eq!(iter.chi_cdf(i, -180.0), 0.0, epsilon = 1e-6);
+            assert_relative_eq!(iter.chi_cdf(i, 180.0), 1.0, epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_quantile_of_single_gaussian() {
+        let iter = single(0.0, 10.0);
+        assert_relative_eq!(iter.chi_quantile(0, 0.5), 0.0, epsilon = 0.01);
+        assert_relative_eq!(iter.chi_quantile(0, 0.975), 19.6, epsilon = 0.01);
+    }
+
+    #[test]
+    fn test_quantile_wrapped_mass() {
+        // Half the mass of a Gaussian at 180° lies just above −180°.
+        let iter = single(180.0, 10.0);
+        assert_relative_eq!(iter.chi_quantile(0, 0.25), -180.0 + 6.745, epsilon = 0.01);
+    }
+
+    #[test]
+    fn test_quantile_monotone() {
+        let iter = Arg::rotamers(-65.0, -43.0);
+        let mut prev = -180.0;
+        for k in 1..20 {
+            let x = iter.chi_quantile(1, k as f32 / 20.0);
+            assert!(x >= prev);
+            prev = x;
+        }
+    }
+
+    #[test]
+    fn test_quantile_trait_matches_iter() {
+        assert_eq!(
+            Arg::chi_quantile(-65.0, -43.0, 2, 0.1),
+            Arg::rotamers(-65.0, -43.0).chi_quantile(2, 0.1)
+        );
+    }
+
+    #[test]
+    fn test_try_quantile_errors() {
+        let iter = single(60.0, 10.0);
+        assert_eq!(iter.try_chi_quantile(0, 0.5), Ok(iter.chi_quantile(0, 0.5)));
+        assert_eq!(
+            iter.try_chi_quantile(1, 0.5),
+            Err(Error::ChiIndexOutOfRange { index: 1, n_chi: 1 })
+        );
+        assert_eq!(iter.try_chi_quantile(0, 1.5), Err(Error::InvalidQuantile));
+        assert_eq!(
+            iter.try_chi_quantile(0, f32::NAN),
+            Err(Error::InvalidQuantile)
+        );
+        assert_eq!(iter.try_chi_cdf(0, f32::NAN), Err(Error::NonFiniteAngle));
+        assert_eq!(iter.try_chi_cdf(0, 60.0), Ok(iter.chi_cdf(0, 60.0)));
+    }
+
+    #[test]
+    fn test_density_of_single_gaussian() {
+        let [at_mean, wrapped, ..] = single(-180.0, 10.0).chi_density::<36>(0);
+        assert_relative_eq!(at_mean, 0.398_942_3 / 10.0, max_relative = 1e-4);
+        // 10° away is one σ: e^{-1/2} of the peak.
+        assert_relative_eq!(wrapped, at_mean * 0.606_530_7, max_relative = 1e-4);
+
+        let density = single(180.0, 10.0).chi_density::<360>(0);
+        assert_relative_eq!(density[350], density[10], max_relative = 1e-4);
+    }
+
+    #[test]
+    fn test_density_integrates_to_cdf() {
+        let iter = Arg::rotamers(-65.0, -43.0);
+        let density = iter.chi_density::<720>(2);
+        let upto = |deg: f32| {
+            let n = ((deg + 180.0) * 2.0) as usize;
+            // Trapezoid rule; the curve is periodic.
+            let ends = (density[0] + density[n % 720]) / 2.0;
+            (density[..n].iter().sum::<f32>() - density[0] + ends) * 0.5
+        };
+        for deg in [-90.0, 0.0, 90.0, 180.0] {
+            assert_relative_eq!(upto(deg), iter.chi_cdf(2, deg), epsilon = 2e-3);
+        }
+        assert_eq!(Arg::chi_density::<720>(-65.0, -43.0, 2), density);
+        assert_eq!(
+            iter.try_chi_density::<8>(4),
+            Err(Error::ChiIndexOutOfRange { index: 4, n_chi: 4 })
+        );
+    }
+
+    #[test]
+    fn test_order_parameter_of_single_gaussian() {
+        // S² of a wrapped Gaussian is exp(−σ²), σ in radians.
+        let sigma: f32 = 15.0;
+        let rad = sigma * super::DEG_TO_RAD;
+        let expected = crate::math::expf(-rad * rad);
+        let [s2] = single(70.0, sigma).chi_order_parameters();
+        assert_relative_eq!(s2, expected, epsilon = 1e-5);
+        let [rigid] = single(-60.0, 0.0).chi_order_parameters();
+        assert_relative_eq!(rigid, 1.0, epsilon = 1e-6);
+        assert_eq!(
+            Arg::chi_order_parameters(-65.0, -43.0),
+            Arg::rotamers(-65.0, -43.0).chi_order_parameters()
+        );
+    }
+}
@@ -0,0 +1,415 @@
+use crate::grid::{GRID_PERIOD, angle_to_grid};
+use crate::interp::{GridEntry, RotamerIter, build_iter_from_corners};
+use crate::residue::*;
+
+/// Largest cell of any residue type, in `f32`s, the `Alt<R>` variants
+/// included: an alternative library may enumerate more rotamers than `R`.
+const MAX_CELL_FLOATS: usize = {
+    let mut max = 0;
+    macro_rules! fold {
+        ($Res:ident, $n:literal, $r:literal) => {
+            if $r * (1 + 3 * $n) > max {
+                max = $r * (1 + 3 * $n);
+            }
+            #[cfg(feature = "alt-data")]
+            if <Alt<$Res> as Residue>::N_ROTAMERS * (1 + 3 * $n) > max {
+                max = <Alt<$Res> as Residue>::N_ROTAMERS * (1 + 3 * $n);
+            }
+        };
+    }
+    for_all_residues!(fold);
+    max
+};
+
+/// Identity of one residue type's tables, as passed to
+/// [`TableStorage::read_cell`].
+///
+/// The `Chi1<R>` and `Alt<R>` variants share [`NAME`](Residue::NAME) with
+/// `R`, so a table is named by its library and shape as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TableKey {
+    /// Residue name, as in [`Residue::NAME`].
+    pub residue: &'static str,
+    /// Table set the cells come from.
+    pub library: Library,
+    /// Number of χ angles, as in [`Residue::N_CHI`].
+    pub n_chi: usize,
+    /// Number of rotamers per cell, as in [`Residue::N_ROTAMERS`].
+    pub n_rotamers: usize,
+}
+
+impl TableKey {
+    /// Key of the tables of residue type `R`.
+    #[inline]
+    pub fn of<R: Residue>() -> Self {
+        Self {
+            residue: R::NAME,
+            library: R::LIBRARY,
+            n_chi: R::N_CHI,
+            n_rotamers: R::N_ROTAMERS,
+        }
+    }
+
+    /// Floats in one grid cell of the table.
+    #[inline]
+    pub const fn cell_len(&self) -> usize {
+        self.n_rotamers * (1 + 3 * self.n_chi)
+    }
+}
+
+/// Source of rotamer table grid cells kept outside the binary, such as
+/// external SPI flash or an SD card, for [`StoredTables`].
+///
+/// A cell of residue type `R` holds [`N_ROTAMERS`](Residue::N_ROTAMERS)
+/// entries in table order, each `1 + 3 × N_CHI` floats: the probability,
+/// then sin χ, cos χ, and σ of every χ angle (the [`GridEntry`] layout).
+/// Grid indices are below [`GRID_PERIOD`](crate::GRID_PERIOD); the +180°
+/// grid points are read as the −180° ones. In a file written by
+/// `write_table_file` (`mmap` feature), cell (`phi_idx`, `psi_idx`) starts
+/// `(phi_idx × 36 + psi_idx) × cell.len() × 4` bytes into the residue's
+/// table.
+pub trait TableStorage {
+    /// Error reported by the storage medium.
+    type Error;
+
+    /// Fill `cell` with grid cell (`phi_idx`, `psi_idx`) of the tables
+    /// identified by `table`; `cell.len()` is exactly
+    /// [`table.cell_len()`](TableKey::cell_len).
+    ///
+    /// # Errors
+    ///
+    /// Any error of the storage medium, passed on to the caller of
+    /// [`StoredTables::rotamers`].
+    fn read_cell(
+        &mut self,
+        table: TableKey,
+        phi_idx: usize,
+        psi_idx: usize,
+        cell: &mut [f32],
+    ) -> Result<(), Self::Error>;
+}
+
+/// One cached grid cell.
+struct Slot {
+    key: Option<(TableKey, usize, usize)>,
+    last_used: u64,
+    data: [f32; MAX_CELL_FLOATS],
+}
+
+/// Rotamer queries over grid cells fetched on demand from a
+/// [`TableStorage`], with a RAM cache of the `SLOTS` most recently used
+/// cells.
+///
+/// Each bilinear query reads the four cells around (φ, ψ); cells already
+/// cached are not read again, so packing runs that revisit a backbone
+/// region touch external storage only once per cell. Every slot holds one
+/// cell of the largest residue type (3.9 KB, more if an `alt-data` library
+/// enumerates more rotamers), so the default four slots — the minimum, one
+/// query's corners — take about 16 KB of RAM. Results are
+/// identical to [`Residue::rotamers`] on the embedded tables when the
+/// storage returns the same data.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{StoredTables, TableKey, TableStorage, Val};
+///
+/// /// Stand-in for a flash chip: every cell has three equiprobable
+/// /// rotamers with χ1 = 0° and σ = 10°.
+/// struct Flash {
+///     reads: usize,
+/// }
+///
+/// impl TableStorage for Flash {
+///     type Error = core::convert::Infallible;
+///
+///     fn read_cell(&mut self, _: TableKey, _: usize, _: usize, cell: &mut [f32]) -> Result<(), Self::Error> {
+///         self.reads += 1;
+///         for entry in cell.chunks_exact_mut(4) {
+///             entry.copy_from_slice(&[1.0 / 3.0, 0.0, 1.0, 10.0]);
+///         }
+///         Ok(())
+///     }
+/// }
+///
+/// let mut tables = StoredTables::<_>::new(Flash { reads: 0 });
+/// let rots: Vec<_> = tables.rotamers::<Val>(-65.0, -40.0)?.collect();
+/// assert!((rots[0].prob - 1.0 / 3.0).abs() < 1e-6);
+/// tables.rotamers::<Val>(-62.0, -38.0)?; // same cell, served from RAM
+/// assert_eq!(tables.storage().reads, 4);
+/// # Ok::<(), core::convert::Infallible>(())
+/// ```
+pub struct StoredTables<S, const SLOTS: usize = 4> {
+    storage: S,
+    slots: [Slot; SLOTS],
+    clock: u64,
+}
+
+impl<S: TableStorage, const SLOTS: usize> StoredTables<S, SLOTS> {
+    /// Evaluated by [`new`](Self::new) to reject caches smaller than the
+    /// 4 cells one query reads.
+    const ENOUGH_SLOTS: () = assert!(SLOTS >= 4, "StoredTables needs at least 4 cache slots");
+
+    /// Wrap `storage` with an empty cache.
+    ///
+    /// Fails to compile if `SLOTS` is below 4, the cells one query reads.
+    pub fn new(storage: S) -> Self {
+        let () = Self::ENOUGH_SLOTS;
+        Self {
+            storage,
+            slots: core::array::from_fn(|_| Slot {
+                key: None,
+                last_used: 0,
+                data: [0.0; MAX_CELL_FLOATS],
+            }),
+            clock: 0,
+        }
+    }
+
+    /// Bilinearly interpolated rotamers of residue type `R`, as
+    /// [`Residue::rotamers`] computes them, from cells read through the
+    /// storage or the cache.
+    ///
+    /// # Errors
+    ///
+    /// Any error returned by [`TableStorage::read_cell`].
+    pub fn rotamers<R: Residue>(&mut self, phi: f32, psi: f32) -> Result<R::Iter, S::Error> {
+        let (phi_idx, _) = angle_to_grid(phi);
+        let (psi_idx, _) = angle_to_grid(psi);
+        let table = TableKey::of::<R>();
+        let len = table.cell_len();
+        let mut at = [0; 4];
+        for (slot, (dphi, dpsi)) in at.iter_mut().zip([(0, 0), (1, 0), (0, 1), (1, 1)]) {
+            *slot = self.fetch(
+                table,
+                (phi_idx + dphi) % GRID_PERIOD,
+                (psi_idx + dpsi) % GRID_PERIOD,
+            )?;
+        }
+        let cells = at.map(|slot| &self.slots[slot].data[..len]);
+        Ok(R::stored_rotamers(cells, phi, psi))
+    }
+
+    /// The wrapped storage.
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// The wrapped storage, mutably.
+    pub fn storage_mut(&mut self) -> &mut S {
+        &mut self.storage
+    }
+
+    /// Drop every cached cell, e.g. after the storage contents changed.
+    pub fn clear(&mut self) {
+        for slot in &mut self.slots {
+            slot.key = None;
+        }
+    }
+
+    /// Returns the slot holding the cell, reading it into the least recently
+    /// used slot on a miss.
+    fn fetch(
+        &mut self,
+        table: TableKey,
+        phi_idx: usize,
+        psi_idx: usize,
+    ) -> Result<usize, S::Error> {
+        self.clock += 1;
+        let key = Some((table, phi_idx, psi_idx));
+        let cached = self.slots.iter().position(|slot| slot.key == key);
+        #[cfg(feature = "metrics")]
+        crate::metrics::cache(cached.is_some());
+        let index = match cached {
+            Some(index) => index,
+            None => {
+                let index = (0..SLOTS)
+                    .min_by_key(|&i| (self.slots[i].key.is_some(), self.slots[i].last_used))
+                    .unwrap_or(0);
+                let slot = &mut self.slots[index];
+                slot.key = None;
+                self.storage.read_cell(
+                    table,
+                    phi_idx,
+                    psi_idx,
+                    &mut slot.data[..table.cell_len()],
+                )?;
+                slot.key = key;
+                index
+            }
+        };
+        self.slots[index].last_used = self.clock;
+        Ok(index)
+    }
+}
+
+/// Evaluates four corner cells read by a [`StoredTables`].
+///
+/// # Panics
+///
+/// Panics if a cell is not exactly `R × (1 + 3N)` floats long.
+pub(crate) fn rotamers<const N: usize, const R: usize>(
+    cells: [&[f32]; 4],
+    keys: &[[u8; N]; R],
+    phi: f32,
+    psi: f32,
+) -> RotamerIter<N, R> {
+    let corners = cells.map(|cell| {
+        assert_eq!(cell.len(), R * (1 + 3 * N));
+        // SAFETY: `GridEntry<N>` is `#[repr(C)]` with only `f32` fields, so
+        // `[GridEntry<N>; R]` has the size of `R × (1 + 3N)` floats (checked
+        // above), the alignment of `f32`, and every bit pattern is valid.
+        unsafe { &*cell.as_ptr().cast::<[GridEntry<N>; R]>() }
+    });
+    build_iter_from_corners(corners, keys, phi, psi)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interp::Table;
+
+    /// Storage serving the embedded tables, counting reads.
+    struct Embedded {
+        reads: usize,
+        fail: bool,
+    }
+
+    fn flatten<const N: usize, const R: usize>(
+        table: &Table<N, R>,
+        phi_idx: usize,
+        psi_idx: usize,
+        cell: &mut [f32],
+    ) {
+        let mut out = cell.iter_mut();
+        for entry in table.cell(phi_idx, psi_idx) {
+            let values = core::iter::once(&entry.prob)
+                .chain(&entry.chi_sin)
+                .chain(&entry.chi_cos)
+                .chain(&entry.chi_sigma);
+            for (&value, slot) in values.zip(&mut out) {
+                *slot = value;
+            }
+        }
+    }
+
+    impl TableStorage for Embedded {
+        type Error = &'static str;
+
+        fn read_cell(
+            &mut self,
+            table: TableKey,
+            phi_idx: usize,
+            psi_idx: usize,
+            cell: &mut [f32],
+        ) -> Result<(), Self::Error> {
+            if self.fail {
+                return Err("bus error");
+            }
+            self.reads += 1;
+            match (table.residue, table.library) {
+                ("ARG", Library::Primary) => flatten(&crate::ARG_TABLE, phi_idx, psi_idx, cell),
+                ("LEU", Library::Primary) => flatten(&crate::LEU_TABLE, phi_idx, psi_idx, cell),
+                ("VAL", Library::Primary) => flatten(&crate::VAL_TABLE, phi_idx, psi_idx, cell),
+                #[cfg(feature = "chi1")]
+                ("LEU", Library::Chi1) => flatten(&crate::LEU_CHI1_TABLE, phi_idx, psi_idx, cell),
+                _ => return Err("not stored"),
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_matches_embedded_tables() {
+        let mut tables = StoredTables::<_, 6>::new(Embedded {
+            reads: 0,
+            fail: false,
+        });
+        for (phi, psi) in [(-63.7, -41.2), (180.0, 180.0), (-180.0, 55.5)] {
+            assert!(
+                tables
+                    .rotamers::<Arg>(phi, psi)
+                    .unwrap()
+                    .eq(Arg::rotamers(phi, psi))
+            );
+            assert!(
+                tables
+                    .rotamers::<Val>(phi, psi)
+                    .unwrap()
+                    .eq(Val::rotamers(phi, psi))
+            );
+        }
+        assert_eq!(tables.rotamers::<Ser>(0.0, 0.0).err(), Some("not stored"));
+    }
+
+    #[test]
+    fn test_cache_reuses_cells() {
+        let mut tables = StoredTables::<_>::new(Embedded {
+            reads: 0,
+            fail: false,
+        });
+        tables.rotamers::<Val>(-65.0, -45.0).unwrap();
+        tables.rotamers::<Val>(-61.0, -41.0).unwrap();
+        assert_eq!(tables.storage().reads, 4);
+        // One cell over in ψ shares two corners.
+        tables.rotamers::<Val>(-65.0, -35.0).unwrap();
+        assert_eq!(tables.storage().reads, 6);
+        tables.clear();
+        tables.rotamers::<Val>(-65.0, -35.0).unwrap();
+        assert_eq!(tables.storage().reads, 10);
+    }
+
+    #[test]
+    fn test_failed_read_is_not_cached() {
+        let mut tables = StoredTables::<_>::new(Embedded {
+            reads: 0,
+            fail: true,
+        });
+        assert_eq!(
+            tables.rotamers::<Val>(-65.0, -45.0).err(),
+            Some("bus error")
+        );
+        tables.storage_mut().fail = false;
+        assert!(
+            tables
+                .rotamers::<Val>(-65.0, -45.0)
+                .unwrap()
+                .eq(Val::rotamers(-65.0, -45.0))
+        );
+    }
+
+    #[cfg(feature = "chi1")]
+    #[test]
+    fn test_variants_do_not_share_cached_cells() {
+        let mut tables = StoredTables::<_>::new(Embedded {
+            reads: 0,
+            fail: false,
+        });
+        for (phi, psi) in [(-65.0, -45.0), (-61.0, -41.0), (-65.0, -45.0)] {
+            assert!(
+                tables
+                    .rotamers::<Leu>(phi, psi)
+                    .unwrap()
+                    .eq(Leu::rotamers(phi, psi))
+            );
+            assert!(
+                tables
+                    .rotamers::<Chi1<Leu>>(phi, psi)
+                    .unwrap()
+                    .eq(Chi1::<Leu>::rotamers(phi, psi))
+            );
+        }
+        assert_ne!(TableKey::of::<Leu>(), TableKey::of::<Chi1<Leu>>());
+    }
+
+    #[cfg(feature = "alt-data")]
+    #[test]
+    fn test_alt_cells_fit_a_slot() {
+        macro_rules! check {
+            ($Res:ident, $n:literal, $r:literal) => {
+                assert!(TableKey::of::<Alt<$Res>>().cell_len() <= MAX_CELL_FLOATS);
+            };
+        }
+        for_all_residues!(check);
+    }
+}
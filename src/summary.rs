@@ -0,0 +1,165 @@
+use crate::grid::GRID_PERIOD;
+use crate::identity::ResidueInfo;
+use crate::math;
+use crate::residue::*;
+
+/// Library-wide statistics of one residue type over its 36 × 36 distinct
+/// grid cells (the +180° row and column, which repeat −180°, are counted
+/// once).
+///
+/// Produced by [`summarize`] and [`summarize_all`]. Entropies are Shannon
+/// entropies of the rotamer distribution of a cell, in nats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ResidueSummary {
+    /// Residue type summarized.
+    pub residue: &'static ResidueInfo,
+    /// Mean over cells of the most probable rotamer's probability.
+    pub top_prob_mean: f32,
+    /// Smallest most-probable-rotamer probability of any cell.
+    pub top_prob_min: f32,
+    /// Largest most-probable-rotamer probability of any cell.
+    pub top_prob_max: f32,
+    /// Probability-weighted mean standard deviation of each χ in degrees,
+    /// averaged over cells; slots past [`ResidueInfo::n_chi`] hold zero.
+    pub mean_sigma: [f32; 4],
+    /// Lowest cell entropy: the most sharply determined backbone.
+    pub entropy_min: f32,
+    /// Mean cell entropy.
+    pub entropy_mean: f32,
+    /// Highest cell entropy: the most ambiguous backbone.
+    pub entropy_max: f32,
+}
+
+impl ResidueSummary {
+    /// Mean standard deviations of the residue's χ angles, in degrees.
+    #[inline]
+    pub fn sigmas(&self) -> &[f32] {
+        &self.mean_sigma[..self.residue.n_chi]
+    }
+}
+
+/// Summarize residue type `R` across the whole backbone grid, for reports
+/// and sanity dashboards that would otherwise traverse the raw tables.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Arg, Residue, Val, summarize};
+///
+/// let val = summarize::<Val>();
+/// assert_eq!(val.residue.name, Val::NAME);
+/// assert!(val.top_prob_min <= val.top_prob_mean && val.top_prob_mean <= val.top_prob_max);
+/// assert_eq!(val.sigmas().len(), 1);
+///
+/// // 75 Arg rotamers spread further than 3 Val rotamers.
+/// assert!(summarize::<Arg>().entropy_mean > val.entropy_mean);
+/// ```
+pub fn summarize<R: Residue>() -> ResidueSummary {
+    let residue = const { &ResidueInfo::of::<R>() };
+    let mut top_sum = 0.0_f64;
+    let (mut top_min, mut top_max) = (f32::INFINITY, 0.0_f32);
+    let (mut h_sum, mut h_min, mut h_max) = (0.0_f64, f32::INFINITY, 0.0_f32);
+    let mut sigma_sum = [0.0_f64; 4];
+    let mut mass = 0.0_f64;
+    for phi_idx in 0..GRID_PERIOD {
+        for psi_idx in 0..GRID_PERIOD {
+            let mut top = 0.0_f32;
+            for k in 0..R::N_ROTAMERS {
+                let rec = R::grid_record(residue, phi_idx, psi_idx, k);
+                top = top.max(rec.prob);
+                for (sum, &sigma) in sigma_sum.iter_mut().zip(&rec.chi_sigma) {
+                    *sum += f64::from(rec.prob * sigma);
+                }
+                mass += f64::from(rec.prob);
+            }
+            let h = math::entropy(
+                (0..R::N_ROTAMERS).map(|k| R::grid_record(residue, phi_idx, psi_idx, k).prob),
+            );
+            top_sum += f64::from(top);
+            top_min = top_min.min(top);
+            top_max = top_max.max(top);
+            h_sum += f64::from(h);
+            h_min = h_min.min(h);
+            h_max = h_max.max(h);
+        }
+    }
+    let cells = (GRID_PERIOD * GRID_PERIOD) as f64;
+    ResidueSummary {
+        residue,
+        top_prob_mean: (top_sum / cells) as f32,
+        top_prob_min: top_min,
+        top_prob_max: top_max,
+        mean_sigma: sigma_sum.map(|sum| (sum / mass) as f32),
+        entropy_min: h_min.max(0.0),
+        entropy_mean: (h_sum / cells).max(0.0) as f32,
+        entropy_max: h_max,
+    }
+}
+
+/// [`summarize`] every residue type, in the order of
+/// [`ALL_RESIDUES`](crate::ALL_RESIDUES).
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{ALL_RESIDUES, summarize_all};
+///
+/// for (summary, info) in summarize_all().iter().zip(ALL_RESIDUES) {
+///     assert_eq!(summary.residue, info);
+///     assert!(summary.sigmas().iter().all(|&s| s > 0.0));
+/// }
+/// ```
+pub fn summarize_all() -> [ResidueSummary; crate::ALL_RESIDUES.len()] {
+    core::array::from_fn(|i| {
+        let name = crate::ALL_RESIDUES[i].name;
+        macro_rules! pick {
+            ($Res:ident, $n:literal, $r:literal) => {
+                if name == <$Res as Residue>::NAME {
+                    return summarize::<$Res>();
+                }
+            };
+        }
+        for_all_residues!(pick);
+        unreachable!("every ALL_RESIDUES entry is a residue type")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::lnf;
+    use crate::residue::{Leu, Pro, Residue};
+
+    #[test]
+    fn test_ranges_are_consistent() {
+        for s in summarize_all() {
+            let name = s.residue.name;
+            assert!(s.top_prob_min > 0.0 && s.top_prob_max <= 1.0, "{name}");
+            assert!(s.top_prob_min <= s.top_prob_mean && s.top_prob_mean <= s.top_prob_max);
+            assert!(s.entropy_min <= s.entropy_mean && s.entropy_mean <= s.entropy_max);
+            assert!(
+                s.entropy_max <= lnf(s.residue.n_rotamers as f32) + 1e-4,
+                "{name}"
+            );
+            assert!(s.mean_sigma[s.residue.n_chi..].iter().all(|&x| x == 0.0));
+        }
+    }
+
+    #[test]
+    fn test_matches_grid_cell() {
+        // The top probability at one grid point bounds the extremes.
+        let top = Leu::rotamers(-60.0, -40.0)
+            .map(|r| r.prob)
+            .fold(0.0, f32::max);
+        let s = summarize::<Leu>();
+        assert!(s.top_prob_min <= top && top <= s.top_prob_max);
+        assert_eq!(s.residue, &crate::ALL_RESIDUES[11]);
+    }
+
+    #[test]
+    fn test_proline_is_sharp() {
+        // Two ring puckers: entropy never exceeds ln 2.
+        let s = summarize::<Pro>();
+        assert!(s.entropy_max <= core::f32::consts::LN_2 + 1e-4);
+    }
+}
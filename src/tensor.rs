@@ -0,0 +1,249 @@
+//! Conversions into `candle` tensors.
+
+use std::vec::Vec;
+
+use candle_core::{Device, Result, Tensor};
+
+use crate::gpu::GpuTableLayout;
+use crate::grid::GRID_COUNT;
+use crate::interp::RotamerIter;
+use crate::residue::Residue;
+
+/// A rotamer set as tensors, one row per rotamer.
+///
+/// Produced by [`RotamerIter::to_tensors`] with shapes `[n]` and `[n, N]`
+/// for `n` rotamers of `N` χ angles, or by [`stack`](Self::stack) with a
+/// leading batch dimension. Angles are in degrees, as in
+/// [`Rotamer`](crate::Rotamer). Requires the `candle` feature.
+///
+/// # Examples
+///
+/// ```
+/// use candle_core::Device;
+/// use dunbrack::{Arg, Residue, RotamerTensors};
+///
+/// let set = Arg::rotamers(-65.0, -40.0).to_tensors(&Device::Cpu).unwrap();
+/// assert_eq!(set.prob.dims(), [75]);
+/// assert_eq!(set.chi_mean.dims(), [75, 4]);
+///
+/// let backbones = [(-65.0, -40.0), (-120.0, 130.0)];
+/// let sets = backbones.map(|(phi, psi)| Arg::rotamers(phi, psi));
+/// let batch = RotamerTensors::stack(&sets, &Device::Cpu).unwrap();
+/// assert_eq!(batch.chi_sigma.dims(), [2, 75, 4]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RotamerTensors {
+    /// Rotamer bin indices (1-based), `u8`, shape `[.., n, N]`.
+    pub bins: Tensor,
+    /// Prior probabilities, `f32`, shape `[.., n]`.
+    pub prob: Tensor,
+    /// Mean χ angles in degrees, `f32`, shape `[.., n, N]`.
+    pub chi_mean: Tensor,
+    /// χ standard deviations in degrees, `f32`, shape `[.., n, N]`.
+    pub chi_sigma: Tensor,
+}
+
+impl RotamerTensors {
+    /// Stack the remaining rotamers of several sets into one batch, adding
+    /// a leading dimension of `sets.len()`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `sets` is empty or the sets hold different numbers of
+    /// rotamers, e.g. after pruning.
+    pub fn stack<const N: usize, const R: usize>(
+        sets: &[RotamerIter<N, R>],
+        device: &Device,
+    ) -> Result<Self> {
+        let parts = sets
+            .iter()
+            .map(|set| set.to_tensors(device))
+            .collect::<Result<Vec<_>>>()?;
+        let field = |get: fn(&Self) -> &Tensor| {
+            Tensor::stack(&parts.iter().map(get).collect::<Vec<_>>(), 0)
+        };
+        Ok(Self {
+            bins: field(|t| &t.bins)?,
+            prob: field(|t| &t.prob)?,
+            chi_mean: field(|t| &t.chi_mean)?,
+            chi_sigma: field(|t| &t.chi_sigma)?,
+        })
+    }
+}
+
+impl<const N: usize, const R: usize> RotamerIter<N, R> {
+    /// The remaining rotamers as tensors on `device`, in iteration order.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `device` cannot allocate the tensors.
+    pub fn to_tensors(&self, device: &Device) -> Result<RotamerTensors> {
+        let rotamers = self.as_slice();
+        let n = rotamers.len();
+        Ok(RotamerTensors {
+            bins: Tensor::from_vec(
+                rotamers.iter().flat_map(|rot| rot.r).collect::<Vec<_>>(),
+                (n, N),
+                device,
+            )?,
+            prob: Tensor::from_vec(
+                rotamers.iter().map(|rot| rot.prob).collect::<Vec<_>>(),
+                n,
+                device,
+            )?,
+            chi_mean: Tensor::from_vec(
+                rotamers
+                    .iter()
+                    .flat_map(|rot| rot.chi_mean)
+                    .collect::<Vec<_>>(),
+                (n, N),
+                device,
+            )?,
+            chi_sigma: Tensor::from_vec(
+                rotamers
+                    .iter()
+                    .flat_map(|rot| rot.chi_sigma)
+                    .collect::<Vec<_>>(),
+                (n, N),
+                device,
+            )?,
+        })
+    }
+}
+
+/// The full grid table of one residue type as tensors, for models that
+/// interpolate or learn on top of the library themselves.
+///
+/// Grid cell (`i`, `j`) lies at φ = `GRID_MIN + i × GRID_STEP`, ψ likewise,
+/// and rotamer `k` is the `k`-th row of `bins`. The values are the ones
+/// every query interpolates, with χ means stored as sines and cosines like
+/// [`GridEntry`](crate::GridEntry). Requires the `candle` feature.
+///
+/// # Examples
+///
+/// ```
+/// use candle_core::Device;
+/// use dunbrack::{Leu, TableTensors};
+///
+/// let table = TableTensors::of::<Leu>(&Device::Cpu).unwrap();
+/// assert_eq!(table.prob.dims(), [37, 37, 9]);
+/// assert_eq!(table.chi_sin.dims(), [37, 37, 9, 2]);
+///
+/// // Each cell holds a full distribution.
+/// let mass = table.prob.sum(2).unwrap().to_vec2::<f32>().unwrap();
+/// assert!((mass[11][14] - 1.0).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TableTensors {
+    /// Rotamer bin indices (1-based), `u8`, shape `[R, N]`.
+    pub bins: Tensor,
+    /// Probabilities, `f32`, shape `[37, 37, R]`.
+    pub prob: Tensor,
+    /// Sines of the mean χ angles, `f32`, shape `[37, 37, R, N]`.
+    pub chi_sin: Tensor,
+    /// Cosines of the mean χ angles, `f32`, shape `[37, 37, R, N]`.
+    pub chi_cos: Tensor,
+    /// χ standard deviations in degrees, `f32`, shape `[37, 37, R, N]`.
+    pub chi_sigma: Tensor,
+}
+
+impl TableTensors {
+    /// The table of residue type `Res` as tensors on `device`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `device` cannot allocate the tensors.
+    pub fn of<Res: Residue>(device: &Device) -> Result<Self> {
+        let layout = GpuTableLayout::of::<Res>();
+        let mut bytes = std::vec![0_u8; layout.len()];
+        Res::write_gpu_table(&mut bytes).map_err(candle_core::Error::wrap)?;
+
+        let (n, r) = (Res::N_CHI, Res::N_ROTAMERS);
+        let (stride, array) = (layout.rotamer_stride as usize, layout.array_stride as usize);
+        let read = |at: usize| f32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+        // Gathers `[cell][k][i]` from the per-χ arrays starting at `offset`.
+        let gather = |offset: u32, chis: usize| {
+            let mut values = Vec::with_capacity(GRID_COUNT * GRID_COUNT * r * chis);
+            for cell in 0..GRID_COUNT * GRID_COUNT {
+                for k in 0..r {
+                    for i in 0..chis {
+                        values.push(read(offset as usize + i * array + (cell * stride + k) * 4));
+                    }
+                }
+            }
+            values
+        };
+        let grid = (GRID_COUNT, GRID_COUNT, r, n);
+        let keys = layout.keys_offset as usize;
+        Ok(Self {
+            bins: Tensor::from_vec(
+                (0..r)
+                    .flat_map(|k| bytes[keys + k * 4..][..n].iter().copied())
+                    .collect::<Vec<_>>(),
+                (r, n),
+                device,
+            )?,
+            prob: Tensor::from_vec(
+                gather(layout.prob_offset, 1),
+                (GRID_COUNT, GRID_COUNT, r),
+                device,
+            )?,
+            chi_sin: Tensor::from_vec(gather(layout.chi_sin_offset, n), grid, device)?,
+            chi_cos: Tensor::from_vec(gather(layout.chi_cos_offset, n), grid, device)?,
+            chi_sigma: Tensor::from_vec(gather(layout.chi_sigma_offset, n), grid, device)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::query::Query;
+    use crate::residue::{Leu, Ser};
+
+    #[test]
+    fn test_set_tensors_follow_iteration_order() {
+        let set = Query::new(-65.0, -40.0)
+            .prune(0.05)
+            .sorted(true)
+            .run::<Leu>();
+        let tensors = set.to_tensors(&Device::Cpu).unwrap();
+        let prob = tensors.prob.to_vec1::<f32>().unwrap();
+        let bins = tensors.bins.to_vec2::<u8>().unwrap();
+        let sigma = tensors.chi_sigma.to_vec2::<f32>().unwrap();
+        assert_eq!(prob.len(), set.len());
+        for (k, rot) in set.as_slice().iter().enumerate() {
+            assert_eq!(prob[k], rot.prob);
+            assert_eq!(bins[k], rot.r);
+            assert_eq!(sigma[k], rot.chi_sigma);
+        }
+    }
+
+    #[test]
+    fn test_stack_requires_equal_sets() {
+        let full = Leu::rotamers(-65.0, -40.0);
+        let pruned = Query::new(-65.0, -40.0).prune(0.05).run::<Leu>();
+        assert!(RotamerTensors::stack(&[full.clone(), pruned], &Device::Cpu).is_err());
+        assert!(RotamerTensors::stack::<2, 9>(&[], &Device::Cpu).is_err());
+
+        let batch = RotamerTensors::stack(&[full.clone(), full.clone()], &Device::Cpu).unwrap();
+        let chi = batch.chi_mean.to_vec3::<f32>().unwrap();
+        assert_eq!(chi[0], chi[1]);
+        assert_eq!(chi[1][3], full.as_slice()[3].chi_mean);
+    }
+
+    #[test]
+    fn test_table_matches_grid_queries() {
+        let table = TableTensors::of::<Ser>(&Device::Cpu).unwrap();
+        let prob = table.prob.to_vec3::<f32>().unwrap();
+        let bins = table.bins.to_vec2::<u8>().unwrap();
+        // At a grid point the query returns the raw cell.
+        for rot in Ser::rotamers(-60.0, -40.0) {
+            let k = bins.iter().position(|b| *b == rot.r).unwrap();
+            assert!((prob[12][14][k] - rot.prob).abs() < 1e-6);
+        }
+        let norm = (table.chi_sin.sqr().unwrap() + table.chi_cos.sqr().unwrap()).unwrap();
+        let norm = norm.flatten_all().unwrap().to_vec1::<f32>().unwrap();
+        assert!(norm.iter().all(|v| (v - 1.0).abs() < 1e-3));
+    }
+}
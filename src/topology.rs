@@ -0,0 +1,358 @@
+/// Chemical element of a side-chain heavy atom.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Element {
+    /// Carbon.
+    C,
+    /// Nitrogen.
+    N,
+    /// Oxygen.
+    O,
+    /// Sulfur.
+    S,
+}
+
+impl Element {
+    /// Element symbol, as written in PDB/mmCIF files.
+    #[inline]
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Self::C => "C",
+            Self::N => "N",
+            Self::O => "O",
+            Self::S => "S",
+        }
+    }
+}
+
+/// One side-chain heavy atom and the atom it is built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Atom {
+    /// PDB atom name (e.g. `"CB"`, `"OD1"`).
+    pub name: &'static str,
+    /// Chemical element.
+    pub element: Element,
+    /// Name of the bonded atom this one hangs off: `"CA"` for `CB`, otherwise
+    /// a side-chain atom listed earlier.
+    pub parent: &'static str,
+}
+
+/// Side-chain topology of one residue type: heavy atoms in build order,
+/// ring-closing bonds, and the four atoms defining each χ dihedral.
+///
+/// Backbone atoms (`N`, `CA`, `C`, `O`) are implicit and may be referenced
+/// by name. Hydrogens are omitted.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Element, Phe, Residue};
+///
+/// let topo = Phe::TOPOLOGY;
+/// assert_eq!(topo.chi_atoms.len(), Phe::N_CHI);
+/// assert_eq!(topo.chi_atoms[1], ["CA", "CB", "CG", "CD1"]);
+/// assert_eq!(topo.atom("CZ").unwrap().element, Element::C);
+/// // Six ring bonds: five tree edges plus the closure.
+/// assert_eq!(topo.bonds().filter(|&(a, _)| a != "CB" && a != "CA").count(), 6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Topology {
+    /// Side-chain heavy atoms; every atom's parent precedes it.
+    pub atoms: &'static [Atom],
+    /// Bonds that close rings, in addition to the parent bonds.
+    pub ring_bonds: &'static [(&'static str, &'static str)],
+    /// Atoms `(a, b, c, d)` of each χ dihedral, `N_CHI` entries in χ order.
+    pub chi_atoms: &'static [[&'static str; 4]],
+}
+
+impl Topology {
+    /// Looks up a side-chain atom by PDB name.
+    pub fn atom(&self, name: &str) -> Option<&'static Atom> {
+        self.atoms.iter().find(|atom| atom.name == name)
+    }
+
+    /// Every side-chain bond as `(parent, child)` pairs, followed by the
+    /// ring-closing bonds.
+    pub fn bonds(&self) -> impl Iterator<Item = (&'static str, &'static str)> + use<> {
+        let (atoms, ring_bonds) = (self.atoms, self.ring_bonds);
+        atoms
+            .iter()
+            .map(|atom| (atom.parent, atom.name))
+            .chain(ring_bonds.iter().copied())
+    }
+}
+
+const fn atom(name: &'static str, element: Element, parent: &'static str) -> Atom {
+    Atom {
+        name,
+        element,
+        parent,
+    }
+}
+
+use Element::{C, N, O, S};
+
+const CHI1_G: [&str; 4] = ["N", "CA", "CB", "CG"];
+const CHI2_D: [&str; 4] = ["CA", "CB", "CG", "CD"];
+
+pub(crate) const ARG: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG", C, "CB"),
+        atom("CD", C, "CG"),
+        atom("NE", N, "CD"),
+        atom("CZ", C, "NE"),
+        atom("NH1", N, "CZ"),
+        atom("NH2", N, "CZ"),
+    ],
+    ring_bonds: &[],
+    chi_atoms: &[
+        CHI1_G,
+        CHI2_D,
+        ["CB", "CG", "CD", "NE"],
+        ["CG", "CD", "NE", "CZ"],
+    ],
+};
+
+pub(crate) const ASN: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG", C, "CB"),
+        atom("OD1", O, "CG"),
+        atom("ND2", N, "CG"),
+    ],
+    ring_bonds: &[],
+    chi_atoms: &[CHI1_G, ["CA", "CB", "CG", "OD1"]],
+};
+
+pub(crate) const ASP: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG", C, "CB"),
+        atom("OD1", O, "CG"),
+        atom("OD2", O, "CG"),
+    ],
+    ring_bonds: &[],
+    chi_atoms: &[CHI1_G, ["CA", "CB", "CG", "OD1"]],
+};
+
+pub(crate) const CYS: Topology = Topology {
+    atoms: &[atom("CB", C, "CA"), atom("SG", S, "CB")],
+    ring_bonds: &[],
+    chi_atoms: &[["N", "CA", "CB", "SG"]],
+};
+
+pub(crate) const GLN: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG", C, "CB"),
+        atom("CD", C, "CG"),
+        atom("OE1", O, "CD"),
+        atom("NE2", N, "CD"),
+    ],
+    ring_bonds: &[],
+    chi_atoms: &[CHI1_G, CHI2_D, ["CB", "CG", "CD", "OE1"]],
+};
+
+pub(crate) const GLU: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG", C, "CB"),
+        atom("CD", C, "CG"),
+        atom("OE1", O, "CD"),
+        atom("OE2", O, "CD"),
+    ],
+    ring_bonds: &[],
+    chi_atoms: &[CHI1_G, CHI2_D, ["CB", "CG", "CD", "OE1"]],
+};
+
+pub(crate) const HIS: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG", C, "CB"),
+        atom("ND1", N, "CG"),
+        atom("CD2", C, "CG"),
+        atom("CE1", C, "ND1"),
+        atom("NE2", N, "CD2"),
+    ],
+    ring_bonds: &[("CE1", "NE2")],
+    chi_atoms: &[CHI1_G, ["CA", "CB", "CG", "ND1"]],
+};
+
+pub(crate) const ILE: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG1", C, "CB"),
+        atom("CG2", C, "CB"),
+        atom("CD1", C, "CG1"),
+    ],
+    ring_bonds: &[],
+    chi_atoms: &[["N", "CA", "CB", "CG1"], ["CA", "CB", "CG1", "CD1"]],
+};
+
+pub(crate) const LEU: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG", C, "CB"),
+        atom("CD1", C, "CG"),
+        atom("CD2", C, "CG"),
+    ],
+    ring_bonds: &[],
+    chi_atoms: &[CHI1_G, ["CA", "CB", "CG", "CD1"]],
+};
+
+pub(crate) const LYS: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG", C, "CB"),
+        atom("CD", C, "CG"),
+        atom("CE", C, "CD"),
+        atom("NZ", N, "CE"),
+    ],
+    ring_bonds: &[],
+    chi_atoms: &[
+        CHI1_G,
+        CHI2_D,
+        ["CB", "CG", "CD", "CE"],
+        ["CG", "CD", "CE", "NZ"],
+    ],
+};
+
+pub(crate) const MET: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG", C, "CB"),
+        atom("SD", S, "CG"),
+        atom("CE", C, "SD"),
+    ],
+    ring_bonds: &[],
+    chi_atoms: &[CHI1_G, ["CA", "CB", "CG", "SD"], ["CB", "CG", "SD", "CE"]],
+};
+
+pub(crate) const PHE: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG", C, "CB"),
+        atom("CD1", C, "CG"),
+        atom("CD2", C, "CG"),
+        atom("CE1", C, "CD1"),
+        atom("CE2", C, "CD2"),
+        atom("CZ", C, "CE1"),
+    ],
+    ring_bonds: &[("CE2", "CZ")],
+    chi_atoms: &[CHI1_G, ["CA", "CB", "CG", "CD1"]],
+};
+
+pub(crate) const PRO: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG", C, "CB"),
+        atom("CD", C, "CG"),
+    ],
+    ring_bonds: &[("CD", "N")],
+    chi_atoms: &[CHI1_G, CHI2_D, ["CB", "CG", "CD", "N"]],
+};
+
+pub(crate) const SER: Topology = Topology {
+    atoms: &[atom("CB", C, "CA"), atom("OG", O, "CB")],
+    ring_bonds: &[],
+    chi_atoms: &[["N", "CA", "CB", "OG"]],
+};
+
+pub(crate) const THR: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("OG1", O, "CB"),
+        atom("CG2", C, "CB"),
+    ],
+    ring_bonds: &[],
+    chi_atoms: &[["N", "CA", "CB", "OG1"]],
+};
+
+pub(crate) const TRP: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG", C, "CB"),
+        atom("CD1", C, "CG"),
+        atom("CD2", C, "CG"),
+        atom("NE1", N, "CD1"),
+        atom("CE2", C, "CD2"),
+        atom("CE3", C, "CD2"),
+        atom("CZ2", C, "CE2"),
+        atom("CZ3", C, "CE3"),
+        atom("CH2", C, "CZ2"),
+    ],
+    ring_bonds: &[("NE1", "CE2"), ("CZ3", "CH2")],
+    chi_atoms: &[CHI1_G, ["CA", "CB", "CG", "CD1"]],
+};
+
+pub(crate) const TYR: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG", C, "CB"),
+        atom("CD1", C, "CG"),
+        atom("CD2", C, "CG"),
+        atom("CE1", C, "CD1"),
+        atom("CE2", C, "CD2"),
+        atom("CZ", C, "CE1"),
+        atom("OH", O, "CZ"),
+    ],
+    ring_bonds: &[("CE2", "CZ")],
+    chi_atoms: &[CHI1_G, ["CA", "CB", "CG", "CD1"]],
+};
+
+pub(crate) const VAL: Topology = Topology {
+    atoms: &[
+        atom("CB", C, "CA"),
+        atom("CG1", C, "CB"),
+        atom("CG2", C, "CB"),
+    ],
+    ring_bonds: &[],
+    chi_atoms: &[["N", "CA", "CB", "CG1"]],
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::residue::*;
+
+    const BACKBONE: [&str; 4] = ["N", "CA", "C", "O"];
+
+    fn check<R: Residue>() {
+        let topo = R::TOPOLOGY;
+        assert_eq!(topo.chi_atoms.len(), R::N_CHI, "{}", R::NAME);
+        for (k, atom) in topo.atoms.iter().enumerate() {
+            let earlier = topo.atoms[..k].iter().any(|a| a.name == atom.parent);
+            assert!(
+                earlier || (k == 0 && atom.parent == "CA"),
+                "{}: {} has parent {}",
+                R::NAME,
+                atom.name,
+                atom.parent
+            );
+        }
+        let known = |name: &str| BACKBONE.contains(&name) || topo.atom(name).is_some();
+        for chi in topo.chi_atoms {
+            assert!(chi.iter().all(|a| known(a)), "{}: {chi:?}", R::NAME);
+        }
+        for (a, b) in topo.ring_bonds {
+            assert!(known(a) && known(b), "{}: {a}-{b}", R::NAME);
+        }
+    }
+
+    #[test]
+    fn test_topologies_consistent() {
+        macro_rules! run {
+            ($Res:ident, $n:literal, $r:literal) => {
+                check::<$Res>();
+            };
+        }
+        for_all_residues!(run);
+    }
+
+    #[test]
+    fn test_cysteine_and_proline_variants_share_topology() {
+        assert_eq!(Cyd::TOPOLOGY, Cys::TOPOLOGY);
+        assert_eq!(Cyh::TOPOLOGY, Cys::TOPOLOGY);
+        assert_eq!(Cpr::TOPOLOGY, Pro::TOPOLOGY);
+        assert_eq!(Tpr::TOPOLOGY, Pro::TOPOLOGY);
+    }
+}
@@ -0,0 +1,99 @@
+//! `log` events emitted with the `trace` feature.
+//!
+//! Every event is logged under the `dunbrack` target as `key=value` pairs,
+//! so log processors can parse them without a structured-logging backend.
+//! Lookups are logged at `trace` level, rejected input at `debug` level.
+//! The crate keeps no caches: every query is evaluated against the static
+//! tables, so there are no hit or miss events to report.
+
+use crate::error::Error;
+use crate::query::Query;
+
+const TARGET: &str = "dunbrack";
+
+/// One table lookup with interpolation scheme `I`.
+#[inline]
+pub(crate) fn lookup<I>(residue: &str, phi: f32, psi: f32) {
+    log::trace!(
+        target: TARGET,
+        "lookup residue={residue} phi={phi} psi={psi} interpolation={}",
+        short_name::<I>()
+    );
+}
+
+/// One [`Query`] run.
+#[inline]
+pub(crate) fn query(residue: &str, query: &Query) {
+    log::trace!(target: TARGET, "query residue={residue} {query:?}");
+}
+
+/// The outcome of a fallible lookup: errors only.
+#[inline]
+pub(crate) fn outcome<T>(residue: &str, result: &Result<T, Error>) {
+    if let Err(err) = result {
+        log::debug!(target: TARGET, "rejected residue={residue} error=\"{err}\"");
+    }
+}
+
+/// A pruning pass that kept `kept` of `total` rotamers.
+#[inline]
+pub(crate) fn pruned(min_prob: f32, total: usize, kept: usize) {
+    log::trace!(target: TARGET, "prune min_prob={min_prob} total={total} kept={kept}");
+}
+
+/// Last path segment of a type name, e.g. `Bilinear`.
+fn short_name<I>() -> &'static str {
+    let name = core::any::type_name::<I>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use std::string::{String, ToString};
+    use std::sync::Mutex;
+    use std::vec::Vec;
+
+    use crate::query::Query;
+    use crate::residue::{Residue, Val};
+
+    static EVENTS: Mutex<Vec<(log::Level, String)>> = Mutex::new(Vec::new());
+
+    struct Capture;
+
+    impl log::Log for Capture {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.target() == super::TARGET
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                let message = record.args().to_string();
+                EVENTS.lock().unwrap().push((record.level(), message));
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_events() {
+        log::set_logger(&Capture).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+
+        Query::new(-65.0, -40.0).prune(0.2).run::<Val>();
+        let _ = Val::try_rotamers(f32::NAN, 0.0);
+
+        let events = EVENTS.lock().unwrap();
+        let messages: Vec<&str> = events.iter().map(|(_, m)| m.as_str()).collect();
+        assert!(messages[0].starts_with("query residue=VAL Query { phi: -65.0"));
+        assert_eq!(messages[1], "prune min_prob=0.2 total=3 kept=1");
+        assert_eq!(
+            messages[2],
+            "lookup residue=VAL phi=NaN psi=0 interpolation=Bilinear"
+        );
+        assert_eq!(events[3].0, log::Level::Debug);
+        assert!(messages[3].starts_with("rejected residue=VAL error="));
+    }
+}
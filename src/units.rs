@@ -0,0 +1,119 @@
+/// Molar gas constant in kcal mol⁻¹ K⁻¹.
+const R_KCAL: f32 = 1.987_204_3e-3;
+
+/// Molar gas constant in kJ mol⁻¹ K⁻¹.
+const R_KJ: f32 = 8.314_463e-3;
+
+/// Unit of the energies the crate returns or accepts.
+///
+/// Library energies are natively dimensionless (`−ln p`, in units of kT);
+/// the molar units fix kT through a temperature in kelvin, so one setting
+/// carries both the unit and the temperature it was derived at. Energy
+/// APIs take an `EnergyUnits` (`SelfEnergy::units` with `alloc`,
+/// [`RestraintScale::in_units`](crate::RestraintScale::in_units),
+/// [`Residue::reference_energy`](crate::Residue::reference_energy)) instead
+/// of a bare scale factor, so a pipeline mixing tools converts once, in one
+/// place.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::EnergyUnits;
+///
+/// let kcal = EnergyUnits::kcal_per_mol(298.15);
+/// assert!((kcal.kt() - 0.5925).abs() < 1e-4);
+/// assert!((kcal.from_kt(1.0) - kcal.kt()).abs() < 1e-7);
+///
+/// // The same energy in kJ/mol.
+/// let kj = EnergyUnits::kj_per_mol(298.15);
+/// assert!((kcal.convert(1.0, kj) - 4.184).abs() < 1e-3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum EnergyUnits {
+    /// Dimensionless energies in units of kT.
+    #[default]
+    Kt,
+    /// kcal/mol at the given temperature in kelvin.
+    KcalPerMol {
+        /// Temperature in kelvin.
+        temperature: f32,
+    },
+    /// kJ/mol at the given temperature in kelvin.
+    KjPerMol {
+        /// Temperature in kelvin.
+        temperature: f32,
+    },
+}
+
+impl EnergyUnits {
+    /// kcal/mol at `temperature` kelvin.
+    #[inline]
+    pub const fn kcal_per_mol(temperature: f32) -> Self {
+        Self::KcalPerMol { temperature }
+    }
+
+    /// kJ/mol at `temperature` kelvin.
+    #[inline]
+    pub const fn kj_per_mol(temperature: f32) -> Self {
+        Self::KjPerMol { temperature }
+    }
+
+    /// Value of kT in these units: `1.0` for [`Kt`](Self::Kt), `R·T`
+    /// otherwise.
+    #[inline]
+    pub fn kt(self) -> f32 {
+        match self {
+            Self::Kt => 1.0,
+            Self::KcalPerMol { temperature } => R_KCAL * temperature,
+            Self::KjPerMol { temperature } => R_KJ * temperature,
+        }
+    }
+
+    /// Convert an energy in units of kT into these units.
+    #[inline]
+    pub fn from_kt(self, energy: f32) -> f32 {
+        energy * self.kt()
+    }
+
+    /// Convert an energy in these units into units of kT.
+    #[inline]
+    pub fn to_kt(self, energy: f32) -> f32 {
+        energy / self.kt()
+    }
+
+    /// Convert an energy in these units into `target` units, through kT at
+    /// each side's temperature.
+    #[inline]
+    pub fn convert(self, energy: f32, target: EnergyUnits) -> f32 {
+        target.from_kt(self.to_kt(energy))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let units = [
+            EnergyUnits::Kt,
+            EnergyUnits::kcal_per_mol(310.0),
+            EnergyUnits::kj_per_mol(300.0),
+        ];
+        for a in units {
+            for b in units {
+                let e = 2.5;
+                assert!((b.convert(a.convert(e, b), a) - e).abs() < 1e-5);
+            }
+            assert_eq!(a.to_kt(a.from_kt(3.0)), 3.0);
+        }
+        assert_eq!(EnergyUnits::default().kt(), 1.0);
+    }
+
+    #[test]
+    fn test_kcal_to_kj_is_thermochemical_calorie() {
+        let t = 300.0;
+        let ratio = EnergyUnits::kj_per_mol(t).kt() / EnergyUnits::kcal_per_mol(t).kt();
+        assert!((ratio - 4.184).abs() < 1e-4);
+    }
+}
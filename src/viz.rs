@@ -0,0 +1,428 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{self, Write as _};
+
+use crate::error::Error;
+use crate::grid::{GRID_COUNT, GRID_PERIOD};
+use crate::identity::ResidueInfo;
+use crate::math::{self, lnf};
+use crate::residue::Residue;
+
+/// Stops of the perceptually uniform viridis colour map, low to high.
+const VIRIDIS: [[u8; 3]; 5] = [
+    [0x44, 0x01, 0x54],
+    [0x3b, 0x52, 0x8b],
+    [0x21, 0x91, 0x8c],
+    [0x5e, 0xc9, 0x62],
+    [0xfd, 0xe7, 0x25],
+];
+
+/// SVG plot area offset and side length, in user units (1 unit = 1°).
+const SVG_LEFT: usize = 60;
+const SVG_TOP: usize = 30;
+const SVG_SIDE: usize = 360;
+
+/// What a [`Heatmap`] shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeatmapKind {
+    /// Probability of one rotamer, scaled over `[0, 1]`.
+    Probability,
+    /// Shannon entropy of the rotamer distribution in nats, scaled over
+    /// `[0, ln N_ROTAMERS]`.
+    Entropy,
+}
+
+/// A per-residue map over the Ramachandran plane, rendered to SVG or PNG.
+///
+/// Each of the 36 × 36 cells between grid nodes holds the mean of its four
+/// corner values, which is the cell average of the
+/// [`Bilinear`](crate::Bilinear) surface, so the figure is exactly what
+/// bilinear queries see. Colours follow the viridis map over a fixed
+/// [`range`](Self::range), so maps of the same kind are comparable across
+/// residues. Requires the `viz` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Heatmap, Leu};
+///
+/// let map = Heatmap::probability::<Leu>(&[3, 2]).unwrap();
+/// let svg = map.to_svg();
+/// assert!(svg.starts_with("<svg "));
+///
+/// let png = Heatmap::entropy::<Leu>().to_png(4);
+/// assert_eq!(&png[1..4], b"PNG");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Heatmap {
+    residue: &'static ResidueInfo,
+    kind: HeatmapKind,
+    label: String,
+    range: (f32, f32),
+    /// Cell values, `cells[phi_idx * GRID_PERIOD + psi_idx]`.
+    cells: Vec<f32>,
+}
+
+impl Heatmap {
+    /// Probability map of the rotamer of `R` with bin indices `r`.
+    ///
+    /// # Errors
+    ///
+    /// [`Error::InvalidBinKey`] if `R` has no rotamer with bin indices `r`.
+    pub fn probability<R: Residue>(r: &[u8]) -> Result<Self, Error> {
+        let residue = const { &ResidueInfo::of::<R>() };
+        let k = (0..R::N_ROTAMERS)
+            .find(|&k| R::grid_record(residue, 0, 0, k).bins() == r)
+            .ok_or(Error::InvalidBinKey)?;
+        let label = r
+            .iter()
+            .filter_map(|&bin| char::from_digit(bin.into(), 10))
+            .collect();
+        Ok(Self::from_nodes(
+            residue,
+            HeatmapKind::Probability,
+            label,
+            (0.0, 1.0),
+            |phi_idx, psi_idx| R::grid_record(residue, phi_idx, psi_idx, k).prob,
+        ))
+    }
+
+    /// Entropy map of the rotamer distribution of `R`.
+    pub fn entropy<R: Residue>() -> Self {
+        let residue = const { &ResidueInfo::of::<R>() };
+        Self::from_nodes(
+            residue,
+            HeatmapKind::Entropy,
+            String::from("entropy"),
+            (0.0, lnf(R::N_ROTAMERS as f32)),
+            |phi_idx, psi_idx| {
+                let probs =
+                    (0..R::N_ROTAMERS).map(|k| R::grid_record(residue, phi_idx, psi_idx, k).prob);
+                math::entropy(probs).max(0.0)
+            },
+        )
+    }
+
+    fn from_nodes(
+        residue: &'static ResidueInfo,
+        kind: HeatmapKind,
+        label: String,
+        range: (f32, f32),
+        node: impl Fn(usize, usize) -> f32,
+    ) -> Self {
+        let mut nodes = [[0.0_f32; GRID_COUNT]; GRID_COUNT];
+        for (phi_idx, row) in nodes.iter_mut().enumerate() {
+            for (psi_idx, value) in row.iter_mut().enumerate() {
+                *value = node(phi_idx, psi_idx);
+            }
+        }
+        let mut cells = Vec::with_capacity(GRID_PERIOD * GRID_PERIOD);
+        for i in 0..GRID_PERIOD {
+            for j in 0..GRID_PERIOD {
+                let sum = nodes[i][j] + nodes[i + 1][j] + nodes[i][j + 1] + nodes[i + 1][j + 1];
+                cells.push(sum / 4.0);
+            }
+        }
+        Self {
+            residue,
+            kind,
+            label,
+            range,
+            cells,
+        }
+    }
+
+    /// Residue type mapped.
+    #[inline]
+    pub fn residue(&self) -> &'static ResidueInfo {
+        self.residue
+    }
+
+    /// What the map shows.
+    #[inline]
+    pub fn kind(&self) -> HeatmapKind {
+        self.kind
+    }
+
+    /// Values mapped to the low and high ends of the colour scale.
+    #[inline]
+    pub fn range(&self) -> (f32, f32) {
+        self.range
+    }
+
+    /// Value of the cell spanning φ node `phi_idx` to `phi_idx + 1` and ψ
+    /// node `psi_idx` to `psi_idx + 1`.
+    ///
+    /// # Panics
+    ///
+    /// If either index is 36 or more.
+    #[inline]
+    pub fn cell(&self, phi_idx: usize, psi_idx: usize) -> f32 {
+        assert!(phi_idx < GRID_PERIOD && psi_idx < GRID_PERIOD);
+        self.cells[phi_idx * GRID_PERIOD + psi_idx]
+    }
+
+    /// Colour of a value on the map's scale, as RGB.
+    pub fn color(&self, value: f32) -> [u8; 3] {
+        let (lo, hi) = self.range;
+        let t = if hi > lo {
+            (value - lo) / (hi - lo)
+        } else {
+            0.0
+        };
+        let x = t.clamp(0.0, 1.0) * (VIRIDIS.len() - 1) as f32;
+        let i = (x as usize).min(VIRIDIS.len() - 2);
+        let f = x - i as f32;
+        core::array::from_fn(|c| {
+            let (a, b) = (f32::from(VIRIDIS[i][c]), f32::from(VIRIDIS[i + 1][c]));
+            (a + f * (b - a) + 0.5) as u8
+        })
+    }
+
+    /// Render the map as a standalone SVG figure: the 36 × 36 cells with φ
+    /// left to right and ψ bottom to top, labelled axes ticked every 90°, a
+    /// title, and a colour bar.
+    pub fn to_svg(&self) -> String {
+        let mut svg = String::new();
+        self.write_svg(&mut svg)
+            .expect("formatting into a String cannot fail");
+        svg
+    }
+
+    fn write_svg(&self, out: &mut String) -> fmt::Result {
+        let (left, top, side) = (SVG_LEFT, SVG_TOP, SVG_SIDE);
+        let bar = left + side + 20;
+        let (width, height) = (bar + 70, top + side + 50);
+        writeln!(
+            out,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+             viewBox=\"0 0 {width} {height}\" font-family=\"sans-serif\" font-size=\"12\">"
+        )?;
+        let title = match self.kind {
+            HeatmapKind::Probability => "probability of rotamer ",
+            HeatmapKind::Entropy => "rotamer ",
+        };
+        writeln!(
+            out,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"14\">{} {title}{}</text>",
+            left + side / 2,
+            top - 10,
+            self.residue.name,
+            self.label
+        )?;
+        writeln!(out, "<g shape-rendering=\"crispEdges\">")?;
+        for i in 0..GRID_PERIOD {
+            for j in 0..GRID_PERIOD {
+                let [r, g, b] = self.color(self.cell(i, j));
+                writeln!(
+                    out,
+                    "<rect x=\"{}\" y=\"{}\" width=\"10\" height=\"10\" fill=\"#{r:02x}{g:02x}{b:02x}\"/>",
+                    left + 10 * i,
+                    top + side - 10 * (j + 1)
+                )?;
+            }
+        }
+        writeln!(out, "</g>")?;
+        writeln!(
+            out,
+            "<rect x=\"{left}\" y=\"{top}\" width=\"{side}\" height=\"{side}\" fill=\"none\" stroke=\"black\"/>"
+        )?;
+        for tick in (0..=side).step_by(90) {
+            let angle = tick as i32 - 180;
+            let (x, y) = (left + tick, top + side - tick);
+            writeln!(
+                out,
+                "<line x1=\"{x}\" y1=\"{}\" x2=\"{x}\" y2=\"{}\" stroke=\"black\"/>\
+                 <text x=\"{x}\" y=\"{}\" text-anchor=\"middle\">{angle}</text>",
+                top + side,
+                top + side + 5,
+                top + side + 18
+            )?;
+            writeln!(
+                out,
+                "<line x1=\"{}\" y1=\"{y}\" x2=\"{left}\" y2=\"{y}\" stroke=\"black\"/>\
+                 <text x=\"{}\" y=\"{}\" text-anchor=\"end\">{angle}</text>",
+                left - 5,
+                left - 8,
+                y + 4
+            )?;
+        }
+        writeln!(
+            out,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">φ (°)</text>",
+            left + side / 2,
+            top + side + 38
+        )?;
+        writeln!(
+            out,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" transform=\"rotate(-90 {0} {1})\">ψ (°)</text>",
+            left - 40,
+            top + side / 2
+        )?;
+        writeln!(
+            out,
+            "<defs><linearGradient id=\"scale\" x1=\"0\" y1=\"1\" x2=\"0\" y2=\"0\">"
+        )?;
+        for (i, [r, g, b]) in VIRIDIS.iter().enumerate() {
+            writeln!(
+                out,
+                "<stop offset=\"{}\" stop-color=\"#{r:02x}{g:02x}{b:02x}\"/>",
+                i as f32 / (VIRIDIS.len() - 1) as f32
+            )?;
+        }
+        writeln!(out, "</linearGradient></defs>")?;
+        writeln!(
+            out,
+            "<rect x=\"{bar}\" y=\"{top}\" width=\"15\" height=\"{side}\" fill=\"url(#scale)\" stroke=\"black\"/>"
+        )?;
+        let (lo, hi) = self.range;
+        writeln!(
+            out,
+            "<text x=\"{}\" y=\"{}\">{hi:.2}</text><text x=\"{0}\" y=\"{}\">{lo:.2}</text>",
+            bar + 20,
+            top + 10,
+            top + side
+        )?;
+        writeln!(out, "</svg>")
+    }
+
+    /// Render the map as an 8-bit RGB PNG with `scale` pixels per 10° cell
+    /// (at least 1), φ left to right and ψ bottom to top, for direct
+    /// embedding in documents and notebooks.
+    pub fn to_png(&self, scale: usize) -> Vec<u8> {
+        let scale = scale.max(1);
+        let side = GRID_PERIOD * scale;
+        let mut pixels = Vec::with_capacity(side * (3 * side + 1));
+        for y in 0..side {
+            pixels.push(0); // filter: none
+            let psi_idx = GRID_PERIOD - 1 - y / scale;
+            for x in 0..side {
+                pixels.extend_from_slice(&self.color(self.cell(x / scale, psi_idx)));
+            }
+        }
+
+        let mut png = Vec::from(*b"\x89PNG\r\n\x1a\n");
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(side as u32).to_be_bytes());
+        header.extend_from_slice(&(side as u32).to_be_bytes());
+        header.extend_from_slice(&[8, 2, 0, 0, 0]); // 8-bit RGB, no interlace
+        png_chunk(&mut png, b"IHDR", &header);
+        png_chunk(&mut png, b"IDAT", &zlib_stored(&pixels));
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+/// Append one PNG chunk: length, type, data, and CRC-32 of type and data.
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Wrap `data` in a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len() + data.len() / 65_535 * 5 + 11);
+    out.extend_from_slice(&[0x78, 0x01]);
+    let mut blocks = data.chunks(65_535).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        out.push(u8::from(blocks.peek().is_none()));
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+/// CRC-32 (ISO-HDLC), as PNG chunks use.
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0_u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// Adler-32, as zlib streams use.
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for &byte in bytes {
+        a = (a + u32::from(byte)) % 65_521;
+        b = (b + a) % 65_521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::residue::{Arg, Leu, Val};
+
+    #[test]
+    fn test_checksum_reference_vectors() {
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+
+    #[test]
+    fn test_cells_average_nodes() {
+        let map = Heatmap::probability::<Val>(&[2]).unwrap();
+        let node = |i, j| <Val as crate::sealed::Sealed>::grid_record(map.residue(), i, j, 1).prob;
+        let expected = (node(3, 4) + node(4, 4) + node(3, 5) + node(4, 5)) / 4.0;
+        assert!((map.cell(3, 4) - expected).abs() < 1e-6);
+        assert_eq!(Heatmap::probability::<Val>(&[4]), Err(Error::InvalidBinKey));
+    }
+
+    #[test]
+    fn test_entropy_range() {
+        let map = Heatmap::entropy::<Arg>();
+        let (lo, hi) = map.range();
+        assert_eq!(lo, 0.0);
+        for i in 0..GRID_PERIOD {
+            for j in 0..GRID_PERIOD {
+                assert!((lo..=hi).contains(&map.cell(i, j)));
+            }
+        }
+        assert_eq!(map.color(lo), VIRIDIS[0]);
+        assert_eq!(map.color(hi), VIRIDIS[4]);
+        assert_eq!(map.color(2.0 * hi), VIRIDIS[4]);
+    }
+
+    #[test]
+    fn test_png_layout() {
+        let map = Heatmap::entropy::<Leu>();
+        let png = map.to_png(2);
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(png[16..20], 72_u32.to_be_bytes());
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+        // One stored block: the raw scanlines follow the zlib and block
+        // headers. The first pixel is the top-left cell, ψ = 170°..180°.
+        let pixels = &png[33 + 8 + 2 + 5..];
+        assert_eq!(pixels[0], 0);
+        assert_eq!(pixels[1..4], map.color(map.cell(0, GRID_PERIOD - 1)));
+    }
+
+    #[test]
+    fn test_svg_has_every_cell() {
+        let svg = Heatmap::probability::<Leu>(&[3, 2]).unwrap().to_svg();
+        assert_eq!(
+            svg.matches("width=\"10\"").count(),
+            GRID_PERIOD * GRID_PERIOD
+        );
+        assert!(svg.contains("LEU probability of rotamer 32"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
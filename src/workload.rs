@@ -0,0 +1,202 @@
+use crate::identity::ResidueInfo;
+use crate::math::{probit, wrap_deg};
+use crate::sample::splitmix64;
+
+/// Approximate PDB composition (per mille of all residues) of every
+/// residue type, in the order of [`ALL_RESIDUES`](crate::ALL_RESIDUES).
+///
+/// The cysteine and proline variant pools are never drawn: structures
+/// rarely annotate disulfide or cis state, so pipelines query the combined
+/// pools.
+const FREQUENCIES: [(&str, u32); 22] = [
+    ("ARG", 55),
+    ("ASN", 41),
+    ("ASP", 55),
+    ("CPR", 0),
+    ("CYD", 0),
+    ("CYH", 0),
+    ("CYS", 14),
+    ("GLN", 39),
+    ("GLU", 67),
+    ("HIS", 23),
+    ("ILE", 59),
+    ("LEU", 97),
+    ("LYS", 58),
+    ("MET", 24),
+    ("PHE", 39),
+    ("PRO", 47),
+    ("SER", 66),
+    ("THR", 53),
+    ("TPR", 0),
+    ("TRP", 11),
+    ("TYR", 29),
+    ("VAL", 69),
+];
+
+/// Backbone regions as `(weight per mille, φ mean, ψ mean, φ σ, ψ σ)` in
+/// degrees: α helix, β strand, polyproline II, and left-handed helix. The
+/// remaining weight is spread uniformly over the plane.
+const REGIONS: [(u32, f32, f32, f32, f32); 4] = [
+    (450, -63.0, -43.0, 10.0, 10.0),
+    (300, -120.0, 130.0, 15.0, 15.0),
+    (150, -65.0, 145.0, 10.0, 10.0),
+    (50, 60.0, 45.0, 10.0, 10.0),
+];
+
+/// One query of a [`Workload`]: a residue type and its backbone dihedral
+/// angles in degrees, in \[−180°, 180°).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkloadQuery {
+    /// Residue type, an entry of [`ALL_RESIDUES`](crate::ALL_RESIDUES).
+    pub residue: &'static ResidueInfo,
+    /// Backbone φ in degrees.
+    pub phi: f32,
+    /// Backbone ψ in degrees.
+    pub psi: f32,
+}
+
+/// Endless, reproducible stream of realistic rotamer queries, for
+/// benchmarking pipelines built on the library against representative
+/// input instead of uniform noise.
+///
+/// Residue types follow their PDB frequencies (Ala and Gly, which have no
+/// rotamers, excluded; the cysteine and proline variant pools are never
+/// drawn). Backbones are drawn from a Ramachandran mixture: 45% α helix,
+/// 30% β strand, 15% polyproline II, 5% left-handed helix, and 5% uniform
+/// over the plane; proline φ is held near −65°. The stream depends only on
+/// the seed, so runs on different machines and releases compare like with
+/// like.
+///
+/// Requires the `bench-util` feature.
+///
+/// # Examples
+///
+/// ```
+/// use dunbrack::{Leu, Residue, Workload};
+///
+/// let queries: Vec<_> = Workload::new(42).take(1000).collect();
+/// assert_eq!(queries, Workload::new(42).take(1000).collect::<Vec<_>>());
+///
+/// let helical = queries.iter().filter(|q| (q.phi + 63.0).abs() < 30.0 && (q.psi + 43.0).abs() < 30.0);
+/// assert!(helical.count() > 300);
+///
+/// for q in queries.iter().filter(|q| q.residue.name == Leu::NAME) {
+///     let best = Leu::rotamers(q.phi, q.psi).map(|r| r.prob).fold(0.0, f32::max);
+///     assert!(best > 0.0);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Workload {
+    state: u64,
+}
+
+impl Workload {
+    /// The stream determined by `seed`.
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Uniform draw in (0, 1).
+    fn uniform(&mut self) -> f32 {
+        ((splitmix64(&mut self.state) >> 40) as f32 + 0.5) * (1.0 / (1u32 << 24) as f32)
+    }
+
+    /// Uniform draw in `0..total`.
+    fn below(&mut self, total: u32) -> u32 {
+        (((splitmix64(&mut self.state) >> 32) * u64::from(total)) >> 32) as u32
+    }
+
+    /// Normal draw with the given mean and standard deviation, wrapped into
+    /// \[−180°, 180°).
+    fn normal_deg(&mut self, mean: f32, sigma: f32) -> f32 {
+        wrap_deg(mean + sigma * probit(self.uniform()))
+    }
+}
+
+impl Iterator for Workload {
+    type Item = WorkloadQuery;
+
+    fn next(&mut self) -> Option<WorkloadQuery> {
+        let total: u32 = FREQUENCIES.iter().map(|&(_, w)| w).sum();
+        let mut pick = self.below(total);
+        let index = FREQUENCIES
+            .iter()
+            .position(|&(_, w)| {
+                let hit = pick < w;
+                pick = pick.saturating_sub(w);
+                hit
+            })
+            .unwrap_or(0);
+        let residue = &crate::ALL_RESIDUES[index];
+
+        let mut pick = self.below(1000);
+        let region = REGIONS.iter().find(|&&(w, ..)| {
+            let hit = pick < w;
+            pick = pick.saturating_sub(w);
+            hit
+        });
+        let (mut phi, psi) = match region {
+            Some(&(_, phi, psi, sigma_phi, sigma_psi)) => (
+                self.normal_deg(phi, sigma_phi),
+                self.normal_deg(psi, sigma_psi),
+            ),
+            None => (
+                wrap_deg(self.uniform() * 360.0),
+                wrap_deg(self.uniform() * 360.0),
+            ),
+        };
+        if matches!(residue.name, "PRO" | "CPR" | "TPR") {
+            phi = self.normal_deg(-65.0, 10.0);
+        }
+
+        Some(WorkloadQuery { residue, phi, psi })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frequencies_follow_registry_order() {
+        for (&(name, _), info) in FREQUENCIES.iter().zip(crate::ALL_RESIDUES) {
+            assert_eq!(name, info.name);
+        }
+        assert_eq!(FREQUENCIES.len(), crate::ALL_RESIDUES.len());
+    }
+
+    #[test]
+    fn test_residue_mix() {
+        let mut counts = [0u32; 22];
+        for q in Workload::new(7).take(100_000) {
+            let i = crate::ALL_RESIDUES
+                .iter()
+                .position(|info| info == q.residue)
+                .unwrap();
+            counts[i] += 1;
+        }
+        let total: u32 = FREQUENCIES.iter().map(|&(_, w)| w).sum();
+        for (&(name, w), &n) in FREQUENCIES.iter().zip(&counts) {
+            let expected = 100_000.0 * w as f32 / total as f32;
+            assert!(
+                (n as f32 - expected).abs() <= 0.05 * expected + 50.0,
+                "{name}: {n} vs {expected}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_angles_in_range_and_seeded() {
+        let a: [WorkloadQuery; 64] = {
+            let mut it = Workload::new(1);
+            core::array::from_fn(|_| it.next().unwrap())
+        };
+        for q in &a {
+            assert!((-180.0..180.0).contains(&q.phi) && (-180.0..180.0).contains(&q.psi));
+            if q.residue.name == "PRO" {
+                assert!((q.phi + 65.0).abs() < 60.0);
+            }
+        }
+        assert_ne!(Some(a[0]), Workload::new(2).next());
+    }
+}
@@ -9,7 +9,7 @@ use dunbrack::{
     Arg, Asn, Asp, Cpr, Cyd, Cyh, Cys, Gln, Glu, His, Ile, Leu, Lys, Met, Phe, Pro, Ser, Thr, Tpr,
     Trp, Tyr, Val,
 };
-use dunbrack::{Residue, Rotamer, for_all_residues};
+use dunbrack::{GRID_MIN, GRID_STEP, Nearest, Residue, Rotamer, for_all_residues};
 use std::path::Path;
 
 trait RotamerAccess {
@@ -288,3 +288,66 @@ fn test_residue_names() {
     }
     for_all_residues!(check_name);
 }
+
+#[test]
+fn test_residue_identity_consts() {
+    let mut seen = std::collections::HashSet::new();
+    macro_rules! check_identity {
+        ($Res:ident, $_n_chi:literal, $_n_rot:literal) => {
+            let one = <$Res as Residue>::ONE_LETTER;
+            assert!(
+                one.is_ascii_uppercase(),
+                "{}: ONE_LETTER {one:?}",
+                stringify!($Res)
+            );
+            assert!(seen.insert(<$Res as Residue>::NAME));
+            for alias in <$Res as Residue>::ALIASES {
+                assert!(
+                    seen.insert(*alias),
+                    "{}: alias {alias} not unique",
+                    stringify!($Res)
+                );
+            }
+        };
+    }
+    for_all_residues!(check_identity);
+    assert_eq!(Arg::ONE_LETTER, 'R');
+    assert_eq!(Cyd::ALIASES, &["CYX"]);
+}
+
+#[test]
+fn test_reference_energies_match_grid_average() {
+    let mut mean = [0.0_f64; 9];
+    for phi_idx in 0..36 {
+        for psi_idx in 0..36 {
+            let phi = GRID_MIN + GRID_STEP * phi_idx as f32;
+            let psi = GRID_MIN + GRID_STEP * psi_idx as f32;
+            for (m, rot) in mean.iter_mut().zip(Leu::rotamers_with::<Nearest>(phi, psi)) {
+                *m += rot.prob as f64 / (36.0 * 36.0);
+            }
+        }
+    }
+    for (k, &m) in mean.iter().enumerate() {
+        approx::assert_relative_eq!(Leu::REFERENCE_ENERGIES[k], -(m.ln()) as f32, epsilon = 1e-3);
+    }
+}
+
+#[test]
+fn test_default_rotamer_is_most_common() {
+    macro_rules! check_default {
+        ($Res:ident, $n:literal, $r:literal) => {
+            let rot = <$Res as Residue>::DEFAULT_ROTAMER;
+            let energies = <$Res as Residue>::REFERENCE_ENERGIES;
+            let lowest = energies.iter().copied().fold(f32::INFINITY, f32::min);
+            let k = <$Res as Residue>::rotamers(0.0, 0.0)
+                .position(|other| other.r == rot.r)
+                .unwrap();
+            assert_eq!(energies[k], lowest, "{}", stringify!($Res));
+            approx::assert_relative_eq!(rot.prob, (-lowest).exp(), epsilon = 1e-4);
+            for i in 0..$n {
+                assert!(rot.chi_sigma[i] > 0.0 && rot.chi_mean[i].abs() <= 180.0);
+            }
+        };
+    }
+    for_all_residues!(check_default);
+}
@@ -0,0 +1,34 @@
+#![cfg(feature = "alt-data")]
+
+use approx::assert_relative_eq;
+use dunbrack::*;
+
+#[test]
+fn test_alt_sets_are_normalized() {
+    macro_rules! check {
+        ($Res:ident, $n:literal, $_r:literal) => {
+            assert_eq!(<Alt<$Res> as Residue>::N_CHI, $n);
+            assert_eq!(<Alt<$Res> as Residue>::NAME, <$Res as Residue>::NAME);
+            for &(phi, psi) in &[(-65.0, -43.0), (-120.0, 130.0), (60.0, 40.0)] {
+                let total: f32 = Alt::<$Res>::rotamers(phi, psi).map(|r| r.prob).sum();
+                assert_relative_eq!(total, 1.0, epsilon = 1e-3);
+            }
+        };
+    }
+    for_all_residues!(check);
+}
+
+#[test]
+fn test_alt_namespace_aliases_wrapper() {
+    let a: Vec<_> = alt::Leu::rotamers(-65.0, -43.0).collect();
+    let b: Vec<_> = Alt::<Leu>::rotamers(-65.0, -43.0).collect();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_alt_footprint_counted() {
+    let alt: usize = ALT_TABLE_FOOTPRINT.iter().map(|f| f.bytes()).sum();
+    assert!(alt > 0);
+    assert!(alt < TOTAL_TABLE_BYTES);
+    assert_eq!(ALT_TABLE_FOOTPRINT[0].bytes(), <Alt<Arg>>::TABLE_BYTES);
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "chi1")]
+
+use approx::assert_relative_eq;
+use dunbrack::*;
+
+#[test]
+fn test_chi1_probabilities_are_marginals() {
+    for &(phi, psi) in &[(-65.0, -43.0), (-120.0, 130.0), (60.0, 40.0)] {
+        let reduced: Vec<_> = Chi1::<Arg>::rotamers_with::<Nearest>(phi, psi).collect();
+        let full: Vec<_> = Arg::rotamers_with::<Nearest>(phi, psi).collect();
+        assert_eq!(reduced.len(), 3);
+        for rot in &reduced {
+            let marginal: f32 = full
+                .iter()
+                .filter(|f| f.r[0] == rot.r[0])
+                .map(|f| f.prob)
+                .sum();
+            assert_relative_eq!(rot.prob, marginal, epsilon = 1e-5);
+        }
+    }
+}
+
+#[test]
+fn test_chi1_keeps_single_chi_residues() {
+    let a: Vec<_> = Chi1::<Val>::rotamers(-60.0, -40.0).collect();
+    let b: Vec<_> = Val::rotamers(-60.0, -40.0).collect();
+    for (x, y) in a.iter().zip(&b) {
+        assert_eq!(x.r, y.r);
+        assert_relative_eq!(x.prob, y.prob, epsilon = 1e-5);
+        assert_relative_eq!(x.chi_mean[0], y.chi_mean[0], epsilon = 0.01);
+        assert_relative_eq!(x.chi_sigma[0], y.chi_sigma[0], epsilon = 0.01);
+    }
+}
+
+#[test]
+fn test_chi1_bin_counts() {
+    macro_rules! check {
+        ($Res:ident, $_n:literal, $_r:literal) => {
+            let n = <Chi1<$Res> as Residue>::N_ROTAMERS;
+            assert!(
+                (2..=3).contains(&n),
+                "{}: {n} χ1 bins",
+                <$Res as Residue>::NAME
+            );
+            assert_eq!(<Chi1<$Res> as Residue>::N_CHI, 1);
+        };
+    }
+    for_all_residues!(check);
+}
+
+#[test]
+fn test_chi1_footprint_is_small() {
+    let full: usize = TABLE_FOOTPRINT.iter().map(|f| f.bytes()).sum();
+    let reduced: usize = CHI1_TABLE_FOOTPRINT.iter().map(|f| f.bytes()).sum();
+    // `delta-tables` encodes only the full tables, about five times smaller.
+    let ratio = if cfg!(feature = "delta-tables") {
+        5
+    } else {
+        10
+    };
+    assert!(reduced * ratio < full, "{reduced} vs {full}");
+    #[allow(unused_mut)]
+    let mut total = full + reduced;
+    #[cfg(feature = "alt-data")]
+    {
+        total += ALT_TABLE_FOOTPRINT.iter().map(|f| f.bytes()).sum::<usize>();
+    }
+    assert_eq!(TOTAL_TABLE_BYTES, total);
+}
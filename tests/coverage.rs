@@ -116,3 +116,38 @@ macro_rules! fuzz_no_panic_test {
 }
 
 for_all_residues!(fuzz_no_panic_test);
+
+macro_rules! bin_count_test {
+    ($Res:ident, $n_chi:literal, $n_rot:literal) => {
+        paste::paste! {
+            #[test]
+            fn [<test_bin_counts_ $Res:lower>]() {
+                let bins = <$Res>::BINS_PER_CHI;
+                assert_eq!(bins.len(), $n_chi);
+                let space: usize = bins.iter().map(|&b| usize::from(b)).product();
+                assert!(space >= $n_rot, "{}: {space} < {}", stringify!($Res), $n_rot);
+
+                let ranges = <$Res>::BIN_RANGES;
+                assert_eq!(ranges.len(), $n_chi);
+                for (i, chi_ranges) in ranges.iter().enumerate() {
+                    assert_eq!(chi_ranges.len(), usize::from(bins[i]));
+                    let width: f32 = chi_ranges.iter().map(|range| range.width()).sum();
+                    assert_eq!(width, chi_ranges[0].period, "{}: χ{} ranges do not tile", stringify!($Res), i + 1);
+                }
+
+                let mut used = [[false; 12]; 4];
+                for rot in <$Res>::rotamers(-65.0, -40.0) {
+                    for i in 0..$n_chi {
+                        assert!((1..=bins[i]).contains(&rot.r[i]), "{}: r={:?}", stringify!($Res), rot.r);
+                        used[i][usize::from(rot.r[i]) - 1] = true;
+                    }
+                }
+                for i in 0..$n_chi {
+                    assert!(used[i][..usize::from(bins[i])].iter().all(|&u| u), "{}: χ{} has unused bins", stringify!($Res), i + 1);
+                }
+            }
+        }
+    };
+}
+
+for_all_residues!(bin_count_test);
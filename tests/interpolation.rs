@@ -1,14 +1,14 @@
 //! Interpolation correctness tests.
 //!
 //! Verifies that the bilinear interpolation algorithm works correctly:
-//! grid degeneracy, continuity, circular chi_mean wrapping, and probability
-//! normalization.
+//! grid degeneracy, continuity, circular chi_mean wrapping, probability
+//! normalization, and agreement of the alternative interpolators at grid points.
 
 use dunbrack::{
     Arg, Asn, Asp, Cpr, Cyd, Cyh, Cys, Gln, Glu, His, Ile, Leu, Lys, Met, Phe, Pro, Ser, Thr, Tpr,
     Trp, Tyr, Val,
 };
-use dunbrack::{Residue, Rotamer, for_all_residues};
+use dunbrack::{Bicubic, Bilinear, Nearest, Residue, Rotamer, for_all_residues};
 
 const GRID_COUNT: usize = 37;
 const GRID_MIN: f32 = -180.0;
@@ -179,3 +179,39 @@ macro_rules! prob_normalization_test {
 }
 
 for_all_residues!(prob_normalization_test);
+
+macro_rules! interpolator_agreement_test {
+    ($Res:ident, $n_chi:literal, $n_rot:literal) => {
+        paste::paste! {
+            #[test]
+            fn [<test_interpolator_agreement_ $Res:lower>]() {
+                for phi_idx in (0..GRID_COUNT).step_by(3) {
+                    for psi_idx in (0..GRID_COUNT).step_by(3) {
+                        let phi = GRID_MIN + phi_idx as f32 * GRID_STEP;
+                        let psi = GRID_MIN + psi_idx as f32 * GRID_STEP;
+
+                        let lin: Vec<Rotamer<$n_chi>> =
+                            <$Res>::rotamers_with::<Bilinear>(phi, psi).collect();
+                        let near: Vec<Rotamer<$n_chi>> =
+                            <$Res>::rotamers_with::<Nearest>(phi, psi).collect();
+                        let cubic: Vec<Rotamer<$n_chi>> =
+                            <$Res>::rotamers_with::<Bicubic>(phi, psi).collect();
+
+                        for k in 0..$n_rot {
+                            for other in [&near[k], &cubic[k]] {
+                                assert!(
+                                    (other.prob - lin[k].prob).abs() < 1e-5,
+                                    "{}(φ={phi},ψ={psi})[{k}]: prob {} vs {}",
+                                    stringify!($Res), other.prob, lin[k].prob
+                                );
+                                assert_eq!(other.r, lin[k].r);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+for_all_residues!(interpolator_agreement_test);
@@ -0,0 +1,35 @@
+#![cfg(feature = "generic-const-exprs")]
+#![feature(generic_const_exprs)]
+#![allow(incomplete_features)]
+
+use dunbrack::*;
+
+fn check_fields<R: TypedResidue>(phi: f32, psi: f32)
+where
+    [(); R::N_CHI]:,
+    [(); R::N_ROTAMERS]:,
+{
+    let rots = R::rotamers(phi, psi);
+    assert_eq!(rots.len(), R::N_ROTAMERS);
+    let mut total = 0.0;
+    for rot in rots {
+        total += rot.prob;
+        for i in 0..R::N_CHI {
+            assert!((1..=R::BINS_PER_CHI[i]).contains(&rot.r[i]));
+            assert!((-180.0..=180.0).contains(&rot.chi_mean[i]));
+            assert!(rot.chi_sigma[i] > 0.0);
+        }
+    }
+    assert!((total - 1.0).abs() < 1e-4, "{}: Σp = {total}", R::NAME);
+}
+
+#[test]
+fn test_typed_fields_for_all_residues() {
+    macro_rules! check {
+        ($Res:ident, $_n:literal, $_r:literal) => {
+            check_fields::<$Res>(-65.0, -43.0);
+            check_fields::<$Res>(-120.0, 130.0);
+        };
+    }
+    for_all_residues!(check);
+}